@@ -13,18 +13,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{error, Commit, HostFunctions};
+use crate::{block_hash_storage_key, error, Commit, HostFunctions};
 use alloc::collections::{BTreeMap, BTreeSet};
 use anyhow::anyhow;
 use codec::{Decode, Encode};
 use finality_grandpa::voter_set::VoterSet;
 use frame_support::log;
+use hash_db::Hasher;
+use sp_core::H256;
 use sp_finality_grandpa::{
 	AuthorityId, AuthorityList, AuthoritySignature, ConsensusLog, Equivocation, RoundNumber,
 	ScheduledChange, SetId, GRANDPA_ENGINE_ID,
 };
 use sp_runtime::{generic::OpaqueDigestItemId, traits::Header as HeaderT};
 use sp_std::prelude::*;
+use sp_trie::{LayoutV0, StorageProof, Trie, TrieDBBuilder};
 
 /// A GRANDPA justification for block finality, it includes a commit message and
 /// an ancestry proof including all headers routing all precommit target blocks
@@ -110,17 +113,15 @@ where
 				 qed.",
 			);
 
+		let mut signatures = Vec::with_capacity(self.commit.precommits.len());
 		let mut visited_hashes = BTreeSet::new();
 		for signed in self.commit.precommits.iter() {
 			let message = finality_grandpa::Message::Precommit(signed.precommit.clone());
-
-			check_message_signature::<Host, _, _>(
-				&message,
-				&signed.id,
-				&signed.signature,
-				self.round,
-				set_id,
-			)?;
+			signatures.push((
+				signed.signature.as_ref().clone(),
+				signature_payload(&message, self.round, set_id),
+				signed.id.as_ref().clone(),
+			));
 
 			if base_hash == signed.precommit.target_hash {
 				continue
@@ -146,6 +147,10 @@ where
 			))?
 		}
 
+		if !Host::ed25519_batch_verify(&signatures) {
+			Err(anyhow!("invalid signature for precommit in grandpa justification"))?
+		}
+
 		Ok(())
 	}
 
@@ -201,6 +206,81 @@ where
 	}
 }
 
+impl<H> AncestryChain<H>
+where
+	H: HeaderT<Hash = H256, Number = u32>,
+{
+	/// Returns the route from `block` back to `base`, the block at height `base_number`, through
+	/// a sparse set of headers. Each header above `base_number` must either be a direct child of
+	/// the preceding one, or have a state proof of the preceding header's hash in its
+	/// `System::BlockHash` storage in `proofs`, keyed by its own hash. Headers at or below
+	/// `base_number` are ignored.
+	pub fn sparse_ancestry<Host>(
+		&self,
+		base_number: u32,
+		base: H256,
+		block: H256,
+		proofs: &BTreeMap<H256, Vec<Vec<u8>>>,
+	) -> Result<Vec<H256>, error::Error>
+	where
+		Host: HostFunctions,
+		Host::BlakeTwo256: Hasher<Out = H256>,
+	{
+		let target = self.header(&block).ok_or_else(|| anyhow!("Header {block:?} not found"))?;
+		let mut headers = self
+			.ancestry
+			.values()
+			.filter(|header| *header.number() > base_number && header.number() <= target.number())
+			.collect::<Vec<_>>();
+		headers.sort_by_key(|header| *header.number());
+
+		if headers.last().map(|header| header.hash()) != Some(block) {
+			Err(anyhow!("Header {block:?} is not the only one at its height"))?
+		}
+
+		let mut route = vec![(base_number, base)];
+		for header in headers {
+			let (number, hash) = *route.last().expect("route starts with the base; qed");
+			let header_hash = header.hash();
+			let parent_hash = if *header.number() == number + 1 {
+				*header.parent_hash()
+			} else {
+				let proof = proofs
+					.get(&header_hash)
+					.ok_or_else(|| anyhow!("Missing ancestry proof for header {header_hash:?}"))?;
+				read_block_hash::<Host>(header.state_root(), number, proof)?
+			};
+			if parent_hash != hash {
+				Err(anyhow!("Header {header_hash:?} doesn't descend from block {hash:?}"))?
+			}
+			route.push((*header.number(), header_hash));
+		}
+
+		Ok(route.into_iter().rev().map(|(_, hash)| hash).collect())
+	}
+}
+
+/// Reads the hash of the block with the given number from the `System::BlockHash` storage at
+/// `state_root`, using a state proof.
+fn read_block_hash<Host>(
+	state_root: &H256,
+	number: u32,
+	proof: &[Vec<u8>],
+) -> Result<H256, error::Error>
+where
+	Host: HostFunctions,
+	Host::BlakeTwo256: Hasher<Out = H256>,
+{
+	let db = StorageProof::new(proof.to_vec()).into_memory_db::<Host::BlakeTwo256>();
+	let trie = TrieDBBuilder::<LayoutV0<Host::BlakeTwo256>>::new(&db, state_root).build();
+	let value = trie
+		.get(block_hash_storage_key(number).as_ref())
+		.map_err(|err| anyhow!("error verifying block hash state proof: {err:?}"))?
+		.ok_or_else(|| anyhow!("Invalid proof, hash of block {number} not found"))?;
+
+	Ok(H256::decode(&mut &value[..])?)
+}
+
 /// Checks the given header for a consensus digest signalling a **standard** scheduled change and
 /// extracts it.
 pub fn find_scheduled_change<H: HeaderT>(header: &H) -> Option<ScheduledChange<H::Number>> {
@@ -248,7 +328,7 @@ where
 	N: Encode,
 {
 	log::trace!(target: "pallet_ibc", "Justification Message {:?}", (round, set_id));
-	let buf = (message, round, set_id).encode();
+	let buf = signature_payload(message, round, set_id);
 
 	if !Host::ed25519_verify(signature.as_ref(), &buf, id.as_ref()) {
 		Err(anyhow!("invalid signature for precommit in grandpa justification"))?
@@ -257,6 +337,19 @@ where
 	Ok(())
 }
 
+/// The payload a GRANDPA voter signs for a message in the given round and authority set.
+pub fn signature_payload<H, N>(
+	message: &finality_grandpa::Message<H, N>,
+	round: RoundNumber,
+	set_id: SetId,
+) -> Vec<u8>
+where
+	H: Encode,
+	N: Encode,
+{
+	(message, round, set_id).encode()
+}
+
 /// Verifies the equivocation proof by making sure that both votes target
 /// different blocks and that its signatures are valid.
 pub fn check_equivocation_proof<Host, H, N>(
@@ -72,6 +72,9 @@ pub struct ClientState {
 	pub latest_relay_hash: Hash,
 	/// para_id of associated parachain
 	pub para_id: u32,
+	/// Whether the client tracks a standalone chain, whose headers are finalized directly by its
+	/// GRANDPA authorities. The "relay chain" fields then describe the chain itself.
+	pub standalone: bool,
 }
 
 /// Holds relavant parachain proofs for both header and timestamp extrinsic.
@@ -94,9 +97,16 @@ pub struct ParachainHeadersWithFinalityProof<H: codec::Codec> {
 	/// Contains a map of relay chain header hashes to parachain headers
 	/// finalzed at the relay chain height. We check for this parachain header finalization
 	/// via state proofs. Also contains extrinsic proof for timestamp.
+	/// For standalone chains, the keys are the hashes of the finalized headers themselves and the
+	/// state proofs are empty.
 	pub parachain_headers: BTreeMap<Hash, ParachainHeaderProofs>,
 	/// The latest finalized height on the parachain.
 	pub latest_para_height: u32,
+	/// The `unknown_headers` of the finality proof may skip blocks. Every header that isn't a
+	/// direct child of the preceding one (or of the last finalized block) has an entry here,
+	/// keyed by its hash: a state proof of the preceding header's hash in its `System::BlockHash`
+	/// storage.
+	pub ancestry_proofs: BTreeMap<Hash, Vec<Vec<u8>>>,
 }
 
 /// Host functions that allow the light client perform cryptographic operations in native.
@@ -106,6 +116,21 @@ pub trait HostFunctions: light_client_common::HostFunctions + 'static {
 
 	/// Verify an ed25519 signature
 	fn ed25519_verify(sig: &ed25519::Signature, msg: &[u8], pub_key: &ed25519::Public) -> bool;
+	/// Verify a batch of ed25519 signatures, returns `true` only if all of them are valid. By
+	/// default the signatures are verified one by one, in parallel with the `parallel` feature.
+	fn ed25519_batch_verify(batch: &[(ed25519::Signature, Vec<u8>, ed25519::Public)]) -> bool {
+		#[cfg(feature = "parallel")]
+		{
+			use rayon::prelude::*;
+			batch
+				.par_iter()
+				.all(|(sig, msg, pub_key)| Self::ed25519_verify(sig, msg, pub_key))
+		}
+		#[cfg(not(feature = "parallel"))]
+		{
+			batch.iter().all(|(sig, msg, pub_key)| Self::ed25519_verify(sig, msg, pub_key))
+		}
+	}
 	/// Stores the given list of RelayChain header hashes in the light client's storage.
 	fn insert_relay_header_hashes(headers: &[<Self::Header as Header>::Hash]);
 	/// Checks if a RelayChain header hash exists in the light client's storage.
@@ -120,3 +145,13 @@ pub fn parachain_header_storage_key(para_id: u32) -> StorageKey {
 	storage_key.extend_from_slice(&encoded_para_id);
 	StorageKey(storage_key)
 }
+
+/// This returns the storage key for the hash of the block with the given number in the
+/// `System::BlockHash` map, which holds the hashes of the last `BlockHashCount` blocks.
+pub fn block_hash_storage_key(number: u32) -> StorageKey {
+	let mut storage_key = frame_support::storage::storage_prefix(b"System", b"BlockHash").to_vec();
+	let encoded_number = number.encode();
+	storage_key.extend_from_slice(sp_io::hashing::twox_64(&encoded_number).as_slice());
+	storage_key.extend_from_slice(&encoded_number);
+	StorageKey(storage_key)
+}
@@ -27,8 +27,8 @@ use finality_grandpa_rpc::GrandpaApiClient;
 use jsonrpsee::{async_client::Client, tracing::log, ws_client::WsClientBuilder};
 use light_client_common::config::{AsInner, RuntimeStorage};
 use primitives::{
-	parachain_header_storage_key, ClientState, FinalityProof, ParachainHeaderProofs,
-	ParachainHeadersWithFinalityProof,
+	block_hash_storage_key, parachain_header_storage_key, ClientState, FinalityProof,
+	ParachainHeaderProofs, ParachainHeadersWithFinalityProof,
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -50,6 +50,9 @@ use tokio::{task::JoinSet, time::sleep};
 pub const PROCESS_CHANGES_SET_BATCH_SIZE: usize = 100;
 /// The maximum number of blocks to request at once
 pub const PROCESS_BLOCKS_BATCH_SIZE: usize = 100;
+/// Headers of a compact ancestry proof that are at most this many blocks apart are linked by the
+/// headers in between, which are smaller than a storage proof of the `System::BlockHash` entry.
+pub const ANCESTRY_PROOF_MIN_GAP: u32 = 8;
 
 /// Host function implementation for the verifier
 pub mod host_functions;
@@ -68,6 +71,9 @@ pub struct GrandpaProver<T: Config> {
 	pub para_id: u32,
 	/// Delay between rpc calls to the RPC
 	pub rpc_call_delay: Duration,
+	/// Whether the relay chain clients connect to a standalone chain whose own headers are
+	/// finalized by GRANDPA. The parachain clients then connect to the same chain.
+	pub standalone: bool,
 }
 
 // We redefine these here because we want the header to be bounded by subxt::config::Header in the
@@ -100,6 +106,7 @@ impl<T: Config> Clone for GrandpaProver<T> {
 			para_ws_client: self.para_ws_client.clone(),
 			para_id: self.para_id,
 			rpc_call_delay: self.rpc_call_delay,
+			standalone: self.standalone,
 		}
 	}
 }
@@ -130,6 +137,7 @@ where
 			para_client,
 			para_id,
 			rpc_call_delay,
+			standalone: false,
 		})
 	}
 
@@ -201,10 +209,12 @@ where
 			para_id: self.para_id,
 			// we'll set this below
 			latest_para_height: u32::from(finalized_para_header.number()),
+			standalone: self.standalone,
 		})
 	}
 
 	/// Returns the latest finalized parachain header at the given finalized relay chain height.
+	/// For standalone chains, this is the finalized header itself.
 	pub async fn query_latest_finalized_parachain_header(
 		&self,
 		latest_finalized_height: u32,
@@ -218,6 +228,14 @@ where
 			.block_hash(Some(latest_finalized_height.into()))
 			.await?
 			.ok_or_else(|| anyhow!("Block hash not found for number: {latest_finalized_height}"))?;
+		if self.standalone {
+			return self
+				.relay_client
+				.rpc()
+				.header(Some(latest_finalized_hash))
+				.await?
+				.ok_or_else(|| anyhow!("Header not found for hash: {latest_finalized_hash:?}"))
+		}
 		let key = T::Storage::paras_heads(self.para_id);
 		let header = <T::Storage as RuntimeStorage>::HeadData::from_inner(
 			self.relay_client
@@ -302,9 +320,25 @@ where
 			.await?
 			.ok_or_else(|| anyhow!("Failed to fetch previous finalized hash + 1"))?;
 
+		let (parachain_headers_with_proof, relay_heights, latest_para_height) = if self.standalone {
+			self.query_standalone_header_proofs(header_numbers).await?
+		} else {
+			self.query_parachain_header_proofs(start, latest_finalized_hash, header_numbers)
+				.await?
+		};
+
+		// only the headers of the blocks that include parachain headers are sent, linked to each
+		// other by proofs of their ancestors' hashes in the `System::BlockHash` storage.
+		let block_hash_count = self.block_hash_count().await?;
+		let heights = compact_ancestry_heights(
+			previous_finalized_height,
+			latest_finalized_height,
+			relay_heights,
+			block_hash_count,
+		);
+
 		let mut unknown_headers = vec![];
 		let mut unknown_headers_join_set: JoinSet<Result<_, anyhow::Error>> = JoinSet::new();
-		let heights = (previous_finalized_height..=latest_finalized_height).collect::<Vec<_>>();
 		for heights in heights.chunks(PROCESS_BLOCKS_BATCH_SIZE) {
 			for height in heights.to_owned() {
 				log::trace!(target: "hyperspace", "Processing height: {height}");
@@ -338,6 +372,57 @@ where
 			}
 		}
 
+		unknown_headers.sort_by_key(|header| header.number());
+
+		let mut ancestry_proofs = BTreeMap::new();
+		let mut previous_height = previous_finalized_height;
+		for header in &unknown_headers {
+			let height = u32::from(header.number());
+			if height <= previous_finalized_height {
+				continue
+			}
+			if height > previous_height + 1 {
+				let hash = T::Hash::from(header.hash());
+				let key = block_hash_storage_key(previous_height);
+				let proof = self
+					.relay_client
+					.rpc()
+					.read_proof(vec![key.as_ref()], Some(hash))
+					.await?
+					.proof
+					.into_iter()
+					.map(|p| p.0)
+					.collect();
+				ancestry_proofs.insert(H256::from(hash), proof);
+			}
+			previous_height = height;
+		}
+
+		// overwrite unknown headers
+		finality_proof.unknown_headers = unknown_headers;
+
+		Ok(ParachainHeadersWithFinalityProof {
+			finality_proof,
+			parachain_headers: parachain_headers_with_proof,
+			latest_para_height,
+			ancestry_proofs,
+		})
+	}
+
+	/// Returns the state proofs of the parachain headers with the given numbers, which changed
+	/// in the relay chain blocks `start..=latest_finalized_hash`, keyed by the relay chain block
+	/// hash, together with the heights of these relay chain blocks and the highest of the
+	/// parachain header numbers.
+	async fn query_parachain_header_proofs(
+		&self,
+		start: T::Hash,
+		latest_finalized_hash: T::Hash,
+		header_numbers: Vec<<<T as subxt::Config>::Header as Header>::Number>,
+	) -> Result<(BTreeMap<H256, ParachainHeaderProofs>, Vec<u32>, u32), anyhow::Error>
+	where
+		<<T as subxt::Config>::Header as Header>::Number: Clone + Sync + Send,
+		<T as subxt::Config>::Header: Decode + Sync,
+	{
 		// we are interested only in the blocks where our parachain header changes.
 		let para_storage_key = parachain_header_storage_key(self.para_id);
 		let keys = vec![para_storage_key.as_ref()];
@@ -350,6 +435,7 @@ where
 
 		let mut change_set_join_set: JoinSet<Result<Option<_>, anyhow::Error>> = JoinSet::new();
 		let mut parachain_headers_with_proof = BTreeMap::<H256, ParachainHeaderProofs>::default();
+		let mut relay_heights = vec![];
 		log::debug!(target:"hyperspace", "Got {} authority set changes", change_set.len());
 
 		fn clone_storage_change_sets<T: light_client_common::config::Config + Send + Sync>(
@@ -422,26 +508,74 @@ where
 						.map_err(|err| anyhow!("Error fetching timestamp with proof: {err:?}"))?;
 					let proofs = ParachainHeaderProofs { state_proof, extrinsic, extrinsic_proof };
 					latest_para_height.fetch_max(u32::from(para_block_number), Ordering::SeqCst);
-					Ok(Some((H256::from(header.hash()), proofs)))
+					Ok(Some((H256::from(header.hash()), u32::from(header.number()), proofs)))
 				});
 			}
 
 			while let Some(res) = change_set_join_set.join_next().await {
-				if let Some((hash, proofs)) = res?? {
+				if let Some((hash, relay_height, proofs)) = res?? {
 					parachain_headers_with_proof.insert(hash, proofs);
+					relay_heights.push(relay_height);
 				}
 			}
 		}
 
-		unknown_headers.sort_by_key(|header| header.number());
-		// overwrite unknown headers
-		finality_proof.unknown_headers = unknown_headers;
+		Ok((parachain_headers_with_proof, relay_heights, latest_para_height.load(Ordering::SeqCst)))
+	}
 
-		Ok(ParachainHeadersWithFinalityProof {
-			finality_proof,
-			parachain_headers: parachain_headers_with_proof,
-			latest_para_height: latest_para_height.load(Ordering::SeqCst),
-		})
+	/// Returns the timestamp extrinsic proofs of the standalone chain headers with the given
+	/// numbers, keyed by the header hash, together with these numbers and the highest of them.
+	async fn query_standalone_header_proofs(
+		&self,
+		header_numbers: Vec<<<T as subxt::Config>::Header as Header>::Number>,
+	) -> Result<(BTreeMap<H256, ParachainHeaderProofs>, Vec<u32>, u32), anyhow::Error>
+	where
+		<<T as subxt::Config>::Header as Header>::Number: Clone + Sync + Send,
+	{
+		let mut headers_with_proof = BTreeMap::<H256, ParachainHeaderProofs>::default();
+		let mut heights = vec![];
+		let mut latest_height = 0u32;
+		let mut join_set: JoinSet<Result<_, anyhow::Error>> = JoinSet::new();
+		for header_numbers in header_numbers.chunks(PROCESS_BLOCKS_BATCH_SIZE) {
+			for number in header_numbers.to_owned() {
+				let client = self.clone();
+				let to = self.rpc_call_delay.as_millis();
+				let duration = Duration::from_millis(rand::thread_rng().gen_range(1..to) as u64);
+				join_set.spawn(async move {
+					sleep(duration).await;
+					let height = u32::from(number);
+					// skip the genesis header
+					if height == 0 {
+						return Ok(None)
+					}
+					let hash = client
+						.relay_client
+						.rpc()
+						.block_hash(Some(height.into()))
+						.await?
+						.ok_or_else(|| anyhow!("Failed to fetch block hash for height {height}"))?;
+					let TimeStampExtWithProof { ext: extrinsic, proof: extrinsic_proof } =
+						fetch_timestamp_extrinsic_with_proof(&client.relay_client, Some(hash))
+							.await
+							.map_err(|err| {
+								anyhow!("Error fetching timestamp with proof: {err:?}")
+							})?;
+					let proofs =
+						ParachainHeaderProofs { state_proof: vec![], extrinsic, extrinsic_proof };
+					Ok(Some((H256::from(hash), height, proofs)))
+				});
+			}
+
+			while let Some(res) = join_set.join_next().await {
+				if let Some((hash, height, proofs)) = res?? {
+					latest_height = latest_height.max(height);
+					headers_with_proof.insert(hash, proofs);
+					heights.push(height);
+				}
+			}
+		}
+
+		Ok((headers_with_proof, heights, latest_height))
 	}
 
 	/// Queries the block at which the epoch for the given block belongs to ends.
@@ -475,4 +609,100 @@ where
 		let metadata = metadata.pallet("Babe")?.constant("EpochDuration")?;
 		Ok(Decode::decode(&mut &metadata.value[..])?)
 	}
+
+	/// Returns the number of ancestors whose hashes are kept in the `System::BlockHash` storage
+	pub async fn block_hash_count(&self) -> Result<u32, anyhow::Error> {
+		let metadata = self.relay_client.rpc().metadata(None).await?;
+		let metadata = metadata.pallet("System")?.constant("BlockHashCount")?;
+		Ok(Decode::decode(&mut &metadata.value[..])?)
+	}
+}
+
+/// Returns the heights of the headers of a compact ancestry proof from `base` to `target` that
+/// includes the headers at the `required` heights. Blocks that are at most
+/// [`ANCESTRY_PROOF_MIN_GAP`] blocks apart are linked by all the headers in between, otherwise
+/// headers no more than `block_hash_count` blocks apart are linked by the `System::BlockHash`
+/// storage of the descendant.
+fn compact_ancestry_heights(
+	base: u32,
+	target: u32,
+	required: Vec<u32>,
+	block_hash_count: u32,
+) -> Vec<u32> {
+	let mut heights =
+		required.into_iter().filter(|height| *height <= target).collect::<BTreeSet<_>>();
+	heights.insert(target);
+
+	let mut links = vec![];
+	let mut previous = base;
+	for height in heights.iter().copied().filter(|height| *height > base) {
+		if height - previous <= ANCESTRY_PROOF_MIN_GAP {
+			links.extend(previous + 1..height);
+		} else {
+			let mut hop = previous + block_hash_count;
+			while hop < height {
+				links.push(hop);
+				hop += block_hash_count;
+			}
+		}
+		previous = height;
+	}
+	heights.extend(links);
+
+	heights.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const BLOCK_HASH_COUNT: u32 = 100;
+
+	#[test]
+	fn short_gaps_are_linked_by_every_header() {
+		let gap = ANCESTRY_PROOF_MIN_GAP;
+		assert_eq!(
+			compact_ancestry_heights(10, 10 + gap, vec![], BLOCK_HASH_COUNT),
+			(11..=10 + gap).collect::<Vec<_>>()
+		);
+		assert_eq!(compact_ancestry_heights(10, 11, vec![], BLOCK_HASH_COUNT), vec![11]);
+		// One block more and the target's `System::BlockHash` storage links it to the base
+		assert_eq!(
+			compact_ancestry_heights(10, 11 + gap, vec![], BLOCK_HASH_COUNT),
+			vec![11 + gap]
+		);
+	}
+
+	#[test]
+	fn gaps_of_block_hash_count_need_no_hops() {
+		assert_eq!(
+			compact_ancestry_heights(0, BLOCK_HASH_COUNT, vec![], BLOCK_HASH_COUNT),
+			vec![BLOCK_HASH_COUNT]
+		);
+		assert_eq!(
+			compact_ancestry_heights(0, BLOCK_HASH_COUNT + 1, vec![], BLOCK_HASH_COUNT),
+			vec![BLOCK_HASH_COUNT, BLOCK_HASH_COUNT + 1]
+		);
+	}
+
+	#[test]
+	fn long_gaps_hop_every_block_hash_count_blocks() {
+		assert_eq!(
+			compact_ancestry_heights(0, 350, vec![], BLOCK_HASH_COUNT),
+			vec![100, 200, 300, 350]
+		);
+		// Hops restart from every required header
+		assert_eq!(
+			compact_ancestry_heights(0, 350, vec![50], BLOCK_HASH_COUNT),
+			vec![50, 150, 250, 350]
+		);
+	}
+
+	#[test]
+	fn required_headers_are_linked_in_order() {
+		assert_eq!(
+			compact_ancestry_heights(0, 320, vec![400, 150, 4], BLOCK_HASH_COUNT),
+			vec![1, 2, 3, 4, 104, 150, 250, 320]
+		);
+	}
 }
@@ -43,7 +43,8 @@ mod tests;
 /// This function verifies the GRANDPA finality proof for relay chain headers.
 ///
 /// Next, we prove the finality of parachain headers, by verifying patricia-merkle trie state proofs
-/// of these headers, stored at the recently finalized relay chain heights.
+/// of these headers, stored at the recently finalized relay chain heights. For standalone chains,
+/// the finalized headers are the chain's own, so only their timestamp extrinsics are verified.
 pub fn verify_parachain_headers_with_grandpa_finality_proof<H, Host>(
 	mut client_state: ClientState,
 	proof: ParachainHeadersWithFinalityProof<H>,
@@ -54,8 +55,12 @@ where
 	Host: HostFunctions,
 	Host::BlakeTwo256: Hasher<Out = H256>,
 {
-	let ParachainHeadersWithFinalityProof { finality_proof, parachain_headers, latest_para_height } =
-		proof;
+	let ParachainHeadersWithFinalityProof {
+		finality_proof,
+		parachain_headers,
+		latest_para_height,
+		ancestry_proofs,
+	} = proof;
 
 	// 1. First validate unknown headers.
 	let headers = AncestryChain::<H>::new(&finality_proof.unknown_headers);
@@ -93,9 +98,18 @@ where
 		})?;
 	}
 
-	let mut finalized = headers.ancestry(from, target.hash()).map_err(|_| {
-		anyhow!("[verify_parachain_headers_with_grandpa_finality_proof] Invalid ancestry!")
-	})?;
+	// unknown headers may skip blocks, in which case their ancestry is proven by the
+	// `System::BlockHash` storage of their descendants.
+	let mut finalized = headers
+		.sparse_ancestry::<Host>(
+			client_state.latest_relay_height,
+			from,
+			target.hash(),
+			&ancestry_proofs,
+		)
+		.map_err(|_| {
+			anyhow!("[verify_parachain_headers_with_grandpa_finality_proof] Invalid ancestry!")
+		})?;
 	finalized.sort();
 
 	// 2. verify justification.
@@ -112,19 +126,24 @@ where
 			headers.header(&hash).expect("Headers have been checked by AncestryChain; qed");
 
 		let ParachainHeaderProofs { extrinsic_proof, extrinsic, state_proof } = proofs;
-		let proof = StorageProof::new(state_proof);
-		let key = parachain_header_storage_key(client_state.para_id);
-		// verify patricia-merkle state proofs
-		let header = state_machine::read_proof_check::<Host::BlakeTwo256, _>(
-			relay_chain_header.state_root(),
-			proof,
-			&[key.as_ref()],
-		)
-		.map_err(|err| anyhow!("error verifying parachain header state proof: {err}"))?
-		.remove(key.as_ref())
-		.flatten()
-		.ok_or_else(|| anyhow!("Invalid proof, parachain header not found"))?;
-		let parachain_header = H::decode(&mut &header[..])?;
+		let parachain_header = if client_state.standalone {
+			// the finalized header is the chain's own header
+			relay_chain_header.clone()
+		} else {
+			let proof = StorageProof::new(state_proof);
+			let key = parachain_header_storage_key(client_state.para_id);
+			// verify patricia-merkle state proofs
+			let header = state_machine::read_proof_check::<Host::BlakeTwo256, _>(
+				relay_chain_header.state_root(),
+				proof,
+				&[key.as_ref()],
+			)
+			.map_err(|err| anyhow!("error verifying parachain header state proof: {err}"))?
+			.remove(key.as_ref())
+			.flatten()
+			.ok_or_else(|| anyhow!("Invalid proof, parachain header not found"))?;
+			H::decode(&mut &header[..])?
+		};
 		para_heights.push(parachain_header.number().clone().into());
 		// Timestamp extrinsic should be the first inherent and hence the first extrinsic
 		// https://github.com/paritytech/substrate/blob/d602397a0bbb24b5d627795b797259a44a5e29e9/primitives/trie/src/lib.rs#L99-L101
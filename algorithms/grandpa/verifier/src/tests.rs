@@ -22,11 +22,15 @@ use grandpa_prover::{
 use hyperspace_core::substrate::DefaultConfig as PolkadotConfig;
 use polkadot_core_primitives::Header;
 use primitives::{
-	justification::GrandpaJustification, FinalityProof, ParachainHeadersWithFinalityProof,
+	block_hash_storage_key,
+	justification::{signature_payload, AncestryChain, GrandpaJustification},
+	ClientState, FinalityProof, ParachainHeaderProofs, ParachainHeadersWithFinalityProof,
 };
 use serde::{Deserialize, Serialize};
-use sp_core::H256;
-use std::time::Duration;
+use sp_core::{ed25519, Pair, H256};
+use sp_finality_grandpa::{AuthorityId, AuthoritySignature};
+use sp_trie::{LayoutV0, MemoryDB, TrieDBMutBuilder, TrieMut};
+use std::{collections::BTreeMap, time::Duration};
 use subxt::{
 	config::substrate::{BlakeTwo256, SubstrateHeader},
 	rpc_params,
@@ -157,3 +161,166 @@ async fn follow_grandpa_justifications() {
 		println!("========= Successfully verified grandpa justification =========");
 	}
 }
+
+#[test]
+fn verify_sparse_ancestry() {
+	type Hashing = sp_runtime::traits::BlakeTwo256;
+
+	// build a chain where the state of every block holds the hashes of its ancestors
+	let mut db = MemoryDB::<Hashing>::default();
+	let mut block_hashes = vec![];
+	let mut headers = vec![];
+	for number in 1..=10u32 {
+		let mut state_root = Default::default();
+		{
+			let mut trie =
+				TrieDBMutBuilder::<LayoutV0<Hashing>>::new(&mut db, &mut state_root).build();
+			for (number, hash) in &block_hashes {
+				trie.insert(block_hash_storage_key(*number).as_ref(), &H256::encode(hash))
+					.unwrap();
+			}
+		}
+		let header = Header {
+			parent_hash: block_hashes.last().map(|(_, hash)| *hash).unwrap_or_default(),
+			number,
+			state_root,
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		};
+		block_hashes.push((number, header.hash()));
+		headers.push(header);
+	}
+	let proof = db.drain().into_iter().map(|(_, (node, ..))| node).collect::<Vec<_>>();
+
+	// blocks 3 to 5 and 7 to 9 are skipped
+	let base = headers[0].hash();
+	let target = headers[9].hash();
+	let chain = AncestryChain::new(&[headers[1].clone(), headers[5].clone(), headers[9].clone()]);
+	let mut proofs = BTreeMap::new();
+	proofs.insert(headers[5].hash(), proof.clone());
+	proofs.insert(target, proof.clone());
+
+	let route = chain
+		.sparse_ancestry::<HostFunctionsProvider>(1, base, target, &proofs)
+		.unwrap();
+	assert_eq!(route, vec![target, headers[5].hash(), headers[1].hash(), base]);
+
+	// the route has to start at the last finalized block
+	assert!(chain
+		.sparse_ancestry::<HostFunctionsProvider>(1, H256::repeat_byte(1), target, &proofs)
+		.is_err());
+
+	// headers that skip blocks need an ancestry proof
+	proofs.remove(&headers[5].hash());
+	assert!(chain
+		.sparse_ancestry::<HostFunctionsProvider>(1, base, target, &proofs)
+		.is_err());
+}
+
+#[test]
+fn verify_standalone_chain_headers() {
+	type Hashing = sp_runtime::traits::BlakeTwo256;
+
+	// every header has the same timestamp extrinsic
+	let extrinsic = vec![4, 3, 2, 1];
+	let key = codec::Compact(0u32).encode();
+	let mut db = MemoryDB::<Hashing>::default();
+	let mut extrinsics_root = Default::default();
+	{
+		let mut trie =
+			TrieDBMutBuilder::<LayoutV0<Hashing>>::new(&mut db, &mut extrinsics_root).build();
+		trie.insert(&key, &extrinsic).unwrap();
+	}
+	let extrinsic_proof =
+		sp_trie::generate_trie_proof::<LayoutV0<Hashing>, _, _, _>(&db, extrinsics_root, [&key])
+			.unwrap();
+
+	let mut headers: Vec<Header> = vec![];
+	for number in 1..=3u32 {
+		headers.push(Header {
+			parent_hash: headers.last().map(|header| header.hash()).unwrap_or_default(),
+			number,
+			state_root: Default::default(),
+			extrinsics_root,
+			digest: Default::default(),
+		});
+	}
+	let target = headers[2].clone();
+
+	// a single authority finalizes the chain's own headers
+	let authority = ed25519::Pair::from_seed(&[1; 32]);
+	let precommit =
+		finality_grandpa::Precommit { target_hash: target.hash(), target_number: target.number };
+	let message = finality_grandpa::Message::Precommit(precommit.clone());
+	let signature = authority.sign(&signature_payload(&message, 1, 0));
+	let justification = Justification {
+		round: 1,
+		commit: finality_grandpa::Commit {
+			target_hash: target.hash(),
+			target_number: target.number,
+			precommits: vec![finality_grandpa::SignedPrecommit {
+				precommit,
+				signature: AuthoritySignature::from(signature),
+				id: AuthorityId::from(authority.public()),
+			}],
+		},
+		votes_ancestries: vec![],
+	};
+
+	let client_state = ClientState {
+		current_authorities: vec![(AuthorityId::from(authority.public()), 1)],
+		current_set_id: 0,
+		latest_relay_height: 1,
+		latest_para_height: 1,
+		latest_relay_hash: headers[0].hash(),
+		para_id: 2000,
+		standalone: true,
+	};
+	let header_proofs = ParachainHeaderProofs {
+		state_proof: vec![],
+		extrinsic: extrinsic.clone(),
+		extrinsic_proof: extrinsic_proof.clone(),
+	};
+	let proof = ParachainHeadersWithFinalityProof {
+		finality_proof: FinalityProof {
+			block: target.hash(),
+			justification: justification.encode(),
+			unknown_headers: headers[1..].to_vec(),
+		},
+		parachain_headers: headers[1..]
+			.iter()
+			.map(|header| (header.hash(), header_proofs.clone()))
+			.collect(),
+		latest_para_height: 3,
+		ancestry_proofs: BTreeMap::new(),
+	};
+
+	// the finalized headers are the chain's own, without any state proof
+	let new_state = verify_parachain_headers_with_grandpa_finality_proof::<
+		Header,
+		HostFunctionsProvider,
+	>(client_state.clone(), proof.clone())
+	.unwrap();
+	assert_eq!(new_state.latest_relay_hash, target.hash());
+	assert_eq!(new_state.latest_relay_height, 3);
+	assert_eq!(new_state.latest_para_height, 3);
+
+	// the timestamp extrinsic must still be proven
+	let mut invalid = proof.clone();
+	for header_proofs in invalid.parachain_headers.values_mut() {
+		header_proofs.extrinsic = vec![1, 2, 3, 4];
+	}
+	assert!(verify_parachain_headers_with_grandpa_finality_proof::<Header, HostFunctionsProvider>(
+		client_state.clone(),
+		invalid
+	)
+	.is_err());
+
+	// parachain clients expect the headers to be proven in the relay chain state
+	let parachain_state = ClientState { standalone: false, ..client_state };
+	assert!(verify_parachain_headers_with_grandpa_finality_proof::<Header, HostFunctionsProvider>(
+		parachain_state,
+		proof
+	)
+	.is_err());
+}
@@ -282,6 +282,19 @@ pub struct QueryDenomTraceResponse {
 	pub denom: Vec<u8>,
 }
 
+#[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd)]
+pub struct QueryDenomMetadataResponse {
+	/// Full ibc denom, including the trace path
+	pub denom: Vec<u8>,
+	/// Base denom on the chain the token originates from
+	pub base_denom: Vec<u8>,
+	/// Series of `{port-id}/{channel-id}`s the token went through
+	pub trace_path: Vec<u8>,
+	pub name: Vec<u8>,
+	pub symbol: Vec<u8>,
+	pub decimals: u8,
+}
+
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd)]
 pub struct QueryDenomTracesResponse {
 	pub denoms: Vec<Vec<u8>>,
@@ -12,6 +12,55 @@ pub enum SS58CodecError {
 	InvalidAccountId,
 }
 
+/// Verifies that `signature` is the BLS12-381 aggregate signature of `message` by all the
+/// `public_keys`, with the proof of possession ciphersuite of the Ethereum beacon chain. This runs
+/// in the runtime, so that validators can check the PVF without any extra host functions.
+pub fn bls12_381_fast_aggregate_verify(
+	public_keys: &[[u8; 48]],
+	message: &[u8; 32],
+	signature: &[u8],
+) -> bool {
+	use bls12_381::{
+		hash_to_curve::{ExpandMsgXmd, HashToCurve},
+		multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+	};
+
+	const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+	if public_keys.is_empty() {
+		return false
+	}
+	// `from_compressed` checks that the points are in the prime order subgroups, the points at
+	// infinity are rejected as in the `KeyValidate` and signature validation of the spec.
+	let mut aggregate_key = G1Projective::identity();
+	for public_key in public_keys {
+		let public_key: Option<G1Affine> = G1Affine::from_compressed(public_key).into();
+		match public_key {
+			Some(public_key) if !bool::from(public_key.is_identity()) =>
+				aggregate_key += G1Projective::from(public_key),
+			_ => return false,
+		}
+	}
+	let signature = match <[u8; 96]>::try_from(signature) {
+		Ok(signature) => Option::<G2Affine>::from(G2Affine::from_compressed(&signature)),
+		Err(_) => return false,
+	};
+	let signature = match signature {
+		Some(signature) if !bool::from(signature.is_identity()) => signature,
+		_ => return false,
+	};
+	let message_point =
+		<G2Projective as HashToCurve<ExpandMsgXmd<sha2_09::Sha256>>>::hash_to_curve(message, DST);
+
+	// e(aggregate_key, H(message)) == e(g1, signature)
+	multi_miller_loop(&[
+		(&G1Affine::from(aggregate_key), &G2Prepared::from(G2Affine::from(message_point))),
+		(&-G1Affine::generator(), &G2Prepared::from(signature)),
+	])
+	.final_exponentiation() ==
+		Gt::identity()
+}
+
 pub fn blake2_256_verify_non_membership_proof(root: &H256, proof: &[Vec<u8>], key: &[u8]) -> bool {
 	sp_trie::verify_trie_proof::<sp_trie::LayoutV0<sp_runtime::traits::BlakeTwo256>, _, _, &[u8]>(
 		root,
@@ -200,4 +249,30 @@ mod tests {
 		let k = from_ss58check_with_version::<Public>(&s).unwrap();
 		assert_eq!(k.as_slice(), public.as_slice());
 	}
+
+	#[test]
+	fn bls12_381_fast_aggregate_verify_matches_blst() {
+		use blst::min_pk::{AggregateSignature, SecretKey};
+
+		const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+		let message = [7u8; 32];
+		let secret_keys = (0..4u8)
+			.map(|i| SecretKey::key_gen(&[i + 1; 32], &[]).unwrap())
+			.collect::<Vec<_>>();
+		let public_keys =
+			secret_keys.iter().map(|key| key.sk_to_pk().compress()).collect::<Vec<_>>();
+		let signatures =
+			secret_keys.iter().map(|key| key.sign(&message, DST, &[])).collect::<Vec<_>>();
+		let signature = AggregateSignature::aggregate(&signatures.iter().collect::<Vec<_>>(), true)
+			.unwrap()
+			.to_signature()
+			.compress();
+
+		assert!(bls12_381_fast_aggregate_verify(&public_keys, &message, &signature));
+		// a missing signer, another message or a truncated signature must all be rejected
+		assert!(!bls12_381_fast_aggregate_verify(&public_keys[1..], &message, &signature));
+		assert!(!bls12_381_fast_aggregate_verify(&public_keys, &[8u8; 32], &signature));
+		assert!(!bls12_381_fast_aggregate_verify(&public_keys, &message, &signature[..95]));
+		assert!(!bls12_381_fast_aggregate_verify(&[], &message, &signature));
+	}
 }
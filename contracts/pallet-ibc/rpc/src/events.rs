@@ -1,9 +1,155 @@
 //! Relayer events.
-use ibc::{core::ics04_channel::packet::Packet, events::IbcEvent as RawIbcEvent};
+use ibc::{
+	core::{
+		ics02_client::events::Attributes as ClientAttributes,
+		ics03_connection::events::Attributes as ConnectionAttributes,
+		ics04_channel::{events::Attributes as ChannelAttributes, packet::Packet},
+	},
+	events::IbcEvent as RawIbcEvent,
+};
 use ibc_runtime_api::IbcRuntimeApi;
+use jsonrpsee::tracing::log;
 use pallet_ibc::events::IbcEvent;
 use sc_client_api::HeaderBackend;
+use serde::{Deserialize, Serialize};
 use sp_api::{ApiRef, BlockT, ProvideRuntimeApi};
+use sp_runtime::traits::UniqueSaturatedInto;
+
+/// Filters applied to the events streamed by `ibc_subscribeEvents`.
+/// Unset filters match every event.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+	/// Event types to stream, e.g. `send_packet` or `update_client`
+	#[serde(default)]
+	pub event_types: Vec<String>,
+	/// Port id of the packet or channel, on either end
+	pub port_id: Option<String>,
+	/// Channel id of the packet or channel, on either end
+	pub channel_id: Option<String>,
+	/// Client id of the client or connection, on either end
+	pub client_id: Option<String>,
+}
+
+impl EventFilter {
+	/// Returns true if the event passes all the filters that are set
+	pub fn matches(&self, event: &RawIbcEvent) -> bool {
+		if !self.event_types.is_empty() &&
+			!self.event_types.iter().any(|ty| ty == event.event_type().as_str())
+		{
+			return false
+		}
+		if (self.port_id.is_some() || self.channel_id.is_some()) && !self.matches_channel(event) {
+			return false
+		}
+		if self.client_id.is_some() && !self.matches_client(event) {
+			return false
+		}
+		true
+	}
+
+	fn matches_ids(&self, port_id: &str, channel_id: Option<String>) -> bool {
+		self.port_id.as_ref().map(|id| id == port_id).unwrap_or(true) &&
+			self.channel_id
+				.as_ref()
+				.map(|id| Some(id) == channel_id.as_ref())
+				.unwrap_or(true)
+	}
+
+	fn matches_channel(&self, event: &RawIbcEvent) -> bool {
+		let matches_attributes = |attributes: ChannelAttributes| {
+			self.matches_ids(
+				attributes.port_id.as_str(),
+				attributes.channel_id.map(|id| id.to_string()),
+			) || self.matches_ids(
+				attributes.counterparty_port_id.as_str(),
+				attributes.counterparty_channel_id.map(|id| id.to_string()),
+			)
+		};
+		if let Some(packet) = event.packet() {
+			return self
+				.matches_ids(packet.source_port.as_str(), Some(packet.source_channel.to_string())) ||
+				self.matches_ids(
+					packet.destination_port.as_str(),
+					Some(packet.destination_channel.to_string()),
+				)
+		}
+		match event {
+			RawIbcEvent::OpenInitChannel(ev) => matches_attributes(ev.clone().into()),
+			RawIbcEvent::OpenTryChannel(ev) => matches_attributes(ev.clone().into()),
+			RawIbcEvent::OpenAckChannel(ev) => matches_attributes(ev.clone().into()),
+			RawIbcEvent::OpenConfirmChannel(ev) => matches_attributes(ev.clone().into()),
+			RawIbcEvent::CloseInitChannel(ev) => matches_attributes(ev.clone().into()),
+			RawIbcEvent::CloseConfirmChannel(ev) => matches_attributes(ev.clone().into()),
+			_ => false,
+		}
+	}
+
+	fn matches_client(&self, event: &RawIbcEvent) -> bool {
+		let client_id = match self.client_id.as_ref() {
+			Some(client_id) => client_id,
+			None => return true,
+		};
+		let matches_client =
+			|attributes: &ClientAttributes| attributes.client_id.as_str() == client_id;
+		let matches_connection = |attributes: &ConnectionAttributes| {
+			attributes.client_id.as_str() == client_id ||
+				attributes.counterparty_client_id.as_str() == client_id
+		};
+		match event {
+			RawIbcEvent::CreateClient(ev) => matches_client(&ev.0),
+			RawIbcEvent::UpdateClient(ev) => matches_client(&ev.common),
+			RawIbcEvent::UpgradeClient(ev) => matches_client(&ev.0),
+			RawIbcEvent::ClientMisbehaviour(ev) => matches_client(&ev.0),
+			ev => ev.connection_attributes().map(matches_connection).unwrap_or(false),
+		}
+	}
+}
+
+/// Ibc events deposited in a finalized block
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEvents<Hash> {
+	/// Block hash
+	pub block_hash: Hash,
+	/// Block number
+	pub block_number: u64,
+	/// Events matching the subscription filters
+	pub events: Vec<RawIbcEvent>,
+}
+
+/// Relayer events deposited in a block that match the filter,
+/// returns `None` if there are none.
+pub fn filter_block_events<C, Block, AssetId>(
+	client: &C,
+	block_hash: <Block as BlockT>::Hash,
+	filter: &EventFilter,
+) -> Option<BlockEvents<<Block as BlockT>::Hash>>
+where
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: IbcRuntimeApi<Block, AssetId>,
+	Block: BlockT,
+	AssetId: codec::Codec,
+{
+	let api = client.runtime_api();
+	let events = api
+		.block_events(block_hash, None)
+		.map_err(
+			|e| log::error!(target: "ibc_rpc", "Failed to read events of block {:?}: {:?}", block_hash, e),
+		)
+		.ok()?
+		.into_iter()
+		.filter_map(|event| {
+			filter_map_pallet_event::<C, Block, AssetId>(block_hash, &api, event.ok()?)
+		})
+		.filter(|event| filter.matches(event))
+		.collect::<Vec<_>>();
+	if events.is_empty() {
+		return None
+	}
+	let block_number = client.number(block_hash).ok()??.unique_saturated_into();
+	Some(BlockEvents { block_hash, block_number, events })
+}
 
 /// Filter out none relayer events and modify
 /// Fetch actual packet and acknowledgements from off chain storage and modify packets
@@ -135,3 +281,136 @@ where
 		_ => Some(event),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::{
+		core::{
+			ics02_client::events::CreateClient,
+			ics03_connection::events::OpenInit as ConnectionOpenInit,
+			ics04_channel::events::{OpenInit as ChannelOpenInit, SendPacket},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		},
+		Height,
+	};
+	use std::str::FromStr;
+
+	fn send_packet(source_channel: u64, destination_channel: u64) -> RawIbcEvent {
+		RawIbcEvent::SendPacket(SendPacket {
+			height: Height::new(0, 1),
+			packet: Packet {
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(source_channel),
+				destination_port: PortId::from_str("counterparty").unwrap(),
+				destination_channel: ChannelId::new(destination_channel),
+				..Default::default()
+			},
+		})
+	}
+
+	fn channel_open_init(channel: u64, counterparty_channel: Option<u64>) -> RawIbcEvent {
+		RawIbcEvent::OpenInitChannel(ChannelOpenInit {
+			height: Height::new(0, 1),
+			port_id: PortId::transfer(),
+			channel_id: Some(ChannelId::new(channel)),
+			connection_id: ConnectionId::new(0),
+			counterparty_port_id: PortId::from_str("counterparty").unwrap(),
+			counterparty_channel_id: counterparty_channel.map(ChannelId::new),
+		})
+	}
+
+	fn create_client(client_id: &str) -> RawIbcEvent {
+		RawIbcEvent::CreateClient(CreateClient(ClientAttributes {
+			client_id: ClientId::from_str(client_id).unwrap(),
+			..Default::default()
+		}))
+	}
+
+	fn connection_open_init(client_id: &str, counterparty_client_id: &str) -> RawIbcEvent {
+		RawIbcEvent::OpenInitConnection(ConnectionOpenInit(ConnectionAttributes {
+			client_id: ClientId::from_str(client_id).unwrap(),
+			counterparty_client_id: ClientId::from_str(counterparty_client_id).unwrap(),
+			..Default::default()
+		}))
+	}
+
+	#[test]
+	fn empty_filter_matches_everything() {
+		let filter = EventFilter::default();
+		assert!(filter.matches(&send_packet(0, 1)));
+		assert!(filter.matches(&channel_open_init(0, None)));
+		assert!(filter.matches(&create_client("10-grandpa-0")));
+		assert!(filter.matches(&connection_open_init("10-grandpa-0", "07-tendermint-0")));
+	}
+
+	#[test]
+	fn filters_by_event_type() {
+		let filter = EventFilter {
+			event_types: vec!["send_packet".to_string(), "create_client".to_string()],
+			..Default::default()
+		};
+		assert!(filter.matches(&send_packet(0, 1)));
+		assert!(filter.matches(&create_client("10-grandpa-0")));
+		assert!(!filter.matches(&channel_open_init(0, None)));
+		assert!(!filter.matches(&connection_open_init("10-grandpa-0", "07-tendermint-0")));
+	}
+
+	#[test]
+	fn filters_packets_by_port_and_channel_on_either_end() {
+		let source = EventFilter {
+			port_id: Some("transfer".to_string()),
+			channel_id: Some("channel-0".to_string()),
+			..Default::default()
+		};
+		let destination = EventFilter {
+			port_id: Some("counterparty".to_string()),
+			channel_id: Some("channel-1".to_string()),
+			..Default::default()
+		};
+		assert!(source.matches(&send_packet(0, 1)));
+		assert!(destination.matches(&send_packet(0, 1)));
+		assert!(!source.matches(&send_packet(2, 1)));
+		assert!(!destination.matches(&send_packet(0, 2)));
+
+		// Port and channel must match on the same end
+		let mixed = EventFilter {
+			port_id: Some("transfer".to_string()),
+			channel_id: Some("channel-1".to_string()),
+			..Default::default()
+		};
+		assert!(!mixed.matches(&send_packet(0, 1)));
+	}
+
+	#[test]
+	fn filters_channel_events_by_port_and_channel_on_either_end() {
+		let filter =
+			EventFilter { channel_id: Some("channel-3".to_string()), ..Default::default() };
+		assert!(filter.matches(&channel_open_init(3, None)));
+		assert!(filter.matches(&channel_open_init(0, Some(3))));
+		assert!(!filter.matches(&channel_open_init(0, None)));
+
+		let filter =
+			EventFilter { port_id: Some("counterparty".to_string()), ..Default::default() };
+		assert!(filter.matches(&channel_open_init(0, None)));
+	}
+
+	#[test]
+	fn port_or_channel_filter_rejects_events_without_channels() {
+		let filter = EventFilter { port_id: Some("transfer".to_string()), ..Default::default() };
+		assert!(!filter.matches(&create_client("10-grandpa-0")));
+		assert!(!filter.matches(&connection_open_init("10-grandpa-0", "07-tendermint-0")));
+	}
+
+	#[test]
+	fn filters_by_client_id_on_either_end() {
+		let filter =
+			EventFilter { client_id: Some("07-tendermint-0".to_string()), ..Default::default() };
+		assert!(filter.matches(&create_client("07-tendermint-0")));
+		assert!(!filter.matches(&create_client("10-grandpa-0")));
+		assert!(filter.matches(&connection_open_init("07-tendermint-0", "10-grandpa-0")));
+		assert!(filter.matches(&connection_open_init("10-grandpa-0", "07-tendermint-0")));
+		assert!(!filter.matches(&connection_open_init("10-grandpa-0", "10-grandpa-1")));
+		assert!(!filter.matches(&send_packet(0, 1)));
+	}
+}
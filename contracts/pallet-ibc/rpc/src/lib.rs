@@ -13,7 +13,10 @@ use ibc::{
 };
 use ibc_primitives::PacketInfo as RawPacketInfo;
 use ibc_proto::{
-	cosmos::base::{query::v1beta1::PageResponse, v1beta1::Coin},
+	cosmos::{
+		bank::v1beta1::{DenomUnit, Metadata, QueryDenomMetadataResponse},
+		base::{query::v1beta1::PageResponse, v1beta1::Coin},
+	},
 	ibc::{
 		applications::transfer::v1::{QueryDenomTraceResponse, QueryDenomTracesResponse},
 		core::{
@@ -38,18 +41,19 @@ use jsonrpsee::{
 	core::{Error as RpcError, RpcResult as Result},
 	proc_macros::rpc,
 	tracing::log,
-	types::{error::CallError, ErrorObject},
+	types::{error::CallError, ErrorObject, SubscriptionResult},
+	SubscriptionSink,
 };
 use pallet_ibc::{
 	events::IbcEvent,
 	light_clients::{AnyClientState, AnyConsensusState},
 };
 use sc_chain_spec::Properties;
-use sc_client_api::{BlockBackend, ProofProvider};
+use sc_client_api::{BlockBackend, BlockchainEvents, ProofProvider};
 use serde::{Deserialize, Serialize};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_core::{blake2_256, storage::ChildInfo};
+use sp_core::{blake2_256, storage::ChildInfo, traits::SpawnNamed};
 use sp_runtime::{
 	generic::{BlockId, SignedBlock},
 	traits::{Block as BlockT, Header as HeaderT},
@@ -57,10 +61,14 @@ use sp_runtime::{
 use std::{collections::HashMap, fmt::Display, str::FromStr, sync::Arc};
 use tendermint_proto::Protobuf;
 pub mod events;
-use events::filter_map_pallet_event;
+use events::{filter_block_events, filter_map_pallet_event, BlockEvents, EventFilter};
+use futures::{channel::mpsc, future, stream, FutureExt, StreamExt};
 use ibc_proto::ibc::core::channel::v1::IdentifiedChannel;
 use pallet_ibc::errors::IbcError;
 
+/// Maximum number of finality notifications buffered for an `ibc_subscribeEvents` subscriber
+const MAX_PENDING_FINALITY_NOTIFICATIONS: usize = 256;
+
 /// Connection handshake proof
 #[derive(Serialize, Deserialize)]
 pub struct ConnHandshakeProof {
@@ -418,6 +426,14 @@ where
 		count_total: bool,
 	) -> Result<QueryDenomTracesResponse>;
 
+	/// Query the human readable metadata registered for the ibc denom of an asset Id
+	#[method(name = "ibc_queryDenomMetadata")]
+	fn query_denom_metadata(&self, asset_id: AssetId) -> Result<QueryDenomMetadataResponse>;
+
+	/// Query the total amount of each denom recorded as escrowed on a transfer channel
+	#[method(name = "ibc_queryTotalEscrow")]
+	fn query_total_escrow(&self, channel_id: String) -> Result<Vec<Coin>>;
+
 	/// Query newly created client in block and extrinsic
 	#[method(name = "ibc_queryNewlyCreatedClient")]
 	fn query_newly_created_client(
@@ -449,6 +465,15 @@ where
 		&self,
 		block_numbers: Vec<BlockNumberOrHash<Hash>>,
 	) -> Result<HashMap<String, Vec<RawIbcEvent>>>;
+
+	/// Subscribe to Ibc Events deposited in finalized blocks
+	/// Only blocks with events matching the filter are streamed
+	#[subscription(
+		name = "ibc_subscribeEvents" => "ibc_events",
+		unsubscribe = "ibc_unsubscribeEvents",
+		item = BlockEvents<Hash>
+	)]
+	fn subscribe_events(&self, filter: Option<EventFilter>);
 }
 
 /// Converts a runtime trap into an RPC error.
@@ -465,13 +490,15 @@ pub struct IbcRpcHandler<C, B, AssetId> {
 	client: Arc<C>,
 	/// A copy of the chain properties.
 	pub chain_props: Properties,
+	/// Executor for subscription tasks
+	executor: Arc<dyn SpawnNamed>,
 	_marker: std::marker::PhantomData<(B, AssetId)>,
 }
 
 impl<C, B, AssetId> IbcRpcHandler<C, B, AssetId> {
 	/// Create new `IbcRpcHandler` with the given reference to the client.
-	pub fn new(client: Arc<C>, chain_props: Properties) -> Self {
-		Self { client, chain_props, _marker: Default::default() }
+	pub fn new(client: Arc<C>, chain_props: Properties, executor: Arc<dyn SpawnNamed>) -> Self {
+		Self { client, chain_props, executor, _marker: Default::default() }
 	}
 }
 
@@ -486,7 +513,8 @@ where
 		+ ProvideRuntimeApi<Block>
 		+ HeaderBackend<Block>
 		+ ProofProvider<Block>
-		+ BlockBackend<Block>,
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>,
 	C::Api: IbcRuntimeApi<Block, AssetId>,
 	AssetId: codec::Codec + Copy + Send + Sync + 'static,
 {
@@ -1636,6 +1664,69 @@ where
 		})
 	}
 
+	fn query_denom_metadata(&self, asset_id: AssetId) -> Result<QueryDenomMetadataResponse> {
+		let api = self.client.runtime_api();
+		let block_hash = self.client.info().best_hash;
+
+		let result = api.denom_metadata(block_hash, asset_id).ok().flatten().ok_or_else(|| {
+			runtime_error_into_rpc_error(
+				"[ibc_rpc]: Could not find denom metadata for asset id provided",
+			)
+		})?;
+
+		let to_string = |bytes: Vec<u8>| {
+			String::from_utf8(bytes).map_err(|_| {
+				runtime_error_into_rpc_error(
+					"[ibc_rpc]: Could not decode denom metadata into a valid string",
+				)
+			})
+		};
+		let denom = to_string(result.denom)?;
+		let base_denom = to_string(result.base_denom)?;
+		let trace_path = to_string(result.trace_path)?;
+		let symbol = to_string(result.symbol)?;
+
+		Ok(QueryDenomMetadataResponse {
+			metadata: Some(Metadata {
+				description: format!("IBC token {} from {}", base_denom, trace_path),
+				denom_units: vec![
+					DenomUnit { denom: denom.clone(), exponent: 0, aliases: vec![] },
+					DenomUnit {
+						denom: symbol.clone(),
+						exponent: result.decimals as u32,
+						aliases: vec![],
+					},
+				],
+				base: denom,
+				display: symbol.clone(),
+				name: to_string(result.name)?,
+				symbol,
+				uri: Default::default(),
+				uri_hash: Default::default(),
+			}),
+		})
+	}
+
+	fn query_total_escrow(&self, channel_id: String) -> Result<Vec<Coin>> {
+		let api = self.client.runtime_api();
+		let block_hash = self.client.info().best_hash;
+
+		let escrowed = api
+			.total_escrow(block_hash, channel_id.as_bytes().to_vec())
+			.map_err(|_| runtime_error_into_rpc_error("[ibc_rpc]: Failed to query total escrow"))?;
+		escrowed
+			.into_iter()
+			.map(|(denom, amount)| {
+				let denom = String::from_utf8(denom).map_err(|_| {
+					runtime_error_into_rpc_error(
+						"[ibc_rpc]: Could not decode ibc denom into a valid string",
+					)
+				})?;
+				Ok(Coin { denom, amount: amount.to_string() })
+			})
+			.collect()
+	}
+
 	fn query_newly_created_client(
 		&self,
 		block_hash: Block::Hash,
@@ -1766,6 +1857,53 @@ where
 		}
 		Ok(events)
 	}
+
+	fn subscribe_events(
+		&self,
+		mut sink: SubscriptionSink,
+		filter: Option<EventFilter>,
+	) -> SubscriptionResult {
+		let client = self.client.clone();
+		let filter = filter.unwrap_or_default();
+		// Finality notifications arrive on an unbounded channel, so they are drained eagerly into
+		// a bounded one. A subscriber that falls more than `MAX_PENDING_FINALITY_NOTIFICATIONS`
+		// blocks behind is disconnected instead of growing the backlog without limit.
+		let (mut tx, rx) = mpsc::channel(MAX_PENDING_FINALITY_NOTIFICATIONS);
+		let mut notifications = self.client.finality_notification_stream();
+		let forward = async move {
+			while let Some(notification) = notifications.next().await {
+				if let Err(e) = tx.try_send(notification) {
+					if e.is_full() {
+						log::warn!(
+							target: "ibc_rpc",
+							"Closing ibc events subscription, subscriber is lagging behind finality"
+						);
+					}
+					break
+				}
+			}
+		};
+		let stream = rx
+			.flat_map(|notification| {
+				// Blocks finalized implicitly by this notification come first
+				let mut hashes = notification.tree_route.to_vec();
+				hashes.push(notification.hash);
+				stream::iter(hashes)
+			})
+			.filter_map(move |block_hash| {
+				future::ready(filter_block_events::<C, Block, AssetId>(
+					&client, block_hash, &filter,
+				))
+			});
+
+		let fut = async move {
+			sink.pipe_from_stream(stream).await;
+		};
+		self.executor
+			.spawn("ibc-rpc-subscription-forward", Some("rpc"), forward.boxed());
+		self.executor.spawn("ibc-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
 }
 
 impl<C, Block, AssetId> IbcRpcHandler<C, Block, AssetId>
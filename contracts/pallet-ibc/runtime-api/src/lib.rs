@@ -77,6 +77,12 @@ sp_api::decl_runtime_apis! {
 		/// Key is the asset id from which to start looking up results
 		fn denom_traces(key: Option<AssetId>, offset: Option<u32>, limit: u64, count_total: bool) -> QueryDenomTracesResponse;
 
+		/// Returns the human readable metadata registered for the ibc denom of this asset
+		fn denom_metadata(asset_id: AssetId) -> Option<QueryDenomMetadataResponse>;
+
+		/// Returns the total amount of each denom recorded as escrowed on this channel
+		fn total_escrow(channel_id: Vec<u8>) -> Vec<(Vec<u8>, u128)>;
+
 		fn block_events(extrinsic_index: Option<u32>) -> Vec<Result<pallet_ibc::events::IbcEvent, pallet_ibc::errors::IbcError>>;
 	}
 }
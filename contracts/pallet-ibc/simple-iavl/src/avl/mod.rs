@@ -1,11 +1,10 @@
 //! # AVL Tree
 //!
-//! This module hosts a simple implementation of an AVL Merkle Tree that support the `get` and
-//! `insert` instructions (no delete yet, it's not needed as the on-chain store is supposed to be
-//! immutable).
+//! This module hosts a simple implementation of an AVL Merkle Tree that support the `get`,
+//! `insert` and `remove` instructions.
 //!
-//! Proof of existence are supported using [ICS23](https://github.com/cosmos/ics23), but proof of
-//! non-existence are not yet implemented.
+//! Proofs of existence and non-existence, as well as batches of both, are supported using
+//! [ICS23](https://github.com/cosmos/ics23).
 //!
 //! Keys needs to implement `Ord` and `AsBytes` (see `as_bytes` module), while values are required
 //! to implement `Borrow<[u8]>`.
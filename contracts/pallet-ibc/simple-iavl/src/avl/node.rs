@@ -4,7 +4,11 @@ use core::{borrow::Borrow, mem};
 use sha2::{Digest, Sha256};
 use tendermint::hash::Hash;
 
-use crate::avl::{as_bytes::AsBytes, proof::LEAF_PREFIX, HASH_ALGO};
+use crate::avl::{
+	as_bytes::AsBytes,
+	proof::{EMPTY_CHILD, LEAF_PREFIX},
+	HASH_ALGO,
+};
 
 pub type NodeRef<T, V> = Option<Box<AvlNode<T, V>>>;
 
@@ -39,7 +43,11 @@ where
 		sha.update(key.as_bytes().as_ref());
 		sha.update(value.borrow());
 		let hash = sha.finalize();
-		let merkle_hash = Hash::from_bytes(HASH_ALGO, &Sha256::digest(&hash)).unwrap();
+		let mut sha = Sha256::new();
+		sha.update(EMPTY_CHILD);
+		sha.update(&hash);
+		sha.update(EMPTY_CHILD);
+		let merkle_hash = Hash::from_bytes(HASH_ALGO, &sha.finalize()).unwrap();
 		let hash = Hash::from_bytes(HASH_ALGO, &hash).unwrap();
 
 		AvlNode { key, value, hash, merkle_hash, height: 0, left: None, right: None }
@@ -99,15 +107,12 @@ where
 	}
 
 	/// Update the node's merkle hash by looking at the hashes of its two children.
+	/// Missing children are replaced by `EMPTY_CHILD`.
 	fn update_hashes(&mut self) {
 		let mut sha = Sha256::new();
-		if let Some(left) = &self.left {
-			sha.update(&left.merkle_hash.as_bytes());
-		}
-		sha.update(&self.hash.as_bytes());
-		if let Some(right) = &self.right {
-			sha.update(right.merkle_hash.as_bytes())
-		}
+		sha.update(self.left_hash().unwrap_or(&EMPTY_CHILD));
+		sha.update(self.hash.as_bytes());
+		sha.update(self.right_hash().unwrap_or(&EMPTY_CHILD));
 		self.merkle_hash = Hash::from_bytes(HASH_ALGO, sha.finalize().as_slice()).unwrap();
 	}
 
@@ -1,12 +1,16 @@
 //! # ICS23 Proof
 //!
-//! This module provides the ICS23 proof spec, which can be used to verify the existence of a value
-//! in the AVL Tree.
+//! This module provides the ICS23 proof spec, which can be used to verify the existence or
+//! non-existence of a value in the AVL Tree.
 use alloc::vec;
 use ics23::{HashOp, InnerSpec, LeafOp, LengthOp, ProofSpec};
 
 pub const LEAF_PREFIX: [u8; 64] = [0; 64]; // 64 bytes of zeroes.
 
+/// Placeholder hashed in place of a missing child, so that every inner node commits to exactly
+/// three children of `child_size` bytes, as required by ICS23 neighbour checks.
+pub const EMPTY_CHILD: [u8; 32] = [0; 32];
+
 #[allow(dead_code)]
 /// Return the `ProofSpec` of tendermock AVL Tree.
 pub fn get_proof_spec() -> ProofSpec {
@@ -22,8 +26,8 @@ pub fn get_proof_spec() -> ProofSpec {
 			child_order: vec![0, 1, 2],
 			child_size: 32,
 			min_prefix_length: 0,
-			max_prefix_length: 64,
-			empty_child: vec![0, 32],
+			max_prefix_length: 0,
+			empty_child: EMPTY_CHILD.to_vec(),
 			hash: HashOp::Sha256.into(),
 		}),
 		max_depth: 0,
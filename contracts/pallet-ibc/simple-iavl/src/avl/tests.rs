@@ -1,6 +1,12 @@
 //! # Test suite of tendermock AVL Tree.
 
-use ics23::{commitment_proof::Proof, verify_membership, HostFunctionsManager};
+use std::collections::BTreeMap;
+
+use ics23::{
+	commitment_proof::Proof, verify_batch_membership, verify_batch_non_membership,
+	verify_membership, verify_non_membership, HostFunctionsManager,
+};
+use proptest::prelude::*;
 use sha2::{Digest, Sha256};
 
 use crate::avl::{
@@ -149,6 +155,145 @@ fn integration() {
 	assert!(verify_membership::<HostFunctionsManager>(&proof, &spec, &root, "K".as_bytes(), &[0]));
 }
 
+#[test]
+fn remove() {
+	let data = [42];
+	let mut tree = AvlTree::new();
+	tree.insert([1], data);
+	tree.insert([0], data);
+	tree.insert([2], data);
+	let target = AvlTree { root: build_node([2], data, as_node_ref([0], data), None) };
+	assert_eq!(tree.remove(&[1]), Some(data));
+	assert_eq!(tree.remove(&[1]), None);
+	assert_eq!(tree, target);
+	assert_eq!(tree.get(&[1]), None);
+
+	assert_eq!(tree.remove(&[0]), Some(data));
+	assert_eq!(tree.remove(&[2]), Some(data));
+	assert_eq!(tree.root, None);
+}
+
+#[test]
+fn remove_rebalances() {
+	let mut tree = AvlTree::new();
+	for key in 0..64u8 {
+		tree.insert(vec![key], [key]);
+	}
+	for key in (0..64u8).filter(|key| key % 3 != 0) {
+		assert_eq!(tree.remove(&[key][..]), Some([key]));
+		assert!(check_integrity(&tree.root));
+	}
+	let keys = tree.get_keys().into_iter().cloned().collect::<Vec<_>>();
+	assert_eq!(keys, (0..64u8).filter(|key| key % 3 == 0).map(|key| vec![key]).collect::<Vec<_>>());
+}
+
+#[test]
+fn non_existence_proof() {
+	let mut tree = AvlTree::new();
+	tree.insert("B", [0]);
+	tree.insert("D", [0]);
+	tree.insert("F", [0]);
+	tree.insert("H", [0]);
+	let root = tree.root_hash().expect("Unable to retrieve root hash").as_bytes().to_vec();
+	let spec = get_proof_spec();
+
+	assert!(tree.get_non_existence_proof("D").is_none());
+	for key in ["A", "C", "E", "G", "Z"] {
+		let proof = tree.get_non_existence_proof(key).expect("Unable to retrieve proof");
+		match proof.proof.as_ref().unwrap() {
+			Proof::Nonexist(_) => {},
+			_ => panic!("Should return a non-existence proof"),
+		}
+		assert!(verify_non_membership::<HostFunctionsManager>(
+			&proof,
+			&spec,
+			&root,
+			key.as_bytes()
+		));
+	}
+
+	// A proof of non-existence must not be accepted for a neighbouring key.
+	let proof = tree.get_non_existence_proof("C").unwrap();
+	assert!(!verify_non_membership::<HostFunctionsManager>(&proof, &spec, &root, "E".as_bytes()));
+}
+
+#[test]
+fn batch_proof() {
+	let mut tree = AvlTree::new();
+	for key in ["M", "N", "O", "L", "K", "Q", "P", "H", "I", "A"] {
+		tree.insert(key, [0]);
+	}
+	let root = tree.root_hash().expect("Unable to retrieve root hash").as_bytes().to_vec();
+	let spec = get_proof_spec();
+
+	let proof = tree.get_batch_proof(&["A", "B", "K", "Z"]).expect("Unable to retrieve proof");
+	let items = BTreeMap::from([("A".as_bytes(), &[0][..]), ("K".as_bytes(), &[0][..])]);
+	assert!(verify_batch_membership::<HostFunctionsManager>(&proof, &spec, &root, items));
+	assert!(verify_batch_non_membership::<HostFunctionsManager>(
+		&proof,
+		&spec,
+		&root,
+		&["B".as_bytes(), "Z".as_bytes()]
+	));
+
+	assert!(AvlTree::<&str, [u8; 1]>::new().get_batch_proof(&["A"]).is_none());
+}
+
+proptest! {
+	#[test]
+	fn proofs_verify_after_inserts_and_removals(
+		keys in prop::collection::btree_set(prop::collection::vec(any::<u8>(), 1..4), 1..64),
+		removed in prop::collection::vec(any::<prop::sample::Index>(), 0..32),
+		absent in prop::collection::vec(prop::collection::vec(any::<u8>(), 1..4), 1..16),
+	) {
+		let keys = keys.into_iter().collect::<Vec<_>>();
+		let mut tree = AvlTree::new();
+		for key in &keys {
+			tree.insert(key.clone(), key.clone());
+		}
+		for index in &removed {
+			let key = index.get(&keys);
+			tree.remove(&key[..]);
+		}
+		prop_assert!(check_integrity(&tree.root));
+
+		let root = match tree.root_hash() {
+			Some(root) => root.as_bytes().to_vec(),
+			None => return Ok(()),
+		};
+		let spec = get_proof_spec();
+		for key in keys.iter().chain(absent.iter()) {
+			match tree.get(&key[..]) {
+				Some(value) => {
+					let proof = tree.get_proof(&key[..]).unwrap();
+					prop_assert!(verify_membership::<HostFunctionsManager>(
+						&proof, &spec, &root, key, value
+					));
+				},
+				None => {
+					let proof = tree.get_non_existence_proof(&key[..]).unwrap();
+					prop_assert!(verify_non_membership::<HostFunctionsManager>(
+						&proof, &spec, &root, key
+					));
+				},
+			}
+		}
+
+		let queried = keys.iter().chain(absent.iter()).map(|key| &key[..]).collect::<Vec<_>>();
+		let proof = tree.get_batch_proof(&queried).unwrap();
+		let (present, missing): (Vec<&[u8]>, Vec<&[u8]>) =
+			queried.iter().copied().partition(|key| tree.get(*key).is_some());
+		let items = present
+			.into_iter()
+			.map(|key| (key, &tree.get(key).unwrap()[..]))
+			.collect::<BTreeMap<_, _>>();
+		prop_assert!(verify_batch_membership::<HostFunctionsManager>(&proof, &spec, &root, items));
+		prop_assert!(verify_batch_non_membership::<HostFunctionsManager>(
+			&proof, &spec, &root, &missing
+		));
+	}
+}
+
 /// Check that nodes are ordered, heights are correct and that balance factors are in {-1, 0, 1}.
 fn check_integrity<T: Ord, V>(node_ref: &NodeRef<T, V>) -> bool {
 	if let Some(node) = node_ref {
@@ -8,20 +8,21 @@ use core::{
 	},
 };
 
-use alloc::{borrow::ToOwned, vec, vec::Vec};
+use alloc::{borrow::ToOwned, boxed::Box, vec, vec::Vec};
 use ics23::{
-	commitment_proof::Proof, CommitmentProof, ExistenceProof, HashOp, InnerOp, LeafOp, LengthOp,
+	batch_entry, commitment_proof::Proof, BatchEntry, BatchProof, CommitmentProof, ExistenceProof,
+	HashOp, InnerOp, LeafOp, LengthOp, NonExistenceProof,
 };
 use tendermint::hash::Hash;
 
 use crate::avl::{
-	node::{as_node_ref, NodeRef},
-	proof::LEAF_PREFIX,
+	node::{as_node_ref, AvlNode, NodeRef},
+	proof::{EMPTY_CHILD, LEAF_PREFIX},
 	AsBytes,
 };
 
-/// An AVL Tree that supports `get` and `insert` operation and can be used to prove existence of a
-/// given key-value couple.
+/// An AVL Tree that supports `get`, `insert` and `remove` operations and can be used to prove
+/// existence or non-existence of a given key-value couple.
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct AvlTree<K: Ord + AsBytes, V> {
 	pub root: NodeRef<K, V>,
@@ -82,6 +83,71 @@ where
 		}
 	}
 
+	/// Remove a key from the AVL tree, returning its value if it was present. The tree is
+	/// rebalanced on the way up, this operation runs in O(log(n)).
+	pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
+	where
+		K: Borrow<Q>,
+		Q: Ord,
+	{
+		AvlTree::remove_rec(&mut self.root, key)
+	}
+
+	/// Remove a key from the tree.
+	fn remove_rec<Q: ?Sized>(node_ref: &mut NodeRef<K, V>, key: &Q) -> Option<V>
+	where
+		K: Borrow<Q>,
+		Q: Ord,
+	{
+		let ordering = node_ref.as_ref()?.key.borrow().cmp(key);
+		let removed = match ordering {
+			Ordering::Greater => {
+				let node = node_ref.as_mut().expect("[AVL]: Empty node in node removal");
+				AvlTree::remove_rec(&mut node.left, key)?
+			},
+			Ordering::Less => {
+				let node = node_ref.as_mut().expect("[AVL]: Empty node in node removal");
+				AvlTree::remove_rec(&mut node.right, key)?
+			},
+			Ordering::Equal => {
+				let mut node = node_ref.take().expect("[AVL]: Empty node in node removal");
+				*node_ref = match (node.left.take(), node.right.take()) {
+					(None, None) => None,
+					(Some(child), None) | (None, Some(child)) => Some(child),
+					(left, mut right) => {
+						// Replace the removed node by its in-order successor.
+						let mut successor = AvlTree::remove_min(&mut right);
+						successor.left = left;
+						successor.right = right;
+						Some(successor)
+					},
+				};
+				if node_ref.is_none() {
+					return Some(node.value)
+				}
+				node.value
+			},
+		};
+		node_ref.as_mut().expect("[AVL]: Empty node in node removal").update();
+		AvlTree::balance_node(node_ref);
+		Some(removed)
+	}
+
+	/// Detach the node holding the smallest key of a non-empty subtree, rebalancing the subtree.
+	fn remove_min(node_ref: &mut NodeRef<K, V>) -> Box<AvlNode<K, V>> {
+		let node = node_ref.as_mut().expect("[AVL]: Empty node in min removal");
+		if node.left.is_some() {
+			let min = AvlTree::remove_min(&mut node.left);
+			node.update();
+			AvlTree::balance_node(node_ref);
+			min
+		} else {
+			let mut min = node_ref.take().expect("[AVL]: Empty node in min removal");
+			*node_ref = min.right.take();
+			min
+		}
+	}
+
 	#[allow(dead_code)]
 	/// Return an existence proof for the given element, if it exists.
 	pub fn get_proof<Q: ?Sized>(&self, key: &Q) -> Option<CommitmentProof>
@@ -93,6 +159,80 @@ where
 		Some(CommitmentProof { proof: Some(Proof::Exist(proof)) })
 	}
 
+	/// Return a non-existence proof for the given key, if it is absent from a non-empty tree.
+	///
+	/// The proof is made of the existence proofs of the closest keys on each side of the missing
+	/// key.
+	pub fn get_non_existence_proof<Q: ?Sized>(&self, key: &Q) -> Option<CommitmentProof>
+	where
+		K: Borrow<Q>,
+		Q: Ord + AsBytes,
+	{
+		let proof = self.get_non_existence_proof_inner(key)?;
+		Some(CommitmentProof { proof: Some(Proof::Nonexist(proof)) })
+	}
+
+	/// Return a batch proof covering all the given keys: an existence proof for the keys present
+	/// in the tree and a non-existence proof for the others. Returns `None` if the tree is empty.
+	pub fn get_batch_proof<Q: ?Sized>(&self, keys: &[&Q]) -> Option<CommitmentProof>
+	where
+		K: Borrow<Q>,
+		Q: Ord + AsBytes,
+	{
+		let entries = keys
+			.iter()
+			.map(|key| {
+				let proof = match self.get_proof_rec(*key, &self.root) {
+					Some(proof) => batch_entry::Proof::Exist(proof),
+					None => batch_entry::Proof::Nonexist(self.get_non_existence_proof_inner(*key)?),
+				};
+				Some(BatchEntry { proof: Some(proof) })
+			})
+			.collect::<Option<Vec<_>>>()?;
+		Some(CommitmentProof { proof: Some(Proof::Batch(BatchProof { entries })) })
+	}
+
+	/// Build a proof of non-existence from the existence proofs of the key's neighbours.
+	fn get_non_existence_proof_inner<Q: ?Sized>(&self, key: &Q) -> Option<NonExistenceProof>
+	where
+		K: Borrow<Q>,
+		Q: Ord + AsBytes,
+	{
+		let (left, right) = self.get_neighbours(key)?;
+		if left.is_none() && right.is_none() {
+			return None
+		}
+		let left = left.and_then(|left| self.get_proof_rec::<Q>(left.borrow(), &self.root));
+		let right = right.and_then(|right| self.get_proof_rec::<Q>(right.borrow(), &self.root));
+		Some(NonExistenceProof { key: key.as_bytes().as_ref().to_owned(), left, right })
+	}
+
+	/// Return the closest keys strictly smaller and strictly greater than the given key, or `None`
+	/// if the key is present in the tree.
+	fn get_neighbours<Q: ?Sized>(&self, key: &Q) -> Option<(Option<&K>, Option<&K>)>
+	where
+		K: Borrow<Q>,
+		Q: Ord,
+	{
+		let mut left = None;
+		let mut right = None;
+		let mut node_ref = &self.root;
+		while let Some(ref node) = node_ref {
+			match node.key.borrow().cmp(key) {
+				Ordering::Greater => {
+					right = Some(&node.key);
+					node_ref = &node.left;
+				},
+				Ordering::Less => {
+					left = Some(&node.key);
+					node_ref = &node.right;
+				},
+				Ordering::Equal => return None,
+			}
+		}
+		Some((left, right))
+	}
+
 	/// Recursively build a proof of existence for the desired value.
 	fn get_proof_rec<Q: ?Sized>(&self, key: &Q, node: &NodeRef<K, V>) -> Option<ExistenceProof>
 	where
@@ -100,21 +240,20 @@ where
 		Q: Ord,
 	{
 		if let Some(node) = node {
-			let empty_hash = [];
 			let (mut proof, prefix, suffix) = match node.key.borrow().cmp(key) {
 				Ordering::Greater => {
 					let proof = self.get_proof_rec(key, &node.left)?;
 					let prefix = vec![];
 					let mut suffix = Vec::with_capacity(64);
 					suffix.extend(node.hash.as_bytes());
-					suffix.extend(node.right_hash().unwrap_or(&empty_hash));
+					suffix.extend(node.right_hash().unwrap_or(&EMPTY_CHILD));
 					(proof, prefix, suffix)
 				},
 				Ordering::Less => {
 					let proof = self.get_proof_rec(key, &node.right)?;
 					let suffix = vec![];
 					let mut prefix = Vec::with_capacity(64);
-					prefix.extend(node.left_hash().unwrap_or(&empty_hash));
+					prefix.extend(node.left_hash().unwrap_or(&EMPTY_CHILD));
 					prefix.extend(node.hash.as_bytes());
 					(proof, prefix, suffix)
 				},
@@ -132,8 +271,8 @@ where
 						leaf,
 						path: vec![],
 					};
-					let prefix = node.left_hash().unwrap_or(&empty_hash).to_vec();
-					let suffix = node.right_hash().unwrap_or(&empty_hash).to_vec();
+					let prefix = node.left_hash().unwrap_or(&EMPTY_CHILD).to_vec();
+					let suffix = node.right_hash().unwrap_or(&EMPTY_CHILD).to_vec();
 					(proof, prefix, suffix)
 				},
 			};
@@ -146,18 +285,21 @@ where
 	}
 
 	/// Rebalance the AVL tree by performing rotations, if needed.
+	///
+	/// A child with a balance factor of zero (which can only happen after a removal) only requires
+	/// a single rotation.
 	fn balance_node(node_ref: &mut NodeRef<K, V>) {
 		let node = node_ref.as_mut().expect("[AVL]: Empty node in node balance");
 		let balance_factor = node.balance_factor();
 		if balance_factor >= 2 {
 			let left = node.left.as_mut().expect("[AVL]: Unexpected empty left node");
-			if left.balance_factor() < 1 {
+			if left.balance_factor() < 0 {
 				AvlTree::rotate_left(&mut node.left);
 			}
 			AvlTree::rotate_right(node_ref);
 		} else if balance_factor <= -2 {
 			let right = node.right.as_mut().expect("[AVL]: Unexpected empty right node");
-			if right.balance_factor() > -1 {
+			if right.balance_factor() > 0 {
 				AvlTree::rotate_right(&mut node.right);
 			}
 			AvlTree::rotate_left(node_ref);
@@ -0,0 +1,194 @@
+use crate::{
+	benchmarks::grandpa_benchmark_utils::{
+		timestamp_extrinsic_with_proof, GRANDPA_UPDATE_TIMESTAMP,
+	},
+	light_clients::{AnyClientMessage, HostFunctionsManager},
+};
+use alloc::format;
+use beefy_client_primitives::{
+	BeefyNextAuthoritySet, MerkleHasher, MmrUpdateProof, NodesUtils, PartialMmrLeaf,
+	SignatureWithAuthorityIndex, SignedCommitment,
+};
+use beefy_mmr::BeefyEcdsaToEthereum;
+use beefy_primitives::{
+	crypto::AuthorityId,
+	known_payloads::MMR_ROOT_ID,
+	mmr::{MmrLeaf, MmrLeafVersion},
+	Commitment, Payload, KEY_TYPE,
+};
+use codec::Encode;
+use ibc::timestamp::Timestamp;
+use ics11_beefy::{
+	client_message::{BeefyHeader, ClientMessage, ParachainHeader, ParachainHeadersWithProof},
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+};
+use mmr_lib::util::MemMMR;
+use pallet_mmr_primitives::Proof;
+use sp_core::H256;
+use sp_io::hashing::keccak_256;
+use sp_runtime::{
+	traits::{BlakeTwo256, Convert},
+	Digest, DigestItem, SaturatedConversion,
+};
+use sp_std::prelude::*;
+
+/// Builds a beefy client message that contains the requested number of authority signatures and
+/// parachain headers, proven in an mmr of `2^mmr_proof_items` leaves, so that the proof of its
+/// latest leaf has `mmr_proof_items` items. The parachain headers are the last leaves of the mmr,
+/// so there can't be more than `2^mmr_proof_items` of them. The digest of the first parachain
+/// header is padded with `padding` bytes, which the client decodes and hashes.
+pub fn generate_beefy_update(
+	signatures: u32,
+	mmr_proof_items: u32,
+	parachain_headers: u32,
+	padding: u32,
+) -> (ClientState<HostFunctionsManager>, ConsensusState, AnyClientMessage) {
+	let para_id = 2000u32;
+	let latest_para_height = 1u32;
+	let latest_beefy_height = 1u32;
+	let set_id = 1;
+
+	// Build the authority set, the authorities are committed to by the merkle root of the keccak
+	// hashes of their ethereum addresses.
+	let authorities = (0..signatures)
+		.map(|i| {
+			sp_io::crypto::ecdsa_generate(KEY_TYPE, Some(format!("//{}", i).as_bytes().to_vec()))
+		})
+		.collect::<Vec<_>>();
+	let authority_leaves = authorities
+		.iter()
+		.map(|public_key| {
+			keccak_256(&BeefyEcdsaToEthereum::convert(AuthorityId::from(public_key.clone())))
+		})
+		.collect::<Vec<_>>();
+	let authority_tree =
+		rs_merkle::MerkleTree::<MerkleHasher<HostFunctionsManager>>::from_leaves(&authority_leaves);
+	let authority_set = BeefyNextAuthoritySet {
+		id: set_id,
+		len: signatures,
+		root: H256::from(authority_tree.root().unwrap()),
+	};
+	let next_authority_set = BeefyNextAuthoritySet { id: set_id + 1, ..authority_set.clone() };
+
+	// Build the parachain headers, each one is the only parachain head of its mmr leaf.
+	let (timestamp_extrinsic, extrinsics_root, extrinsic_proof) = timestamp_extrinsic_with_proof();
+	let mmr_leaves = (1u32 << mmr_proof_items)
+		.checked_sub(parachain_headers)
+		.expect("The mmr must have room for the parachain headers");
+	let mut mmr = MemMMR::<H256, MerkleHasher<HostFunctionsManager>>::default();
+	for i in 0..mmr_leaves {
+		mmr.push(H256::from(keccak_256(&i.encode()))).unwrap();
+	}
+	let mut headers = vec![];
+	let mut mmr_leaf = None;
+	let mut positions = vec![];
+	let mut leaf_indices = vec![];
+	for i in 0..parachain_headers {
+		let mut digest = Digest::default();
+		if i == 0 {
+			digest.push(DigestItem::Other(vec![0u8; padding as usize]));
+		}
+		let parachain_header = sp_runtime::generic::Header::<u32, BlakeTwo256> {
+			parent_hash: Default::default(),
+			number: latest_para_height + 1 + i,
+			state_root: Default::default(),
+			extrinsics_root,
+			digest,
+		};
+		let heads_root = keccak_256(&(para_id, parachain_header.encode()).encode());
+		let partial_mmr_leaf = PartialMmrLeaf {
+			version: MmrLeafVersion::new(0, 0),
+			parent_number_and_hash: (latest_beefy_height + i, H256::zero()),
+			beefy_next_authority_set: next_authority_set.clone(),
+		};
+		let leaf = MmrLeaf {
+			version: partial_mmr_leaf.version.clone(),
+			parent_number_and_hash: partial_mmr_leaf.parent_number_and_hash,
+			beefy_next_authority_set: partial_mmr_leaf.beefy_next_authority_set.clone(),
+			leaf_extra: H256::from(heads_root),
+		};
+		positions.push(mmr.push(H256::from(keccak_256(&leaf.encode()))).unwrap());
+		leaf_indices.push((mmr_leaves + i) as u64);
+		mmr_leaf = Some(leaf);
+		headers.push(ParachainHeader {
+			parachain_header,
+			partial_mmr_leaf,
+			parachain_heads_proof: vec![],
+			heads_leaf_index: 0,
+			heads_total_count: 1,
+			extrinsic_proof: extrinsic_proof.clone(),
+			timestamp_extrinsic: timestamp_extrinsic.clone(),
+		});
+	}
+	let latest_mmr_leaf = mmr_leaf.expect("At least one parachain header is required");
+	let leaf_count = (mmr_leaves + parachain_headers) as u64;
+	let mmr_root = mmr.get_root().unwrap();
+	let latest_leaf_proof = mmr.gen_proof(vec![*positions.last().unwrap()]).unwrap();
+	let headers_proof = mmr.gen_proof(positions).unwrap();
+
+	// Sign the commitment to the mmr root with every authority
+	let commitment = Commitment {
+		payload: Payload::from_single_entry(MMR_ROOT_ID, mmr_root.encode()),
+		block_number: latest_beefy_height + parachain_headers,
+		validator_set_id: set_id,
+	};
+	let commitment_hash = keccak_256(&commitment.encode());
+	let signatures = authorities
+		.iter()
+		.enumerate()
+		.map(|(index, public_key)| SignatureWithAuthorityIndex {
+			signature: sp_io::crypto::ecdsa_sign_prehashed(KEY_TYPE, public_key, &commitment_hash)
+				.unwrap()
+				.0,
+			index: index as u32,
+		})
+		.collect::<Vec<_>>();
+	let authority_indices = (0..authorities.len()).collect::<Vec<_>>();
+
+	let mmr_update_proof = MmrUpdateProof {
+		signed_commitment: SignedCommitment { commitment, signatures },
+		latest_mmr_leaf,
+		mmr_proof: Proof {
+			leaf_indices: vec![leaf_count - 1],
+			leaf_count,
+			items: latest_leaf_proof.proof_items().to_vec(),
+		},
+		authority_proof: authority_tree.proof(&authority_indices).proof_hashes().to_vec(),
+	};
+	let headers_with_proof = ParachainHeadersWithProof {
+		headers,
+		mmr_proofs: headers_proof.proof_items().iter().map(|item| item.encode()).collect(),
+		mmr_size: NodesUtils::new(leaf_count).size(),
+		leaf_indices,
+		leaf_count,
+	};
+	let client_message = AnyClientMessage::Beefy(ClientMessage::Header(BeefyHeader {
+		headers_with_proof: Some(headers_with_proof),
+		mmr_update_proof: Some(mmr_update_proof),
+	}));
+
+	let client_state = ClientState {
+		chain_id: Default::default(),
+		relay_chain: Default::default(),
+		mmr_root_hash: Default::default(),
+		latest_beefy_height,
+		frozen_height: None,
+		latest_para_height,
+		para_id,
+		authority: authority_set,
+		next_authority_set,
+		_phantom: Default::default(),
+	};
+
+	let time = core::time::Duration::from_millis(GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000));
+	let consensus_state = ConsensusState {
+		timestamp: Timestamp::from_nanoseconds(time.as_nanos().saturated_into::<u64>())
+			.unwrap()
+			.into_tm_time()
+			.unwrap(),
+		root: H256::zero().as_bytes().to_vec().into(),
+	};
+
+	(client_state, consensus_state, client_message)
+}
@@ -4,12 +4,23 @@
 use super::super::*;
 use crate::{
 	benchmarks::{
-		grandpa_benchmark_utils::{generate_finality_proof, GRANDPA_UPDATE_TIMESTAMP},
+		beefy_benchmark_utils::generate_beefy_update,
+		ethereum_benchmark_utils::{generate_ethereum_update, generate_storage_proof},
+		grandpa_benchmark_utils::{
+			create_grandpa_ack_packet, create_grandpa_client_state, create_grandpa_recv_packet,
+			create_grandpa_timeout_packet, generate_finality_proof, generate_justification,
+			SequentialHostFunctions, GRANDPA_UPDATE_TIMESTAMP,
+		},
 		tendermint_benchmark_utils::*,
+		wasm_benchmark_utils::contract_code,
 	},
 	ics20::IbcModule,
 	ics23::client_states::ClientStates,
-	light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState},
+	light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager},
+	weight::{
+		MAX_BEEFY_MMR_PROOF_ITEMS, MAX_BEEFY_PARACHAIN_HEADERS, MAX_BEEFY_PROOFS_LEN,
+		MAX_BEEFY_SIGNATURES, MAX_ETHEREUM_PROOF_BRANCHES,
+	},
 	Any, Config,
 };
 
@@ -31,8 +42,9 @@ use ibc::{
 	},
 	core::{
 		ics02_client::{
+			client_message::ClientMessage as _,
 			client_state::ClientState,
-			context::ClientKeeper,
+			context::{ClientKeeper, ClientReader},
 			height::Height,
 			msgs::{
 				create_client::{MsgCreateAnyClient, TYPE_URL},
@@ -1191,13 +1203,15 @@ benchmarks! {
 	// update_grandpa_client
 	update_grandpa_client {
 		let i in 1..100u32;
+		let j in 1..50u32;
+		let k in 1..100_000u32;
 		let mut ctx = routing::Context::<T>::new();
 		// Set timestamp to the same timestamp used in generating tendermint header, because there
 		// will be a comparison between the local timestamp and the timestamp existing in the header
 		// after factoring in the trusting period for the light client.
 		let now: <T as pallet_timestamp::Config>::Moment = GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000);
 		pallet_timestamp::Pallet::<T>::set_timestamp(now);
-		let (mock_client_state, mock_cs_state, client_message) = generate_finality_proof(i);
+		let (mock_client_state, mock_cs_state, client_message) = generate_finality_proof(i, j, k);
 		let mock_client_state = AnyClientState::Grandpa(mock_client_state);
 		let mock_cs_state = AnyConsensusState::Grandpa(mock_cs_state);
 		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
@@ -1222,4 +1236,285 @@ benchmarks! {
 		let client_state = AnyClientState::decode_vec(&*client_state).unwrap();
 		assert_eq!(client_state.latest_height(), Height::new(2000, 2));
 	}
+
+	// grandpa justification verification, with the precommit signatures verified one by one
+	verify_grandpa_justification_sequential {
+		let i in 1..1000u32;
+		let (justification, set_id, authorities) = generate_justification(i);
+	}: {
+		justification.verify::<SequentialHostFunctions>(set_id, &authorities).unwrap();
+	}
+
+	// grandpa justification verification, with the precommit signatures verified in a batch
+	verify_grandpa_justification_batch {
+		let i in 1..1000u32;
+		let (justification, set_id, authorities) = generate_justification(i);
+	}: {
+		justification.verify::<HostFunctionsManager>(set_id, &authorities).unwrap();
+	}
+
+	// update_beefy_client, the mmr proof of the latest leaf has j items and the parachain headers
+	// are padded with l bytes
+	update_beefy_client {
+		let i in 1..MAX_BEEFY_SIGNATURES;
+		let j in 6..MAX_BEEFY_MMR_PROOF_ITEMS;
+		let k in 1..MAX_BEEFY_PARACHAIN_HEADERS;
+		let l in 1..MAX_BEEFY_PROOFS_LEN;
+		let mut ctx = routing::Context::<T>::new();
+		let now: <T as pallet_timestamp::Config>::Moment = GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000);
+		pallet_timestamp::Pallet::<T>::set_timestamp(now);
+		let (mock_client_state, mock_cs_state, client_message) = generate_beefy_update(i, j, k, l);
+		let mock_client_state = AnyClientState::Beefy(mock_client_state);
+		let mock_cs_state = AnyConsensusState::Beefy(mock_cs_state);
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		ctx.store_consensus_state(client_id.clone(), Height::new(2000, 1), mock_cs_state).unwrap();
+		let time = core::time::Duration::from_millis(GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000));
+		let time = Timestamp::from_nanoseconds(time.as_nanos() as u64).unwrap();
+		ctx.store_update_time(client_id.clone(), Height::new(2000, 1), time).unwrap();
+		let msg = MsgUpdateAnyClient::<routing::Context<T>> {
+			client_id: client_id.clone(),
+			client_message,
+			signer: Signer::from_str("relayer").unwrap()
+		};
+
+		let msg = Any { type_url: UPDATE_CLIENT_TYPE_URL.to_string(), value: msg.encode_vec().unwrap() };
+		let caller: <T as frame_system::Config>::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		let client_state = ClientStates::<T>::get(&client_id).unwrap();
+		let client_state = AnyClientState::decode_vec(&*client_state).unwrap();
+		assert_eq!(client_state.latest_height(), Height::new(2000, (k + 1).into()));
+	}
+
+	// update_ethereum_client
+	update_ethereum_client {
+		let mut ctx = routing::Context::<T>::new();
+		let (mock_client_state, mock_cs_state, client_message, time) = generate_ethereum_update();
+		let now: <T as pallet_timestamp::Config>::Moment = time.saturating_mul(1000);
+		pallet_timestamp::Pallet::<T>::set_timestamp(now);
+		let height = mock_client_state.latest_height();
+		let mock_client_state = AnyClientState::Ethereum(mock_client_state);
+		let mock_cs_state = AnyConsensusState::Ethereum(mock_cs_state);
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		let update_height = client_message.maybe_header_height().unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		ctx.store_consensus_state(client_id.clone(), height, mock_cs_state).unwrap();
+		let time = Timestamp::from_nanoseconds(time.saturating_mul(1_000_000_000)).unwrap();
+		ctx.store_update_time(client_id.clone(), height, time).unwrap();
+		let msg = MsgUpdateAnyClient::<routing::Context<T>> {
+			client_id: client_id.clone(),
+			client_message,
+			signer: Signer::from_str("relayer").unwrap()
+		};
+
+		let msg = Any { type_url: UPDATE_CLIENT_TYPE_URL.to_string(), value: msg.encode_vec().unwrap() };
+		let caller: <T as frame_system::Config>::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		let client_state = ClientStates::<T>::get(&client_id).unwrap();
+		let client_state = AnyClientState::decode_vec(&*client_state).unwrap();
+		assert_eq!(client_state.latest_height(), update_height);
+	}
+
+	// verification of a Merkle-Patricia storage proof of an ethereum client, through i full branch
+	// nodes
+	verify_ethereum_storage_proof {
+		let i in 1..MAX_ETHEREUM_PROOF_BRANCHES;
+		let key = sp_io::hashing::keccak_256(b"ibc packet commitment");
+		let (root, proof) = generate_storage_proof(i, &key);
+	}: {
+		ics12_ethereum::proof::read_proof::<HostFunctionsManager>(&root, &key, &proof)
+			.unwrap()
+			.unwrap();
+	}
+
+	// recv_packet proven against a grandpa client, the proof is padded to j bytes
+	recv_packet_grandpa {
+		let i in 1..1000u32;
+		let j in 1..10000u32;
+		let data = vec![0u8;i.try_into().unwrap()];
+		let mut ctx = routing::Context::<T>::new();
+		let now: <T as pallet_timestamp::Config>::Moment = GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000);
+		pallet_timestamp::Pallet::<T>::set_timestamp(now);
+		frame_system::Pallet::<T>::set_block_number(2u32.into());
+		let mock_client_state = AnyClientState::Grandpa(create_grandpa_client_state());
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		let counterparty_client_id = ClientId::new("10-grandpa", 1).unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		let time = core::time::Duration::from_millis(GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000));
+		let time = Timestamp::from_nanoseconds(time.as_nanos() as u64).unwrap();
+		ctx.store_update_time(client_id.clone(), Height::new(2000, 2), time).unwrap();
+		let host_height = ctx.host_height();
+		ctx.store_update_height(client_id.clone(), Height::new(2000, 2), host_height).unwrap();
+		let connection_id = ConnectionId::new(0);
+		let commitment_prefix: CommitmentPrefix = <T as Config>::PalletPrefix::get().to_vec().try_into().unwrap();
+		let delay_period = core::time::Duration::from_nanos(0);
+		let connection_counterparty = Counterparty::new(counterparty_client_id, Some(ConnectionId::new(1)), commitment_prefix);
+		let connection_end = ConnectionEnd::new(State::Open, client_id.clone(), connection_counterparty, vec![ConnVersion::default()], delay_period);
+
+		ctx.store_connection(connection_id.clone(), &connection_end).unwrap();
+		ctx.store_connection_to_client(connection_id, &client_id).unwrap();
+
+		let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+		let counterparty_channel = ibc::core::ics04_channel::channel::Counterparty::new(port_id.clone(), Some(ChannelId::new(0)));
+		let channel_end = ChannelEnd::new(
+			ibc::core::ics04_channel::channel::State::Open,
+			ibc::core::ics04_channel::channel::Order::Unordered,
+			counterparty_channel,
+			vec![ConnectionId::new(0)],
+			ibc::core::ics04_channel::Version::default()
+		);
+
+		ctx.store_channel((port_id.clone(), ChannelId::new(0)), &channel_end).unwrap();
+		ctx.store_connection_channels(ConnectionId::new(0), &(port_id.clone(), ChannelId::new(0))).unwrap();
+		ctx.store_next_sequence_recv((port_id.clone(), ChannelId::new(0)), 1u64.into()).unwrap();
+
+		let (cs_state, value) = create_grandpa_recv_packet::<T>(data, j);
+		ctx.store_consensus_state(client_id, Height::new(2000, 2), AnyConsensusState::Grandpa(cs_state)).unwrap();
+		let msg = Any {
+			type_url: RECV_PACKET_TYPE_URL.to_string(),
+			value: value.encode_vec().unwrap()
+		};
+		let caller: <T as frame_system::Config>::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		let receipt = ctx.get_packet_receipt(&(PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap(), ChannelId::new(0), 1u64.into())).unwrap();
+		match receipt {
+			Receipt::Ok => {},
+			_ => panic!("Commitment should not exist")
+		}
+	}
+
+	// ack_packet proven against a grandpa client, the proof is padded to k bytes
+	ack_packet_grandpa {
+		let i in 1..1000u32;
+		let j in 1..1000u32;
+		let k in 1..10000u32;
+		let data = vec![0u8;i.try_into().unwrap()];
+		let ack = vec![0u8;j.try_into().unwrap()];
+		let mut ctx = routing::Context::<T>::new();
+		let now: <T as pallet_timestamp::Config>::Moment = GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000);
+		pallet_timestamp::Pallet::<T>::set_timestamp(now);
+		frame_system::Pallet::<T>::set_block_number(2u32.into());
+		let mock_client_state = AnyClientState::Grandpa(create_grandpa_client_state());
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		let counterparty_client_id = ClientId::new("10-grandpa", 1).unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		let time = core::time::Duration::from_millis(GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000));
+		let time = Timestamp::from_nanoseconds(time.as_nanos() as u64).unwrap();
+		ctx.store_update_time(client_id.clone(), Height::new(2000, 2), time).unwrap();
+		let host_height = ctx.host_height();
+		ctx.store_update_height(client_id.clone(), Height::new(2000, 2), host_height).unwrap();
+		let connection_id = ConnectionId::new(0);
+		let commitment_prefix: CommitmentPrefix = <T as Config>::PalletPrefix::get().to_vec().try_into().unwrap();
+		let delay_period = core::time::Duration::from_nanos(0);
+		let connection_counterparty = Counterparty::new(counterparty_client_id, Some(ConnectionId::new(1)), commitment_prefix);
+		let connection_end = ConnectionEnd::new(State::Open, client_id.clone(), connection_counterparty, vec![ConnVersion::default()], delay_period);
+
+		ctx.store_connection(connection_id.clone(), &connection_end).unwrap();
+		ctx.store_connection_to_client(connection_id, &client_id).unwrap();
+
+		let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+		let counterparty_channel = ibc::core::ics04_channel::channel::Counterparty::new(port_id.clone(), Some(ChannelId::new(0)));
+		let channel_end = ChannelEnd::new(
+			ibc::core::ics04_channel::channel::State::Open,
+			ibc::core::ics04_channel::channel::Order::Unordered,
+			counterparty_channel,
+			vec![ConnectionId::new(0)],
+			ibc::core::ics04_channel::Version::default()
+		);
+
+		ctx.store_channel((port_id.clone(), ChannelId::new(0)), &channel_end).unwrap();
+		ctx.store_connection_channels(ConnectionId::new(0), &(port_id.clone(), ChannelId::new(0))).unwrap();
+		ctx.store_next_sequence_recv((port_id.clone(), ChannelId::new(0)), 1u64.into()).unwrap();
+
+		let (cs_state, value) = create_grandpa_ack_packet::<T>(data, ack, k);
+		ctx.store_consensus_state(client_id, Height::new(2000, 2), AnyConsensusState::Grandpa(cs_state)).unwrap();
+		let msg = Any {
+			type_url: ACK_PACKET_TYPE_URL.to_string(),
+			value: value.encode_vec().unwrap()
+		};
+		let caller: <T as frame_system::Config>::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		let res = ctx.get_packet_commitment(&(PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap(), ChannelId::new(0), 1u64.into()));
+		match res {
+			Ok(_) => panic!("Commitment should not exist"),
+			Err(e) => assert_eq!(e.detail(), Ics04Error::packet_commitment_not_found(1u64.into()).detail())
+		}
+	}
+
+	// timeout_packet proven against a grandpa client, the proof is padded to j bytes
+	timeout_packet_grandpa {
+		let i in 1..1000u32;
+		let j in 1..10000u32;
+		let data = vec![0u8;i.try_into().unwrap()];
+		let mut ctx = routing::Context::<T>::new();
+		let now: <T as pallet_timestamp::Config>::Moment = GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000);
+		pallet_timestamp::Pallet::<T>::set_timestamp(now);
+		frame_system::Pallet::<T>::set_block_number(2u32.into());
+		let mock_client_state = AnyClientState::Grandpa(create_grandpa_client_state());
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		let counterparty_client_id = ClientId::new("10-grandpa", 1).unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		let time = core::time::Duration::from_millis(GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000));
+		let time = Timestamp::from_nanoseconds(time.as_nanos() as u64).unwrap();
+		ctx.store_update_time(client_id.clone(), Height::new(2000, 2), time).unwrap();
+		let host_height = ctx.host_height();
+		ctx.store_update_height(client_id.clone(), Height::new(2000, 2), host_height).unwrap();
+		let connection_id = ConnectionId::new(0);
+		let commitment_prefix: CommitmentPrefix = <T as Config>::PalletPrefix::get().to_vec().try_into().unwrap();
+		let delay_period = core::time::Duration::from_nanos(0);
+		let connection_counterparty = Counterparty::new(counterparty_client_id, Some(ConnectionId::new(1)), commitment_prefix);
+		let connection_end = ConnectionEnd::new(State::Open, client_id.clone(), connection_counterparty, vec![ConnVersion::default()], delay_period);
+
+		ctx.store_connection(connection_id.clone(), &connection_end).unwrap();
+		ctx.store_connection_to_client(connection_id, &client_id).unwrap();
+
+		let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+		let counterparty_channel = ibc::core::ics04_channel::channel::Counterparty::new(port_id.clone(), Some(ChannelId::new(0)));
+		let channel_end = ChannelEnd::new(
+			ibc::core::ics04_channel::channel::State::Open,
+			ibc::core::ics04_channel::channel::Order::Unordered,
+			counterparty_channel,
+			vec![ConnectionId::new(0)],
+			ibc::core::ics04_channel::Version::default()
+		);
+
+		ctx.store_channel((port_id.clone(), ChannelId::new(0)), &channel_end).unwrap();
+		ctx.store_connection_channels(ConnectionId::new(0), &(port_id.clone(), ChannelId::new(0))).unwrap();
+		ctx.store_next_sequence_recv((port_id.clone(), ChannelId::new(0)), 1u64.into()).unwrap();
+		ctx.store_next_sequence_send((port_id.clone(), ChannelId::new(0)), 1u64.into()).unwrap();
+
+		let (cs_state, value) = create_grandpa_timeout_packet::<T>(data, j);
+		ctx.store_consensus_state(client_id, Height::new(2000, 2), AnyConsensusState::Grandpa(cs_state)).unwrap();
+		let msg = Any {
+			type_url: TIMEOUT_TYPE_URL.to_string(),
+			value: value.encode_vec().unwrap()
+		};
+		let caller: <T as frame_system::Config>::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		let res = ctx.get_packet_commitment(&(PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap(), ChannelId::new(0), 1u64.into()));
+		match res {
+			Ok(_) => panic!("Commitment should not exist"),
+			Err(e) => assert_eq!(e.detail(), Ics04Error::packet_commitment_not_found(1u64.into()).detail())
+		}
+	}
+
+	// upload_wasm
+	upload_wasm {
+		let i in 128..T::MaxWasmCodeSize::get();
+		let code = contract_code(i);
+		let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+	}: _(RawOrigin::Root, code)
+	verify {
+		assert!(WasmCodes::<T>::contains_key(&code_id));
+	}
 }
@@ -0,0 +1,220 @@
+use crate::light_clients::{AnyClientMessage, HostFunctionsManager};
+use alloc::string::ToString;
+use core::{marker::PhantomData, time::Duration};
+use ibc::timestamp::Timestamp;
+use ics12_ethereum::{
+	client_message::{ClientMessage, Header},
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+	types::{
+		BeaconBlockHeader, ExecutionPayloadHeader, Fork, ForkParameters, LightClientHeader,
+		LightClientUpdate, SyncAggregate, SyncCommittee,
+	},
+};
+use serde_json::Value;
+use sp_core::{H160, H256, U256};
+use sp_std::prelude::*;
+
+// Synthetic updates of the ics12-ethereum tests, see their README: the first one finalizes a
+// header in sync committee period 800 signed by almost all of the committee, and brings the next
+// sync committee.
+const BOOTSTRAP: &str =
+	include_str!("../../../../light-clients/ics12-ethereum/src/fixtures/bootstrap.json");
+const UPDATES: &str =
+	include_str!("../../../../light-clients/ics12-ethereum/src/fixtures/updates.json");
+const PROOFS: &str =
+	include_str!("../../../../light-clients/ics12-ethereum/src/fixtures/proofs.json");
+
+const GENESIS_VALIDATORS_ROOT: [u8; 32] =
+	hex_literal::hex!("4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95");
+const IBC_ADDRESS: [u8; 20] = hex_literal::hex!("7a2f1e6b9c4d0a5e3f8b2c1d6e9a0f4b3c7d8e5a");
+const IBC_COMMITMENTS_SLOT: u64 = 3;
+
+fn bytes(value: &Value) -> Vec<u8> {
+	hex::decode(value.as_str().unwrap().trim_start_matches("0x")).unwrap()
+}
+
+fn hash(value: &Value) -> H256 {
+	H256::from_slice(&bytes(value))
+}
+
+fn number(value: &Value) -> u64 {
+	value.as_str().unwrap().parse().unwrap()
+}
+
+fn branch(value: &Value) -> Vec<H256> {
+	value.as_array().unwrap().iter().map(hash).collect()
+}
+
+fn light_client_header(value: &Value) -> LightClientHeader {
+	let beacon = &value["beacon"];
+	let execution = &value["execution"];
+	LightClientHeader {
+		beacon: BeaconBlockHeader {
+			slot: number(&beacon["slot"]),
+			proposer_index: number(&beacon["proposer_index"]),
+			parent_root: hash(&beacon["parent_root"]),
+			state_root: hash(&beacon["state_root"]),
+			body_root: hash(&beacon["body_root"]),
+		},
+		execution: ExecutionPayloadHeader {
+			parent_hash: hash(&execution["parent_hash"]),
+			fee_recipient: H160::from_slice(&bytes(&execution["fee_recipient"])),
+			state_root: hash(&execution["state_root"]),
+			receipts_root: hash(&execution["receipts_root"]),
+			logs_bloom: bytes(&execution["logs_bloom"]),
+			prev_randao: hash(&execution["prev_randao"]),
+			block_number: number(&execution["block_number"]),
+			gas_limit: number(&execution["gas_limit"]),
+			gas_used: number(&execution["gas_used"]),
+			timestamp: number(&execution["timestamp"]),
+			extra_data: bytes(&execution["extra_data"]),
+			base_fee_per_gas: U256::from_dec_str(execution["base_fee_per_gas"].as_str().unwrap())
+				.unwrap(),
+			block_hash: hash(&execution["block_hash"]),
+			transactions_root: hash(&execution["transactions_root"]),
+			withdrawals_root: hash(&execution["withdrawals_root"]),
+			blob_gas_used: None,
+			excess_blob_gas: None,
+		},
+		execution_branch: branch(&value["execution_branch"]),
+	}
+}
+
+fn sync_committee(value: &Value) -> SyncCommittee {
+	SyncCommittee {
+		public_keys: value["pubkeys"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|key| bytes(key).try_into().unwrap())
+			.collect(),
+		aggregate_public_key: bytes(&value["aggregate_pubkey"]).try_into().unwrap(),
+	}
+}
+
+/// `eth_getProof` response for the IBC contract at the block
+fn contract_proof(block_number: u64) -> Value {
+	let proofs: Value = serde_json::from_str(PROOFS).unwrap();
+	proofs[block_number.to_string()].clone()
+}
+
+/// Builds an ethereum client bootstrapped from the fixtures along with the first update of the
+/// fixtures, returning the unix time in seconds of the block it finalizes.
+pub fn generate_ethereum_update(
+) -> (ClientState<HostFunctionsManager>, ConsensusState, AnyClientMessage, u64) {
+	let bootstrap: Value = serde_json::from_str(BOOTSTRAP).unwrap();
+	let bootstrap = &bootstrap["data"];
+	let header = light_client_header(&bootstrap["header"]);
+	let client_state = ClientState {
+		chain_id: 1,
+		genesis_validators_root: H256::from(GENESIS_VALIDATORS_ROOT),
+		fork_parameters: ForkParameters {
+			genesis_fork_version: [0, 0, 0, 0],
+			forks: vec![
+				Fork { version: [1, 0, 0, 0], epoch: 74240 },
+				Fork { version: [2, 0, 0, 0], epoch: 144896 },
+				Fork { version: [3, 0, 0, 0], epoch: 194048 },
+				Fork { version: [4, 0, 0, 0], epoch: 269568 },
+			],
+		},
+		finalized_slot: header.beacon.slot,
+		latest_execution_height: header.execution.block_number,
+		current_sync_committee: sync_committee(&bootstrap["current_sync_committee"]),
+		next_sync_committee: None,
+		ibc_address: H160::from(IBC_ADDRESS),
+		ibc_commitments_slot: H256::from_low_u64_be(IBC_COMMITMENTS_SLOT),
+		trusting_period: Duration::from_secs(27 * 60 * 60),
+		frozen_height: None,
+		_phantom: PhantomData,
+	};
+	let timestamp = Timestamp::from_nanoseconds(header.execution.timestamp * 1_000_000_000)
+		.unwrap()
+		.into_tm_time()
+		.unwrap();
+	let proof = contract_proof(header.execution.block_number);
+	let consensus_state =
+		ConsensusState::new(hash(&proof["storageHash"]).as_bytes().to_vec(), timestamp);
+
+	let updates: Value = serde_json::from_str(UPDATES).unwrap();
+	let update = &updates[0]["data"];
+	let update = LightClientUpdate {
+		attested_header: light_client_header(&update["attested_header"]),
+		next_sync_committee: Some(sync_committee(&update["next_sync_committee"])),
+		next_sync_committee_branch: branch(&update["next_sync_committee_branch"]),
+		finalized_header: light_client_header(&update["finalized_header"]),
+		finality_branch: branch(&update["finality_branch"]),
+		sync_aggregate: SyncAggregate {
+			sync_committee_bits: bytes(&update["sync_aggregate"]["sync_committee_bits"]),
+			sync_committee_signature: bytes(&update["sync_aggregate"]["sync_committee_signature"])
+				.try_into()
+				.unwrap(),
+		},
+		signature_slot: number(&update["signature_slot"]),
+	};
+	let time = update.finalized_header.execution.timestamp;
+	let proof = contract_proof(update.finalized_header.execution.block_number);
+	let account_proof =
+		proof["accountProof"].as_array().unwrap().iter().map(bytes).collect::<Vec<_>>();
+	let client_message =
+		AnyClientMessage::Ethereum(ClientMessage::Header(Header { update, account_proof }));
+
+	(client_state, consensus_state, client_message, time)
+}
+
+fn rlp_length_prefix(offset: u8, len: usize) -> Vec<u8> {
+	if len <= 55 {
+		return vec![offset + len as u8]
+	}
+	let len = (len as u64).to_be_bytes();
+	let len = &len[len.iter().position(|byte| *byte != 0).unwrap_or(7)..];
+	let mut prefix = vec![offset + 55 + len.len() as u8];
+	prefix.extend(len);
+	prefix
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+	if bytes.len() == 1 && bytes[0] < 0x80 {
+		return bytes.to_vec()
+	}
+	let mut encoded = rlp_length_prefix(0x80, bytes.len());
+	encoded.extend(bytes);
+	encoded
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+	let payload = items.concat();
+	let mut encoded = rlp_length_prefix(0xc0, payload.len());
+	encoded.extend(payload);
+	encoded
+}
+
+/// Builds the Merkle-Patricia proof of `key` in a trie where the path to its leaf goes through
+/// `branches` branch nodes with 16 children each, the largest proof of that depth. Returns the
+/// root of the trie and the proof, ordered from the root.
+pub fn generate_storage_proof(branches: u32, key: &[u8; 32]) -> (H256, Vec<Vec<u8>>) {
+	let nibbles = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect::<Vec<_>>();
+	let depth = branches as usize;
+	let path = &nibbles[depth..];
+	// hex prefix encoding of the leaf path
+	let mut encoded_path = if path.len() % 2 == 1 { vec![0x30 | path[0]] } else { vec![0x20] };
+	encoded_path.extend(path[path.len() % 2..].chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+	let mut proof = vec![rlp_list(&[rlp_bytes(&encoded_path), rlp_bytes(&rlp_bytes(&[1; 32]))])];
+	for level in (0..depth).rev() {
+		let child = sp_io::hashing::keccak_256(proof.last().expect("proof has a leaf; qed"));
+		let mut items = (0..16u8)
+			.map(|nibble| {
+				if nibble == nibbles[level] {
+					rlp_bytes(&child)
+				} else {
+					rlp_bytes(&[nibble; 32])
+				}
+			})
+			.collect::<Vec<_>>();
+		items.push(rlp_bytes(&[]));
+		proof.push(rlp_list(&items));
+	}
+	let root = H256::from(sp_io::hashing::keccak_256(proof.last().expect("proof has a leaf; qed")));
+	proof.reverse();
+	(root, proof)
+}
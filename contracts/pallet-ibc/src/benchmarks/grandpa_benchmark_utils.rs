@@ -1,36 +1,56 @@
-use crate::light_clients::{AnyClientMessage, HostFunctionsManager};
-use alloc::{collections::BTreeMap, format};
-use codec::{Compact, Encode};
+use crate::{
+	light_clients::{AnyClientMessage, HostFunctionsManager},
+	routing::Context,
+	Config, MODULE_ID,
+};
+use alloc::{
+	collections::BTreeMap,
+	format,
+	string::{String, ToString},
+};
+use codec::{Compact, Decode, Encode};
+use core::str::FromStr;
 use finality_grandpa::{Precommit, SignedPrecommit};
 use grandpa_client_primitives::{
 	justification::GrandpaJustification, parachain_header_storage_key, Commit, FinalityProof,
 	ParachainHeaderProofs,
 };
-use ibc::{timestamp::Timestamp, Height};
+use ibc::{
+	core::{
+		ics04_channel::{
+			context::{ChannelKeeper, ChannelReader},
+			msgs::{
+				acknowledgement::MsgAcknowledgement, recv_packet::MsgRecvPacket,
+				timeout::MsgTimeout,
+			},
+			packet::Packet,
+		},
+		ics24_host::{
+			identifier::{ChannelId, PortId},
+			path::{AcksPath, CommitmentsPath},
+		},
+	},
+	proofs::Proofs,
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
 use ics10_grandpa::{
 	client_message::{ClientMessage, Header as GrandpaHeader, RelayChainHeader},
 	client_state::ClientState,
 	consensus_state::ConsensusState,
 };
-use sp_core::H256;
-use sp_finality_grandpa::{AuthorityId, AuthoritySignature, KEY_TYPE};
+use sp_core::{crypto::AccountId32, ed25519, storage::ChildInfo, Get, H256};
+use sp_finality_grandpa::{AuthorityId, AuthorityList, AuthoritySignature, KEY_TYPE};
 use sp_runtime::{traits::BlakeTwo256, SaturatedConversion};
 use sp_std::prelude::*;
 use sp_trie::{generate_trie_proof, LayoutV0, MemoryDB, StorageProof, TrieDBMutBuilder, TrieMut};
 
 pub const GRANDPA_UPDATE_TIMESTAMP: u64 = 1650894363;
-/// Builds a grandpa client message that that contains the requested number of precommits
-pub fn generate_finality_proof(
-	pre_commits: u32,
-) -> (ClientState<HostFunctionsManager>, ConsensusState, AnyClientMessage) {
-	let para_id = 2000u32;
-	let latest_para_height = 1u32;
-	let relay_chain_hash = H256::zero();
-	let latest_relay_height = 1u32;
-	let round = 1;
-	let set_id = 1;
 
-	// Build timestamp extrinsic with proof
+/// Builds the timestamp extrinsic of a parachain block, returning it along with the extrinsics
+/// root and the proof of the extrinsic in it.
+pub fn timestamp_extrinsic_with_proof() -> (Vec<u8>, H256, Vec<Vec<u8>>) {
 	let mut para_db = MemoryDB::<BlakeTwo256>::default();
 
 	let mut timestamp_extrinsic =
@@ -52,6 +72,26 @@ pub fn generate_finality_proof(
 	)
 	.unwrap();
 
+	(timestamp_extrinsic, extrinsics_root, extrinsic_proof)
+}
+
+/// Builds a grandpa client message that that contains the requested number of precommits and
+/// parachain headers. The state proof of the first parachain header is padded with a node of
+/// `padding` bytes, which is ignored by the verifier but still has to be decoded and hashed.
+pub fn generate_finality_proof(
+	pre_commits: u32,
+	parachain_headers: u32,
+	padding: u32,
+) -> (ClientState<HostFunctionsManager>, ConsensusState, AnyClientMessage) {
+	let para_id = 2000u32;
+	let latest_para_height = 1u32;
+	let relay_chain_hash = H256::zero();
+	let latest_relay_height = 1u32;
+	let round = 1;
+	let set_id = 1;
+
+	let (timestamp_extrinsic, extrinsics_root, extrinsic_proof) = timestamp_extrinsic_with_proof();
+
 	// build a chain of relaychain blocks
 	// construct a state root from the parachain header
 
@@ -75,7 +115,7 @@ pub fn generate_finality_proof(
 
 	let mut prev_hash = relay_chain_hash;
 	let mut headers = vec![];
-	for i in 0..3 {
+	for i in 0..parachain_headers {
 		let header = RelayChainHeader {
 			parent_hash: prev_hash,
 			number: latest_relay_height + 1 + i,
@@ -122,7 +162,7 @@ pub fn generate_finality_proof(
 		justification: justification.encode(),
 		unknown_headers: headers.clone(),
 	};
-	let mut parachain_headers = BTreeMap::default();
+	let mut parachain_header_proofs = BTreeMap::default();
 
 	let state_proof =
 		StorageProof::new(para_db.drain().into_iter().map(|(_, (val, ..))| val.to_vec()))
@@ -130,11 +170,15 @@ pub fn generate_finality_proof(
 			.into_iter()
 			.collect::<Vec<_>>();
 	// For each relay chain header we construct a corresponding ParachainHeaderProofs
-	for header in &headers {
-		parachain_headers.insert(
+	for (i, header) in headers.iter().enumerate() {
+		let mut state_proof = state_proof.clone();
+		if i == 0 && padding > 0 {
+			state_proof.push(vec![0u8; padding as usize]);
+		}
+		parachain_header_proofs.insert(
 			header.hash(),
 			ParachainHeaderProofs {
-				state_proof: state_proof.clone(),
+				state_proof,
 				extrinsic: timestamp_extrinsic.clone(),
 				extrinsic_proof: extrinsic_proof.clone(),
 			},
@@ -143,7 +187,8 @@ pub fn generate_finality_proof(
 
 	let grandpa_header = GrandpaHeader {
 		finality_proof,
-		parachain_headers,
+		parachain_headers: parachain_header_proofs,
+		ancestry_proofs: Default::default(),
 		height: Height::new(para_id.into(), parachain_header.number.into()),
 	};
 	let client_message = AnyClientMessage::Grandpa(ClientMessage::Header(grandpa_header));
@@ -157,17 +202,248 @@ pub fn generate_finality_proof(
 		para_id,
 		current_set_id: set_id,
 		current_authorities: authorities.into_iter().map(|authority| (authority, 100)).collect(),
+		standalone: false,
 		_phantom: Default::default(),
 	};
 
+	(client_state, create_consensus_state(H256::zero()), client_message)
+}
+
+/// Builds a grandpa justification that contains the requested number of precommits, along with
+/// the set id and authorities that signed it.
+pub fn generate_justification(
+	pre_commits: u32,
+) -> (GrandpaJustification<RelayChainHeader>, u64, AuthorityList) {
+	let (client_state, _, client_message) = generate_finality_proof(pre_commits, 1, 0);
+	let justification = match client_message {
+		AnyClientMessage::Grandpa(ClientMessage::Header(header)) =>
+			GrandpaJustification::decode(&mut &*header.finality_proof.justification).unwrap(),
+		_ => unreachable!("generate_finality_proof builds a grandpa header; qed"),
+	};
+
+	(justification, client_state.current_set_id, client_state.current_authorities)
+}
+
+/// Host functions that verify precommit signatures one by one, used as the baseline against
+/// which the batch verification of [`HostFunctionsManager`] is benchmarked.
+pub struct SequentialHostFunctions;
+
+impl grandpa_client_primitives::HostFunctions for SequentialHostFunctions {
+	type Header = RelayChainHeader;
+
+	fn ed25519_verify(sig: &ed25519::Signature, msg: &[u8], pub_key: &ed25519::Public) -> bool {
+		<HostFunctionsManager as grandpa_client_primitives::HostFunctions>::ed25519_verify(
+			sig, msg, pub_key,
+		)
+	}
+
+	fn insert_relay_header_hashes(new_hashes: &[H256]) {
+		<HostFunctionsManager as grandpa_client_primitives::HostFunctions>::insert_relay_header_hashes(
+			new_hashes,
+		)
+	}
+
+	fn contains_relay_header_hash(hash: H256) -> bool {
+		<HostFunctionsManager as grandpa_client_primitives::HostFunctions>::contains_relay_header_hash(
+			hash,
+		)
+	}
+}
+
+impl light_client_common::HostFunctions for SequentialHostFunctions {
+	type BlakeTwo256 = BlakeTwo256;
+}
+
+/// Builds a consensus state for the state `root` at [`GRANDPA_UPDATE_TIMESTAMP`]
+fn create_consensus_state(root: H256) -> ConsensusState {
 	let time = core::time::Duration::from_millis(GRANDPA_UPDATE_TIMESTAMP.saturating_mul(1000));
-	let consensus_state = ConsensusState {
+	ConsensusState {
 		timestamp: Timestamp::from_nanoseconds(time.as_nanos().saturated_into::<u64>())
 			.unwrap()
 			.into_tm_time()
 			.unwrap(),
-		root: H256::zero().as_bytes().to_vec().into(),
+		root: root.as_bytes().to_vec().into(),
+	}
+}
+
+/// Builds a proof of `value` stored at `path` in the ibc child trie under `prefix`, the way
+/// commitments of a substrate chain are proven to a grandpa or beefy client. Proving another path
+/// against the returned root yields a non-membership proof. The proof is padded with a node of
+/// `padding` bytes, which is ignored by the verifier but still has to be decoded.
+pub fn generate_child_trie_proof(
+	prefix: &[u8],
+	path: String,
+	value: Vec<u8>,
+	padding: u32,
+) -> (H256, Vec<u8>) {
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut key = prefix.to_vec();
+	key.extend(path.as_bytes());
+	let child_root = {
+		let mut root = Default::default();
+		let mut trie = TrieDBMutBuilder::<LayoutV0<BlakeTwo256>>::new(&mut db, &mut root).build();
+		trie.insert(&key, &value).unwrap();
+		*trie.root()
+	};
+	let child_info = ChildInfo::new_default(prefix);
+	let root = {
+		let mut root = Default::default();
+		let mut trie = TrieDBMutBuilder::<LayoutV0<BlakeTwo256>>::new(&mut db, &mut root).build();
+		trie.insert(&child_info.prefixed_storage_key(), child_root.as_bytes()).unwrap();
+		*trie.root()
+	};
+	let mut proof = db.drain().into_iter().map(|(_, (val, ..))| val.to_vec()).collect::<Vec<_>>();
+	proof.push(vec![0u8; padding as usize]);
+
+	(root, proof.encode())
+}
+
+/// Builds a grandpa client state for parachain 2000 at height 2, packets are proven at that height.
+pub fn create_grandpa_client_state() -> ClientState<HostFunctionsManager> {
+	ClientState {
+		relay_chain: Default::default(),
+		latest_relay_height: 1,
+		latest_relay_hash: Default::default(),
+		frozen_height: None,
+		latest_para_height: 2,
+		para_id: 2000,
+		current_set_id: 1,
+		current_authorities: vec![],
+		standalone: false,
+		_phantom: Default::default(),
+	}
+}
+
+pub(crate) fn create_grandpa_recv_packet<T: Config + Send + Sync>(
+	data: Vec<u8>,
+	padding: u32,
+) -> (ConsensusState, MsgRecvPacket)
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+	let packet = Packet {
+		sequence: 1u64.into(),
+		source_port: port_id.clone(),
+		source_channel: ChannelId::new(0),
+		destination_port: port_id.clone(),
+		destination_channel: ChannelId::new(0),
+		data,
+		timeout_height: Height::new(2087, 5),
+		timeout_timestamp: Timestamp::from_nanoseconds(1690894363u64.saturating_mul(1000000000))
+			.unwrap(),
 	};
+	let ctx = Context::<T>::new();
+	let commitment =
+		ctx.packet_commitment(packet.data.clone(), packet.timeout_height, packet.timeout_timestamp);
+	let path = CommitmentsPath { port_id, channel_id: ChannelId::new(0), sequence: 1.into() };
+	let (root, proof) = generate_child_trie_proof(
+		T::PalletPrefix::get(),
+		path.to_string(),
+		commitment.into_vec(),
+		padding,
+	);
 
-	(client_state, consensus_state, client_message)
+	(
+		create_consensus_state(root),
+		MsgRecvPacket {
+			packet,
+			proofs: Proofs::new(proof.try_into().unwrap(), None, None, None, Height::new(2000, 2))
+				.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		},
+	)
+}
+
+pub(crate) fn create_grandpa_ack_packet<T: Config + Send + Sync>(
+	data: Vec<u8>,
+	ack: Vec<u8>,
+	padding: u32,
+) -> (ConsensusState, MsgAcknowledgement)
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+	let packet = Packet {
+		sequence: 1u64.into(),
+		source_port: port_id.clone(),
+		source_channel: ChannelId::new(0),
+		destination_port: port_id.clone(),
+		destination_channel: ChannelId::new(0),
+		data: data.clone(),
+		timeout_height: Height::new(2087, 5),
+		timeout_timestamp: Timestamp::from_nanoseconds(1690894363u64.saturating_mul(1000000000))
+			.unwrap(),
+	};
+	let mut ctx = Context::<T>::new();
+	let commitment = ctx.packet_commitment(data, packet.timeout_height, packet.timeout_timestamp);
+	ctx.store_packet_commitment((port_id.clone(), ChannelId::new(0), 1.into()), commitment)
+		.unwrap();
+	let ack_commitment = ctx.ack_commitment(ack.clone().into());
+	let path = AcksPath { port_id, channel_id: ChannelId::new(0), sequence: 1.into() };
+	let (root, proof) = generate_child_trie_proof(
+		T::PalletPrefix::get(),
+		path.to_string(),
+		ack_commitment.into_vec(),
+		padding,
+	);
+
+	(
+		create_consensus_state(root),
+		MsgAcknowledgement {
+			packet,
+			acknowledgement: ack.into(),
+			proofs: Proofs::new(proof.try_into().unwrap(), None, None, None, Height::new(2000, 2))
+				.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		},
+	)
+}
+
+/// Builds a timeout of a packet sent on an unordered channel, proven by the absence of its receipt
+/// from a trie that only holds the packet commitment.
+pub(crate) fn create_grandpa_timeout_packet<T: Config + Send + Sync>(
+	data: Vec<u8>,
+	padding: u32,
+) -> (ConsensusState, MsgTimeout)
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+	let packet = Packet {
+		sequence: 1u64.into(),
+		source_port: port_id.clone(),
+		source_channel: ChannelId::new(0),
+		destination_port: port_id.clone(),
+		destination_channel: ChannelId::new(0),
+		data: data.clone(),
+		timeout_height: Height::new(0, 1),
+		timeout_timestamp: Timestamp::from_nanoseconds(1620894363u64.saturating_mul(1000000000))
+			.unwrap(),
+	};
+	let mut ctx = Context::<T>::new();
+	let commitment = ctx.packet_commitment(data, packet.timeout_height, packet.timeout_timestamp);
+	ctx.store_packet_commitment((port_id.clone(), ChannelId::new(0), 1.into()), commitment.clone())
+		.unwrap();
+	let path = CommitmentsPath { port_id, channel_id: ChannelId::new(0), sequence: 1.into() };
+	let (root, proof) = generate_child_trie_proof(
+		T::PalletPrefix::get(),
+		path.to_string(),
+		commitment.into_vec(),
+		padding,
+	);
+
+	(
+		create_consensus_state(root),
+		MsgTimeout {
+			packet,
+			next_sequence_recv: Default::default(),
+			proofs: Proofs::new(proof.try_into().unwrap(), None, None, None, Height::new(2000, 2))
+				.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		},
+	)
 }
@@ -6,3 +6,12 @@ pub mod tendermint_benchmark_utils;
 
 #[cfg(feature = "runtime-benchmarks")]
 pub mod grandpa_benchmark_utils;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod beefy_benchmark_utils;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod ethereum_benchmark_utils;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod wasm_benchmark_utils;
@@ -318,6 +318,7 @@ pub(crate) fn create_mock_grandpa_client_state() -> (
 		para_id: 2087,
 		current_set_id: 0,
 		current_authorities: vec![],
+		standalone: false,
 		_phantom: Default::default(),
 	};
 
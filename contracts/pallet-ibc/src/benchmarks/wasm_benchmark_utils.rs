@@ -0,0 +1,72 @@
+use alloc::{vec, vec::Vec};
+
+/// Unsigned LEB128 encoding of `value`.
+fn leb128(mut value: u32) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	loop {
+		let byte = (value & 0x7f) as u8;
+		value >>= 7;
+		if value == 0 {
+			bytes.push(byte);
+			return bytes
+		}
+		bytes.push(byte | 0x80);
+	}
+}
+
+/// A wasm section with id `id`.
+fn section(id: u8, content: &[u8]) -> Vec<u8> {
+	[&[id][..], &leb128(content.len() as u32), content].concat()
+}
+
+/// A light client contract exporting the CosmWasm entry points, whose code is at most `size`
+/// bytes. The `query` entry point is padded with `nop`s, so that most of the code is instructions
+/// to validate.
+pub fn contract_code(size: u32) -> Vec<u8> {
+	// (i32) -> i32, (i32) -> (), (i32, i32, i32) -> i32, (i32, i32) -> i32
+	let types = [
+		&[0x04][..],
+		&[0x60, 0x01, 0x7f, 0x01, 0x7f],
+		&[0x60, 0x01, 0x7f, 0x00],
+		&[0x60, 0x03, 0x7f, 0x7f, 0x7f, 0x01, 0x7f],
+		&[0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f],
+	]
+	.concat();
+	let functions = [0x04, 0x00, 0x01, 0x02, 0x03];
+	let memories = [0x01, 0x00, 0x01];
+	let mut exports = vec![0x05];
+	for (index, (name, kind)) in [
+		("memory", 0x02),
+		("allocate", 0x00),
+		("deallocate", 0x00),
+		("execute", 0x00),
+		("query", 0x00),
+	]
+	.into_iter()
+	.enumerate()
+	{
+		let index = if kind == 0x02 { 0 } else { index as u8 - 1 };
+		exports.extend([&leb128(name.len() as u32)[..], name.as_bytes(), &[kind, index]].concat());
+	}
+	// The other sections and the bodies of the other functions take less than 128 bytes.
+	let nops = size.saturating_sub(128) as usize;
+	let mut code = vec![0x04];
+	for body in [
+		vec![0x00, 0x00, 0x0b],
+		vec![0x00, 0x00, 0x0b],
+		vec![0x00, 0x00, 0x0b],
+		[&[0x00][..], &vec![0x01; nops], &[0x00, 0x0b]].concat(),
+	] {
+		code.extend(leb128(body.len() as u32));
+		code.extend(body);
+	}
+	[
+		&[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00][..],
+		&section(0x01, &types),
+		&section(0x03, &functions),
+		&section(0x05, &memories),
+		&section(0x07, &exports),
+		&section(0x0a, &code),
+	]
+	.concat()
+}
@@ -5,6 +5,7 @@ use crate::{
 	impls::host_height,
 	light_clients::{AnyClient, AnyClientMessage, AnyClientState, AnyConsensusState},
 	routing::Context,
+	wasm,
 };
 
 use alloc::string::{String, ToString};
@@ -313,6 +314,43 @@ where
 
 		Ok(count as u64)
 	}
+
+	fn execute_wasm_contract(
+		&self,
+		client_id: &ClientId,
+		code_id: &[u8],
+		msg: &[u8],
+	) -> Result<Vec<u8>, ICS02Error> {
+		wasm::execute::<T>(client_id, code_id, &wasm::BlockInfo::current::<T>(), msg)
+			.map_err(|e| ICS02Error::implementation_specific(e.to_string()))
+	}
+
+	fn execute_wasm_substitute_contract(
+		&self,
+		subject_client_id: &ClientId,
+		substitute_client_id: &ClientId,
+		code_id: &[u8],
+		msg: &[u8],
+	) -> Result<Vec<u8>, ICS02Error> {
+		wasm::execute_substitute::<T>(
+			subject_client_id,
+			substitute_client_id,
+			code_id,
+			&wasm::BlockInfo::current::<T>(),
+			msg,
+		)
+		.map_err(|e| ICS02Error::implementation_specific(e.to_string()))
+	}
+
+	fn query_wasm_contract(
+		&self,
+		client_id: &ClientId,
+		code_id: &[u8],
+		msg: &[u8],
+	) -> Result<Vec<u8>, ICS02Error> {
+		wasm::query::<T>(client_id, code_id, &wasm::BlockInfo::current::<T>(), msg)
+			.map_err(|e| ICS02Error::implementation_specific(e.to_string()))
+	}
 }
 
 impl<T: Config> ClientTypes for Context<T> {
@@ -337,6 +375,9 @@ where
 			client_type
 		);
 
+		if !T::LightClients::supports(&client_type) {
+			return Err(ICS02Error::unknown_client_type(client_type))
+		}
 		let client_type = client_type.as_bytes().to_vec();
 		<Clients<T>>::insert(&client_id, client_type);
 		Ok(())
@@ -438,19 +479,19 @@ where
 					Err(ICS02Error::implementation_specific(format!("client state is frozen")))?
 				}
 
-				(client_state.relay_chain, client_state.para_id, client_state.latest_para_height)
+				(&client_state.relay_chain, client_state.para_id, client_state.latest_para_height)
 			},
 			AnyClientState::Grandpa(client_state) => {
 				if client_state.frozen_height.is_some() {
 					Err(ICS02Error::implementation_specific(format!("client state is frozen")))?
 				}
 
-				(client_state.relay_chain, client_state.para_id, client_state.latest_para_height)
+				(&client_state.relay_chain, client_state.para_id, client_state.latest_para_height)
 			},
 			client => Err(ICS02Error::unknown_client_type(format!("{}", client.client_type())))?,
 		};
 
-		if relay_chain != T::RelayChain::get() {
+		if !relay_chain.is_same_chain(&T::RelayChain::get()) {
 			log::warn!(target : "pallet_ibc", "configured relay {} differs from provided in data {}", T::RelayChain::get(), relay_chain);
 			Err(ICS02Error::implementation_specific(format!("relay chain mis-match")))?
 		}
@@ -39,12 +39,12 @@ where
 		})
 	}
 
-	fn is_send_enabled(&self) -> bool {
-		T::IsSendEnabled::get()
+	fn is_send_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+		!Pallet::<T>::is_send_paused(port_id, channel_id)
 	}
 
-	fn is_receive_enabled(&self) -> bool {
-		T::IsReceiveEnabled::get()
+	fn is_receive_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+		!Pallet::<T>::is_receive_paused(port_id, channel_id)
 	}
 }
 
@@ -243,6 +243,11 @@ where
 				};
 				T::Ics20RateLimiter::allow(&msg, FlowType::Deliver)
 					.map_err(|_| Ics04Error::implementation_specific("rate limiter".to_string()))?;
+				if Pallet::<T>::is_escrow_channel_blocked(&packet.destination_channel) {
+					return Err(Ics04Error::implementation_specific(
+						"channel is blocked".to_string(),
+					))
+				}
 				let amount = packet_data.token.amount.as_u256();
 				u128::try_from(amount)
 					.map_err(|e| Ics04Error::implementation_specific(format!("{:?}", e)))?;
@@ -273,6 +278,21 @@ where
 				let prefixed_denom = PrefixedDenom::from_str(&denom).map_err(|_| {
 					Ics04Error::implementation_specific("Failed to parse token denom".to_string())
 				})?;
+				if is_receiver_chain_source(
+					packet.source_port.clone(),
+					packet.source_channel,
+					&packet_data.token.denom,
+				) {
+					// Tokens have been released from the escrow account of the channel
+					Pallet::<T>::decrease_total_escrow(
+						&packet.destination_channel,
+						&PrefixedCoin {
+							denom: prefixed_denom.clone(),
+							amount: packet_data.token.amount,
+						},
+					)?;
+				}
+				Pallet::<T>::register_denom_metadata_on_receipt(&prefixed_denom);
 				Pallet::<T>::deposit_event(Event::<T>::TokenReceived {
 					from: packet_data.sender,
 					to: packet_data.receiver,
@@ -355,6 +375,7 @@ where
 					"error: acknowledgement error: {e}",
 				);
 				Self::refund_fee(packet, &packet_data)?;
+				Self::release_refunded_escrow(packet, &packet_data)?;
 				Pallet::<T>::deposit_event(Event::<T>::ChargingFeeFailedAcknowledgement {
 					sequence,
 				});
@@ -396,6 +417,7 @@ where
 			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
 		let sequence: u64 = packet.sequence.into();
 		Self::refund_fee(packet, &packet_data)?;
+		Self::release_refunded_escrow(packet, &packet_data)?;
 		Pallet::<T>::deposit_event(Event::<T>::ChargingFeeTimeout { sequence });
 
 		Pallet::<T>::deposit_event(Event::<T>::TokenTransferTimeout {
@@ -480,6 +502,22 @@ where
 			})?;
 		Ok(())
 	}
+
+	/// Tokens refunded to the sender after a failed transfer are released from the escrow account
+	/// of the channel if this chain is the source of the token
+	fn release_refunded_escrow(
+		packet: &Packet,
+		packet_data: &PacketData,
+	) -> Result<(), Ics04Error> {
+		if is_sender_chain_source(
+			packet.source_port.clone(),
+			packet.source_channel,
+			&packet_data.token.denom,
+		) {
+			Pallet::<T>::decrease_total_escrow(&packet.source_channel, &packet_data.token)?;
+		}
+		Ok(())
+	}
 }
 
 pub struct WeightHandler<T: Config>(PhantomData<T>);
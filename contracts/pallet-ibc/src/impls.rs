@@ -10,15 +10,22 @@ use crate::{
 	},
 	light_clients::AnyClientState,
 	routing::Context,
-	Acks, ChannelsConnection, Config, ConnectionClient, DenomToAssetId, Error, EscrowAddresses,
-	IbcAssets, Pallet, PendingRecvPacketSeqs, PendingSendPacketSeqs, RecvPackets, SendPackets,
-	MODULE_ID,
+	wasm, Acks, Any, ChannelPauseFlags, ChannelsConnection, Config, ConnectionClient,
+	DenomMetadata, DenomToAssetId, Error, EscrowAddresses, EscrowAuditCursor,
+	EscrowBlockedChannels, Event, IbcAssets, IbcDenomMetadata, Pallet, PauseFlags,
+	PendingRecvPacketSeqs, PendingSendPacketSeqs, PortPauseFlags, RecvPackets, SendPackets,
+	TotalEscrow, WeightInfo, MODULE_ID,
 };
 use codec::{Decode, Encode};
-use frame_support::traits::{fungibles::Inspect, Currency};
+use frame_support::{
+	traits::{fungibles::Inspect, Currency},
+	weights::Weight,
+	BoundedVec,
+};
 use ibc::{
 	applications::transfer::{
-		msgs::transfer::MsgTransfer, relay::send_transfer::send_transfer, PrefixedCoin,
+		is_sender_chain_source, msgs::transfer::MsgTransfer, relay::send_transfer::send_transfer,
+		PrefixedCoin, PrefixedDenom,
 	},
 	core::{
 		ics02_client::{
@@ -29,7 +36,11 @@ use ibc::{
 			channel::ChannelEnd,
 			context::{ChannelKeeper, ChannelReader},
 			error::Error as Ics04Error,
-			msgs::{chan_close_init::MsgChannelCloseInit, chan_open_init::MsgChannelOpenInit},
+			msgs::{
+				chan_close_init::MsgChannelCloseInit,
+				chan_open_init::MsgChannelOpenInit,
+				recv_packet::{self, MsgRecvPacket},
+			},
 			packet::{Packet, Sequence},
 		},
 		ics24_host::{
@@ -696,6 +707,24 @@ where
 			.nanoseconds();
 		Ok((update_height.revision_height, update_time))
 	}
+
+	/// Query the light client contract of a wasm client with a JSON encoded `QueryMsg`
+	pub fn query_wasm_client(client_id: Vec<u8>, msg: Vec<u8>) -> Result<Vec<u8>, Error<T>> {
+		let ctx = Context::<T>::default();
+		let client_id = client_id_from_bytes(client_id).map_err(|_| Error::<T>::DecodingError)?;
+		let client_state =
+			ctx.client_state(&client_id).map_err(|_| Error::<T>::ClientStateNotFound)?;
+		let code_id = match client_state {
+			AnyClientState::Wasm(client_state) => client_state.code_id,
+			_ => return Err(Error::<T>::InvalidParams),
+		};
+		wasm::query::<T>(&client_id, &code_id, &wasm::BlockInfo::current::<T>(), &msg).map_err(
+			|e| {
+				log::debug!(target: "pallet_ibc", "Failed to query the contract of {}: {}", client_id, e);
+				Error::<T>::Other
+			},
+		)
+	}
 }
 
 impl<T: Config> Pallet<T> {
@@ -727,6 +756,182 @@ impl<T: Config> Pallet<T> {
 		let set = EscrowAddresses::<T>::get();
 		set.contains(&address)
 	}
+
+	/// Amount of `coin` as recorded in the escrow totals
+	fn escrow_amount(coin: &PrefixedCoin) -> Result<u128, Ics04Error> {
+		u128::try_from(coin.amount.as_u256()).map_err(|_| {
+			Ics04Error::implementation_specific(format!(
+				"Escrowed amount {} of {} overflows u128",
+				coin.amount, coin.denom
+			))
+		})
+	}
+
+	/// Records tokens moved into the escrow account of a channel
+	pub(crate) fn increase_total_escrow(
+		channel_id: &ChannelId,
+		coin: &PrefixedCoin,
+	) -> Result<(), Ics04Error> {
+		let amount = Self::escrow_amount(coin)?;
+		TotalEscrow::<T>::mutate(
+			channel_id.to_string().as_bytes(),
+			coin.denom.to_string().as_bytes(),
+			|total| *total = total.saturating_add(amount),
+		);
+		Ok(())
+	}
+
+	/// Records tokens released from the escrow account of a channel
+	pub(crate) fn decrease_total_escrow(
+		channel_id: &ChannelId,
+		coin: &PrefixedCoin,
+	) -> Result<(), Ics04Error> {
+		let amount = Self::escrow_amount(coin)?;
+		TotalEscrow::<T>::mutate_exists(
+			channel_id.to_string().as_bytes(),
+			coin.denom.to_string().as_bytes(),
+			|total| {
+				let remaining = total.unwrap_or_default().saturating_sub(amount);
+				*total = (remaining != 0).then_some(remaining);
+			},
+		);
+		Ok(())
+	}
+
+	/// Returns the total amount escrowed on a channel for every denom
+	pub fn get_total_escrow(channel_id: Vec<u8>) -> Vec<(Vec<u8>, u128)> {
+		TotalEscrow::<T>::iter_prefix(channel_id).collect()
+	}
+
+	/// Returns true if the channel was blocked after an escrow invariant violation
+	pub fn is_escrow_channel_blocked(channel_id: &ChannelId) -> bool {
+		EscrowBlockedChannels::<T>::contains_key(channel_id.to_string().as_bytes())
+	}
+
+	/// Traffic paused on a channel, either directly or through its port
+	pub fn pause_flags(port_id: &PortId, channel_id: &ChannelId) -> PauseFlags {
+		let port_id = port_id.as_bytes();
+		let port_flags = PortPauseFlags::<T>::get(port_id);
+		let channel_flags = ChannelPauseFlags::<T>::get(port_id, channel_id.to_string().as_bytes());
+		PauseFlags {
+			send: port_flags.send || channel_flags.send,
+			receive: port_flags.receive || channel_flags.receive,
+		}
+	}
+
+	/// Returns true if outgoing packets are rejected on this channel
+	pub fn is_send_paused(port_id: &PortId, channel_id: &ChannelId) -> bool {
+		Self::pause_flags(port_id, channel_id).send
+	}
+
+	/// Returns true if incoming packets are rejected on this channel
+	pub fn is_receive_paused(port_id: &PortId, channel_id: &ChannelId) -> bool {
+		Self::pause_flags(port_id, channel_id).receive
+	}
+
+	/// Returns true if the message is a packet that must not be delivered because receiving is
+	/// paused on its destination channel.
+	/// ICS-20 packets are let through so that they get acknowledged with an error and refunded on
+	/// the sender chain, packets of other applications are left unreceived until receiving
+	/// resumes.
+	pub(crate) fn is_recv_packet_paused(message: &Any) -> bool {
+		if message.type_url != recv_packet::TYPE_URL {
+			return false
+		}
+		match MsgRecvPacket::decode_vec(&message.value) {
+			Ok(msg) =>
+				msg.packet.destination_port != PortId::transfer() &&
+					Self::is_receive_paused(
+						&msg.packet.destination_port,
+						&msg.packet.destination_channel,
+					),
+			Err(_) => false,
+		}
+	}
+
+	/// Current balance of the escrow account of a channel for an ibc denom
+	fn escrow_balance(channel_id: &[u8], ibc_denom: &[u8]) -> Option<T::Balance> {
+		let channel_id = channel_id_from_bytes(channel_id.to_vec()).ok()?;
+		let escrow_address = get_channel_escrow_address(&PortId::transfer(), channel_id).ok()?;
+		let account_id = T::AccountIdConversion::try_from(escrow_address).ok()?.into_account();
+		let denom = String::from_utf8(ibc_denom.to_vec()).ok()?;
+		let asset_id = T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom).ok()?;
+		let balance = if asset_id == T::NativeAssetId::get() {
+			<T::NativeCurrency as Currency<T::AccountId>>::free_balance(&account_id)
+		} else {
+			<T::Fungibles as Inspect<T::AccountId>>::balance(asset_id, &account_id)
+		};
+		Some(balance)
+	}
+
+	/// Returns `(channel_id, ibc_denom, expected, actual)` for every channel escrow account
+	/// holding less than the total recorded as escrowed. Escrow accounts holding more than the
+	/// recorded total are not reported since anyone can deposit into them.
+	pub fn escrow_shortfalls() -> Vec<(Vec<u8>, Vec<u8>, T::Balance, T::Balance)> {
+		TotalEscrow::<T>::iter()
+			.filter_map(|(channel_id, ibc_denom, total)| {
+				let expected: T::Balance = total.into();
+				let actual = Self::escrow_balance(&channel_id, &ibc_denom).unwrap_or_default();
+				(actual < expected).then_some((channel_id, ibc_denom, expected, actual))
+			})
+			.collect()
+	}
+
+	/// Audits as many escrow totals as `max_weight` allows, raising an event (and blocking the
+	/// channel if `Config::BlockChannelOnEscrowDrift` is set) for each shortfall found.
+	/// An audit that runs out of weight is resumed by the next one from where it stopped.
+	/// Channels that are already blocked are skipped until they are unblocked.
+	/// Returns the number of entries audited.
+	pub(crate) fn audit_escrows(max_weight: Weight) -> u32 {
+		let weight_per_entry = T::WeightInfo::one_escrow_audit();
+		let mut consumed = Weight::zero();
+		let mut audited = 0;
+		let mut entries = match EscrowAuditCursor::<T>::take() {
+			Some(cursor) => TotalEscrow::<T>::iter_from(cursor),
+			None => TotalEscrow::<T>::iter(),
+		};
+		loop {
+			if consumed.saturating_add(weight_per_entry).any_gt(max_weight) {
+				EscrowAuditCursor::<T>::put(entries.last_raw_key().to_vec());
+				break
+			}
+			let (channel_id, ibc_denom, total) = match entries.next() {
+				Some(entry) => entry,
+				None => break,
+			};
+			consumed = consumed.saturating_add(weight_per_entry);
+			if EscrowBlockedChannels::<T>::contains_key(&channel_id) {
+				continue
+			}
+			audited += 1;
+
+			let expected: T::Balance = total.into();
+			let actual = Self::escrow_balance(&channel_id, &ibc_denom).unwrap_or_default();
+			if actual >= expected {
+				continue
+			}
+			log::warn!(
+				target: "pallet_ibc",
+				"Escrow invariant violated on {:?} for {:?}: expected {:?}, found {:?}",
+				String::from_utf8_lossy(&channel_id),
+				String::from_utf8_lossy(&ibc_denom),
+				expected,
+				actual
+			);
+			let channel_blocked = T::BlockChannelOnEscrowDrift::get();
+			if channel_blocked {
+				EscrowBlockedChannels::<T>::insert(&channel_id, ());
+			}
+			Self::deposit_event(Event::<T>::EscrowInvariantViolated {
+				channel_id,
+				ibc_denom,
+				expected,
+				actual,
+				channel_blocked,
+			});
+		}
+		audited
+	}
 }
 
 impl<T: Config> Pallet<T> {
@@ -751,6 +956,54 @@ impl<T: Config> Pallet<T> {
 			next_key: next_id.map(|key| key.encode()),
 		}
 	}
+
+	pub fn get_denom_metadata(
+		asset_id: T::AssetId,
+	) -> Option<ibc_primitives::QueryDenomMetadataResponse> {
+		let denom = T::IbcDenomToAssetIdConversion::from_asset_id_to_denom(asset_id)?;
+		let DenomMetadata { name, symbol, decimals } =
+			IbcDenomMetadata::<T>::get(denom.as_bytes())?;
+		let (name, symbol) = (name.into_inner(), symbol.into_inner());
+		let prefixed_denom = PrefixedDenom::from_str(&denom).ok()?;
+		Some(ibc_primitives::QueryDenomMetadataResponse {
+			denom: denom.into_bytes(),
+			base_denom: prefixed_denom.base_denom().to_string().into_bytes(),
+			trace_path: prefixed_denom.trace_path().to_string().into_bytes(),
+			name,
+			symbol,
+			decimals,
+		})
+	}
+
+	/// Parses `ibc_denom` as a prefixed denom and returns its canonical encoding, which is the key
+	/// of `IbcDenomMetadata`.
+	pub(crate) fn normalize_ibc_denom(ibc_denom: Vec<u8>) -> Result<Vec<u8>, Error<T>> {
+		let denom = String::from_utf8(ibc_denom).map_err(|_| Error::<T>::Utf8Error)?;
+		let denom = PrefixedDenom::from_str(&denom).map_err(|_| Error::<T>::PrefixedDenomParse)?;
+		Ok(denom.to_string().into_bytes())
+	}
+
+	/// Registers default metadata for a voucher denom received for the first time, if enabled
+	/// through `Config::DefaultDenomDecimals`. Native denoms returning home are skipped and
+	/// metadata set by governance is never overwritten.
+	pub(crate) fn register_denom_metadata_on_receipt(denom: &PrefixedDenom) {
+		let decimals = match T::DefaultDenomDecimals::get() {
+			Some(decimals) if !denom.trace_path().is_empty() => decimals,
+			_ => return,
+		};
+		let ibc_denom = denom.to_string().into_bytes();
+		if IbcDenomMetadata::<T>::contains_key(&ibc_denom) {
+			return
+		}
+		// Long denoms are truncated, governance can set a better name and symbol
+		let metadata = DenomMetadata {
+			name: BoundedVec::truncate_from(ibc_denom.clone()),
+			symbol: BoundedVec::truncate_from(denom.base_denom().to_string().into_bytes()),
+			decimals,
+		};
+		IbcDenomMetadata::<T>::insert(&ibc_denom, metadata.clone());
+		Self::deposit_event(Event::<T>::DenomMetadataSet { ibc_denom, metadata });
+	}
 }
 
 impl<T: Config + Send + Sync> IbcHandler<<T as frame_system::Config>::AccountId> for Pallet<T>
@@ -1050,8 +1303,15 @@ where
 	pub(crate) fn send_transfer(msg: MsgTransfer<PrefixedCoin>) -> Result<(), IbcHandlerError> {
 		let mut ctx = Context::<T>::default();
 		let mut handler_output = HandlerOutputBuilder::default();
+		let is_sender_source =
+			is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &msg.token.denom);
+		let (source_channel, token) = (msg.source_channel, msg.token.clone());
 		send_transfer::<_, _>(&mut ctx, &mut handler_output, msg)
 			.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
+		if is_sender_source {
+			Self::increase_total_escrow(&source_channel, &token)
+				.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
+		}
 		let result = handler_output.with_result(());
 		Self::deposit_event(result.events.into());
 		Ok(())
@@ -33,6 +33,7 @@ extern crate alloc;
 use codec::{Decode, Encode};
 use core::fmt::Debug;
 use cumulus_primitives_core::ParaId;
+use frame_support::{traits::ConstU32, BoundedVec};
 pub use pallet::*;
 use scale_info::{
 	prelude::{
@@ -55,6 +56,7 @@ mod ics23;
 pub mod light_clients;
 mod port;
 pub mod routing;
+mod wasm;
 pub use client::HostConsensusProof;
 pub use ibc_primitives::Timeout;
 pub use light_client_common;
@@ -110,6 +112,43 @@ pub struct TransferParams<AccountId> {
 	pub timeout: Timeout,
 }
 
+/// Maximum length of a denom name in bytes
+pub const MAX_DENOM_NAME_LEN: u32 = 128;
+/// Maximum length of a denom symbol in bytes
+pub const MAX_DENOM_SYMBOL_LEN: u32 = 32;
+
+/// Human readable metadata attached to an ibc denom
+#[derive(
+	frame_support::RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo, Encode, Decode, Clone,
+)]
+pub struct DenomMetadata {
+	/// Full name of the token
+	pub name: BoundedVec<u8, ConstU32<MAX_DENOM_NAME_LEN>>,
+	/// Ticker symbol of the token
+	pub symbol: BoundedVec<u8, ConstU32<MAX_DENOM_SYMBOL_LEN>>,
+	/// Number of decimals of the base denom
+	pub decimals: u8,
+}
+
+/// Traffic paused on a port or a channel
+#[derive(
+	frame_support::RuntimeDebug,
+	PartialEq,
+	Eq,
+	scale_info::TypeInfo,
+	Encode,
+	Decode,
+	Clone,
+	Copy,
+	Default,
+)]
+pub struct PauseFlags {
+	/// Outgoing packets are rejected
+	pub send: bool,
+	/// Incoming packets are rejected
+	pub receive: bool,
+}
+
 #[derive(
 	frame_support::RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo, Encode, Decode, Clone,
 )]
@@ -130,7 +169,7 @@ pub mod ics20_fee;
 mod impls;
 pub mod weight;
 
-pub use weight::WeightInfo;
+pub use weight::{LightClient, LightClientRegistry, WeightInfo};
 
 use crate::{
 	ics20::{FlowType, Ics20RateLimiter},
@@ -174,7 +213,10 @@ pub mod pallet {
 		timestamp::Timestamp,
 		Height,
 	};
-	use ibc_primitives::{client_id_from_bytes, get_channel_escrow_address, IbcHandler};
+	use ibc_primitives::{
+		channel_id_from_bytes, client_id_from_bytes, get_channel_escrow_address,
+		port_id_from_bytes, IbcHandler,
+	};
 	use light_clients::AnyClientState;
 	use sp_runtime::{
 		traits::{IdentifyAccount, Saturating, Zero},
@@ -245,6 +287,9 @@ pub mod pallet {
 		type RelayChain: Get<light_client_common::RelayChain>;
 		/// benchmarking weight info
 		type WeightInfo: WeightInfo;
+		/// Light clients supported by the runtime, e.g.
+		/// `(TendermintWeights, GrandpaWeights, WasmWeights)`
+		type LightClients: LightClientRegistry<Self>;
 		/// Origin allowed to unfreeze light clients
 		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 		/// Origin allowed to freeze light clients
@@ -267,8 +312,6 @@ pub mod pallet {
 			+ Clone
 			+ Eq;
 
-		type IsSendEnabled: Get<bool>;
-		type IsReceiveEnabled: Get<bool>;
 		type FeeAccount: Get<Self::AccountIdConversion>;
 		/// Cleanup packets period (in blocks)
 		#[pallet::constant]
@@ -297,6 +340,32 @@ pub mod pallet {
 		type FlatFeeAssetId: Get<Self::AssetId>;
 		//Asset amount that will be charged. for example 10 (USDT)
 		type FlatFeeAmount: Get<Self::Balance>;
+
+		/// Origin allowed to set the metadata of ibc denoms, e.g. governance or a trusted oracle
+		type DenomMetadataOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Decimals used to register metadata for ibc denoms received for the first time.
+		/// Automatic registration is disabled when `None`.
+		#[pallet::constant]
+		type DefaultDenomDecimals: Get<Option<u8>>;
+
+		/// Period (in blocks) at which channel escrow balances are audited against the amounts
+		/// recorded as escrowed
+		#[pallet::constant]
+		type EscrowAuditPeriod: Get<Self::BlockNumber>;
+		/// Block a channel when the balance of its escrow account falls short of the amount
+		/// recorded as escrowed
+		#[pallet::constant]
+		type BlockChannelOnEscrowDrift: Get<bool>;
+
+		/// Maximum amount of fuel a call to an 08-wasm light client contract may consume
+		#[pallet::constant]
+		type MaxWasmFuel: Get<u64>;
+		/// Weight of a unit of fuel consumed by 08-wasm light client contracts
+		#[pallet::constant]
+		type WeightPerWasmFuel: Get<Weight>;
+		/// Maximum size in bytes of the code of an 08-wasm light client contract
+		#[pallet::constant]
+		type MaxWasmCodeSize: Get<u32>;
 	}
 
 	#[pallet::pallet]
@@ -389,6 +458,12 @@ pub mod pallet {
 	pub type IbcDenoms<T: Config> =
 		CountedStorageMap<_, Twox64Concat, Vec<u8>, T::AssetId, OptionQuery>;
 
+	#[pallet::storage]
+	/// Map of ibc denom to its human readable metadata
+	/// ibc denoms represented as utf8 string bytes
+	pub type IbcDenomMetadata<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, DenomMetadata, OptionQuery>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	/// ChannelIds open from this module
@@ -400,6 +475,43 @@ pub mod pallet {
 	pub type EscrowAddresses<T: Config> =
 		StorageValue<_, BTreeSet<<T as frame_system::Config>::AccountId>, ValueQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// channel_id, ibc denom => Total amount of tokens escrowed on the channel
+	/// ibc denoms represented as utf8 string bytes
+	pub type TotalEscrow<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, Vec<u8>, u128, ValueQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// Raw storage key of the last escrow total audited, the next audit resumes after it
+	pub type EscrowAuditCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// Channels blocked after the balance of their escrow account drifted from the total recorded
+	pub type EscrowBlockedChannels<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, (), OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id => Traffic paused on every channel of the port
+	pub type PortPauseFlags<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, PauseFlags, ValueQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id, channel_id => Traffic paused on the channel
+	pub type ChannelPauseFlags<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		PauseFlags,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	/// Consensus heights
@@ -441,6 +553,11 @@ pub mod pallet {
 	pub type PendingRecvPacketSeqs<T: Config> =
 		StorageMap<_, Blake2_128Concat, (Vec<u8>, Vec<u8>), (BTreeSet<u64>, u64), ValueQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// Code of the 08-wasm light client contracts, keyed by their sha256 checksum
+	pub type WasmCodes<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, Vec<u8>, OptionQuery>;
+
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub struct AssetConfig<AssetId> {
 		pub id: AssetId,
@@ -588,6 +705,47 @@ pub mod pallet {
 		ChargingFeeFailedAcknowledgement {
 			sequence: u64,
 		},
+		/// Metadata of an ibc denom has been set
+		DenomMetadataSet {
+			ibc_denom: Vec<u8>,
+			metadata: DenomMetadata,
+		},
+		/// Metadata of an ibc denom has been removed
+		DenomMetadataRemoved {
+			ibc_denom: Vec<u8>,
+		},
+		/// The balance of a channel escrow account is lower than the total recorded as escrowed
+		EscrowInvariantViolated {
+			channel_id: Vec<u8>,
+			ibc_denom: Vec<u8>,
+			expected: T::Balance,
+			actual: T::Balance,
+			channel_blocked: bool,
+		},
+		/// A channel blocked after an escrow invariant violation has been unblocked
+		EscrowChannelUnblocked {
+			channel_id: Vec<u8>,
+		},
+		/// Traffic paused on a port has been updated
+		PortPauseFlagsSet {
+			port_id: Vec<u8>,
+			flags: PauseFlags,
+		},
+		/// Traffic paused on a channel has been updated
+		ChannelPauseFlagsSet {
+			port_id: Vec<u8>,
+			channel_id: Vec<u8>,
+			flags: PauseFlags,
+		},
+		/// The code of an 08-wasm light client contract has been stored
+		WasmCodeUploaded {
+			code_id: Vec<u8>,
+		},
+		/// An 08-wasm client has been migrated to new light client contract code
+		WasmClientMigrated {
+			client_id: Vec<u8>,
+			code_id: Vec<u8>,
+		},
 	}
 
 	/// Errors inform users that something went wrong.
@@ -666,6 +824,28 @@ pub mod pallet {
 		FailedSendFeeToAccount,
 		//Failed to derive origin sender address.
 		OriginAddress,
+		/// No metadata registered for the ibc denom
+		DenomMetadataNotFound,
+		/// Channel is blocked
+		ChannelBlocked,
+		/// Sending is paused on this port or channel
+		SendPaused,
+		/// The code isn't a valid light client contract
+		InvalidWasmCode,
+		/// The code has already been uploaded
+		WasmCodeExists,
+		/// The code is larger than `MaxWasmCodeSize`
+		WasmCodeTooLarge,
+		/// No code was uploaded with this code id, or the code doesn't match its checksum
+		WasmCodeNotFound,
+		/// The client isn't an 08-wasm client
+		NotWasmClient,
+		/// The client already uses this code
+		WasmCodeUnchanged,
+		/// The migrate entry point of the contract failed
+		WasmMigrationFailed,
+		/// The client state pointing at the new code could not be stored
+		WasmClientUpdateFailed,
 	}
 
 	#[pallet::hooks]
@@ -675,7 +855,13 @@ pub mod pallet {
 		T: Send + Sync,
 		AccountId32: From<<T as frame_system::Config>::AccountId>,
 	{
-		fn on_idle(n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+		fn on_idle(n: BlockNumberFor<T>, mut remaining_weight: Weight) -> Weight {
+			if n % T::EscrowAuditPeriod::get() == T::BlockNumber::zero() {
+				log::trace!(target: "pallet_ibc", "Auditing channel escrows");
+				let audited_count = Pallet::<T>::audit_escrows(remaining_weight) as u64;
+				remaining_weight = remaining_weight
+					.saturating_sub(T::WeightInfo::one_escrow_audit() * audited_count);
+			}
 			if n % T::CleanUpPacketsPeriod::get() != T::BlockNumber::zero() {
 				return remaining_weight
 			}
@@ -691,6 +877,15 @@ pub mod pallet {
 		}
 
 		fn offchain_worker(_n: BlockNumberFor<T>) {}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), &'static str> {
+			if Pallet::<T>::escrow_shortfalls().is_empty() {
+				Ok(())
+			} else {
+				Err("Channel escrow balance is lower than the total recorded as escrowed")
+			}
+		}
 	}
 
 	// Dispatch able functions allows users to interact with the pallet and invoke state changes.
@@ -706,11 +901,12 @@ pub mod pallet {
 		#[pallet::call_index(0)]
 		#[pallet::weight(crate::weight::deliver::< T > (messages))]
 		#[frame_support::transactional]
-		pub fn deliver(origin: OriginFor<T>, messages: Vec<Any>) -> DispatchResult {
+		pub fn deliver(origin: OriginFor<T>, messages: Vec<Any>) -> DispatchResultWithPostInfo {
 			use ibc::core::{
 				ics02_client::msgs::create_client, ics03_connection::msgs::conn_open_init,
 			};
 			let sender = T::RelayerOrigin::ensure_origin(origin)?;
+			let weight = crate::weight::deliver::<T>(&messages);
 
 			// reserve a fixed deposit for every client and connection created
 			// so people don't spam our chain with useless clients.
@@ -719,6 +915,10 @@ pub mod pallet {
 			let messages = messages
 				.into_iter()
 				.filter_map(|message| {
+					if Pallet::<T>::is_recv_packet_paused(&message) {
+						log::trace!(target: "pallet_ibc", "Receiving is paused, skipping packet");
+						return None
+					}
 					if matches!(
 						message.type_url.as_str(),
 						create_client::TYPE_URL |
@@ -741,14 +941,27 @@ pub mod pallet {
 					<T as frame_system::Config>::AccountId,
 				>>::reserve(&sender, reserve_amt.into())?;
 			}
+			wasm::take_unused_fuel();
 			Self::execute_ibc_messages(&mut ctx, messages);
 
-			Ok(())
+			// light client contracts are charged for their maximum fuel, the fuel they didn't
+			// consume is refunded
+			let unused_fuel = wasm::take_unused_fuel();
+			if unused_fuel == 0 {
+				return Ok(().into())
+			}
+			let refund = T::WeightPerWasmFuel::get().saturating_mul(unused_fuel);
+			Ok(Some(weight.saturating_sub(refund)).into())
 		}
 
 		#[pallet::call_index(1)]
 		#[frame_support::transactional]
-		#[pallet::weight(<T as Config>::WeightInfo::transfer())]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer().saturating_add(
+			crate::weight::send_packet::<T>(
+				PortId::transfer().as_str(),
+				&ChannelId::new(params.source_channel).to_string(),
+			)
+		))]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			params: TransferParams<<T as frame_system::Config>::AccountId>,
@@ -818,6 +1031,15 @@ pub mod pallet {
 				return Err(Error::<T>::InvalidTimestamp.into())
 			}
 
+			ensure!(
+				!EscrowBlockedChannels::<T>::contains_key(source_channel.to_string().as_bytes()),
+				Error::<T>::ChannelBlocked
+			);
+			ensure!(
+				!Pallet::<T>::is_send_paused(&source_port, &source_channel),
+				Error::<T>::SendPaused
+			);
+
 			let mut ctx = Context::<T>::default();
 			let channel_end = ctx
 				.channel_end(&(PortId::transfer(), source_channel))
@@ -1006,7 +1228,8 @@ pub mod pallet {
 			Ok(())
 		}
 
-		/// Freeze a client at a specific height
+		/// Freeze a client at a specific height. `08-wasm` clients can only be frozen if they wrap
+		/// a client type known to the runtime.
 		#[pallet::call_index(4)]
 		#[pallet::weight(0)]
 		pub fn freeze_client(
@@ -1021,40 +1244,10 @@ pub mod pallet {
 			let mut ctx = routing::Context::<T>::default();
 			let client_state =
 				ctx.client_state(&client_id).map_err(|_| Error::<T>::ClientStateNotFound)?;
-			let frozen_state = match client_state {
-				AnyClientState::Grandpa(grandpa) => {
-					let latest_height = grandpa.latest_height();
-					AnyClientState::wrap(
-						&grandpa
-							.with_frozen_height(Height::new(latest_height.revision_number, height))
-							.map_err(|_| Error::<T>::ClientFreezeFailed)?,
-					)
-				},
-				AnyClientState::Beefy(beefy) => {
-					let latest_height = beefy.latest_height();
-					AnyClientState::wrap(
-						&beefy
-							.with_frozen_height(Height::new(latest_height.revision_number, height))
-							.map_err(|_| Error::<T>::ClientFreezeFailed)?,
-					)
-				},
-				AnyClientState::Tendermint(tm) => {
-					let latest_height = tm.latest_height();
-					AnyClientState::wrap(
-						&tm.with_frozen_height(Height::new(latest_height.revision_number, height))
-							.map_err(|_| Error::<T>::ClientFreezeFailed)?,
-					)
-				},
-				AnyClientState::Wasm(_) => return Err(Error::<T>::ClientFreezeFailed.into()),
-				#[cfg(test)]
-				AnyClientState::Mock(mut ms) => {
-					ms.frozen_height =
-						Some(Height::new(ms.latest_height().revision_number, height));
-					AnyClientState::wrap(&ms)
-				},
-			}
-			.ok_or_else(|| Error::<T>::ClientFreezeFailed)?;
-			let revision_number = frozen_state.latest_height().revision_number;
+			let revision_number = client_state.latest_height().revision_number;
+			let frozen_state = client_state
+				.with_frozen_height(Height::new(revision_number, height))
+				.map_err(|_| Error::<T>::ClientFreezeFailed)?;
 			ctx.store_client_state(client_id.clone(), frozen_state)
 				.map_err(|_| Error::<T>::ClientFreezeFailed)?;
 
@@ -1127,6 +1320,162 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Attach human readable metadata to an ibc denom, overwriting any existing metadata.
+		/// `ibc_denom` is the full denom including its trace path, e.g. `transfer/channel-0/uatom`.
+		#[pallet::call_index(8)]
+		#[pallet::weight(0)]
+		pub fn set_denom_metadata(
+			origin: OriginFor<T>,
+			ibc_denom: Vec<u8>,
+			metadata: DenomMetadata,
+		) -> DispatchResult {
+			T::DenomMetadataOrigin::ensure_origin(origin)?;
+			let ibc_denom = Self::normalize_ibc_denom(ibc_denom)?;
+
+			IbcDenomMetadata::<T>::insert(&ibc_denom, metadata.clone());
+			Self::deposit_event(Event::<T>::DenomMetadataSet { ibc_denom, metadata });
+
+			Ok(())
+		}
+
+		/// Remove the metadata attached to an ibc denom.
+		/// `ibc_denom` is normalised the same way as in `set_denom_metadata`.
+		#[pallet::call_index(9)]
+		#[pallet::weight(0)]
+		pub fn remove_denom_metadata(origin: OriginFor<T>, ibc_denom: Vec<u8>) -> DispatchResult {
+			T::DenomMetadataOrigin::ensure_origin(origin)?;
+			let ibc_denom = Self::normalize_ibc_denom(ibc_denom)?;
+			IbcDenomMetadata::<T>::take(&ibc_denom).ok_or(Error::<T>::DenomMetadataNotFound)?;
+			Self::deposit_event(Event::<T>::DenomMetadataRemoved { ibc_denom });
+
+			Ok(())
+		}
+
+		/// Unblock a channel that was blocked after its escrow balance drifted from the total
+		/// recorded as escrowed.
+		#[pallet::call_index(10)]
+		#[pallet::weight(0)]
+		pub fn unblock_escrow_channel(origin: OriginFor<T>, channel_id: Vec<u8>) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			EscrowBlockedChannels::<T>::take(&channel_id).ok_or(Error::<T>::InvalidChannelId)?;
+			Self::deposit_event(Event::<T>::EscrowChannelUnblocked { channel_id });
+
+			Ok(())
+		}
+
+		/// Pause or resume sending and receiving packets on every channel of a port.
+		#[pallet::call_index(11)]
+		#[pallet::weight(0)]
+		pub fn set_port_pause_flags(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			flags: PauseFlags,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			port_id_from_bytes(port_id.clone()).map_err(|_| Error::<T>::InvalidPortId)?;
+			if flags == PauseFlags::default() {
+				PortPauseFlags::<T>::remove(&port_id);
+			} else {
+				PortPauseFlags::<T>::insert(&port_id, flags);
+			}
+			Self::deposit_event(Event::<T>::PortPauseFlagsSet { port_id, flags });
+
+			Ok(())
+		}
+
+		/// Pause or resume sending and receiving packets on a channel.
+		#[pallet::call_index(12)]
+		#[pallet::weight(0)]
+		pub fn set_channel_pause_flags(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			channel_id: Vec<u8>,
+			flags: PauseFlags,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			port_id_from_bytes(port_id.clone()).map_err(|_| Error::<T>::InvalidPortId)?;
+			channel_id_from_bytes(channel_id.clone()).map_err(|_| Error::<T>::InvalidChannelId)?;
+			if flags == PauseFlags::default() {
+				ChannelPauseFlags::<T>::remove(&port_id, &channel_id);
+			} else {
+				ChannelPauseFlags::<T>::insert(&port_id, &channel_id, flags);
+			}
+			Self::deposit_event(Event::<T>::ChannelPauseFlagsSet { port_id, channel_id, flags });
+
+			Ok(())
+		}
+
+		/// Store the code of an 08-wasm light client contract. Wasm clients whose `code_id` is the
+		/// sha256 checksum of the code are verified by the contract.
+		#[pallet::call_index(13)]
+		#[pallet::weight(<T as Config>::WeightInfo::upload_wasm(code.len() as u32))]
+		pub fn upload_wasm(origin: OriginFor<T>, code: Vec<u8>) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			ensure!(code.len() <= T::MaxWasmCodeSize::get() as usize, Error::<T>::WasmCodeTooLarge);
+			let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+			ensure!(!WasmCodes::<T>::contains_key(&code_id), Error::<T>::WasmCodeExists);
+			wasm::validate_code(&code).map_err(|e| {
+				log::debug!(target: "pallet_ibc", "Invalid light client contract: {}", e);
+				Error::<T>::InvalidWasmCode
+			})?;
+			WasmCodes::<T>::insert(&code_id, code);
+			Self::deposit_event(Event::<T>::WasmCodeUploaded { code_id });
+
+			Ok(())
+		}
+
+		/// Point an 08-wasm client at the uploaded contract with code id `code_id`, then run the
+		/// `migrate` entry point of the contract with the JSON encoded `migrate_msg`, unless it is
+		/// empty.
+		#[pallet::call_index(14)]
+		#[pallet::weight(crate::weight::migrate_wasm_client::<T>(migrate_msg))]
+		#[frame_support::transactional]
+		pub fn migrate_wasm_client(
+			origin: OriginFor<T>,
+			client_id: Vec<u8>,
+			code_id: Vec<u8>,
+			migrate_msg: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let client_id =
+				client_id_from_bytes(client_id).map_err(|_| Error::<T>::DecodingError)?;
+			ensure!(WasmCodes::<T>::contains_key(&code_id), Error::<T>::WasmCodeNotFound);
+			let mut ctx = routing::Context::<T>::default();
+			let client_state =
+				match ctx.client_state(&client_id).map_err(|_| Error::<T>::ClientStateNotFound)? {
+					AnyClientState::Wasm(client_state) => client_state,
+					_ => return Err(Error::<T>::NotWasmClient.into()),
+				};
+			ensure!(client_state.code_id != code_id, Error::<T>::WasmCodeUnchanged);
+			let client_state = AnyClientState::Wasm(ics08_wasm::client_state::ClientState {
+				code_id: code_id.clone(),
+				..client_state
+			});
+			ctx.store_client_state(client_id.clone(), client_state)
+				.map_err(|_| Error::<T>::WasmClientUpdateFailed)?;
+			let weight = crate::weight::migrate_wasm_client::<T>(&migrate_msg);
+			wasm::take_unused_fuel();
+			if !migrate_msg.is_empty() {
+				wasm::migrate::<T>(
+					&client_id,
+					&code_id,
+					&wasm::BlockInfo::current::<T>(),
+					&migrate_msg,
+				)
+				.map_err(|e| {
+					log::debug!(target: "pallet_ibc", "Failed to migrate {}: {}", client_id, e);
+					Error::<T>::WasmMigrationFailed
+				})?;
+			}
+			Self::deposit_event(Event::<T>::WasmClientMigrated {
+				client_id: client_id.as_bytes().to_vec(),
+				code_id,
+			});
+
+			let refund = T::WeightPerWasmFuel::get().saturating_mul(wasm::take_unused_fuel());
+			Ok(Some(weight.saturating_sub(refund)).into())
+		}
 	}
 }
 
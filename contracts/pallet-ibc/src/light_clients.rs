@@ -3,15 +3,8 @@ use frame_support::{
 	pallet_prelude::{StorageValue, ValueQuery},
 	traits::StorageInstance,
 };
-use ibc::{
-	core::{
-		ics02_client,
-		ics02_client::{
-			client_consensus::ConsensusState, client_message::ClientMessage,
-			client_state::ClientState,
-		},
-	},
-	Height,
+use ibc::core::ics02_client::{
+	client_consensus::ConsensusState, client_message::ClientMessage, client_state::ClientState,
 };
 use ibc_derive::{ClientDef, ClientMessage, ClientState, ConsensusState, Protobuf};
 use ibc_primitives::runtime_interface;
@@ -44,6 +37,14 @@ use ics11_beefy::{
 	client_message::BEEFY_CLIENT_MESSAGE_TYPE_URL, client_state::BEEFY_CLIENT_STATE_TYPE_URL,
 	consensus_state::BEEFY_CONSENSUS_STATE_TYPE_URL,
 };
+use ics12_ethereum::{
+	client_message::{
+		ETHEREUM_CLIENT_MESSAGE_TYPE_URL, ETHEREUM_HEADER_TYPE_URL, ETHEREUM_MISBEHAVIOUR_TYPE_URL,
+	},
+	client_state::ETHEREUM_CLIENT_STATE_TYPE_URL,
+	consensus_state::ETHEREUM_CONSENSUS_STATE_TYPE_URL,
+	types::{BlsPublicKey, BlsSignature},
+};
 use prost::Message;
 use sp_core::{crypto::ByteArray, ed25519, H256};
 use sp_runtime::{
@@ -162,6 +163,14 @@ impl grandpa_client_primitives::HostFunctions for HostFunctionsManager {
 		pub_key.verify(&msg, sig)
 	}
 
+	fn ed25519_batch_verify(batch: &[(ed25519::Signature, Vec<u8>, ed25519::Public)]) -> bool {
+		// `sp_io`'s batch verification can't be nested in the block-wide signature batch of the
+		// executive, so the signatures are checked one by one through the standard host function.
+		batch
+			.iter()
+			.all(|(sig, msg, pub_key)| sp_io::crypto::ed25519_verify(sig, msg, pub_key))
+	}
+
 	fn insert_relay_header_hashes(new_hashes: &[<Self::Header as Header>::Hash]) {
 		if new_hashes.is_empty() {
 			return
@@ -212,13 +221,32 @@ impl beefy_client_primitives::HostFunctions for HostFunctionsManager {
 	}
 }
 
+impl ics12_ethereum::HostFunctions for HostFunctionsManager {
+	fn sha256(input: &[u8]) -> [u8; 32] {
+		sp_io::hashing::sha2_256(input)
+	}
+
+	fn keccak_256(input: &[u8]) -> [u8; 32] {
+		sp_io::hashing::keccak_256(input)
+	}
+
+	fn bls12_381_fast_aggregate_verify(
+		public_keys: &[BlsPublicKey],
+		message: &[u8; 32],
+		signature: &BlsSignature,
+	) -> bool {
+		runtime_interface::bls12_381_fast_aggregate_verify(public_keys, message, signature)
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, ClientDef)]
 pub enum AnyClient {
 	Grandpa(ics10_grandpa::client_def::GrandpaClient<HostFunctionsManager>),
 	Beefy(ics11_beefy::client_def::BeefyClient<HostFunctionsManager>),
+	Ethereum(ics12_ethereum::client_def::EthereumClient<HostFunctionsManager>),
 	Tendermint(ics07_tendermint::client_def::TendermintClient<HostFunctionsManager>),
 	Wasm(ics08_wasm::client_def::WasmClient<AnyClient, AnyClientState, AnyConsensusState>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	Mock(ibc::mock::client_def::MockClient),
 }
 
@@ -226,23 +254,29 @@ pub enum AnyClient {
 pub enum AnyUpgradeOptions {
 	Grandpa(ics10_grandpa::client_state::UpgradeOptions),
 	Beefy(ics11_beefy::client_state::UpgradeOptions),
+	Ethereum(ics12_ethereum::client_state::UpgradeOptions),
 	Tendermint(ics07_tendermint::client_state::UpgradeOptions),
 	Wasm(Box<Self>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	Mock(()),
 }
 
+/// Client states of the light clients supported by the runtime. Adding a client type takes a
+/// variant in each of the `Any*` enums, from which freezing, header heights and type urls are
+/// derived, and an entry in the runtime's `Config::LightClients`.
 #[derive(Clone, Debug, PartialEq, Eq, ClientState, Protobuf)]
 pub enum AnyClientState {
 	#[ibc(proto_url = "GRANDPA_CLIENT_STATE_TYPE_URL")]
 	Grandpa(ics10_grandpa::client_state::ClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "BEEFY_CLIENT_STATE_TYPE_URL")]
 	Beefy(ics11_beefy::client_state::ClientState<HostFunctionsManager>),
+	#[ibc(proto_url = "ETHEREUM_CLIENT_STATE_TYPE_URL")]
+	Ethereum(ics12_ethereum::client_state::ClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "TENDERMINT_CLIENT_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::client_state::ClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "WASM_CLIENT_STATE_TYPE_URL")]
 	Wasm(ics08_wasm::client_state::ClientState<AnyClient, Self, AnyConsensusState>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	#[ibc(proto_url = "MOCK_CLIENT_STATE_TYPE_URL")]
 	Mock(ibc::mock::client_state::MockClientState),
 }
@@ -272,10 +306,22 @@ impl AnyClientState {
 
 	pub fn unpack_recursive(&self) -> &Self {
 		match self {
-			AnyClientState::Wasm(wasm_state) => wasm_state.inner.unpack_recursive(),
+			AnyClientState::Wasm(wasm_state) =>
+				wasm_state.inner.as_deref().map_or(self, Self::unpack_recursive),
 			c => c,
 		}
 	}
+
+	/// Period within which the client must be updated before it expires, if the client has one.
+	pub fn trusting_period(&self) -> Option<core::time::Duration> {
+		match self.unpack_recursive() {
+			AnyClientState::Grandpa(client_state) => Some(client_state.trusting_period()),
+			AnyClientState::Beefy(client_state) => Some(client_state.relay_chain.trusting_period()),
+			AnyClientState::Ethereum(client_state) => Some(client_state.trusting_period),
+			AnyClientState::Tendermint(client_state) => Some(client_state.trusting_period),
+			_ => None,
+		}
+	}
 }
 
 impl AnyClientState {
@@ -284,7 +330,7 @@ impl AnyClientState {
 			ics08_wasm::client_state::ClientState::<AnyClient, Self, AnyConsensusState> {
 				data: inner.encode_to_vec()?,
 				latest_height: inner.latest_height(),
-				inner: Box::new(inner),
+				inner: Some(Box::new(inner)),
 				code_id,
 				_phantom: Default::default(),
 			},
@@ -298,11 +344,13 @@ pub enum AnyConsensusState {
 	Grandpa(ics10_grandpa::consensus_state::ConsensusState),
 	#[ibc(proto_url = "BEEFY_CONSENSUS_STATE_TYPE_URL")]
 	Beefy(ics11_beefy::consensus_state::ConsensusState),
+	#[ibc(proto_url = "ETHEREUM_CONSENSUS_STATE_TYPE_URL")]
+	Ethereum(ics12_ethereum::consensus_state::ConsensusState),
 	#[ibc(proto_url = "TENDERMINT_CONSENSUS_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::consensus_state::ConsensusState),
 	#[ibc(proto_url = "WASM_CONSENSUS_STATE_TYPE_URL")]
 	Wasm(ics08_wasm::consensus_state::ConsensusState<Self>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	#[ibc(proto_url = "MOCK_CONSENSUS_STATE_TYPE_URL")]
 	Mock(ibc::mock::client_state::MockConsensusState),
 }
@@ -312,56 +360,46 @@ impl AnyConsensusState {
 		Ok(Self::Wasm(ics08_wasm::consensus_state::ConsensusState {
 			timestamp: inner.timestamp().nanoseconds(),
 			data: inner.encode_to_vec()?,
-			inner: Box::new(inner),
+			inner: Some(Box::new(inner)),
 		}))
 	}
 }
 
-#[derive(Clone, Debug, ClientMessage)]
+#[derive(Clone, Debug, ClientMessage, Protobuf)]
 #[allow(clippy::large_enum_variant)]
 pub enum AnyClientMessage {
-	#[ibc(proto_url = "GRANDPA_CLIENT_MESSAGE_TYPE_URL")]
+	#[ibc(proto_url = "GRANDPA_CLIENT_MESSAGE_TYPE_URL", proto_decode_err = "decode_raw_header")]
+	#[ibc(proto_url = "GRANDPA_HEADER_TYPE_URL", proto_url = "GRANDPA_MISBEHAVIOUR_TYPE_URL")]
 	Grandpa(ics10_grandpa::client_message::ClientMessage),
-	#[ibc(proto_url = "BEEFY_CLIENT_MESSAGE_TYPE_URL")]
+	// TODO: beefy header, misbehaviour impl From<Any>
+	#[ibc(proto_url = "BEEFY_CLIENT_MESSAGE_TYPE_URL", proto_decode_err = "decode_raw_header")]
 	Beefy(ics11_beefy::client_message::ClientMessage),
-	#[ibc(proto_url = "TENDERMINT_CLIENT_MESSAGE_TYPE_URL")]
+	#[ibc(proto_url = "ETHEREUM_CLIENT_MESSAGE_TYPE_URL", proto_decode_err = "decode_raw_header")]
+	#[ibc(proto_url = "ETHEREUM_HEADER_TYPE_URL", proto_url = "ETHEREUM_MISBEHAVIOUR_TYPE_URL")]
+	Ethereum(ics12_ethereum::client_message::ClientMessage),
+	#[ibc(
+		proto_url = "TENDERMINT_CLIENT_MESSAGE_TYPE_URL",
+		proto_decode_err = "decode_raw_header"
+	)]
+	#[ibc(
+		proto_url = "TENDERMINT_HEADER_TYPE_URL",
+		proto_url = "TENDERMINT_MISBEHAVIOUR_TYPE_URL",
+		proto_encode_url = "TENDERMINT_CLIENT_MESSAGE_TYPE_URL"
+	)]
 	Tendermint(ics07_tendermint::client_message::ClientMessage),
-	#[ibc(proto_url = "WASM_CLIENT_MESSAGE_TYPE_URL")]
+	#[ibc(proto_url = "WASM_CLIENT_MESSAGE_TYPE_URL", proto_decode_err = "decode_raw_header")]
+	#[ibc(proto_url = "WASM_HEADER_TYPE_URL", proto_url = "WASM_MISBEHAVIOUR_TYPE_URL")]
 	Wasm(ics08_wasm::client_message::ClientMessage<Self>),
-	#[cfg(test)]
-	#[ibc(proto_url = "MOCK_CLIENT_MESSAGE_TYPE_URL")]
+	#[cfg(any(test, feature = "mocks"))]
+	#[ibc(proto_url = "MOCK_CLIENT_MESSAGE_TYPE_URL", proto_decode_err = "decode_raw_header")]
+	#[ibc(proto_url = "MOCK_HEADER_TYPE_URL", proto_url = "MOCK_MISBEHAVIOUR_TYPE_URL")]
 	Mock(ibc::mock::header::MockClientMessage),
 }
 
 impl AnyClientMessage {
-	pub fn maybe_header_height(&self) -> Option<Height> {
-		match self {
-			Self::Tendermint(inner) => match inner {
-				ics07_tendermint::client_message::ClientMessage::Header(h) => Some(h.height()),
-				ics07_tendermint::client_message::ClientMessage::Misbehaviour(_) => None,
-			},
-			Self::Beefy(inner) => match inner {
-				ics11_beefy::client_message::ClientMessage::Header(_) =>
-					unimplemented!("beefy header height"),
-				ics11_beefy::client_message::ClientMessage::Misbehaviour(_) => None,
-			},
-			Self::Grandpa(inner) => match inner {
-				ics10_grandpa::client_message::ClientMessage::Header(h) => Some(h.height()),
-				ics10_grandpa::client_message::ClientMessage::Misbehaviour(_) => None,
-			},
-			Self::Wasm(inner) => match inner {
-				ics08_wasm::client_message::ClientMessage::Header(h) =>
-					h.inner.maybe_header_height(),
-				ics08_wasm::client_message::ClientMessage::Misbehaviour(_) => None,
-			},
-			#[cfg(test)]
-			Self::Mock(inner) => match inner {
-				ibc::mock::header::MockClientMessage::Header(h) => Some(h.height()),
-				ibc::mock::header::MockClientMessage::Misbehaviour(_) => None,
-			},
-		}
-	}
-
+	/// Wraps the message into an `08-wasm` header if it has a header height, else into an
+	/// `08-wasm` misbehaviour. Beefy headers have no header height, but there is no beefy light
+	/// client contract to wrap them for either.
 	pub fn wasm(inner: Self) -> Result<Self, tendermint_proto::Error> {
 		let maybe_height = inner.maybe_header_height();
 		Ok(match maybe_height {
@@ -369,13 +407,13 @@ impl AnyClientMessage {
 				ics08_wasm::client_message::Header {
 					data: inner.encode_to_vec()?,
 					height,
-					inner: Box::new(inner),
+					inner: Some(Box::new(inner)),
 				},
 			)),
 			None => Self::Wasm(ics08_wasm::client_message::ClientMessage::Misbehaviour(
 				ics08_wasm::client_message::Misbehaviour {
 					data: inner.encode_to_vec()?,
-					inner: Box::new(inner),
+					inner: Some(Box::new(inner)),
 				},
 			)),
 		})
@@ -383,128 +421,27 @@ impl AnyClientMessage {
 
 	pub fn unpack_recursive(&self) -> &Self {
 		match self {
-			Self::Wasm(ics08_wasm::client_message::ClientMessage::Header(h)) =>
-				h.inner.unpack_recursive(),
-			Self::Wasm(ics08_wasm::client_message::ClientMessage::Misbehaviour(m)) =>
-				m.inner.unpack_recursive(),
+			Self::Wasm(msg) => msg.inner().map_or(self, Self::unpack_recursive),
 			_ => self,
 		}
 	}
 
 	pub fn unpack_recursive_into(self) -> Self {
 		match self {
-			Self::Wasm(ics08_wasm::client_message::ClientMessage::Header(h)) =>
-				h.inner.unpack_recursive_into(),
-			Self::Wasm(ics08_wasm::client_message::ClientMessage::Misbehaviour(m)) =>
-				m.inner.unpack_recursive_into(),
+			Self::Wasm(msg) if msg.inner().is_some() =>
+				msg.into_inner().expect("inner message is decoded; qed").unpack_recursive_into(),
 			_ => self,
 		}
 	}
 }
 
-impl Protobuf<Any> for AnyClientMessage {}
-
-impl TryFrom<Any> for AnyClientMessage {
-	type Error = ics02_client::error::Error;
-
-	fn try_from(value: Any) -> Result<Self, Self::Error> {
-		match value.type_url.as_str() {
-			GRANDPA_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Grandpa(
-				ics10_grandpa::client_message::ClientMessage::decode_vec(&value.value)
-					.map_err(ics02_client::error::Error::decode_raw_header)?,
-			)),
-			GRANDPA_HEADER_TYPE_URL =>
-				Ok(Self::Grandpa(ics10_grandpa::client_message::ClientMessage::Header(
-					ics10_grandpa::client_message::Header::decode_vec(&value.value)
-						.map_err(ics02_client::error::Error::decode_raw_header)?,
-				))),
-			GRANDPA_MISBEHAVIOUR_TYPE_URL =>
-				Ok(Self::Grandpa(ics10_grandpa::client_message::ClientMessage::Misbehaviour(
-					ics10_grandpa::client_message::Misbehaviour::decode_vec(&value.value)
-						.map_err(ics02_client::error::Error::decode_raw_header)?,
-				))),
-			// TODO: beefy header, misbehaviour impl From<Any>
-			BEEFY_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Beefy(
-				ics11_beefy::client_message::ClientMessage::decode_vec(&value.value)
-					.map_err(ics02_client::error::Error::decode_raw_header)?,
-			)),
-			TENDERMINT_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Tendermint(
-				ics07_tendermint::client_message::ClientMessage::decode_vec(&value.value)
-					.map_err(ics02_client::error::Error::decode_raw_header)?,
-			)),
-			TENDERMINT_HEADER_TYPE_URL =>
-				Ok(Self::Tendermint(ics07_tendermint::client_message::ClientMessage::Header(
-					ics07_tendermint::client_message::Header::decode_vec(&value.value)
-						.map_err(ics02_client::error::Error::decode_raw_header)?,
-				))),
-			TENDERMINT_MISBEHAVIOUR_TYPE_URL =>
-				Ok(Self::Tendermint(ics07_tendermint::client_message::ClientMessage::Misbehaviour(
-					ics07_tendermint::client_message::Misbehaviour::decode_vec(&value.value)
-						.map_err(ics02_client::error::Error::decode_raw_header)?,
-				))),
-			WASM_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Wasm(
-				ics08_wasm::client_message::ClientMessage::decode_vec(&value.value)
-					.map_err(ics02_client::error::Error::decode_raw_header)?,
-			)),
-			WASM_HEADER_TYPE_URL =>
-				Ok(Self::Wasm(ics08_wasm::client_message::ClientMessage::Header(
-					ics08_wasm::client_message::Header::decode_vec(&value.value)
-						.map_err(ics02_client::error::Error::decode_raw_header)?,
-				))),
-			WASM_MISBEHAVIOUR_TYPE_URL =>
-				Ok(Self::Wasm(ics08_wasm::client_message::ClientMessage::Misbehaviour(
-					ics08_wasm::client_message::Misbehaviour::decode_vec(&value.value)
-						.map_err(ics02_client::error::Error::decode_raw_header)?,
-				))),
-			_ => Err(ics02_client::error::Error::unknown_consensus_state_type(value.type_url)),
-		}
-	}
-}
-
-impl From<AnyClientMessage> for Any {
-	fn from(client_msg: AnyClientMessage) -> Self {
-		match client_msg {
-			AnyClientMessage::Wasm(msg) => match msg {
-				ics08_wasm::client_message::ClientMessage::Header(h) => Any {
-					type_url: WASM_HEADER_TYPE_URL.to_string(),
-					value: h.encode_vec().expect("encode_vec failed"),
-				},
-				ics08_wasm::client_message::ClientMessage::Misbehaviour(m) => Any {
-					type_url: WASM_MISBEHAVIOUR_TYPE_URL.to_string(),
-					value: m.encode_vec().expect("encode_vec failed"),
-				},
-			},
-			AnyClientMessage::Grandpa(msg) => match msg {
-				ics10_grandpa::client_message::ClientMessage::Header(h) => Any {
-					type_url: GRANDPA_HEADER_TYPE_URL.to_string(),
-					value: h.encode_vec().expect("encode_vec failed"),
-				},
-				ics10_grandpa::client_message::ClientMessage::Misbehaviour(m) => Any {
-					type_url: GRANDPA_MISBEHAVIOUR_TYPE_URL.to_string(),
-					value: m.encode_vec().expect("encode_vec failed"),
-				},
-			},
-			AnyClientMessage::Beefy(msg) => Any {
-				type_url: BEEFY_CLIENT_MESSAGE_TYPE_URL.to_string(),
-				value: msg.encode_vec().expect("encode_vec failed"),
-			},
-			AnyClientMessage::Tendermint(msg) => Any {
-				type_url: TENDERMINT_CLIENT_MESSAGE_TYPE_URL.to_string(),
-				value: msg.encode_vec().expect("encode_vec failed"),
-			},
-
-			#[cfg(test)]
-			AnyClientMessage::Mock(_msg) => panic!("MockHeader can't be serialized"),
-		}
-	}
-}
-
-#[cfg(test)]
+#[cfg(any(test, feature = "mocks"))]
 pub use mocks::*;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "mocks"))]
 mod mocks {
 	pub const MOCK_CLIENT_STATE_TYPE_URL: &str = "/ibc.mock.ClientState";
 	pub const MOCK_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.mock.ClientMessage";
 	pub const MOCK_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.mock.ConsensusState";
+	pub use ibc::mock::{header::MOCK_HEADER_TYPE_URL, misbehaviour::MOCK_MISBEHAVIOUR_TYPE_URL};
 }
@@ -7,6 +7,7 @@ use frame_support::{
 		fungibles::{metadata::Mutate, Create, InspectMetadata},
 		AsEnsureOriginWithArg, ConstU64, Everything,
 	},
+	weights::Weight,
 };
 use frame_system as system;
 use frame_system::EnsureSigned;
@@ -146,7 +147,7 @@ impl pallet_assets::Config for Test {
 parameter_types! {
 	pub const MaxLocks: u32 = 256;
 	pub static ParachainId: ParaId = ParaId::from(2087);
-	pub static RelayChainId: RelayChain = RelayChain::Rococo;
+	pub static RelayChainId: RelayChain = RelayChain::rococo();
 	pub const SpamProtectionDeposit: u128 = 0;
 }
 
@@ -180,6 +181,9 @@ parameter_types! {
 	pub const FlatFeeAmount: AssetId = 10_000_000;
 	pub FeeAccount: <Test as Config>::AccountIdConversion = create_alice_key();
 	pub const CleanUpPacketsPeriod: u32 = 10;
+	pub const DefaultDenomDecimals: Option<u8> = Some(12);
+	pub const EscrowAuditPeriod: u32 = 10;
+	pub const WeightPerWasmFuel: Weight = Weight::from_ref_time(1_000);
 }
 
 fn create_alice_key() -> <Test as Config>::AccountIdConversion {
@@ -206,6 +210,14 @@ impl Config for Test {
 	type ParaId = ParachainId;
 	type RelayChain = RelayChainId;
 	type WeightInfo = ();
+	type LightClients = (
+		MockClientWeights,
+		pallet_ibc::weight::TendermintWeights,
+		pallet_ibc::weight::GrandpaWeights,
+		pallet_ibc::weight::BeefyWeights,
+		pallet_ibc::weight::EthereumWeights,
+		pallet_ibc::weight::WasmWeights,
+	);
 	type AdminOrigin = EnsureRoot<AccountId>;
 	type FreezeOrigin = EnsureRoot<AccountId>;
 	type SpamProtectionDeposit = SpamProtectionDeposit;
@@ -214,8 +226,6 @@ impl Config for Test {
 	type RelayerOrigin = EnsureSigned<Self::AccountId>;
 	type HandleMemo = ();
 	type MemoMessage = MemoMessage;
-	type IsReceiveEnabled = sp_core::ConstBool<true>;
-	type IsSendEnabled = sp_core::ConstBool<true>;
 	type Ics20RateLimiter = Everything;
 	type FeeAccount = FeeAccount;
 	type CleanUpPacketsPeriod = CleanUpPacketsPeriod;
@@ -223,7 +233,26 @@ impl Config for Test {
 	type FlatFeeConverter = FlatFeeConverterDummy<Test>;
 	type FlatFeeAssetId = FlatFeeAssetId;
 	type FlatFeeAmount = FlatFeeAmount;
+	type DenomMetadataOrigin = EnsureRoot<AccountId>;
+	type DefaultDenomDecimals = DefaultDenomDecimals;
+	type EscrowAuditPeriod = EscrowAuditPeriod;
+	type BlockChannelOnEscrowDrift = sp_core::ConstBool<true>;
+	type MaxWasmFuel = ConstU64<50_000_000>;
+	type WeightPerWasmFuel = WeightPerWasmFuel;
+	type MaxWasmCodeSize = ConstU32<{ 3 * 1024 * 1024 }>;
 }
+
+/// Mock light client, whose messages weigh nothing.
+pub struct MockClientWeights;
+
+impl<T: Config> pallet_ibc::LightClient<T> for MockClientWeights {
+	const CLIENT_TYPE: &'static str = "9999-mock";
+
+	fn weigh(_msg: pallet_ibc::weight::LightClientMsg) -> Weight {
+		Weight::default()
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct FlatFeeConverterDummy<T: Config>(PhantomData<T>);
 impl<T: Config> FlatFeeConverter for FlatFeeConverterDummy<T> {
@@ -3,20 +3,29 @@ use crate::{
 	light_clients::{AnyClientState, AnyConsensusState},
 	mock::*,
 	routing::Context,
-	Any, Config, ConsensusHeights, DenomToAssetId, MultiAddress, Pallet, PendingRecvPacketSeqs,
-	PendingSendPacketSeqs, Timeout, TransferParams, MODULE_ID,
+	wasm,
+	weight::LightClientMsg,
+	Any, ChannelPauseFlags, Config, ConsensusHeights, DenomMetadata, DenomToAssetId,
+	EscrowAuditCursor, EscrowBlockedChannels, IbcDenomMetadata, LightClientRegistry, MultiAddress,
+	Pallet, PauseFlags, PendingRecvPacketSeqs, PendingSendPacketSeqs, PortPauseFlags, Timeout,
+	TotalEscrow, TransferParams, WasmCodes, MODULE_ID,
 };
 use core::time::Duration;
 use frame_support::{
-	assert_ok,
+	assert_noop, assert_ok,
+	dispatch::GetDispatchInfo,
+	storage::{child, child::ChildInfo},
 	traits::{
 		fungibles::{Inspect, Mutate},
-		Hooks, Len,
+		Get, Hooks, Len,
 	},
 	weights::Weight,
 };
 use ibc::{
-	applications::transfer::{packet::PacketData, Coin, PrefixedDenom, VERSION},
+	applications::transfer::{
+		acknowledgement::Acknowledgement as Ics20Acknowledgement, packet::PacketData, Coin,
+		PrefixedDenom, VERSION,
+	},
 	core::{
 		ics02_client::{
 			client_state::ClientState,
@@ -51,7 +60,7 @@ use ibc::{
 use ibc_primitives::{get_channel_escrow_address, HandlerMessage, IbcHandler};
 use sp_core::Pair;
 use sp_runtime::{
-	traits::{Bounded, IdentifyAccount},
+	traits::{BadOrigin, Bounded, IdentifyAccount},
 	AccountId32,
 };
 use std::str::FromStr;
@@ -106,6 +115,49 @@ fn setup_client_and_consensus_state(port_id: PortId) {
 	ctx.store_next_sequence_recv((port_id, channel_id), 1.into()).unwrap()
 }
 
+/// Key pair of the account receiving test transfers, and its ss58 address.
+fn test_receiver() -> (sp_core::sr25519::Pair, String) {
+	let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+	let ss58_address_bytes =
+		ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+	(pair, String::from_utf8(ss58_address_bytes).unwrap())
+}
+
+/// A `MsgRecvPacket` transferring `amount` of the counterparty's `denom` to `receiver` on
+/// `transfer/channel-0`, with packet sequence 1.
+fn ics20_recv_packet_msg(denom: &str, amount: u128, receiver: &str) -> Any {
+	let packet_data = PacketData {
+		token: Coin {
+			denom: PrefixedDenom::from_str(denom).unwrap(),
+			amount: ibc::applications::transfer::Amount::from_str(&format!("{:?}", amount))
+				.unwrap(),
+		},
+		sender: Signer::from_str("alice").unwrap(),
+		receiver: Signer::from_str(receiver).unwrap(),
+		memo: "".to_string(),
+	};
+	let packet = Packet {
+		sequence: 1u64.into(),
+		source_port: PortId::transfer(),
+		source_channel: ChannelId::new(1),
+		destination_port: PortId::transfer(),
+		destination_channel: ChannelId::new(0),
+		data: serde_json::to_vec(&packet_data).unwrap(),
+		timeout_height: Height::new(2000, 5),
+		timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+			1690894363u64.saturating_mul(1000000000),
+		)
+		.unwrap(),
+	};
+	let msg = MsgRecvPacket {
+		packet,
+		proofs: Proofs::new(vec![0u8; 32].try_into().unwrap(), None, None, None, Height::new(0, 1))
+			.unwrap(),
+		signer: Signer::from_str(MODULE_ID).unwrap(),
+	};
+	Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() }
+}
+
 // Create a client and initialize a connection
 #[test]
 fn initialize_connection() {
@@ -1029,3 +1081,798 @@ fn test_next_and_previous_consensus_state_for_other_client_types() {
 		assert!(ctx.next_consensus_state(&client_id, Height::new(0, 400)).unwrap().is_some());
 	})
 }
+
+#[test]
+fn set_and_remove_denom_metadata() {
+	new_test_ext().execute_with(|| {
+		let metadata = DenomMetadata {
+			name: b"Picasso".to_vec().try_into().unwrap(),
+			symbol: b"PICA".to_vec().try_into().unwrap(),
+			decimals: 12,
+		};
+
+		assert_noop!(
+			Ibc::set_denom_metadata(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				b"PICA".to_vec(),
+				metadata.clone()
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Ibc::set_denom_metadata(RuntimeOrigin::root(), b"".to_vec(), metadata.clone()),
+			crate::Error::<Test>::PrefixedDenomParse
+		);
+
+		assert_ok!(Ibc::set_denom_metadata(
+			RuntimeOrigin::root(),
+			b"PICA".to_vec(),
+			metadata.clone()
+		));
+		assert_eq!(IbcDenomMetadata::<Test>::get(b"PICA".to_vec()), Some(metadata));
+
+		// The mock denom conversion maps every asset id to `PICA`
+		let response = Pallet::<Test>::get_denom_metadata(2).unwrap();
+		assert_eq!(response.denom, b"PICA".to_vec());
+		assert_eq!(response.base_denom, b"PICA".to_vec());
+		assert!(response.trace_path.is_empty());
+		assert_eq!(response.symbol, b"PICA".to_vec());
+		assert_eq!(response.decimals, 12);
+
+		assert_ok!(Ibc::remove_denom_metadata(RuntimeOrigin::root(), b"PICA".to_vec()));
+		assert!(Pallet::<Test>::get_denom_metadata(2).is_none());
+		assert_noop!(
+			Ibc::remove_denom_metadata(RuntimeOrigin::root(), b"PICA".to_vec()),
+			crate::Error::<Test>::DenomMetadataNotFound
+		);
+		assert_noop!(
+			Ibc::remove_denom_metadata(RuntimeOrigin::root(), b"".to_vec()),
+			crate::Error::<Test>::PrefixedDenomParse
+		);
+
+		// Traced denoms are stored and removed under the same normalised key
+		let ibc_denom = b"transfer/channel-0/uatom".to_vec();
+		assert_ok!(Ibc::set_denom_metadata(
+			RuntimeOrigin::root(),
+			ibc_denom.clone(),
+			metadata.clone()
+		));
+		assert_eq!(IbcDenomMetadata::<Test>::get(&ibc_denom), Some(metadata));
+		assert_ok!(Ibc::remove_denom_metadata(RuntimeOrigin::root(), ibc_denom.clone()));
+		assert!(IbcDenomMetadata::<Test>::get(&ibc_denom).is_none());
+	})
+}
+
+#[test]
+fn on_deliver_ics20_recv_packet_registers_denom_metadata() {
+	new_test_ext().execute_with(|| {
+		let (_, ss58_address) = test_receiver();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		// A token originating from the counterparty chain
+		let msg = ics20_recv_packet_msg("uatom", 1000 * MILLIS, &ss58_address);
+
+		let ibc_denom = b"transfer/channel-0/uatom".to_vec();
+		assert!(IbcDenomMetadata::<Test>::get(&ibc_denom).is_none());
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		let metadata = IbcDenomMetadata::<Test>::get(&ibc_denom).unwrap();
+		assert_eq!(metadata.name.into_inner(), ibc_denom);
+		assert_eq!(metadata.symbol.into_inner(), b"uatom".to_vec());
+		assert_eq!(metadata.decimals, <Test as Config>::DefaultDenomDecimals::get().unwrap());
+	})
+}
+
+#[test]
+fn escrow_audit_blocks_channel_on_shortfall() {
+	new_test_ext().execute_with(|| {
+		let (_, ss58_address) = test_receiver();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let balance = 100000 * MILLIS;
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				&"PICA".to_string(),
+			)
+			.unwrap();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::mint_into(asset_id, &AccountId32::new([0; 32]), balance * 2)
+		.unwrap();
+
+		let transfer = || {
+			Ibc::transfer(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				TransferParams {
+					to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+					source_channel: 0,
+					timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				},
+				asset_id,
+				balance,
+				None,
+			)
+		};
+		assert_ok!(transfer());
+
+		let channel_id = ChannelId::new(0).to_string().as_bytes().to_vec();
+		let fee = <Test as crate::ics20_fee::Config>::ServiceChargeIn::get() * balance;
+		let escrowed = balance - fee;
+		assert_eq!(TotalEscrow::<Test>::get(&channel_id, b"PICA".to_vec()), escrowed);
+		assert_eq!(
+			Pallet::<Test>::get_total_escrow(channel_id.clone()),
+			vec![(b"PICA".to_vec(), escrowed)]
+		);
+
+		// Balance matches the recorded total, nothing to report
+		assert_eq!(Pallet::<Test>::audit_escrows(Weight::MAX), 1);
+		assert!(EscrowBlockedChannels::<Test>::get(&channel_id).is_none());
+
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), ChannelId::new(0)).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap()
+				.into_account();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::burn_from(asset_id, &channel_escrow_address, MILLIS)
+		.unwrap();
+
+		assert_eq!(Pallet::<Test>::audit_escrows(Weight::MAX), 1);
+		frame_system::Pallet::<Test>::assert_last_event(
+			crate::Event::<Test>::EscrowInvariantViolated {
+				channel_id: channel_id.clone(),
+				ibc_denom: b"PICA".to_vec(),
+				expected: escrowed,
+				actual: escrowed - MILLIS,
+				channel_blocked: true,
+			}
+			.into(),
+		);
+		assert!(EscrowBlockedChannels::<Test>::get(&channel_id).is_some());
+		assert_noop!(transfer(), crate::Error::<Test>::ChannelBlocked);
+
+		// The blocked channel isn't audited again until it's unblocked
+		let events = frame_system::Pallet::<Test>::events().len();
+		assert_eq!(Pallet::<Test>::audit_escrows(Weight::MAX), 0);
+		assert_eq!(frame_system::Pallet::<Test>::events().len(), events);
+
+		assert_ok!(Ibc::unblock_escrow_channel(RuntimeOrigin::root(), channel_id.clone()));
+		assert!(EscrowBlockedChannels::<Test>::get(&channel_id).is_none());
+		assert_noop!(
+			Ibc::unblock_escrow_channel(RuntimeOrigin::root(), channel_id),
+			crate::Error::<Test>::InvalidChannelId
+		);
+		assert_ok!(transfer());
+	})
+}
+
+#[test]
+fn escrow_audit_resumes_from_cursor() {
+	new_test_ext().execute_with(|| {
+		for channel_id in [ChannelId::new(0), ChannelId::new(1)] {
+			TotalEscrow::<Test>::insert(channel_id.to_string().as_bytes(), b"PICA", 0);
+		}
+		let (channel_id, ibc_denom) = TotalEscrow::<Test>::iter_keys().next().unwrap();
+		EscrowAuditCursor::<Test>::put(TotalEscrow::<Test>::hashed_key_for(channel_id, ibc_denom));
+
+		// Only the entry after the cursor is left to audit, then the audit starts over
+		assert_eq!(Pallet::<Test>::audit_escrows(Weight::MAX), 1);
+		assert!(EscrowAuditCursor::<Test>::get().is_none());
+		assert_eq!(Pallet::<Test>::audit_escrows(Weight::MAX), 2);
+	})
+}
+
+#[test]
+fn pause_flags_block_transfers_and_receipts() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let (pair, ss58_address) = test_receiver();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let balance = 100000 * MILLIS;
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				&"PICA".to_string(),
+			)
+			.unwrap();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::mint_into(asset_id, &AccountId32::new([0; 32]), balance)
+		.unwrap();
+
+		let port_id = PortId::transfer().as_bytes().to_vec();
+		let channel_id = ChannelId::new(0).to_string().as_bytes().to_vec();
+		let send_paused = PauseFlags { send: true, receive: false };
+		assert_noop!(
+			Ibc::set_channel_pause_flags(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				port_id.clone(),
+				channel_id.clone(),
+				send_paused
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Ibc::set_channel_pause_flags(
+				RuntimeOrigin::root(),
+				port_id.clone(),
+				b"channel0".to_vec(),
+				send_paused
+			),
+			crate::Error::<Test>::InvalidChannelId
+		);
+		assert_ok!(Ibc::set_channel_pause_flags(
+			RuntimeOrigin::root(),
+			port_id.clone(),
+			channel_id.clone(),
+			send_paused
+		));
+		frame_system::Pallet::<Test>::assert_last_event(
+			crate::Event::<Test>::ChannelPauseFlagsSet {
+				port_id: port_id.clone(),
+				channel_id: channel_id.clone(),
+				flags: send_paused,
+			}
+			.into(),
+		);
+
+		let transfer = || {
+			Ibc::transfer(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				TransferParams {
+					to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+					source_channel: 0,
+					timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				},
+				asset_id,
+				balance,
+				None,
+			)
+		};
+		assert_noop!(transfer(), crate::Error::<Test>::SendPaused);
+
+		// Resuming traffic removes the flags from storage
+		assert_ok!(Ibc::set_channel_pause_flags(
+			RuntimeOrigin::root(),
+			port_id.clone(),
+			channel_id.clone(),
+			PauseFlags::default()
+		));
+		assert!(!ChannelPauseFlags::<Test>::contains_key(&port_id, &channel_id));
+		assert_ok!(transfer());
+
+		// Pausing receipts on the port rejects incoming ICS-20 packets on all of its channels
+		let receive_paused = PauseFlags { send: false, receive: true };
+		assert_ok!(Ibc::set_port_pause_flags(
+			RuntimeOrigin::root(),
+			port_id.clone(),
+			receive_paused
+		));
+		assert_eq!(PortPauseFlags::<Test>::get(&port_id), receive_paused);
+		assert!(Pallet::<Test>::is_receive_paused(&PortId::transfer(), &ChannelId::new(0)));
+		assert!(!Pallet::<Test>::is_send_paused(&PortId::transfer(), &ChannelId::new(0)));
+
+		let msg = ics20_recv_packet_msg("uatom", 1000 * MILLIS, &ss58_address);
+
+		let receiver = AccountId32::new(pair.public().0);
+		let balance_before =
+			<<Test as Config>::Fungibles as Inspect<AccountId>>::balance(asset_id, &receiver);
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+		assert_eq!(
+			<<Test as Config>::Fungibles as Inspect<AccountId>>::balance(asset_id, &receiver),
+			balance_before
+		);
+		assert!(IbcDenomMetadata::<Test>::get(b"transfer/channel-0/uatom".to_vec()).is_none());
+
+		// The packet is acknowledged with an error, so that the sender gets refunded
+		let ctx = Context::<Test>::default();
+		assert!(ctx
+			.get_packet_acknowledgement(&(PortId::transfer(), ChannelId::new(0), 1u64.into()))
+			.is_ok());
+	});
+
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		let packet_info = Pallet::<Test>::get_recv_packet_info(
+			ChannelId::new(0).to_string().as_bytes().to_vec(),
+			PortId::transfer().as_bytes().to_vec(),
+			vec![1],
+		)
+		.unwrap()
+		.get(0)
+		.unwrap()
+		.clone();
+		let ack: Ics20Acknowledgement = serde_json::from_slice(&packet_info.ack.unwrap()).unwrap();
+		assert!(matches!(ack, Ics20Acknowledgement::Error(_)));
+	})
+}
+
+#[test]
+fn upload_wasm_validates_and_stores_contracts() {
+	new_test_ext().execute_with(|| {
+		let code = wat::parse_str(
+			r#"(module
+				(import "env" "db_read" (func (param i32) (result i32)))
+				(memory (export "memory") 1)
+				(func (export "allocate") (param i32) (result i32) unreachable)
+				(func (export "deallocate") (param i32) unreachable)
+				(func (export "execute") (param i32 i32 i32) (result i32) unreachable)
+				(func (export "query") (param i32 i32) (result i32) unreachable))"#,
+		)
+		.unwrap();
+
+		assert_noop!(
+			Ibc::upload_wasm(RuntimeOrigin::signed(AccountId32::new([0; 32])), code.clone()),
+			BadOrigin
+		);
+		assert_noop!(
+			Ibc::upload_wasm(RuntimeOrigin::root(), b"not wasm".to_vec()),
+			crate::Error::<Test>::InvalidWasmCode
+		);
+		assert_noop!(
+			Ibc::upload_wasm(RuntimeOrigin::root(), vec![0; 3 * 1024 * 1024 + 1]),
+			crate::Error::<Test>::WasmCodeTooLarge
+		);
+		let without_entry_points =
+			wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+		assert_noop!(
+			Ibc::upload_wasm(RuntimeOrigin::root(), without_entry_points),
+			crate::Error::<Test>::InvalidWasmCode
+		);
+		let unknown_import = wat::parse_str(
+			r#"(module
+				(import "env" "gas" (func (param i32)))
+				(memory (export "memory") 1)
+				(func (export "allocate") (param i32) (result i32) unreachable)
+				(func (export "deallocate") (param i32) unreachable)
+				(func (export "execute") (param i32 i32 i32) (result i32) unreachable)
+				(func (export "query") (param i32 i32) (result i32) unreachable))"#,
+		)
+		.unwrap();
+		assert_noop!(
+			Ibc::upload_wasm(RuntimeOrigin::root(), unknown_import),
+			crate::Error::<Test>::InvalidWasmCode
+		);
+
+		assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), code.clone()));
+		let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+		assert_eq!(WasmCodes::<Test>::get(&code_id), Some(code.clone()));
+		assert_noop!(
+			Ibc::upload_wasm(RuntimeOrigin::root(), code),
+			crate::Error::<Test>::WasmCodeExists
+		);
+	})
+}
+
+#[test]
+fn wasm_contracts_execute_and_query_in_the_vm() {
+	new_test_ext().execute_with(|| {
+		// `execute` stores its message under "value", `query` returns the stored value as the
+		// base64 encoded response.
+		let code = wat::parse_str(
+			r#"(module
+				(import "env" "db_read" (func $db_read (param i32) (result i32)))
+				(import "env" "db_write" (func $db_write (param i32 i32)))
+				(memory (export "memory") 1)
+				(global $heap (mut i32) (i32.const 1024))
+				(data (i32.const 0) "\10\00\00\00\05\00\00\00\05\00\00\00")
+				(data (i32.const 16) "value")
+				(data (i32.const 32) "\30\00\00\00\16\00\00\00\16\00\00\00")
+				(data (i32.const 48) "{\"ok\":{\"data\":\"b2s=\"}}")
+				(data (i32.const 80) "{\"ok\":\"")
+				(data (i32.const 88) "\"}")
+				(func $allocate (export "allocate") (param $size i32) (result i32)
+					(local $region i32)
+					(local.set $region (global.get $heap))
+					(i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+					(i32.store offset=4 (local.get $region) (local.get $size))
+					(i32.store offset=8 (local.get $region) (i32.const 0))
+					(global.set $heap
+						(i32.add (local.get $region) (i32.add (i32.const 12) (local.get $size))))
+					(local.get $region))
+				(func (export "deallocate") (param i32))
+				(func $copy (param $dst i32) (param $src i32) (param $len i32)
+					(block $done
+						(loop $next
+							(br_if $done (i32.eqz (local.get $len)))
+							(i32.store8 (local.get $dst) (i32.load8_u (local.get $src)))
+							(local.set $dst (i32.add (local.get $dst) (i32.const 1)))
+							(local.set $src (i32.add (local.get $src) (i32.const 1)))
+							(local.set $len (i32.sub (local.get $len) (i32.const 1)))
+							(br $next))))
+				(func (export "execute") (param i32 i32 i32) (result i32)
+					(call $db_write (i32.const 0) (local.get 2))
+					(i32.const 32))
+				(func (export "query") (param i32 i32) (result i32)
+					(local $value i32) (local $len i32) (local $result i32) (local $data i32)
+					(local.set $value (call $db_read (i32.const 0)))
+					(local.set $len (i32.load offset=8 (local.get $value)))
+					(local.set $result (call $allocate (i32.add (local.get $len) (i32.const 9))))
+					(local.set $data (i32.load (local.get $result)))
+					(call $copy (local.get $data) (i32.const 80) (i32.const 7))
+					(call $copy
+						(i32.add (local.get $data) (i32.const 7))
+						(i32.load (local.get $value))
+						(local.get $len))
+					(call $copy
+						(i32.add (local.get $data) (i32.add (local.get $len) (i32.const 7)))
+						(i32.const 88)
+						(i32.const 2))
+					(i32.store offset=8 (local.get $result) (i32.add (local.get $len) (i32.const 9)))
+					(local.get $result)))"#,
+		)
+		.unwrap();
+		assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), code.clone()));
+		let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+		let client_id = ClientId::new("08-wasm", 0).unwrap();
+		let block = wasm::BlockInfo { height: 1, time: 0, chain_id: "2087".to_string() };
+
+		assert_eq!(
+			wasm::execute::<Test>(&client_id, &code_id, &block, b"aGVsbG8=").unwrap(),
+			b"ok"
+		);
+		assert_eq!(wasm::query::<Test>(&client_id, &code_id, &block, b"{}").unwrap(), b"hello");
+		assert!(wasm::execute::<Test>(&client_id, &[0; 32], &block, b"").is_err());
+	})
+}
+
+/// Key of the storage of the contract of `client_id` under `key` in the ibc trie.
+fn wasm_client_key(client_id: &ClientId, key: &[u8]) -> Vec<u8> {
+	let prefix = ibc_primitives::apply_prefix(
+		<Test as Config>::PalletPrefix::get(),
+		vec![format!("clients/{client_id}/")],
+	);
+	[&prefix[..], key].concat()
+}
+
+fn wasm_client_value(client_id: &ClientId, key: &[u8]) -> Option<Vec<u8>> {
+	child::get(
+		&ChildInfo::new_default(<Test as Config>::PalletPrefix::get()),
+		&wasm_client_key(client_id, key),
+	)
+}
+
+/// A contract whose `execute` entry point scans its storage from "b" to "d" if `bounded`, in the
+/// `order` of CosmWasm, and stores the first byte of each key it reads under "scanned".
+fn wasm_scan_contract(bounded: bool, order: i32) -> Vec<u8> {
+	let (start, end) = if bounded { (32, 48) } else { (0, 0) };
+	wat::parse_str(format!(
+		r#"(module
+				(import "env" "db_scan" (func $db_scan (param i32 i32 i32) (result i32)))
+				(import "env" "db_next" (func $db_next (param i32) (result i32)))
+				(import "env" "db_write" (func $db_write (param i32 i32)))
+				(memory (export "memory") 1)
+				(global $heap (mut i32) (i32.const 1024))
+				(data (i32.const 0) "\10\00\00\00\07\00\00\00\07\00\00\00")
+				(data (i32.const 16) "scanned")
+				(data (i32.const 32) "\40\00\00\00\01\00\00\00\01\00\00\00")
+				(data (i32.const 48) "\41\00\00\00\01\00\00\00\01\00\00\00")
+				(data (i32.const 64) "bd")
+				(data (i32.const 80) "\60\00\00\00\09\00\00\00\09\00\00\00")
+				(data (i32.const 96) "{{\"ok\":{{}}}}")
+				(data (i32.const 128) "\00\02\00\00\00\01\00\00\00\00\00\00")
+				(func $allocate (export "allocate") (param $size i32) (result i32)
+					(local $region i32)
+					(local.set $region (global.get $heap))
+					(i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+					(i32.store offset=4 (local.get $region) (local.get $size))
+					(i32.store offset=8 (local.get $region) (i32.const 0))
+					(global.set $heap
+						(i32.add (local.get $region) (i32.add (i32.const 12) (local.get $size))))
+					(local.get $region))
+				(func (export "deallocate") (param i32))
+				(func (export "execute") (param i32 i32 i32) (result i32)
+					(local $iterator i32) (local $entry i32) (local $len i32)
+					(local.set $iterator
+						(call $db_scan (i32.const {start}) (i32.const {end}) (i32.const {order})))
+					(block $done
+						(loop $next
+							(local.set $entry (call $db_next (local.get $iterator)))
+							(br_if $done (i32.eq (i32.load offset=8 (local.get $entry)) (i32.const 8)))
+							(i32.store8
+								(i32.add (i32.const 512) (local.get $len))
+								(i32.load8_u (i32.load (local.get $entry))))
+							(local.set $len (i32.add (local.get $len) (i32.const 1)))
+							(br $next)))
+					(i32.store offset=8 (i32.const 128) (local.get $len))
+					(call $db_write (i32.const 0) (i32.const 128))
+					(i32.const 80))
+				(func (export "query") (param i32 i32) (result i32) unreachable))"#
+	))
+	.unwrap()
+}
+
+#[test]
+fn wasm_contracts_trap_when_out_of_fuel() {
+	new_test_ext().execute_with(|| {
+		let code = wat::parse_str(
+			r#"(module
+				(memory (export "memory") 1)
+				(global $heap (mut i32) (i32.const 1024))
+				(func $allocate (export "allocate") (param $size i32) (result i32)
+					(local $region i32)
+					(local.set $region (global.get $heap))
+					(i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+					(i32.store offset=4 (local.get $region) (local.get $size))
+					(i32.store offset=8 (local.get $region) (i32.const 0))
+					(global.set $heap
+						(i32.add (local.get $region) (i32.add (i32.const 12) (local.get $size))))
+					(local.get $region))
+				(func (export "deallocate") (param i32))
+				(func (export "execute") (param i32 i32 i32) (result i32)
+					(loop $spin (br $spin))
+					unreachable)
+				(func (export "query") (param i32 i32) (result i32) unreachable))"#,
+		)
+		.unwrap();
+		assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), code.clone()));
+		let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+		let client_id = ClientId::new("08-wasm", 0).unwrap();
+		let block = wasm::BlockInfo { height: 1, time: 0, chain_id: "2087".to_string() };
+
+		assert!(matches!(
+			wasm::execute::<Test>(&client_id, &code_id, &block, b""),
+			Err(wasm::Error::Execution(e)) if e.contains("fuel")
+		));
+	})
+}
+
+#[test]
+fn wasm_contract_errors_roll_back_storage() {
+	new_test_ext().execute_with(|| {
+		// `execute` stores its message under "value", and fails if the message is 4 bytes long.
+		let code = wat::parse_str(
+			r#"(module
+				(import "env" "db_write" (func $db_write (param i32 i32)))
+				(memory (export "memory") 1)
+				(global $heap (mut i32) (i32.const 1024))
+				(data (i32.const 0) "\10\00\00\00\05\00\00\00\05\00\00\00")
+				(data (i32.const 16) "value")
+				(data (i32.const 32) "\40\00\00\00\09\00\00\00\09\00\00\00")
+				(data (i32.const 48) "\60\00\00\00\12\00\00\00\12\00\00\00")
+				(data (i32.const 64) "{\"ok\":{}}")
+				(data (i32.const 96) "{\"error\":\"failed\"}")
+				(func $allocate (export "allocate") (param $size i32) (result i32)
+					(local $region i32)
+					(local.set $region (global.get $heap))
+					(i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+					(i32.store offset=4 (local.get $region) (local.get $size))
+					(i32.store offset=8 (local.get $region) (i32.const 0))
+					(global.set $heap
+						(i32.add (local.get $region) (i32.add (i32.const 12) (local.get $size))))
+					(local.get $region))
+				(func (export "deallocate") (param i32))
+				(func (export "execute") (param i32 i32 i32) (result i32)
+					(call $db_write (i32.const 0) (local.get 2))
+					(if (result i32) (i32.eq (i32.load offset=8 (local.get 2)) (i32.const 4))
+						(then (i32.const 48))
+						(else (i32.const 32))))
+				(func (export "query") (param i32 i32) (result i32) unreachable))"#,
+		)
+		.unwrap();
+		assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), code.clone()));
+		let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+		let client_id = ClientId::new("08-wasm", 0).unwrap();
+		let block = wasm::BlockInfo { height: 1, time: 0, chain_id: "2087".to_string() };
+
+		assert_ok!(wasm::execute::<Test>(&client_id, &code_id, &block, b"saved"));
+		assert_eq!(wasm_client_value(&client_id, b"value"), Some(b"saved".to_vec()));
+		assert!(matches!(
+			wasm::execute::<Test>(&client_id, &code_id, &block, b"fail"),
+			Err(wasm::Error::Contract(e)) if e == "failed"
+		));
+		assert_eq!(wasm_client_value(&client_id, b"value"), Some(b"saved".to_vec()));
+	})
+}
+
+#[test]
+fn wasm_contracts_scan_storage_within_bounds_in_both_orders() {
+	new_test_ext().execute_with(|| {
+		let client_id = ClientId::new("08-wasm", 0).unwrap();
+		let block = wasm::BlockInfo { height: 1, time: 0, chain_id: "2087".to_string() };
+		let child_info = ChildInfo::new_default(<Test as Config>::PalletPrefix::get());
+		for key in [b"a", b"b", b"c", b"d", b"e"] {
+			child::put(&child_info, &wasm_client_key(&client_id, key), &b"1".to_vec());
+		}
+
+		for (bounded, order, scanned) in [
+			(true, 1, &b"bc"[..]),
+			(true, 2, &b"cb"[..]),
+			// "scanned" is one of the keys once the contract wrote it
+			(false, 1, &b"abcdes"[..]),
+			(false, 2, &b"sedcba"[..]),
+		] {
+			let code = wasm_scan_contract(bounded, order);
+			assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), code.clone()));
+			let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+			assert_eq!(wasm::execute::<Test>(&client_id, &code_id, &block, b"").unwrap(), b"");
+			assert_eq!(wasm_client_value(&client_id, b"scanned"), Some(scanned.to_vec()));
+		}
+	})
+}
+
+#[test]
+fn wasm_contracts_only_access_the_storage_of_their_client() {
+	new_test_ext().execute_with(|| {
+		let clients = [0, 1, 10].map(|i| ClientId::new("08-wasm", i).unwrap());
+		let block = wasm::BlockInfo { height: 1, time: 0, chain_id: "2087".to_string() };
+		let child_info = ChildInfo::new_default(<Test as Config>::PalletPrefix::get());
+		for (client_id, keys) in clients.iter().zip([&[b"a", b"b"][..], &[b"c"][..], &[b"d"][..]]) {
+			for key in keys {
+				child::put(&child_info, &wasm_client_key(client_id, *key), &b"1".to_vec());
+			}
+		}
+
+		let code = wasm_scan_contract(false, 1);
+		assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), code.clone()));
+		let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+		for (client_id, scanned) in clients.iter().zip([&b"ab"[..], &b"c"[..], &b"d"[..]]) {
+			assert_ok!(wasm::execute::<Test>(client_id, &code_id, &block, b""));
+			assert_eq!(wasm_client_value(client_id, b"scanned"), Some(scanned.to_vec()));
+		}
+	})
+}
+
+#[test]
+fn migrate_wasm_client_swaps_code_id() {
+	new_test_ext().execute_with(|| {
+		let contract = |extra_export: &str| {
+			wat::parse_str(format!(
+				r#"(module
+					(memory (export "memory") 1)
+					(func (export "allocate") (param i32) (result i32) unreachable)
+					(func (export "deallocate") (param i32) unreachable)
+					(func (export "execute") (param i32 i32 i32) (result i32) unreachable)
+					(func (export "query") (param i32 i32) (result i32) unreachable)
+					{extra_export})"#
+			))
+			.unwrap()
+		};
+		let code = contract("");
+		let new_code =
+			contract(r#"(func (export "migrate") (param i32 i32) (result i32) unreachable)"#);
+		assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), code.clone()));
+		assert_ok!(Ibc::upload_wasm(RuntimeOrigin::root(), new_code.clone()));
+		let code_id = sp_io::hashing::sha2_256(&code).to_vec();
+		let new_code_id = sp_io::hashing::sha2_256(&new_code).to_vec();
+
+		let mock_client_state =
+			MockClientState::new(MockClientMessage::from(MockHeader::new(Height::new(0, 1))));
+		let mock_client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		let wasm_client_id = ClientId::new("08-wasm", 0).unwrap();
+		let mut ctx = Context::<Test>::default();
+		ctx.store_client_state(
+			mock_client_id.clone(),
+			AnyClientState::Mock(mock_client_state.clone()),
+		)
+		.unwrap();
+		ctx.store_client_state(
+			wasm_client_id.clone(),
+			AnyClientState::wasm(AnyClientState::Mock(mock_client_state), code_id.clone()).unwrap(),
+		)
+		.unwrap();
+		let migrate = |origin, client_id: &ClientId, code_id: &[u8], migrate_msg: &[u8]| {
+			Ibc::migrate_wasm_client(
+				origin,
+				client_id.as_bytes().to_vec(),
+				code_id.to_vec(),
+				migrate_msg.to_vec(),
+			)
+		};
+
+		assert_noop!(
+			migrate(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				&wasm_client_id,
+				&new_code_id,
+				b""
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			migrate(RuntimeOrigin::root(), &wasm_client_id, &[0; 32], b""),
+			crate::Error::<Test>::WasmCodeNotFound
+		);
+		assert_noop!(
+			migrate(RuntimeOrigin::root(), &mock_client_id, &new_code_id, b""),
+			crate::Error::<Test>::NotWasmClient
+		);
+		assert_noop!(
+			migrate(RuntimeOrigin::root(), &wasm_client_id, &code_id, b""),
+			crate::Error::<Test>::WasmCodeUnchanged
+		);
+		assert_noop!(
+			migrate(RuntimeOrigin::root(), &wasm_client_id, &new_code_id, b"{}"),
+			crate::Error::<Test>::WasmMigrationFailed
+		);
+
+		assert_ok!(migrate(RuntimeOrigin::root(), &wasm_client_id, &new_code_id, b""));
+		match ctx.client_state(&wasm_client_id).unwrap() {
+			AnyClientState::Wasm(client_state) => assert_eq!(client_state.code_id, new_code_id),
+			client_state => panic!("unexpected client state {client_state:?}"),
+		}
+
+		// migrations are charged for a full run of the contract's `migrate` entry point
+		let call = crate::Call::<Test>::migrate_wasm_client {
+			client_id: wasm_client_id.as_bytes().to_vec(),
+			code_id,
+			migrate_msg: b"{}".to_vec(),
+		};
+		assert_eq!(
+			call.get_dispatch_info().weight,
+			WeightPerWasmFuel::get().saturating_mul(<Test as Config>::MaxWasmFuel::get())
+		);
+	})
+}
+
+#[test]
+fn light_clients_are_dispatched_by_client_type() {
+	type LightClients = <Test as Config>::LightClients;
+	let supports = <LightClients as LightClientRegistry<Test>>::supports;
+	let weigh = <LightClients as LightClientRegistry<Test>>::weigh;
+
+	assert!(supports("07-tendermint"));
+	assert!(supports("08-wasm"));
+	assert!(supports(&MockClientState::client_type()));
+	assert!(!supports("06-solomachine"));
+	assert_eq!(
+		weigh(&MockClientState::client_type(), LightClientMsg::SendPacket),
+		Some(Weight::default())
+	);
+	assert_eq!(weigh("06-solomachine", LightClientMsg::SendPacket), None);
+
+	new_test_ext().execute_with(|| {
+		let mut ctx = Context::<Test>::default();
+		let unsupported_client_id = ClientId::new("06-solomachine", 0).unwrap();
+		assert!(ctx
+			.store_client_type(unsupported_client_id.clone(), "06-solomachine".to_string())
+			.is_err());
+		assert!(ctx.client_type(&unsupported_client_id).is_err());
+
+		let mock_client_id = ClientId::new(&MockClientState::client_type(), 0).unwrap();
+		assert_ok!(ctx.store_client_type(mock_client_id.clone(), MockClientState::client_type()));
+		assert_eq!(ctx.client_type(&mock_client_id).unwrap(), MockClientState::client_type());
+	})
+}
+
+#[test]
+#[cfg(feature = "runtime-benchmarks")]
+fn tendermint_client_messages_keep_their_type_url() {
+	use crate::{
+		benchmarks::tendermint_benchmark_utils::generate_tendermint_header,
+		light_clients::AnyClientMessage,
+	};
+	use ibc::core::ics02_client::client_message::ClientMessage as _;
+	use ics07_tendermint::client_message::{
+		ClientMessage, TENDERMINT_CLIENT_MESSAGE_TYPE_URL, TENDERMINT_HEADER_TYPE_URL,
+	};
+
+	new_test_ext().execute_with(|| {
+		let (.., header) = generate_tendermint_header(2, 2);
+		let client_message = ClientMessage::Header(header.clone());
+
+		let any = ibc_proto::google::protobuf::Any::from(AnyClientMessage::Tendermint(
+			client_message.clone(),
+		));
+		assert_eq!(any.type_url, TENDERMINT_CLIENT_MESSAGE_TYPE_URL);
+		assert_eq!(any.value, client_message.encode_to_vec().unwrap());
+		match AnyClientMessage::try_from(any).unwrap() {
+			AnyClientMessage::Tendermint(decoded) => assert_eq!(decoded, client_message),
+			msg => panic!("unexpected client message {msg:?}"),
+		}
+
+		let any = ibc_proto::google::protobuf::Any {
+			type_url: TENDERMINT_HEADER_TYPE_URL.to_string(),
+			value: header.encode_vec().unwrap(),
+		};
+		match AnyClientMessage::try_from(any).unwrap() {
+			AnyClientMessage::Tendermint(decoded) => assert_eq!(decoded, client_message),
+			msg => panic!("unexpected client message {msg:?}"),
+		}
+	})
+}
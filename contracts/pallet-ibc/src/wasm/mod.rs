@@ -0,0 +1,314 @@
+//! Execution of the light client contracts of 08-wasm clients, uploaded by governance with
+//! `upload_wasm`. Contracts implement the `ExecuteMsg` and `QueryMsg` interface of
+//! `ics10-grandpa-cw` and `ics07-tendermint-cw`, and their storage is the part of the ibc trie
+//! under `clients/{client_id}/`, so that the client and consensus states they write are the ones
+//! read by the pallet.
+
+mod vm;
+
+use crate::{format, impls::host_height, Config, WasmCodes};
+use alloc::string::{String, ToString};
+use core::mem;
+use frame_support::storage::{child, child::ChildInfo, with_transaction, TransactionOutcome};
+use ibc::core::ics24_host::identifier::ClientId;
+use ibc_primitives::apply_prefix;
+use ics08_wasm::{SUBJECT_PREFIX, SUBSTITUTE_PREFIX};
+use sp_core::Get;
+use sp_io::default_child_storage;
+use sp_std::{marker::PhantomData, prelude::*};
+
+pub use vm::{validate_code, Error};
+
+/// Block a contract is run at.
+pub struct BlockInfo {
+	pub height: u64,
+	/// Unix time in nanoseconds
+	pub time: u64,
+	pub chain_id: String,
+}
+
+impl BlockInfo {
+	/// The block being built.
+	pub fn current<T: Config>() -> Self
+	where
+		u32: From<<T as frame_system::Config>::BlockNumber>,
+	{
+		use frame_support::traits::UnixTime;
+		let para_id: u32 = parachain_info::Pallet::<T>::parachain_id().into();
+		Self {
+			height: host_height::<T>(),
+			time: T::TimeProvider::now().as_nanos() as u64,
+			chain_id: para_id.to_string(),
+		}
+	}
+
+	/// The CosmWasm `Env` of a contract run at this block. Client ids and chain ids don't need
+	/// to be escaped.
+	fn env(&self, client_id: &ClientId) -> Vec<u8> {
+		format!(
+			r#"{{"block":{{"height":{},"time":"{}","chain_id":"{}"}},"transaction":null,"contract":{{"address":"{}"}}}}"#,
+			self.height, self.time, self.chain_id, client_id
+		)
+		.into_bytes()
+	}
+}
+
+/// The storage of a client's contract: the keys under `clients/{client_id}/` in the ibc trie.
+struct ClientStore<T> {
+	prefix: Vec<u8>,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Config> ClientStore<T> {
+	fn new(client_id: &ClientId) -> Self {
+		let prefix = apply_prefix(T::PalletPrefix::get(), vec![format!("clients/{}/", client_id)]);
+		Self { prefix, _phantom: PhantomData }
+	}
+
+	fn key(&self, key: &[u8]) -> Vec<u8> {
+		[&self.prefix[..], key].concat()
+	}
+}
+
+impl<T: Config> vm::Storage for ClientStore<T> {
+	fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+		child::get(&ChildInfo::new_default(T::PalletPrefix::get()), &self.key(key))
+	}
+
+	fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+		child::put(
+			&ChildInfo::new_default(T::PalletPrefix::get()),
+			&self.key(key),
+			&value.to_vec(),
+		);
+		Ok(())
+	}
+
+	fn remove(&mut self, key: &[u8]) -> Result<(), Error> {
+		child::kill(&ChildInfo::new_default(T::PalletPrefix::get()), &self.key(key));
+		Ok(())
+	}
+
+	fn range(&self, start: Option<&[u8]>, end: Option<&[u8]>) -> vm::Entries {
+		let entries = ChildEntries::<T> {
+			prefix: self.prefix.clone(),
+			key: self.key(start.unwrap_or_default()),
+			first: true,
+			_phantom: PhantomData,
+		};
+		Box::new(before(entries, end))
+	}
+}
+
+/// The entries of the ibc trie under `prefix` from `key` on, without their prefix. Entries are
+/// read one at a time, as the contract asks for them.
+struct ChildEntries<T> {
+	prefix: Vec<u8>,
+	/// The first key if `first`, else the last key read.
+	key: Vec<u8>,
+	first: bool,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Config> Iterator for ChildEntries<T> {
+	type Item = (Vec<u8>, Vec<u8>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let child_info = ChildInfo::new_default(T::PalletPrefix::get());
+		if !mem::take(&mut self.first) || !child::exists(&child_info, &self.key) {
+			self.key = default_child_storage::next_key(child_info.storage_key(), &self.key)
+				.filter(|key| key.starts_with(&self.prefix))?;
+		}
+		let value = child::get(&child_info, &self.key)?;
+		Some((self.key[self.prefix.len()..].to_vec(), value))
+	}
+}
+
+/// The entries of `entries`, in ascending order of their keys, whose keys are before `end`.
+fn before(
+	entries: impl Iterator<Item = (Vec<u8>, Vec<u8>)>,
+	end: Option<&[u8]>,
+) -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> {
+	let end = end.map(<[u8]>::to_vec);
+	entries.take_while(move |(key, _)| end.as_ref().map_or(true, |end| key < end))
+}
+
+/// Where a range from `start` begins among the keys under `prefix`, without the prefix: `None` if
+/// all the keys under `prefix` are before `start`.
+fn start_under<'a>(prefix: &[u8], start: Option<&'a [u8]>) -> Option<Option<&'a [u8]>> {
+	match start {
+		None => Some(None),
+		Some(start) => match start.strip_prefix(prefix) {
+			Some(start) => Some(Some(start)),
+			None if start < prefix => Some(None),
+			None => None,
+		},
+	}
+}
+
+/// The storage of the contract recovering a client with a substitute: the storage of the subject
+/// client under `subject/`, which the contract updates, and of the substitute under `substitute/`.
+struct SubstituteStore<T> {
+	subject: ClientStore<T>,
+	substitute: ClientStore<T>,
+}
+
+impl<T: Config> vm::Storage for SubstituteStore<T> {
+	fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+		if let Some(key) = key.strip_prefix(SUBJECT_PREFIX) {
+			self.subject.get(key)
+		} else {
+			self.substitute.get(key.strip_prefix(SUBSTITUTE_PREFIX)?)
+		}
+	}
+
+	fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+		match key.strip_prefix(SUBJECT_PREFIX) {
+			Some(key) => self.subject.set(key, value),
+			None => Err(Error::Execution("only the subject client can be written".to_string())),
+		}
+	}
+
+	fn remove(&mut self, key: &[u8]) -> Result<(), Error> {
+		match key.strip_prefix(SUBJECT_PREFIX) {
+			Some(key) => self.subject.remove(key),
+			None => Err(Error::Execution("only the subject client can be written".to_string())),
+		}
+	}
+
+	fn range(&self, start: Option<&[u8]>, end: Option<&[u8]>) -> vm::Entries {
+		// `subject/` sorts before `substitute/`, so chaining both stores keeps keys in order.
+		let prefixed = |prefix: &'static [u8], entries: vm::Entries| {
+			entries.map(move |(key, value)| ([prefix, &key[..]].concat(), value))
+		};
+		let range = |prefix: &'static [u8], store: &ClientStore<T>| {
+			start_under(prefix, start)
+				.map(|start| prefixed(prefix, store.range(start, None)))
+				.into_iter()
+				.flatten()
+		};
+		let entries =
+			range(SUBJECT_PREFIX, &self.subject).chain(range(SUBSTITUTE_PREFIX, &self.substitute));
+		Box::new(before(entries, end))
+	}
+}
+
+/// The uploaded code with code id `code_id`.
+fn code<T: Config>(code_id: &[u8]) -> Result<Vec<u8>, Error> {
+	WasmCodes::<T>::get(code_id)
+		.ok_or_else(|| Error::InvalidCode(format!("no code uploaded for {}", hex::encode(code_id))))
+}
+
+/// Fuel left unused by the contracts run since [`take_unused_fuel`] was last called, out of the
+/// maximum fuel each run is charged for.
+static UNUSED_FUEL: spin::Mutex<u64> = spin::Mutex::new(0);
+
+/// Returns the fuel left unused by the contracts run since the last call, whose weight is refunded
+/// to the caller. Contracts that fail are considered to have consumed all of their fuel.
+pub fn take_unused_fuel() -> u64 {
+	mem::take(&mut *UNUSED_FUEL.lock())
+}
+
+/// Runs `entry_point` on the contract with code id `code_id` with the maximum amount of fuel,
+/// recording the fuel it leaves unused.
+fn run<T: Config, S: vm::Storage + 'static>(
+	client_id: &ClientId,
+	code_id: &[u8],
+	storage: S,
+	entry_point: vm::EntryPoint,
+) -> Result<Vec<u8>, Error> {
+	let max_fuel = T::MaxWasmFuel::get();
+	let (data, fuel) = vm::run(code_id, || code::<T>(code_id), storage, max_fuel, entry_point)?;
+	log::trace!(
+		target: "pallet_ibc",
+		"Light client contract of {} consumed {} fuel",
+		client_id,
+		fuel
+	);
+	let mut unused = UNUSED_FUEL.lock();
+	*unused = unused.saturating_add(max_fuel.saturating_sub(fuel));
+	Ok(data)
+}
+
+/// Runs `entry_point` on the contract with code id `code_id`, discarding the contract's storage
+/// changes if it fails.
+fn execute_with<T: Config, S: vm::Storage + 'static>(
+	client_id: &ClientId,
+	code_id: &[u8],
+	storage: S,
+	entry_point: vm::EntryPoint,
+) -> Result<Vec<u8>, Error> {
+	with_transaction(|| match run::<T, _>(client_id, code_id, storage, entry_point) {
+		Ok(data) => TransactionOutcome::Commit(Ok(data)),
+		Err(e) => TransactionOutcome::Rollback(Err(e)),
+	})
+}
+
+/// Runs the `execute` entry point of the contract with code id `code_id` for the client, passing
+/// it the JSON encoded `msg`, and returns the data of its response. The contract's storage changes
+/// are discarded if it fails.
+pub fn execute<T: Config>(
+	client_id: &ClientId,
+	code_id: &[u8],
+	block: &BlockInfo,
+	msg: &[u8],
+) -> Result<Vec<u8>, Error> {
+	let env = block.env(client_id);
+	let info = format!(r#"{{"sender":"{}","funds":[]}}"#, client_id);
+	let entry_point = vm::EntryPoint::Execute { env: &env, info: info.as_bytes(), msg };
+	execute_with::<T, _>(client_id, code_id, ClientStore::<T>::new(client_id), entry_point)
+}
+
+/// Runs the `execute` entry point of the contract with code id `code_id` to recover the subject
+/// client with the substitute client, passing it the JSON encoded `msg`, and returns the data of
+/// its response. The contract's storage changes are discarded if it fails.
+pub fn execute_substitute<T: Config>(
+	subject_client_id: &ClientId,
+	substitute_client_id: &ClientId,
+	code_id: &[u8],
+	block: &BlockInfo,
+	msg: &[u8],
+) -> Result<Vec<u8>, Error> {
+	let env = block.env(subject_client_id);
+	let info = format!(r#"{{"sender":"{}","funds":[]}}"#, subject_client_id);
+	let entry_point = vm::EntryPoint::Execute { env: &env, info: info.as_bytes(), msg };
+	let storage = SubstituteStore {
+		subject: ClientStore::<T>::new(subject_client_id),
+		substitute: ClientStore::<T>::new(substitute_client_id),
+	};
+	execute_with::<T, _>(subject_client_id, code_id, storage, entry_point)
+}
+
+/// Runs the `migrate` entry point of the contract with code id `code_id` for a client migrated to
+/// it, passing it the JSON encoded `msg`, and returns the data of its response. The contract's
+/// storage changes are discarded if it fails.
+pub fn migrate<T: Config>(
+	client_id: &ClientId,
+	code_id: &[u8],
+	block: &BlockInfo,
+	msg: &[u8],
+) -> Result<Vec<u8>, Error> {
+	let env = block.env(client_id);
+	let entry_point = vm::EntryPoint::Migrate { env: &env, msg };
+	execute_with::<T, _>(client_id, code_id, ClientStore::<T>::new(client_id), entry_point)
+}
+
+/// Runs the `query` entry point of the contract of the client with the JSON encoded `msg` and
+/// returns the contract's response. Storage changes are always discarded.
+pub fn query<T: Config>(
+	client_id: &ClientId,
+	code_id: &[u8],
+	block: &BlockInfo,
+	msg: &[u8],
+) -> Result<Vec<u8>, Error> {
+	let env = block.env(client_id);
+	let entry_point = vm::EntryPoint::Query { env: &env, msg };
+	with_transaction(|| {
+		TransactionOutcome::Rollback(run::<T, _>(
+			client_id,
+			code_id,
+			ClientStore::<T>::new(client_id),
+			entry_point,
+		))
+	})
+}
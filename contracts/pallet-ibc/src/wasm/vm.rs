@@ -0,0 +1,770 @@
+//! A virtual machine running CosmWasm contracts with wasmi. Only the parts of the CosmWasm
+//! interface used by light client contracts are supported: contracts can't send messages or query
+//! the chain, and the host doesn't call their `instantiate` entry point.
+
+use alloc::{
+	boxed::Box,
+	collections::BTreeMap,
+	format,
+	string::{String, ToString},
+	sync::Arc,
+	vec,
+	vec::Vec,
+};
+use core::fmt::Display;
+use serde::Deserialize;
+use sp_core::ed25519;
+use wasmi::{
+	core::Trap, AsContext, AsContextMut, Caller, Config, Engine, Extern, Linker, Memory, Module,
+	StackLimits, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+/// Size of a region, i.e. of its offset, capacity and length.
+const REGION_SIZE: usize = 12;
+/// Maximum length of the data passed through a region, so that contracts can't make the host
+/// allocate arbitrary amounts of memory.
+const MAX_REGION_LENGTH: u32 = 8 * 1024 * 1024;
+
+/// Maximum size in bytes of the linear memory of a contract, i.e. 512 wasm pages.
+const MAX_MEMORY_SIZE: usize = 32 * 1024 * 1024;
+/// Initial and maximum number of values on the stack of a contract.
+const INITIAL_VALUE_STACK_HEIGHT: usize = 1024;
+const MAX_VALUE_STACK_HEIGHT: usize = 256 * 1024;
+/// Maximum depth of the calls of a contract.
+const MAX_RECURSION_DEPTH: usize = 1024;
+/// Maximum number of compiled contracts [`run`] keeps.
+const MAX_CACHED_MODULES: usize = 16;
+
+/// Fuel charged for each storage access.
+const STORAGE_ACCESS_FUEL: u64 = 10_000;
+/// Fuel charged for each byte read from or written to storage.
+const STORAGE_BYTE_FUEL: u64 = 10;
+/// Fuel charged for each signature verified or public key recovered.
+const SIGNATURE_FUEL: u64 = 100_000;
+/// Fuel charged for each byte of a message whose signature is verified, which is hashed.
+const SIGNATURE_BYTE_FUEL: u64 = 10;
+/// Fuel charged for each region a host function reads, before the region is copied.
+const REGION_FUEL: u64 = 1_000;
+/// Fuel charged for each byte of a region a host function reads.
+const REGION_BYTE_FUEL: u64 = 1;
+
+/// Error codes returned by the crypto imports, as defined by `cosmwasm-crypto`.
+const INVALID_HASH_FORMAT: u32 = 3;
+const INVALID_SIGNATURE_FORMAT: u32 = 4;
+const INVALID_PUBKEY_FORMAT: u32 = 5;
+const INVALID_RECOVERY_PARAM: u32 = 6;
+const BATCH_ERROR: u32 = 7;
+
+/// Functions a contract must export.
+const REQUIRED_EXPORTS: &[&str] = &["memory", "allocate", "deallocate", "execute", "query"];
+
+/// Response to the queries of contracts, which aren't supported.
+const UNSUPPORTED_QUERY: &[u8] =
+	br#"{"error":{"unsupported_request":{"kind":"light client contracts can't query the chain"}}}"#;
+
+#[derive(Debug, derive_more::Display)]
+pub enum Error {
+	#[display(fmt = "Invalid contract code: {}", _0)]
+	InvalidCode(String),
+	#[display(fmt = "Contract execution failed: {}", _0)]
+	Execution(String),
+	#[display(fmt = "Contract returned an error: {}", _0)]
+	Contract(String),
+}
+
+impl From<sp_runtime::DispatchError> for Error {
+	fn from(e: sp_runtime::DispatchError) -> Self {
+		Error::Execution(format!("{e:?}"))
+	}
+}
+
+/// The storage of a contract.
+pub trait Storage {
+	fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
+	/// Fails if the contract isn't allowed to write `key`.
+	fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error>;
+	/// Fails if the contract isn't allowed to write `key`.
+	fn remove(&mut self, key: &[u8]) -> Result<(), Error>;
+	/// Entries whose keys are in `start..end`, in ascending order of their keys. Entries are read
+	/// as the iterator advances, so that the contract is charged for each of them.
+	fn range(&self, start: Option<&[u8]>, end: Option<&[u8]>) -> Entries;
+}
+
+/// Entries of the storage of a contract.
+pub type Entries = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)>>;
+
+/// An entry point of a contract, with its JSON encoded arguments. Only `migrate` is optional.
+pub enum EntryPoint<'a> {
+	Execute { env: &'a [u8], info: &'a [u8], msg: &'a [u8] },
+	Query { env: &'a [u8], msg: &'a [u8] },
+	Migrate { env: &'a [u8], msg: &'a [u8] },
+}
+
+struct HostState<S> {
+	storage: S,
+	/// Entries left in the ranges opened by the contract, indexed by iterator id.
+	iterators: Vec<Entries>,
+	limits: StoreLimits,
+}
+
+impl<S> HostState<S> {
+	/// A store for running a contract, whose memory is limited to [`MAX_MEMORY_SIZE`].
+	fn store(engine: &Engine, storage: S) -> Store<Self> {
+		let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_SIZE).memories(1).build();
+		let mut store = Store::new(engine, Self { storage, iterators: Vec::new(), limits });
+		store.limiter(|state| &mut state.limits);
+		store
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ContractResult<T> {
+	Ok(T),
+	Error(String),
+}
+
+#[derive(Deserialize)]
+struct Binary(#[serde(with = "ibc_proto::base64")] Vec<u8>);
+
+/// The parts of a contract's `Response` the host uses. Messages, attributes and events are ignored.
+#[derive(Deserialize)]
+struct Response {
+	data: Option<Binary>,
+}
+
+/// Contracts compiled by [`run`] by code id, and the engine compiling them, which modules are tied
+/// to.
+static MODULES: spin::Mutex<Option<(Engine, BTreeMap<Vec<u8>, Arc<Module>>)>> =
+	spin::Mutex::new(None);
+
+fn engine() -> Engine {
+	let stack_limits =
+		StackLimits::new(INITIAL_VALUE_STACK_HEIGHT, MAX_VALUE_STACK_HEIGHT, MAX_RECURSION_DEPTH)
+			.expect("initial stack height is below the maximum; qed");
+	let mut config = Config::default();
+	config.consume_fuel(true).set_stack_limits(stack_limits);
+	Engine::new(&config)
+}
+
+/// The compiled contract with code id `code_id`, compiling `code` if it isn't cached yet.
+fn module(
+	code_id: &[u8],
+	code: impl FnOnce() -> Result<Vec<u8>, Error>,
+) -> Result<(Engine, Arc<Module>), Error> {
+	let mut modules = MODULES.lock();
+	let (engine, modules) = modules.get_or_insert_with(|| (engine(), BTreeMap::new()));
+	if let Some(module) = modules.get(code_id) {
+		return Ok((engine.clone(), module.clone()))
+	}
+	let module =
+		Arc::new(Module::new(engine, &code()?[..]).map_err(|e| Error::InvalidCode(e.to_string()))?);
+	if modules.len() >= MAX_CACHED_MODULES {
+		modules.pop_first();
+	}
+	modules.insert(code_id.to_vec(), module.clone());
+	Ok((engine.clone(), module))
+}
+
+/// Checks that `code` is a wasm module exporting the CosmWasm entry points, which only imports
+/// functions provided by the host.
+pub fn validate_code(code: &[u8]) -> Result<(), Error> {
+	let engine = engine();
+	let module = Module::new(&engine, code).map_err(|e| Error::InvalidCode(e.to_string()))?;
+	for export in REQUIRED_EXPORTS {
+		if !module.exports().any(|e| e.name() == *export) {
+			return Err(Error::InvalidCode(format!("missing export {export}")))
+		}
+	}
+	// Linking fails if the code imports functions the host doesn't provide.
+	let mut store = HostState::store(&engine, NoStorage);
+	linker(&engine)
+		.map_err(|e| Error::Execution(e.to_string()))?
+		.instantiate(&mut store, &module)
+		.map_err(|e| Error::InvalidCode(e.to_string()))?;
+	Ok(())
+}
+
+/// Runs the entry point of the contract with code id `code_id`, consuming at most `fuel`. The
+/// contract is compiled once and cached, `code` is only loaded if it isn't cached. Returns the data
+/// of the contract's response and the fuel it consumed.
+pub fn run<S: Storage + 'static>(
+	code_id: &[u8],
+	code: impl FnOnce() -> Result<Vec<u8>, Error>,
+	storage: S,
+	fuel: u64,
+	entry_point: EntryPoint,
+) -> Result<(Vec<u8>, u64), Error> {
+	let execution_error = |e: &dyn Display| Error::Execution(e.to_string());
+	let (engine, module) = module(code_id, code)?;
+	let mut store = HostState::store(&engine, storage);
+	store.add_fuel(fuel).map_err(|e| execution_error(&e))?;
+	let linker = linker(&engine).map_err(|e| execution_error(&e))?;
+	let instance = linker
+		.instantiate(&mut store, &module)
+		.and_then(|instance| instance.start(&mut store))
+		.map_err(|e| execution_error(&e))?;
+
+	let memory = instance
+		.get_export(&store, "memory")
+		.and_then(Extern::into_memory)
+		.ok_or_else(|| Error::InvalidCode("missing export memory".to_string()))?;
+	let allocate = instance
+		.get_typed_func::<u32, u32>(&store, "allocate")
+		.map_err(|e| Error::InvalidCode(e.to_string()))?;
+	let exports = Exports { memory, allocate };
+
+	let result = match entry_point {
+		EntryPoint::Execute { env, info, msg } => {
+			let execute = instance
+				.get_typed_func::<(u32, u32, u32), u32>(&store, "execute")
+				.map_err(|e| Error::InvalidCode(e.to_string()))?;
+			let env = exports.write_region(&mut store, env).map_err(|e| execution_error(&e))?;
+			let info = exports.write_region(&mut store, info).map_err(|e| execution_error(&e))?;
+			let msg = exports.write_region(&mut store, msg).map_err(|e| execution_error(&e))?;
+			let result =
+				execute.call(&mut store, (env, info, msg)).map_err(|e| execution_error(&e))?;
+			let result = read_region(&store, memory, result).map_err(|e| execution_error(&e))?;
+			match serde_json::from_slice(&result).map_err(|e| execution_error(&e))? {
+				ContractResult::Ok(Response { data }) =>
+					data.map(|data| data.0).unwrap_or_default(),
+				ContractResult::Error(e) => return Err(Error::Contract(e)),
+			}
+		},
+		EntryPoint::Migrate { env, msg } => {
+			let migrate = instance
+				.get_typed_func::<(u32, u32), u32>(&store, "migrate")
+				.map_err(|e| Error::InvalidCode(e.to_string()))?;
+			let env = exports.write_region(&mut store, env).map_err(|e| execution_error(&e))?;
+			let msg = exports.write_region(&mut store, msg).map_err(|e| execution_error(&e))?;
+			let result = migrate.call(&mut store, (env, msg)).map_err(|e| execution_error(&e))?;
+			let result = read_region(&store, memory, result).map_err(|e| execution_error(&e))?;
+			match serde_json::from_slice(&result).map_err(|e| execution_error(&e))? {
+				ContractResult::Ok(Response { data }) =>
+					data.map(|data| data.0).unwrap_or_default(),
+				ContractResult::Error(e) => return Err(Error::Contract(e)),
+			}
+		},
+		EntryPoint::Query { env, msg } => {
+			let query = instance
+				.get_typed_func::<(u32, u32), u32>(&store, "query")
+				.map_err(|e| Error::InvalidCode(e.to_string()))?;
+			let env = exports.write_region(&mut store, env).map_err(|e| execution_error(&e))?;
+			let msg = exports.write_region(&mut store, msg).map_err(|e| execution_error(&e))?;
+			let result = query.call(&mut store, (env, msg)).map_err(|e| execution_error(&e))?;
+			let result = read_region(&store, memory, result).map_err(|e| execution_error(&e))?;
+			match serde_json::from_slice(&result).map_err(|e| execution_error(&e))? {
+				ContractResult::Ok(Binary(data)) => data,
+				ContractResult::Error(e) => return Err(Error::Contract(e)),
+			}
+		},
+	};
+	Ok((result, store.fuel_consumed().unwrap_or_default()))
+}
+
+fn trap(e: impl Display) -> Trap {
+	Trap::new(e.to_string())
+}
+
+/// The exports of a contract used to pass data to it.
+struct Exports {
+	memory: Memory,
+	allocate: TypedFunc<u32, u32>,
+}
+
+impl Exports {
+	fn of<S>(caller: &Caller<'_, HostState<S>>) -> Result<Self, Trap> {
+		let memory = caller
+			.get_export("memory")
+			.and_then(Extern::into_memory)
+			.ok_or_else(|| trap("missing export memory"))?;
+		let allocate = caller
+			.get_export("allocate")
+			.and_then(Extern::into_func)
+			.ok_or_else(|| trap("missing export allocate"))?
+			.typed::<u32, u32>(caller)
+			.map_err(trap)?;
+		Ok(Self { memory, allocate })
+	}
+
+	/// Copies `data` to a region allocated by the contract and returns the region's pointer.
+	fn write_region(&self, mut ctx: impl AsContextMut, data: &[u8]) -> Result<u32, Trap> {
+		let ptr = self.allocate.call(&mut ctx, data.len() as u32)?;
+		write_to_region(&mut ctx, self.memory, ptr, data)?;
+		Ok(ptr)
+	}
+}
+
+/// Returns the offset, capacity and length of the region at `ptr`.
+fn region(ctx: impl AsContext, memory: Memory, ptr: u32) -> Result<(u32, u32, u32), Trap> {
+	let mut region = [0u8; REGION_SIZE];
+	memory.read(&ctx, ptr as usize, &mut region).map_err(trap)?;
+	let field = |i: usize| {
+		u32::from_le_bytes(region[i * 4..(i + 1) * 4].try_into().expect("field is 4 bytes; qed"))
+	};
+	Ok((field(0), field(1), field(2)))
+}
+
+/// Returns the offset and length of the data of the region at `ptr`, checking that the region
+/// holds it and that it isn't longer than [`MAX_REGION_LENGTH`].
+fn region_data(ctx: impl AsContext, memory: Memory, ptr: u32) -> Result<(u32, u32), Trap> {
+	let (offset, capacity, length) = region(&ctx, memory, ptr)?;
+	if length > capacity || length > MAX_REGION_LENGTH {
+		return Err(trap(format!("invalid region length {length}")))
+	}
+	Ok((offset, length))
+}
+
+fn read_region(ctx: impl AsContext, memory: Memory, ptr: u32) -> Result<Vec<u8>, Trap> {
+	let (offset, length) = region_data(&ctx, memory, ptr)?;
+	let mut data = vec![0; length as usize];
+	memory.read(&ctx, offset as usize, &mut data).map_err(trap)?;
+	Ok(data)
+}
+
+/// Charges the contract for the region at `ptr`, which a host function is about to read, and
+/// returns the length of its data.
+fn charge_region<S>(
+	caller: &mut Caller<'_, HostState<S>>,
+	memory: Memory,
+	ptr: u32,
+) -> Result<u32, Trap> {
+	let (_, length) = region_data(&*caller, memory, ptr)?;
+	consume_fuel(caller, REGION_FUEL.saturating_add(REGION_BYTE_FUEL * u64::from(length)))?;
+	Ok(length)
+}
+
+/// Reads the region at `ptr` for a host function, charging the contract before copying it.
+fn read_charged_region<S>(
+	caller: &mut Caller<'_, HostState<S>>,
+	memory: Memory,
+	ptr: u32,
+) -> Result<Vec<u8>, Trap> {
+	charge_region(caller, memory, ptr)?;
+	read_region(&*caller, memory, ptr)
+}
+
+/// Reads the region at `ptr` like [`read_charged_region`], or returns `None` if `ptr` is null.
+fn read_optional_charged_region<S>(
+	caller: &mut Caller<'_, HostState<S>>,
+	memory: Memory,
+	ptr: u32,
+) -> Result<Option<Vec<u8>>, Trap> {
+	if ptr == 0 {
+		return Ok(None)
+	}
+	read_charged_region(caller, memory, ptr).map(Some)
+}
+
+/// Writes `data` to the region at `ptr`, which must be large enough to hold it.
+fn write_to_region(
+	mut ctx: impl AsContextMut,
+	memory: Memory,
+	ptr: u32,
+	data: &[u8],
+) -> Result<(), Trap> {
+	let (offset, capacity, _) = region(&ctx, memory, ptr)?;
+	if data.len() > capacity as usize {
+		return Err(trap(format!("region of {capacity} bytes can't hold {} bytes", data.len())))
+	}
+	memory.write(&mut ctx, offset as usize, data).map_err(trap)?;
+	memory
+		.write(&mut ctx, ptr as usize + 8, &(data.len() as u32).to_le_bytes())
+		.map_err(trap)
+}
+
+/// Encodes `sections` the way CosmWasm does, each followed by its big endian length.
+fn encode_sections(sections: &[&[u8]]) -> Vec<u8> {
+	let mut encoded = Vec::new();
+	for section in sections {
+		encoded.extend_from_slice(section);
+		encoded.extend_from_slice(&(section.len() as u32).to_be_bytes());
+	}
+	encoded
+}
+
+fn decode_sections(mut data: &[u8]) -> Result<Vec<&[u8]>, Trap> {
+	let mut sections = Vec::new();
+	while !data.is_empty() {
+		let (rest, length) = data.split_at(data.len().saturating_sub(4));
+		let length = u32::from_be_bytes(length.try_into().map_err(trap)?) as usize;
+		if length > rest.len() {
+			return Err(trap("invalid section length"))
+		}
+		let (rest, section) = rest.split_at(rest.len() - length);
+		sections.push(section);
+		data = rest;
+	}
+	sections.reverse();
+	Ok(sections)
+}
+
+fn consume_fuel<S>(caller: &mut Caller<'_, HostState<S>>, fuel: u64) -> Result<(), Trap> {
+	caller.consume_fuel(fuel).map(|_| ()).map_err(trap)
+}
+
+fn consume_storage_fuel<S>(
+	caller: &mut Caller<'_, HostState<S>>,
+	bytes: usize,
+) -> Result<(), Trap> {
+	consume_fuel(caller, STORAGE_ACCESS_FUEL.saturating_add(STORAGE_BYTE_FUEL * bytes as u64))
+}
+
+/// Fuel charged for verifying `signatures` signatures of messages of `message_bytes` bytes in
+/// total.
+fn signature_fuel(signatures: usize, message_bytes: usize) -> u64 {
+	SIGNATURE_FUEL
+		.saturating_mul(signatures as u64)
+		.saturating_add(SIGNATURE_BYTE_FUEL.saturating_mul(message_bytes as u64))
+}
+
+fn secp256k1_verify(hash: &[u8], signature: &[u8], public_key: &[u8]) -> u32 {
+	let hash = match <[u8; 32]>::try_from(hash) {
+		Ok(hash) => hash,
+		Err(_) => return INVALID_HASH_FORMAT,
+	};
+	if signature.len() != 64 {
+		return INVALID_SIGNATURE_FORMAT
+	}
+	if !matches!((public_key.len(), public_key.first()), (33, Some(2 | 3)) | (65, Some(4))) {
+		return INVALID_PUBKEY_FORMAT
+	}
+	for recovery_id in 0..2 {
+		let mut recoverable = [0u8; 65];
+		recoverable[..64].copy_from_slice(signature);
+		recoverable[64] = recovery_id;
+		let matches = if public_key.len() == 33 {
+			sp_io::crypto::secp256k1_ecdsa_recover_compressed(&recoverable, &hash)
+				.map_or(false, |key| key[..] == public_key[..])
+		} else {
+			sp_io::crypto::secp256k1_ecdsa_recover(&recoverable, &hash)
+				.map_or(false, |key| key[..] == public_key[1..])
+		};
+		if matches {
+			return 0
+		}
+	}
+	1
+}
+
+/// Returns the uncompressed public key recovered from the signature, or an error code.
+fn secp256k1_recover_pubkey(
+	hash: &[u8],
+	signature: &[u8],
+	recovery_param: u32,
+) -> Result<Vec<u8>, u32> {
+	let hash = <[u8; 32]>::try_from(hash).map_err(|_| INVALID_HASH_FORMAT)?;
+	if signature.len() != 64 {
+		return Err(INVALID_SIGNATURE_FORMAT)
+	}
+	if recovery_param > 1 {
+		return Err(INVALID_RECOVERY_PARAM)
+	}
+	let mut recoverable = [0u8; 65];
+	recoverable[..64].copy_from_slice(signature);
+	recoverable[64] = recovery_param as u8;
+	let key = sp_io::crypto::secp256k1_ecdsa_recover(&recoverable, &hash)
+		.map_err(|_| INVALID_SIGNATURE_FORMAT)?;
+	Ok([&[4u8][..], &key[..]].concat())
+}
+
+fn ed25519_verify(message: &[u8], signature: &[u8], public_key: &[u8]) -> u32 {
+	let signature = match <[u8; 64]>::try_from(signature) {
+		Ok(signature) => ed25519::Signature::from_raw(signature),
+		Err(_) => return INVALID_SIGNATURE_FORMAT,
+	};
+	let public_key = match <[u8; 32]>::try_from(public_key) {
+		Ok(public_key) => ed25519::Public::from_raw(public_key),
+		Err(_) => return INVALID_PUBKEY_FORMAT,
+	};
+	!sp_io::crypto::ed25519_verify(&signature, message, &public_key) as u32
+}
+
+/// Verifies a batch of signatures. As in CosmWasm, a single message may be signed by several
+/// keys, and a single key may sign several messages.
+fn ed25519_batch_verify(messages: &[&[u8]], signatures: &[&[u8]], public_keys: &[&[u8]]) -> u32 {
+	let count = signatures.len();
+	let valid_lengths = (messages.len() == count || messages.len() == 1) &&
+		(public_keys.len() == count || public_keys.len() == 1);
+	if !valid_lengths {
+		return BATCH_ERROR
+	}
+	for i in 0..count {
+		let message = messages[if messages.len() == 1 { 0 } else { i }];
+		let public_key = public_keys[if public_keys.len() == 1 { 0 } else { i }];
+		match ed25519_verify(message, signatures[i], public_key) {
+			0 => {},
+			result => return result,
+		}
+	}
+	0
+}
+
+/// Storage of the linker used to check the imports of uploaded code.
+struct NoStorage;
+
+impl Storage for NoStorage {
+	fn get(&self, _key: &[u8]) -> Option<Vec<u8>> {
+		None
+	}
+
+	fn set(&mut self, _key: &[u8], _value: &[u8]) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn remove(&mut self, _key: &[u8]) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn range(&self, _start: Option<&[u8]>, _end: Option<&[u8]>) -> Entries {
+		Box::new(core::iter::empty())
+	}
+}
+
+/// Defines the functions the host provides to contracts.
+fn linker<S: Storage + 'static>(
+	engine: &Engine,
+) -> Result<Linker<HostState<S>>, wasmi::errors::LinkerError> {
+	let mut linker = Linker::new(engine);
+	linker.func_wrap(
+		"env",
+		"db_read",
+		|mut caller: Caller<'_, HostState<S>>, key: u32| -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let key = read_charged_region(&mut caller, exports.memory, key)?;
+			let value = caller.data().storage.get(&key);
+			consume_storage_fuel(&mut caller, key.len() + value.as_ref().map_or(0, Vec::len))?;
+			match value {
+				Some(value) => exports.write_region(&mut caller, &value),
+				None => Ok(0),
+			}
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"db_write",
+		|mut caller: Caller<'_, HostState<S>>, key: u32, value: u32| -> Result<(), Trap> {
+			let exports = Exports::of(&caller)?;
+			let key = read_charged_region(&mut caller, exports.memory, key)?;
+			let value = read_charged_region(&mut caller, exports.memory, value)?;
+			consume_storage_fuel(&mut caller, key.len() + value.len())?;
+			caller.data_mut().storage.set(&key, &value).map_err(trap)
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"db_remove",
+		|mut caller: Caller<'_, HostState<S>>, key: u32| -> Result<(), Trap> {
+			let exports = Exports::of(&caller)?;
+			let key = read_charged_region(&mut caller, exports.memory, key)?;
+			consume_storage_fuel(&mut caller, key.len())?;
+			caller.data_mut().storage.remove(&key).map_err(trap)
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"db_scan",
+		|mut caller: Caller<'_, HostState<S>>,
+		 start: u32,
+		 end: u32,
+		 order: i32|
+		 -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let start = read_optional_charged_region(&mut caller, exports.memory, start)?;
+			let end = read_optional_charged_region(&mut caller, exports.memory, end)?;
+			let ascending = match order {
+				1 => true,
+				2 => false,
+				_ => return Err(trap(format!("invalid order {order}"))),
+			};
+			consume_storage_fuel(&mut caller, 0)?;
+			let entries = caller.data().storage.range(start.as_deref(), end.as_deref());
+			let entries: Entries = if ascending {
+				entries
+			} else {
+				// Storage can only be walked in ascending order, so descending ranges are read
+				// upfront, charging for each entry before the next one is read.
+				let mut collected = Vec::new();
+				for (key, value) in entries {
+					consume_storage_fuel(&mut caller, key.len() + value.len())?;
+					collected.push((key, value));
+				}
+				Box::new(collected.into_iter().rev())
+			};
+			let iterators = &mut caller.data_mut().iterators;
+			iterators.push(entries);
+			Ok(iterators.len() as u32)
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"db_next",
+		|mut caller: Caller<'_, HostState<S>>, iterator_id: u32| -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let (key, value) = caller
+				.data_mut()
+				.iterators
+				.get_mut((iterator_id as usize).wrapping_sub(1))
+				.ok_or_else(|| trap(format!("unknown iterator {iterator_id}")))?
+				.next()
+				.unwrap_or_default();
+			consume_storage_fuel(&mut caller, key.len() + value.len())?;
+			exports.write_region(&mut caller, &encode_sections(&[&key, &value]))
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"addr_validate",
+		|mut caller: Caller<'_, HostState<S>>, source: u32| -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let address = read_charged_region(&mut caller, exports.memory, source)?;
+			match address.is_empty() || core::str::from_utf8(&address).is_err() {
+				true => exports.write_region(&mut caller, b"Invalid address"),
+				false => Ok(0),
+			}
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"addr_canonicalize",
+		|mut caller: Caller<'_, HostState<S>>,
+		 source: u32,
+		 destination: u32|
+		 -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let address = read_charged_region(&mut caller, exports.memory, source)?;
+			if address.is_empty() || core::str::from_utf8(&address).is_err() {
+				return exports.write_region(&mut caller, b"Invalid address")
+			}
+			write_to_region(&mut caller, exports.memory, destination, &address)?;
+			Ok(0)
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"addr_humanize",
+		|mut caller: Caller<'_, HostState<S>>,
+		 source: u32,
+		 destination: u32|
+		 -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let address = read_charged_region(&mut caller, exports.memory, source)?;
+			if core::str::from_utf8(&address).is_err() {
+				return exports.write_region(&mut caller, b"Invalid address")
+			}
+			write_to_region(&mut caller, exports.memory, destination, &address)?;
+			Ok(0)
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"secp256k1_verify",
+		|mut caller: Caller<'_, HostState<S>>,
+		 hash: u32,
+		 signature: u32,
+		 public_key: u32|
+		 -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let hash = read_charged_region(&mut caller, exports.memory, hash)?;
+			let signature = read_charged_region(&mut caller, exports.memory, signature)?;
+			let public_key = read_charged_region(&mut caller, exports.memory, public_key)?;
+			consume_fuel(&mut caller, signature_fuel(1, 0))?;
+			Ok(secp256k1_verify(&hash, &signature, &public_key))
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"secp256k1_recover_pubkey",
+		|mut caller: Caller<'_, HostState<S>>,
+		 hash: u32,
+		 signature: u32,
+		 recovery_param: u32|
+		 -> Result<u64, Trap> {
+			let exports = Exports::of(&caller)?;
+			let hash = read_charged_region(&mut caller, exports.memory, hash)?;
+			let signature = read_charged_region(&mut caller, exports.memory, signature)?;
+			consume_fuel(&mut caller, signature_fuel(1, 0))?;
+			// The pointer to the key is returned in the low half, an error code in the high half.
+			match secp256k1_recover_pubkey(&hash, &signature, recovery_param) {
+				Ok(key) => exports.write_region(&mut caller, &key).map(u64::from),
+				Err(code) => Ok(u64::from(code) << 32),
+			}
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"ed25519_verify",
+		|mut caller: Caller<'_, HostState<S>>,
+		 message: u32,
+		 signature: u32,
+		 public_key: u32|
+		 -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let message = read_charged_region(&mut caller, exports.memory, message)?;
+			let signature = read_charged_region(&mut caller, exports.memory, signature)?;
+			let public_key = read_charged_region(&mut caller, exports.memory, public_key)?;
+			consume_fuel(&mut caller, signature_fuel(1, message.len()))?;
+			Ok(ed25519_verify(&message, &signature, &public_key))
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"ed25519_batch_verify",
+		|mut caller: Caller<'_, HostState<S>>,
+		 messages: u32,
+		 signatures: u32,
+		 public_keys: u32|
+		 -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			let messages = read_charged_region(&mut caller, exports.memory, messages)?;
+			let signatures = read_charged_region(&mut caller, exports.memory, signatures)?;
+			let public_keys = read_charged_region(&mut caller, exports.memory, public_keys)?;
+			let (messages, signatures, public_keys) = (
+				decode_sections(&messages)?,
+				decode_sections(&signatures)?,
+				decode_sections(&public_keys)?,
+			);
+			// A single message is verified against each signature.
+			let message_bytes = match messages[..] {
+				[message] => message.len().saturating_mul(signatures.len()),
+				_ => messages.iter().map(|message| message.len()).sum(),
+			};
+			consume_fuel(&mut caller, signature_fuel(signatures.len(), message_bytes))?;
+			Ok(ed25519_batch_verify(&messages, &signatures, &public_keys))
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"debug",
+		|mut caller: Caller<'_, HostState<S>>, source: u32| -> Result<(), Trap> {
+			let exports = Exports::of(&caller)?;
+			let message = read_charged_region(&mut caller, exports.memory, source)?;
+			log::debug!(
+				target: "pallet_ibc",
+				"Light client contract: {}",
+				String::from_utf8_lossy(&message)
+			);
+			Ok(())
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"query_chain",
+		|mut caller: Caller<'_, HostState<S>>, request: u32| -> Result<u32, Trap> {
+			let exports = Exports::of(&caller)?;
+			// The request isn't read, but it's charged like the requests of the other imports.
+			charge_region(&mut caller, exports.memory, request)?;
+			exports.write_region(&mut caller, UNSUPPORTED_QUERY)
+		},
+	)?;
+	linker.func_wrap(
+		"env",
+		"abort",
+		|mut caller: Caller<'_, HostState<S>>, source: u32| -> Result<(), Trap> {
+			let exports = Exports::of(&caller)?;
+			let message = read_charged_region(&mut caller, exports.memory, source)?;
+			Err(trap(format!("contract aborted: {}", String::from_utf8_lossy(&message))))
+		},
+	)?;
+	Ok(linker)
+}
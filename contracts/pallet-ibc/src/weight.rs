@@ -1,8 +1,8 @@
 use super::*;
 use crate::{light_clients::AnyClientMessage, routing::Context};
-use core::marker::PhantomData;
-use frame_support::pallet_prelude::Weight;
-use grandpa_client_primitives::justification::GrandpaJustification;
+use core::{iter, marker::PhantomData};
+use frame_support::{dispatch::DispatchClass, pallet_prelude::Weight};
+use grandpa_client_primitives::{justification::GrandpaJustification, FinalityProof};
 use ibc::core::{
 	ics02_client::msgs::ClientMsg,
 	ics03_connection::{context::ConnectionReader, msgs::ConnectionMsg},
@@ -11,7 +11,8 @@ use ibc::core::{
 	ics26_routing::msgs::Ics26Envelope,
 };
 use ibc_primitives::{client_id_from_bytes, CallbackWeight};
-use ics10_grandpa::client_message::{ClientMessage, RelayChainHeader};
+use ics10_grandpa::client_message::{ClientMessage, Header as GrandpaHeader, RelayChainHeader};
+use ics11_beefy::client_message::{ClientMessage as BeefyClientMessage, ParachainHeadersWithProof};
 use scale_info::prelude::string::ToString;
 
 pub trait WeightInfo {
@@ -41,8 +42,18 @@ pub trait WeightInfo {
 	fn on_chan_close_confirm() -> Weight;
 	fn on_acknowledgement_packet() -> Weight;
 	fn on_timeout_packet() -> Weight;
-	fn update_grandpa_client(i: u32) -> Weight;
+	fn update_grandpa_client(i: u32, j: u32, k: u32) -> Weight;
+	fn verify_grandpa_justification_sequential(i: u32) -> Weight;
+	fn verify_grandpa_justification_batch(i: u32) -> Weight;
+	fn update_beefy_client(i: u32, j: u32, k: u32, l: u32) -> Weight;
+	fn update_ethereum_client() -> Weight;
+	fn verify_ethereum_storage_proof(i: u32) -> Weight;
+	fn recv_packet_grandpa(i: u32, j: u32) -> Weight;
+	fn ack_packet_grandpa(i: u32, j: u32, k: u32) -> Weight;
+	fn timeout_packet_grandpa(i: u32, j: u32) -> Weight;
 	fn one_packet_cleanup() -> Weight;
+	fn one_escrow_audit() -> Weight;
+	fn upload_wasm(i: u32) -> Weight;
 }
 
 impl WeightInfo for () {
@@ -150,13 +161,53 @@ impl WeightInfo for () {
 		Weight::from_ref_time(0)
 	}
 
-	fn update_grandpa_client(_i: u32) -> Weight {
+	fn update_grandpa_client(_i: u32, _j: u32, _k: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn verify_grandpa_justification_sequential(_i: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn verify_grandpa_justification_batch(_i: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn update_beefy_client(_i: u32, _j: u32, _k: u32, _l: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn update_ethereum_client() -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn verify_ethereum_storage_proof(_i: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn recv_packet_grandpa(_i: u32, _j: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn ack_packet_grandpa(_i: u32, _j: u32, _k: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn timeout_packet_grandpa(_i: u32, _j: u32) -> Weight {
 		Weight::from_ref_time(0)
 	}
 
 	fn one_packet_cleanup() -> Weight {
 		Weight::from_ref_time(0)
 	}
+
+	fn one_escrow_audit() -> Weight {
+		Weight::from_ref_time(0)
+	}
+
+	fn upload_wasm(_i: u32) -> Weight {
+		Weight::from_ref_time(0)
+	}
 }
 
 pub struct WeightRouter<T: Config>(PhantomData<T>);
@@ -172,7 +223,7 @@ impl<T: Config> WeightRouter<T> {
 }
 
 /// Get client id for a port and channel combination
-pub fn channel_client<T: Config>(channel_id: &[u8], port_id: &[u8]) -> Result<ClientId, Error<T>> {
+pub fn channel_client<T: Config>(port_id: &[u8], channel_id: &[u8]) -> Result<ClientId, Error<T>> {
 	for (connection_id, channels) in ChannelsConnection::<T>::iter() {
 		if channels.contains(&(port_id.to_vec(), channel_id.to_vec())) {
 			if let Some((client_id, ..)) = ConnectionClient::<T>::iter()
@@ -185,6 +236,419 @@ pub fn channel_client<T: Config>(channel_id: &[u8], port_id: &[u8]) -> Result<Cl
 	Err(Error::<T>::Other)
 }
 
+/// A message verified by a light client, as seen by [`LightClient`].
+#[derive(Clone, Copy)]
+pub enum LightClientMsg<'a> {
+	UpdateClient(&'a AnyClientMessage),
+	ConnOpenTry,
+	ConnOpenAck,
+	ConnOpenConfirm,
+	ChanOpenInit,
+	ChanOpenTry,
+	ChanOpenAck,
+	ChanOpenConfirm,
+	ChanCloseInit,
+	ChanCloseConfirm,
+	RecvPacket {
+		data_len: u32,
+		proof_len: u32,
+	},
+	AckPacket {
+		data_len: u32,
+		ack_len: u32,
+		proof_len: u32,
+	},
+	TimeoutPacket {
+		data_len: u32,
+		proof_len: u32,
+	},
+	/// A packet sent on a channel of the client, which checks that the client isn't frozen.
+	SendPacket,
+}
+
+/// A light client supported by the runtime, implemented by a type of the crate defining the client
+/// or its weights.
+pub trait LightClient<T: Config> {
+	/// Type of the clients, e.g. `07-tendermint`.
+	const CLIENT_TYPE: &'static str;
+	/// Weight of the verification a client does for `msg`.
+	fn weigh(msg: LightClientMsg) -> Weight;
+}
+
+/// The light clients supported by a runtime. Runtimes compose them as a tuple of [`LightClient`]s,
+/// e.g. `(TendermintWeights, GrandpaWeights, WasmWeights)`, which dispatches to the element of the
+/// client's type. Clients of types that aren't part of it can't be created.
+pub trait LightClientRegistry<T: Config> {
+	/// Whether clients of type `client_type` are supported.
+	fn supports(client_type: &str) -> bool;
+	/// Weight of `msg` for a client of type `client_type`, or `None` if the client type isn't
+	/// supported.
+	fn weigh(client_type: &str, msg: LightClientMsg) -> Option<Weight>;
+}
+
+impl<T: Config> LightClientRegistry<T> for () {
+	fn supports(_client_type: &str) -> bool {
+		false
+	}
+
+	fn weigh(_client_type: &str, _msg: LightClientMsg) -> Option<Weight> {
+		None
+	}
+}
+
+macro_rules! impl_light_client_registry_for_tuples {
+	($($ty:ident),+) => {
+		impl<T: Config, $($ty: LightClient<T>),+> LightClientRegistry<T> for ($($ty,)+) {
+			fn supports(client_type: &str) -> bool {
+				false $(|| client_type == $ty::CLIENT_TYPE)+
+			}
+
+			fn weigh(client_type: &str, msg: LightClientMsg) -> Option<Weight> {
+				$(
+					if client_type == $ty::CLIENT_TYPE {
+						return Some($ty::weigh(msg))
+					}
+				)+
+				None
+			}
+		}
+	};
+}
+
+impl_light_client_registry_for_tuples!(A);
+impl_light_client_registry_for_tuples!(A, B);
+impl_light_client_registry_for_tuples!(A, B, C);
+impl_light_client_registry_for_tuples!(A, B, C, D);
+impl_light_client_registry_for_tuples!(A, B, C, D, E);
+impl_light_client_registry_for_tuples!(A, B, C, D, E, F);
+impl_light_client_registry_for_tuples!(A, B, C, D, E, F, G);
+impl_light_client_registry_for_tuples!(A, B, C, D, E, F, G, H);
+
+/// Benchmarked weights of `07-tendermint` clients.
+pub struct TendermintWeights;
+
+impl<T: Config> LightClient<T> for TendermintWeights {
+	const CLIENT_TYPE: &'static str = "07-tendermint";
+
+	fn weigh(msg: LightClientMsg) -> Weight {
+		match msg {
+			LightClientMsg::UpdateClient(AnyClientMessage::Tendermint(client_message)) =>
+				match client_message {
+					ics07_tendermint::client_message::ClientMessage::Header(header) =>
+						<T as Config>::WeightInfo::update_tendermint_client(
+							header.signed_header.commit.signatures.len() as u32,
+						),
+					ics07_tendermint::client_message::ClientMessage::Misbehaviour(misbehaviour) =>
+						<T as Config>::WeightInfo::update_tendermint_client(
+							misbehaviour.header1.signed_header.commit.signatures.len() as u32,
+						)
+						.saturating_add(<T as Config>::WeightInfo::update_tendermint_client(
+							misbehaviour.header2.signed_header.commit.signatures.len() as u32,
+						)),
+				},
+			LightClientMsg::UpdateClient(_) => Weight::MAX,
+			LightClientMsg::RecvPacket { data_len, .. } =>
+				<T as Config>::WeightInfo::recv_packet_tendermint(data_len),
+			LightClientMsg::AckPacket { data_len, ack_len, .. } =>
+				<T as Config>::WeightInfo::ack_packet_tendermint(data_len, ack_len),
+			LightClientMsg::TimeoutPacket { data_len, .. } =>
+				<T as Config>::WeightInfo::timeout_packet_tendermint(data_len),
+			msg => handshake::<T>(msg),
+		}
+	}
+}
+
+/// Benchmarked weights of `10-grandpa` clients. Handshake messages are weighed like those of
+/// tendermint clients.
+pub struct GrandpaWeights;
+
+impl<T: Config> LightClient<T> for GrandpaWeights {
+	const CLIENT_TYPE: &'static str = "10-grandpa";
+
+	fn weigh(msg: LightClientMsg) -> Weight {
+		let finality_proof =
+			|finality_proof: &FinalityProof<RelayChainHeader>, headers: usize, proofs_len: u32| {
+				// the client rejects justifications that don't decode, but only once the
+				// extrinsic is charged, so they're charged as much as possible
+				match GrandpaJustification::<RelayChainHeader>::decode(
+					&mut &*finality_proof.justification,
+				) {
+					Ok(justification) => <T as Config>::WeightInfo::update_grandpa_client(
+						justification.commit.precommits.len() as u32,
+						headers as u32,
+						proofs_len,
+					),
+					Err(_) => Weight::MAX,
+				}
+			};
+		match msg {
+			LightClientMsg::UpdateClient(AnyClientMessage::Grandpa(client_message)) =>
+				match client_message {
+					ClientMessage::Header(header) => finality_proof(
+						&header.finality_proof,
+						header.parachain_headers.len(),
+						grandpa_proofs_len(header),
+					),
+					ClientMessage::Misbehaviour(misbehaviour) =>
+						finality_proof(&misbehaviour.first_finality_proof, 0, 0).saturating_add(
+							finality_proof(&misbehaviour.second_finality_proof, 0, 0),
+						),
+				},
+			LightClientMsg::UpdateClient(_) => Weight::MAX,
+			msg => packet::<T>(msg),
+		}
+	}
+}
+
+/// Length of the trie proofs of a grandpa header, which the client decodes and hashes, including
+/// the ancestry proofs of the relay chain headers that skip blocks.
+fn grandpa_proofs_len(header: &GrandpaHeader) -> u32 {
+	let parachain_proofs = header.parachain_headers.values().flat_map(|proofs| {
+		proofs
+			.state_proof
+			.iter()
+			.chain(&proofs.extrinsic_proof)
+			.chain(iter::once(&proofs.extrinsic))
+	});
+	let len = parachain_proofs
+		.chain(header.ancestry_proofs.values().flatten())
+		.map(Vec::len)
+		.fold(0usize, usize::saturating_add);
+	len.try_into().unwrap_or(u32::MAX)
+}
+
+/// Upper bound of the number of authority signatures in the `update_beefy_client` benchmark.
+pub const MAX_BEEFY_SIGNATURES: u32 = 100;
+/// Upper bound of the number of mmr proof items in the `update_beefy_client` benchmark.
+pub const MAX_BEEFY_MMR_PROOF_ITEMS: u32 = 16;
+/// Upper bound of the number of parachain headers in the `update_beefy_client` benchmark.
+pub const MAX_BEEFY_PARACHAIN_HEADERS: u32 = 50;
+/// Upper bound of the length of the parachain headers and their proofs in the
+/// `update_beefy_client` benchmark.
+pub const MAX_BEEFY_PROOFS_LEN: u32 = 100_000;
+
+/// Length of the parachain headers of a beefy header and of their proofs, which the client
+/// decodes and hashes.
+fn beefy_proofs_len(headers: &ParachainHeadersWithProof) -> u32 {
+	let len = headers
+		.headers
+		.iter()
+		.map(|header| {
+			header.parachain_header.encoded_size() +
+				header.timestamp_extrinsic.len() +
+				header.extrinsic_proof.iter().map(Vec::len).sum::<usize>()
+		})
+		.chain(headers.mmr_proofs.iter().map(Vec::len))
+		.fold(0usize, usize::saturating_add);
+	len.try_into().unwrap_or(u32::MAX)
+}
+
+/// Benchmarked weights of `11-beefy` clients. Handshake messages are weighed like those of
+/// tendermint clients.
+pub struct BeefyWeights;
+
+impl<T: Config> LightClient<T> for BeefyWeights {
+	const CLIENT_TYPE: &'static str = "11-beefy";
+
+	fn weigh(msg: LightClientMsg) -> Weight {
+		match msg {
+			LightClientMsg::UpdateClient(AnyClientMessage::Beefy(client_message)) =>
+				match client_message {
+					BeefyClientMessage::Header(header) => {
+						let (signatures, mmr_proof_items) = header
+							.mmr_update_proof
+							.as_ref()
+							.map(|proof| {
+								(
+									proof.signed_commitment.signatures.len() as u32,
+									proof.mmr_proof.items.len() as u32,
+								)
+							})
+							.unwrap_or_default();
+						let (parachain_headers, proofs_len) = header
+							.headers_with_proof
+							.as_ref()
+							.map(|headers| {
+								(headers.headers.len() as u32, beefy_proofs_len(headers))
+							})
+							.unwrap_or_default();
+						<T as Config>::WeightInfo::update_beefy_client(
+							signatures,
+							mmr_proof_items,
+							parachain_headers,
+							proofs_len,
+						)
+					},
+					// Misbehaviours carry no evidence yet, they're weighed as two updates as
+					// large as the benchmarked ones.
+					BeefyClientMessage::Misbehaviour(_) => {
+						let update = <T as Config>::WeightInfo::update_beefy_client(
+							MAX_BEEFY_SIGNATURES,
+							MAX_BEEFY_MMR_PROOF_ITEMS,
+							MAX_BEEFY_PARACHAIN_HEADERS,
+							MAX_BEEFY_PROOFS_LEN,
+						);
+						update.saturating_add(update)
+					},
+				},
+			LightClientMsg::UpdateClient(_) => Weight::MAX,
+			msg => packet::<T>(msg),
+		}
+	}
+}
+
+/// Upper bound of the number of branch nodes in the `verify_ethereum_storage_proof` benchmark,
+/// a storage key has 64 nibbles.
+pub const MAX_ETHEREUM_PROOF_BRANCHES: u32 = 63;
+/// Length of an encoded branch node with 16 children, the largest Merkle-Patricia trie node.
+const ETHEREUM_BRANCH_NODE_LEN: u32 = 532;
+
+/// Benchmarked weights of `12-ethereum` clients. Updates are weighed as the benchmarked update
+/// signed by almost all of the sync committee. Handshake messages are weighed like those of
+/// tendermint clients.
+pub struct EthereumWeights;
+
+impl<T: Config> LightClient<T> for EthereumWeights {
+	const CLIENT_TYPE: &'static str = "12-ethereum";
+
+	fn weigh(msg: LightClientMsg) -> Weight {
+		match msg {
+			LightClientMsg::UpdateClient(AnyClientMessage::Ethereum(client_message)) =>
+				match client_message {
+					ics12_ethereum::client_message::ClientMessage::Header(_) =>
+						<T as Config>::WeightInfo::update_ethereum_client(),
+					ics12_ethereum::client_message::ClientMessage::Misbehaviour(_) =>
+						<T as Config>::WeightInfo::update_ethereum_client()
+							.saturating_add(<T as Config>::WeightInfo::update_ethereum_client()),
+				},
+			LightClientMsg::UpdateClient(_) => Weight::MAX,
+			msg => ethereum_packet::<T>(msg),
+		}
+	}
+}
+
+/// Weight of verifying a packet message against the storage of the IBC contract of an ethereum
+/// client: the packet handling of the grandpa benchmarks with their smallest proof, and the
+/// Merkle-Patricia proof weighed as if it were made of full branch nodes.
+fn ethereum_packet<T: Config>(msg: LightClientMsg) -> Weight {
+	let (packet, proof_len) = match msg {
+		LightClientMsg::RecvPacket { data_len, proof_len } =>
+			(<T as Config>::WeightInfo::recv_packet_grandpa(data_len, 1), proof_len),
+		LightClientMsg::AckPacket { data_len, ack_len, proof_len } =>
+			(<T as Config>::WeightInfo::ack_packet_grandpa(data_len, ack_len, 1), proof_len),
+		LightClientMsg::TimeoutPacket { data_len, proof_len } =>
+			(<T as Config>::WeightInfo::timeout_packet_grandpa(data_len, 1), proof_len),
+		msg => return handshake::<T>(msg),
+	};
+	let branches = proof_len / ETHEREUM_BRANCH_NODE_LEN + 1;
+	packet.saturating_add(<T as Config>::WeightInfo::verify_ethereum_storage_proof(branches))
+}
+
+/// Weight of verifying a packet message against the child trie root of a substrate chain, which
+/// grandpa and beefy clients do alike, or of a handshake message.
+fn packet<T: Config>(msg: LightClientMsg) -> Weight {
+	match msg {
+		LightClientMsg::RecvPacket { data_len, proof_len } =>
+			<T as Config>::WeightInfo::recv_packet_grandpa(data_len, proof_len),
+		LightClientMsg::AckPacket { data_len, ack_len, proof_len } =>
+			<T as Config>::WeightInfo::ack_packet_grandpa(data_len, ack_len, proof_len),
+		LightClientMsg::TimeoutPacket { data_len, proof_len } =>
+			<T as Config>::WeightInfo::timeout_packet_grandpa(data_len, proof_len),
+		msg => handshake::<T>(msg),
+	}
+}
+
+/// Weight of a handshake message. Only the handshakes of tendermint clients are benchmarked, the
+/// proofs of connection and channel ends of the other clients are assumed to cost about as much
+/// as their ics23 proofs.
+fn handshake<T: Config>(msg: LightClientMsg) -> Weight {
+	match msg {
+		LightClientMsg::ConnOpenTry => <T as Config>::WeightInfo::conn_try_open_tendermint(),
+		LightClientMsg::ConnOpenAck => <T as Config>::WeightInfo::conn_open_ack_tendermint(),
+		LightClientMsg::ConnOpenConfirm =>
+			<T as Config>::WeightInfo::conn_open_confirm_tendermint(),
+		LightClientMsg::ChanOpenInit => <T as Config>::WeightInfo::channel_open_init(),
+		LightClientMsg::ChanOpenTry => <T as Config>::WeightInfo::channel_open_try_tendermint(),
+		LightClientMsg::ChanOpenAck => <T as Config>::WeightInfo::channel_open_ack_tendermint(),
+		LightClientMsg::ChanOpenConfirm =>
+			<T as Config>::WeightInfo::channel_open_confirm_tendermint(),
+		LightClientMsg::ChanCloseInit => <T as Config>::WeightInfo::channel_close_init(),
+		LightClientMsg::ChanCloseConfirm =>
+			<T as Config>::WeightInfo::channel_close_confirm_tendermint(),
+		// updates and packets are weighed by the light client weighers, sending a packet is part
+		// of the benchmarked transfer
+		LightClientMsg::UpdateClient(_) |
+		LightClientMsg::RecvPacket { .. } |
+		LightClientMsg::AckPacket { .. } |
+		LightClientMsg::TimeoutPacket { .. } |
+		LightClientMsg::SendPacket => Weight::default(),
+	}
+}
+
+/// Weights of `08-wasm` clients, charged for the maximum fuel of each contract call. Each proof
+/// verified and each update runs the contract once more to query the status of the client.
+pub struct WasmWeights;
+
+impl<T: Config> LightClient<T> for WasmWeights {
+	const CLIENT_TYPE: &'static str = "08-wasm";
+
+	fn weigh(msg: LightClientMsg) -> Weight {
+		match msg {
+			// status, verify_client_message, check_for_misbehaviour and update_state
+			LightClientMsg::UpdateClient(_) => wasm_contract::<T>(4),
+			// the connection, client state and consensus state proofs
+			LightClientMsg::ConnOpenTry | LightClientMsg::ConnOpenAck => wasm_contract::<T>(6),
+			LightClientMsg::ChanOpenInit | LightClientMsg::ChanCloseInit => Weight::default(),
+			LightClientMsg::SendPacket => wasm_contract::<T>(1),
+			_ => wasm_contract::<T>(2),
+		}
+	}
+}
+
+/// Weight of `msg` verified by the client `client_id`. Messages of clients the runtime does not
+/// support are charged the maximum weight of an extrinsic.
+fn light_client<T: Config>(client_id: &ClientId, msg: LightClientMsg) -> Weight {
+	client_id
+		.as_str()
+		.rsplit_once('-')
+		.and_then(|(client_type, ..)| T::LightClients::weigh(client_type, msg))
+		.unwrap_or_else(unknown_light_client::<T>)
+}
+
+/// Weight of a message verified by a client of a type the runtime does not support.
+fn unknown_light_client<T: Config>() -> Weight {
+	let block_weights = <T as frame_system::Config>::BlockWeights::get();
+	block_weights
+		.get(DispatchClass::Normal)
+		.max_extrinsic
+		.unwrap_or(block_weights.max_block)
+}
+
+/// Weight of `calls` runs of the light client contract of an 08-wasm client, each consuming
+/// the maximum amount of fuel.
+fn wasm_contract<T: Config>(calls: u64) -> Weight {
+	T::WeightPerWasmFuel::get().saturating_mul(T::MaxWasmFuel::get().saturating_mul(calls))
+}
+
+/// Weight of pointing an 08-wasm client at another contract: reading the code and the client
+/// state, writing the client state, and running the `migrate` entry point unless `migrate_msg` is
+/// empty.
+pub(crate) fn migrate_wasm_client<T: Config>(migrate_msg: &[u8]) -> Weight {
+	let migration = if migrate_msg.is_empty() { Weight::default() } else { wasm_contract::<T>(1) };
+	<T as frame_system::Config>::DbWeight::get()
+		.reads_writes(2, 1)
+		.saturating_add(migration)
+}
+
+/// Weight of the light client of the channel checking a packet sent on it. Channels that don't
+/// exist weigh nothing, sending a packet on them fails.
+pub(crate) fn send_packet<T: Config>(port_id: &str, channel_id: &str) -> Weight {
+	match channel_client::<T>(port_id.as_bytes(), channel_id.as_bytes()) {
+		Ok(client_id) => light_client::<T>(&client_id, LightClientMsg::SendPacket),
+		Err(_) => Weight::default(),
+	}
+}
+
 pub(crate) fn deliver<T: Config + Send + Sync>(msgs: &[Any]) -> Weight
 where
 	u32: From<<T as frame_system::Config>::BlockNumber>,
@@ -202,109 +666,31 @@ where
 			let temp = match msg {
 				Ics26Envelope::Ics2Msg(msgs) => match msgs {
 					ClientMsg::CreateClient(_) => <T as Config>::WeightInfo::create_client(),
-					ClientMsg::UpdateClient(msg) => {
-						let client_type = msg
-							.client_id
-							.as_str()
-							.rsplit_once('-')
-							.map(|(client_type_str, ..)| client_type_str);
-						match client_type {
-							Some(ty) if ty.contains("tendermint") => match msg.client_message {
-								AnyClientMessage::Tendermint(client_message) => match client_message {
-									ics07_tendermint::client_message::ClientMessage::Header(header) => {
-										<T as Config>::WeightInfo::update_tendermint_client(header.signed_header.commit.signatures.len() as u32)
-									}
-									ics07_tendermint::client_message::ClientMessage::Misbehaviour(misbehaviour) => {
-										<T as Config>::WeightInfo::update_tendermint_client(misbehaviour.header1.signed_header.commit.signatures.len() as u32).
-											saturating_add(<T as Config>::WeightInfo::update_tendermint_client(misbehaviour.header2.signed_header.commit.signatures.len() as u32))
-									}
-
-								}
-								_ => return Weight::MAX,
-							}
-							Some(ty) if ty.contains("grandpa") => match msg.client_message {
-								AnyClientMessage::Grandpa(client_message) => match client_message {
-									ClientMessage::Header(header) => {
-										let justification =
-											GrandpaJustification::<RelayChainHeader>::decode(
-												&mut &*header.finality_proof.justification,
-											)
-											.expect("Justification should be valid");
-										<T as Config>::WeightInfo::update_grandpa_client(
-											justification.commit.precommits.len() as u32,
-										)
-									},
-									ClientMessage::Misbehaviour(misbehaviour) => {
-										let justification_a =
-											GrandpaJustification::<RelayChainHeader>::decode(
-												&mut &*misbehaviour.first_finality_proof.justification,
-											)
-												.expect("Justification should be valid");
-
-										let justification_b =
-											GrandpaJustification::<RelayChainHeader>::decode(
-												&mut &*misbehaviour.second_finality_proof.justification,
-											)
-												.expect("Justification should be valid");
-
-										<T as Config>::WeightInfo::update_grandpa_client(
-											justification_a.commit.precommits.len() as u32,
-										).saturating_add(<T as Config>::WeightInfo::update_grandpa_client(
-											justification_b.commit.precommits.len() as u32,
-										))
-									},
-								},
-								_ => return Weight::MAX,
-							},
-							_ => Weight::default(),
-						}
-					},
+					ClientMsg::UpdateClient(msg) => light_client::<T>(
+						&msg.client_id,
+						LightClientMsg::UpdateClient(&msg.client_message),
+					),
 					ClientMsg::UpgradeClient(_) => Weight::default(),
 				},
 				Ics26Envelope::Ics3Msg(msgs) => match msgs {
 					ConnectionMsg::ConnectionOpenInit(_) =>
 						<T as Config>::WeightInfo::conn_open_init(),
-					ConnectionMsg::ConnectionOpenTry(msg) => {
-						let client_type = msg
-							.client_id
-							.as_str()
-							.rsplit_once('-')
-							.map(|(client_type_str, ..)| client_type_str);
-						match client_type {
-							Some(ty) if ty.contains("tendermint") =>
-								<T as Config>::WeightInfo::conn_try_open_tendermint(),
-							_ => Weight::default(),
-						}
-					},
+					ConnectionMsg::ConnectionOpenTry(msg) =>
+						light_client::<T>(&msg.client_id, LightClientMsg::ConnOpenTry),
 					ConnectionMsg::ConnectionOpenAck(msg) => {
-						let connection_id = msg.connection_id;
 						let ctx = routing::Context::<T>::new();
-						let connection_end = ctx.connection_end(&connection_id).unwrap_or_default();
-						let client_type = connection_end
-							.client_id()
-							.as_str()
-							.rsplit_once('-')
-							.map(|(client_type_str, ..)| client_type_str);
-						match client_type {
-							Some(ty) if ty.contains("tendermint") =>
-								<T as Config>::WeightInfo::conn_open_ack_tendermint(),
-							_ => Weight::default(),
-						}
+						let connection_end =
+							ctx.connection_end(&msg.connection_id).unwrap_or_default();
+						light_client::<T>(connection_end.client_id(), LightClientMsg::ConnOpenAck)
 					},
 					ConnectionMsg::ConnectionOpenConfirm(msg) => {
-						let connection_id = msg.connection_id;
 						let ctx = routing::Context::<T>::new();
-						let connection_end = ctx.connection_end(&connection_id).unwrap_or_default();
-						let client_type = connection_end
-							.client_id()
-							.as_str()
-							.rsplit_once('-')
-							.map(|(client_type_str, ..)| client_type_str);
-						match client_type {
-							Some(ty) if ty.contains("tendermint") =>
-								<T as Config>::WeightInfo::conn_open_confirm_tendermint(),
-							_ => Weight::default(),
-						}
+						let connection_end =
+							ctx.connection_end(&msg.connection_id).unwrap_or_default();
+						light_client::<T>(
+							connection_end.client_id(),
+							LightClientMsg::ConnOpenConfirm,
+						)
 					},
 				},
 				Ics26Envelope::Ics4ChannelMsg(msgs) => match msgs {
@@ -318,16 +704,10 @@ where
 									let ctx = routing::Context::<T>::new();
 									let connection_end =
 										ctx.connection_end(connection_id).unwrap_or_default();
-									let client_type = connection_end
-										.client_id()
-										.as_str()
-										.rsplit_once('-')
-										.map(|(client_type_str, ..)| client_type_str);
-									match client_type {
-										Some(ty) if ty.contains("tendermint") =>
-											<T as Config>::WeightInfo::channel_open_init(),
-										_ => Weight::default(),
-									}
+									light_client::<T>(
+										connection_end.client_id(),
+										LightClientMsg::ChanOpenInit,
+									)
 								},
 								None => Weight::default(),
 							};
@@ -343,16 +723,10 @@ where
 									let ctx = routing::Context::<T>::new();
 									let connection_end =
 										ctx.connection_end(connection_id).unwrap_or_default();
-									let client_type = connection_end
-										.client_id()
-										.as_str()
-										.rsplit_once('-')
-										.map(|(client_type_str, ..)| client_type_str);
-									match client_type {
-										Some(ty) if ty.contains("tendermint") =>
-											<T as Config>::WeightInfo::channel_open_try_tendermint(),
-										_ => Weight::default(),
-									}
+									light_client::<T>(
+										connection_end.client_id(),
+										LightClientMsg::ChanOpenTry,
+									)
 								},
 								None => Weight::default(),
 							};
@@ -367,17 +741,8 @@ where
 							channel_msg.port_id.as_bytes(),
 							channel_msg.channel_id.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_open_ack_tendermint(),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) =>
+								light_client::<T>(&client_id, LightClientMsg::ChanOpenAck),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
@@ -391,17 +756,8 @@ where
 							channel_msg.port_id.as_bytes(),
 							channel_msg.channel_id.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_open_confirm_tendermint(),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) =>
+								light_client::<T>(&client_id, LightClientMsg::ChanOpenConfirm),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
@@ -415,17 +771,8 @@ where
 							channel_msg.port_id.as_bytes(),
 							channel_msg.channel_id.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_close_init(),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) =>
+								light_client::<T>(&client_id, LightClientMsg::ChanCloseInit),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
@@ -439,18 +786,8 @@ where
 							channel_msg.port_id.as_bytes(),
 							channel_msg.channel_id.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_close_confirm_tendermint(
-										),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) =>
+								light_client::<T>(&client_id, LightClientMsg::ChanCloseConfirm),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
@@ -467,19 +804,14 @@ where
 							packet_msg.packet.destination_port.as_bytes(),
 							packet_msg.packet.destination_channel.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::recv_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) => light_client::<T>(
+								&client_id,
+								LightClientMsg::RecvPacket {
+									data_len: packet_msg.packet.data.len() as u32,
+									proof_len: packet_msg.proofs.object_proof().as_bytes().len()
+										as u32,
+								},
+							),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
@@ -497,20 +829,15 @@ where
 							packet_msg.packet.destination_port.as_bytes(),
 							packet_msg.packet.destination_channel.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::ack_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-											packet_msg.acknowledgement.into_bytes().len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) => light_client::<T>(
+								&client_id,
+								LightClientMsg::AckPacket {
+									data_len: packet_msg.packet.data.len() as u32,
+									ack_len: packet_msg.acknowledgement.into_bytes().len() as u32,
+									proof_len: packet_msg.proofs.object_proof().as_bytes().len()
+										as u32,
+								},
+							),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
@@ -525,19 +852,14 @@ where
 							packet_msg.packet.destination_port.as_bytes(),
 							packet_msg.packet.destination_channel.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::timeout_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) => light_client::<T>(
+								&client_id,
+								LightClientMsg::TimeoutPacket {
+									data_len: packet_msg.packet.data.len() as u32,
+									proof_len: packet_msg.proofs.object_proof().as_bytes().len()
+										as u32,
+								},
+							),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
@@ -552,19 +874,14 @@ where
 							packet_msg.packet.destination_port.as_bytes(),
 							packet_msg.packet.destination_channel.to_string().as_bytes(),
 						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::timeout_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
+							Ok(client_id) => light_client::<T>(
+								&client_id,
+								LightClientMsg::TimeoutPacket {
+									data_len: packet_msg.packet.data.len() as u32,
+									proof_len: packet_msg.proofs.object_proof().as_bytes().len()
+										as u32,
+								},
+							),
 							Err(_) => Weight::default(),
 						};
 						cb_weight.saturating_add(lc_verification_weight)
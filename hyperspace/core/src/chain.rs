@@ -16,9 +16,12 @@
 
 use crate::{
 	chains,
+	client_refresh::ClientRefreshConfig,
+	policy::PacketPolicy,
 	substrate::{
 		default::DefaultConfig, ComposableConfig, PicassoKusamaConfig, PicassoRococoConfig,
 	},
+	wallet::WalletConfig,
 };
 use async_trait::async_trait;
 #[cfg(feature = "cosmos")]
@@ -66,10 +69,9 @@ use pallet_ibc::Timeout;
 use parachain::{ParachainClient, ParachainClientConfig};
 use primitives::{
 	mock::LocalClientTypes, Chain, IbcProvider, KeyProvider, LightClientSync, MisbehaviourHandler,
-	UpdateType,
+	UpdateType, WasmClientMigration,
 };
-use serde::{Deserialize, Serialize};
-use std::{pin::Pin, time::Duration};
+use std::{collections::HashMap, pin::Pin, time::Duration};
 use tendermint_proto::Protobuf;
 use thiserror::Error;
 
@@ -83,6 +85,26 @@ pub struct Config {
 #[derive(Serialize, Deserialize)]
 pub struct CoreConfig {
 	pub prometheus_endpoint: Option<String>,
+	/// Forced client updates for light clients close to expiring
+	#[serde(default)]
+	pub client_refresh: ClientRefreshConfig,
+	/// Relayer accounts whose balance is monitored, keyed by chain name
+	#[serde(default)]
+	pub wallets: HashMap<String, WalletConfig>,
+	/// Rules restricting which packets of the whitelisted channels are relayed
+	#[serde(default)]
+	pub packet_policy: PacketPolicy,
+}
+
+impl CoreConfig {
+	/// Rejects settings the relayer can't run with.
+	pub fn validate(&self) -> Result<(), anyhow::Error> {
+		self.client_refresh.validate()?;
+		for wallet in self.wallets.values() {
+			wallet.validate()?;
+		}
+		Ok(())
+	}
 }
 
 impl From<String> for AnyError {
@@ -101,6 +123,9 @@ chains! {
 	Cosmos(CosmosClientConfig, CosmosClient<DefaultConfig>),
 }
 
+/// Wraps the client states, consensus states and client messages of `msg` into their `08-wasm`
+/// counterparts. Wrapping goes through the `Any*` types, so it works for any client type they
+/// know of.
 fn wrap_any_msg_into_wasm(msg: Any, code_id: Bytes) -> Result<Any, anyhow::Error> {
 	// TODO: consider rewriting with Ics26Envelope
 	use ibc::core::{
@@ -116,25 +141,21 @@ fn wrap_any_msg_into_wasm(msg: Any, code_id: Bytes) -> Result<Any, anyhow::Error
 
 	let msg = match msg.type_url.as_str() {
 		CREATE_CLIENT_TYPE_URL => {
-			let mut msg_decoded =
-				MsgCreateAnyClient::<LocalClientTypes>::decode_vec(&msg.value).unwrap();
+			let mut msg_decoded = MsgCreateAnyClient::<LocalClientTypes>::decode_vec(&msg.value)?;
 			msg_decoded.consensus_state = AnyConsensusState::wasm(msg_decoded.consensus_state)?;
 			msg_decoded.client_state = AnyClientState::wasm(msg_decoded.client_state, code_id)?;
 			msg_decoded.to_any()
 		},
 		CONN_OPEN_TRY_TYPE_URL => {
-			let msg_decoded =
-				MsgConnectionOpenTry::<LocalClientTypes>::decode_vec(&msg.value).unwrap();
+			let msg_decoded = MsgConnectionOpenTry::<LocalClientTypes>::decode_vec(&msg.value)?;
 			msg_decoded.to_any()
 		},
 		CONN_OPEN_ACK_TYPE_URL => {
-			let msg_decoded =
-				MsgConnectionOpenAck::<LocalClientTypes>::decode_vec(&msg.value).unwrap();
+			let msg_decoded = MsgConnectionOpenAck::<LocalClientTypes>::decode_vec(&msg.value)?;
 			msg_decoded.to_any()
 		},
 		UPDATE_CLIENT_TYPE_URL => {
-			let mut msg_decoded =
-				MsgUpdateAnyClient::<LocalClientTypes>::decode_vec(&msg.value).unwrap();
+			let mut msg_decoded = MsgUpdateAnyClient::<LocalClientTypes>::decode_vec(&msg.value)?;
 			msg_decoded.client_message = AnyClientMessage::wasm(msg_decoded.client_message)?;
 			let any = msg_decoded.to_any();
 			any
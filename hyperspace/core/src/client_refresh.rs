@@ -0,0 +1,218 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keeps light clients alive on quiet channels, by forcing a client update once a configurable
+//! fraction of the client's trusting period has passed since its last update.
+
+use metrics::handler::MetricsHandler;
+use pallet_ibc::light_clients::AnyClientState;
+use primitives::{error::Error, Chain};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ClientRefreshConfig {
+	/// Fraction of the trusting period after which a client update is forced.
+	#[serde(default = "default_refresh_fraction")]
+	pub refresh_fraction: f64,
+	/// How often, in seconds, the client state is read back from the counterparty.
+	#[serde(default = "default_check_interval_secs")]
+	pub check_interval_secs: u64,
+}
+
+fn default_refresh_fraction() -> f64 {
+	2.0 / 3.0
+}
+
+fn default_check_interval_secs() -> u64 {
+	60
+}
+
+impl ClientRefreshConfig {
+	/// Rejects settings the scheduler can't run with.
+	pub fn validate(&self) -> Result<(), anyhow::Error> {
+		anyhow::ensure!(
+			self.check_interval_secs > 0,
+			"client_refresh check_interval_secs must be positive"
+		);
+		anyhow::ensure!(
+			self.refresh_fraction > 0.0 && self.refresh_fraction < 1.0,
+			"client_refresh refresh_fraction must be between 0 and 1, got {}",
+			self.refresh_fraction
+		);
+		Ok(())
+	}
+}
+
+impl Default for ClientRefreshConfig {
+	fn default() -> Self {
+		Self {
+			refresh_fraction: default_refresh_fraction(),
+			check_interval_secs: default_check_interval_secs(),
+		}
+	}
+}
+
+/// When the source chain's light client on the sink chain is updated without new events.
+#[derive(Debug, Clone, Copy)]
+pub enum ClientRefresh {
+	/// Once a fraction of the client's trusting period has passed since its last update.
+	Scheduled(ClientRefreshConfig),
+	/// On every finality notification, so that new states can be proven right away.
+	Always,
+}
+
+impl Default for ClientRefresh {
+	fn default() -> Self {
+		Self::Scheduled(ClientRefreshConfig::default())
+	}
+}
+
+/// Tracks when the source chain's light client on the sink chain must next be updated.
+pub struct ClientRefreshScheduler {
+	refresh: ClientRefresh,
+	next_check: Option<Instant>,
+	refresh_due: bool,
+}
+
+impl ClientRefreshScheduler {
+	pub fn new(refresh: ClientRefresh) -> Self {
+		Self { refresh, next_check: None, refresh_due: false }
+	}
+
+	/// Returns true if `source`'s light client on `sink` must be updated now, regardless of new
+	/// events. The client state is only read back every `check_interval_secs`.
+	pub async fn is_refresh_due<A: Chain, B: Chain>(
+		&mut self,
+		source: &A,
+		sink: &B,
+		metrics: Option<&mut MetricsHandler>,
+	) -> Result<bool, anyhow::Error> {
+		let config = match self.refresh {
+			ClientRefresh::Scheduled(config) => config,
+			ClientRefresh::Always => return Ok(true),
+		};
+		if self.refresh_due || self.next_check.map_or(false, |next| Instant::now() < next) {
+			return Ok(self.refresh_due)
+		}
+		self.next_check = Some(Instant::now() + Duration::from_secs(config.check_interval_secs));
+
+		let (trusting_period, elapsed) = match client_age(source, sink).await? {
+			Some(age) => age,
+			None => return Ok(false),
+		};
+		let time_to_expiry = trusting_period.as_secs() as i64 - elapsed.as_secs() as i64;
+		log::debug!(
+			target: "hyperspace",
+			"{}'s client on {} expires in {time_to_expiry}s",
+			source.name(),
+			sink.name()
+		);
+		if let Some(metrics) = metrics {
+			metrics.handle_client_expiry(&source.client_id(), time_to_expiry)?;
+		}
+
+		self.refresh_due = refresh_due(trusting_period, elapsed, config.refresh_fraction);
+		if self.refresh_due {
+			log::info!(
+				target: "hyperspace",
+				"{}'s client on {} is due for a refresh, {time_to_expiry}s left in its trusting period",
+				source.name(),
+				sink.name()
+			);
+		}
+		Ok(self.refresh_due)
+	}
+
+	/// Must be called once a client update was submitted to the sink.
+	pub fn on_client_updated(&mut self) {
+		self.refresh_due = false;
+		if let ClientRefresh::Scheduled(config) = self.refresh {
+			self.next_check =
+				Some(Instant::now() + Duration::from_secs(config.check_interval_secs));
+		}
+	}
+}
+
+/// Returns true once `elapsed` covers `refresh_fraction` of `trusting_period`.
+fn refresh_due(trusting_period: Duration, elapsed: Duration, refresh_fraction: f64) -> bool {
+	elapsed >= trusting_period.mul_f64(refresh_fraction)
+}
+
+/// Returns the trusting period of `source`'s light client on `sink` and the time elapsed, on the
+/// sink, since the client was last updated. Returns `None` for clients without a trusting period.
+async fn client_age<A: Chain, B: Chain>(
+	source: &A,
+	sink: &B,
+) -> Result<Option<(Duration, Duration)>, anyhow::Error> {
+	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+	let client_state = sink
+		.query_client_state(sink_height, source.client_id())
+		.await?
+		.client_state
+		.ok_or_else(|| {
+			Error::Custom(format!("Client state for {} not found", source.client_id()))
+		})?;
+	let client_state = AnyClientState::try_from(client_state)?;
+	let trusting_period = match client_state.trusting_period() {
+		Some(trusting_period) => trusting_period,
+		None => return Ok(None),
+	};
+
+	let (_, update_time) = sink
+		.query_client_update_time_and_height(source.client_id(), client_state.latest_height())
+		.await?;
+	let elapsed = sink_timestamp.duration_since(&update_time).unwrap_or_default();
+	Ok(Some((trusting_period, elapsed)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+	#[test]
+	fn refresh_is_due_once_the_fraction_has_elapsed() {
+		let trusting_period = DAY * 3;
+		assert!(!refresh_due(trusting_period, Duration::ZERO, 2.0 / 3.0));
+		assert!(!refresh_due(trusting_period, DAY * 2 - Duration::from_secs(1), 2.0 / 3.0));
+		assert!(refresh_due(trusting_period, DAY * 2, 2.0 / 3.0));
+		assert!(refresh_due(trusting_period, DAY * 4, 2.0 / 3.0));
+	}
+
+	#[test]
+	fn refresh_fraction_scales_the_deadline() {
+		let trusting_period = DAY * 10;
+		assert!(refresh_due(trusting_period, DAY, 0.1));
+		assert!(!refresh_due(trusting_period, DAY, 0.5));
+		assert!(refresh_due(trusting_period, DAY * 5, 0.5));
+		assert!(!refresh_due(trusting_period, DAY * 9, 0.95));
+	}
+
+	#[test]
+	fn validate_rejects_fractions_outside_the_unit_interval() {
+		assert!(ClientRefreshConfig::default().validate().is_ok());
+		for refresh_fraction in [0.0, 1.0, -0.5, 1.5, f64::NAN] {
+			let config = ClientRefreshConfig { refresh_fraction, ..Default::default() };
+			assert!(config.validate().is_err(), "{refresh_fraction} should be rejected");
+		}
+	}
+
+	#[test]
+	fn validate_rejects_a_zero_check_interval() {
+		let config = ClientRefreshConfig { check_interval_secs: 0, ..Default::default() };
+		assert!(config.validate().is_err());
+	}
+}
@@ -14,23 +14,47 @@
 
 use crate::{
 	chain::{AnyConfig, Config, CoreConfig},
-	fish, relay, Mode,
+	client_refresh::ClientRefresh,
+	fish,
+	handshake::{
+		channel_status, complete_channel, complete_connection, connection_status,
+		monitor_handshakes, NextStep, HANDSHAKE_CHECK_INTERVAL,
+	},
+	logging::LogFormat,
+	relay,
+	wallet::monitor_balance,
+	Mode,
 };
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use ibc::core::{ics04_channel::channel::Order, ics24_host::identifier::PortId};
+use ibc::core::{
+	ics04_channel::channel::Order,
+	ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+};
 use metrics::{data::Metrics, handler::MetricsHandler, init_prometheus};
 use primitives::{
-	utils::{create_channel, create_clients, create_connection},
-	Chain, IbcProvider,
+	utils::{create_channel, create_clients, create_connection, timeout_future},
+	Chain, IbcProvider, WasmClientMigration,
 };
 use prometheus::Registry;
 use std::{num::NonZeroU64, path::PathBuf, str::FromStr, time::Duration};
 
+mod doctor;
+mod query;
+
+pub use doctor::DoctorCmd;
+pub use query::{OutputFormat, Query, QueryCmd};
+
 #[derive(Debug, Parser)]
 pub struct Cli {
 	#[structopt(subcommand)]
 	pub subcommand: Subcommand,
+	/// Format of the logs.
+	#[clap(long, arg_enum, global = true, default_value = "text")]
+	pub log_format: LogFormat,
+	/// OpenTelemetry collector gRPC endpoint to export traces to, e.g. `http://localhost:4317`.
+	#[clap(long, global = true)]
+	pub otlp_endpoint: Option<String>,
 }
 
 /// Possible subcommands of the main binary.
@@ -40,6 +64,11 @@ pub enum Subcommand {
 	Relay(Cmd),
 	#[clap(name = "upload-wasm", about = "Upload a WASM blob to the chain")]
 	UploadWasm(UploadWasmCmd),
+	#[clap(
+		name = "migrate-wasm-client",
+		about = "Migrate a WASM client to code uploaded with upload-wasm"
+	)]
+	MigrateWasmClient(MigrateWasmClientCmd),
 	#[clap(
 		name = "fish",
 		about = "Start the relayer in fishing mode (catching malicious transactions)"
@@ -51,6 +80,23 @@ pub enum Subcommand {
 	CreateConnection(Cmd),
 	#[clap(name = "create-channel", about = "Creates a channel on the specified port")]
 	CreateChannel(Cmd),
+	#[clap(
+		name = "complete-connection",
+		about = "Resumes a half-finished connection handshake between both chains"
+	)]
+	CompleteConnection(Cmd),
+	#[clap(
+		name = "complete-channel",
+		about = "Resumes a half-finished channel handshake between both chains"
+	)]
+	CompleteChannel(Cmd),
+	#[clap(name = "query", about = "Query IBC state of a chain")]
+	Query(QueryCmd),
+	#[clap(
+		name = "doctor",
+		about = "Checks the configuration and the state of both chains, reporting every problem found"
+	)]
+	Doctor(DoctorCmd),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -67,6 +113,12 @@ pub struct Cmd {
 	/// Port id for channel creation
 	#[clap(long)]
 	port_id: Option<String>,
+	/// Connection id on chain A of the handshake to complete, defaults to the configured one
+	#[clap(long)]
+	connection_id: Option<String>,
+	/// Channel id on chain A of the handshake to complete
+	#[clap(long)]
+	channel_id: Option<String>,
 	/// Connection delay period in seconds
 	#[clap(long)]
 	delay_period: Option<std::num::NonZeroU32>,
@@ -118,6 +170,66 @@ impl UploadWasmCmd {
 	}
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct MigrateWasmClientCmd {
+	/// Relayer chain config path.
+	#[clap(long)]
+	config: String,
+	/// New config path to avoid overriding existing configuration.
+	#[clap(long)]
+	pub out_config: Option<String>,
+	/// Id of the WASM client on the chain.
+	#[clap(long)]
+	client_id: String,
+	/// Hex-encoded code id of the new code, as printed by upload-wasm.
+	#[clap(long)]
+	code_id: String,
+	/// JSON message passed to the `migrate` entry point of the new contract. The entry point isn't
+	/// run if this is omitted.
+	#[clap(long)]
+	migrate_msg: Option<String>,
+}
+
+impl MigrateWasmClientCmd {
+	pub async fn run(&self) -> Result<AnyConfig> {
+		use tokio::fs::read_to_string;
+		let path: PathBuf = self.config.parse()?;
+		let file_content = read_to_string(path).await?;
+		let mut config: AnyConfig = toml::from_str(&file_content)?;
+		let client = config.clone().into_client().await?;
+		let client_id = ClientId::from_str(&self.client_id)?;
+		let code_id = hex::decode(self.code_id.trim_start_matches("0x"))?;
+		let migrate_msg = self.migrate_msg.clone().unwrap_or_default().into_bytes();
+		let migration = client
+			.migrate_wasm_client(client_id.clone(), code_id.clone(), migrate_msg)
+			.await?;
+		match migration {
+			WasmClientMigration::Executed => {
+				log::info!("Migrated {} to code {}", client_id, hex::encode(&code_id));
+				// Other wasm clients on the chain don't change the code id of the configured
+				// client.
+				if config.client_id() == Some(&client_id) {
+					config.set_wasm_code_id(hex::encode(&code_id));
+				}
+			},
+			// The client keeps its code until the proposal passes, the code id is updated once
+			// the migration is executed.
+			WasmClientMigration::Proposed { proposal_id } => log::info!(
+				"Submitted proposal {} migrating {} to code {}",
+				proposal_id.as_deref().unwrap_or("with an unknown id"),
+				client_id,
+				hex::encode(&code_id)
+			),
+		}
+		Ok(config)
+	}
+
+	pub async fn save_config(&self, new_config: &AnyConfig) -> Result<()> {
+		let path = self.out_config.as_ref().cloned().unwrap_or_else(|| self.config.clone());
+		write_config(path, &new_config).await
+	}
+}
+
 impl Cmd {
 	async fn parse_config(&self) -> Result<Config> {
 		use tokio::fs::read_to_string;
@@ -130,6 +242,7 @@ impl Cmd {
 		let config_b: AnyConfig = toml::from_str(&file_content)?;
 		let file_content = read_to_string(path_core).await?;
 		let config_core: CoreConfig = toml::from_str(&file_content)?;
+		config_core.validate()?;
 
 		Ok(Config { chain_a: config_a, chain_b: config_b, core: config_core })
 	}
@@ -145,6 +258,23 @@ impl Cmd {
 			Registry::new_custom(None, None).expect("this can only fail if the prefix is empty");
 		let metrics_a = Metrics::register(chain_a.name(), &registry)?;
 		let metrics_b = Metrics::register(chain_b.name(), &registry)?;
+		for (chain, metrics) in [(&chain_a, &metrics_a), (&chain_b, &metrics_b)] {
+			if let Some(wallet) = config.core.wallets.get(chain.name()) {
+				let asset_id = chain.parse_asset_id(&wallet.asset_id)?;
+				tokio::spawn(monitor_balance(
+					chain.clone(),
+					asset_id,
+					wallet.clone(),
+					Some(metrics.clone()),
+				));
+			}
+		}
+		// look for handshakes that are not making progress, without holding up relaying
+		tokio::spawn(monitor_handshakes(
+			chain_a.clone(),
+			chain_b.clone(),
+			HANDSHAKE_CHECK_INTERVAL,
+		));
 		let mut metrics_handler_a = MetricsHandler::new(registry.clone(), metrics_a);
 		let mut metrics_handler_b = MetricsHandler::new(registry.clone(), metrics_b);
 		metrics_handler_a.link_with_counterparty(&mut metrics_handler_b);
@@ -153,7 +283,16 @@ impl Cmd {
 			tokio::spawn(init_prometheus(addr, registry.clone()));
 		}
 
-		relay(chain_a, chain_b, Some(metrics_handler_a), Some(metrics_handler_b), None).await
+		relay(
+			chain_a,
+			chain_b,
+			Some(metrics_handler_a),
+			Some(metrics_handler_b),
+			None,
+			Some(ClientRefresh::Scheduled(config.core.client_refresh)),
+			Some(config.core.packet_policy),
+		)
+		.await
 	}
 
 	/// Run fisherman
@@ -203,7 +342,7 @@ impl Cmd {
 		let chain_a_clone = chain_a.clone();
 		let chain_b_clone = chain_b.clone();
 		let handle = tokio::task::spawn(async move {
-			relay(chain_a_clone, chain_b_clone, None, None, Some(Mode::Light))
+			relay(chain_a_clone, chain_b_clone, None, None, Some(Mode::Light), None, None)
 				.await
 				.unwrap();
 		});
@@ -241,7 +380,7 @@ impl Cmd {
 		let chain_a_clone = chain_a.clone();
 		let chain_b_clone = chain_b.clone();
 		let handle = tokio::task::spawn(async move {
-			relay(chain_a_clone, chain_b_clone, None, None, Some(Mode::Light))
+			relay(chain_a_clone, chain_b_clone, None, None, Some(Mode::Light), None, None)
 				.await
 				.unwrap();
 		});
@@ -268,6 +407,100 @@ impl Cmd {
 		Ok(config)
 	}
 
+	pub async fn complete_connection(&self) -> Result<Config> {
+		let mut config = self.parse_config().await?;
+		let mut chain_a = config.chain_a.clone().into_client().await?;
+		let mut chain_b = config.chain_b.clone().into_client().await?;
+		let connection_id = match &self.connection_id {
+			Some(connection_id) => ConnectionId::from_str(connection_id)?,
+			None => chain_a
+				.connection_id()
+				.ok_or_else(|| anyhow!("connection_id must be specified or configured"))?,
+		};
+
+		let handle = spawn_handshake_relay(chain_a.clone(), chain_b.clone());
+		complete_connection(&mut chain_a, &mut chain_b, connection_id.clone()).await?;
+
+		// the remaining steps are relayed as they are finalized
+		let status = timeout_future(
+			async {
+				loop {
+					match connection_status(&chain_a, &chain_b, connection_id.clone()).await {
+						Ok(status) if status.next_step == NextStep::Complete => break status,
+						Ok(_) => {},
+						Err(e) =>
+							log::debug!(target: "hyperspace", "Failed to query connection status: {e:?}"),
+					}
+					tokio::time::sleep(chain_a.expected_block_time()).await;
+				}
+			},
+			15 * 60,
+			format!("Connection {connection_id} on {} wasn't opened", chain_a.name()),
+		)
+		.await;
+		handle.abort();
+
+		let (connection_id_b, ..) =
+			status.connection_b.expect("both ends are open, so the counterparty exists");
+		log::info!("ConnectionId on Chain {}: {}", chain_a.name(), connection_id);
+		log::info!("ConnectionId on Chain {}: {}", chain_b.name(), connection_id_b);
+		config.chain_a.set_connection_id(connection_id);
+		config.chain_b.set_connection_id(connection_id_b);
+
+		Ok(config)
+	}
+
+	pub async fn complete_channel(&self) -> Result<Config> {
+		let port_id = PortId::from_str(
+			self.port_id
+				.as_ref()
+				.expect("port_id must be specified when completing a channel")
+				.as_str(),
+		)
+		.expect("Port id was invalid");
+		let channel_id = ChannelId::from_str(
+			self.channel_id
+				.as_ref()
+				.expect("channel_id must be specified when completing a channel")
+				.as_str(),
+		)
+		.expect("Channel id was invalid");
+		let mut config = self.parse_config().await?;
+		let mut chain_a = config.chain_a.clone().into_client().await?;
+		let mut chain_b = config.chain_b.clone().into_client().await?;
+
+		let handle = spawn_handshake_relay(chain_a.clone(), chain_b.clone());
+		complete_channel(&mut chain_a, &mut chain_b, port_id.clone(), channel_id).await?;
+
+		// the remaining steps are relayed as they are finalized
+		let status = timeout_future(
+			async {
+				loop {
+					match channel_status(&chain_a, &chain_b, port_id.clone(), channel_id).await {
+						Ok(status) if status.next_step == NextStep::Complete => break status,
+						Ok(_) => {},
+						Err(e) =>
+							log::debug!(target: "hyperspace", "Failed to query channel status: {e:?}"),
+					}
+					tokio::time::sleep(chain_a.expected_block_time()).await;
+				}
+			},
+			15 * 60,
+			format!("Channel {channel_id}/{port_id} on {} wasn't opened", chain_a.name()),
+		)
+		.await;
+		handle.abort();
+
+		let (port_id_b, channel_id_b, ..) =
+			status.channel_b.expect("both ends are open, so the counterparty exists");
+		log::info!("ChannelId on Chain {}: {}", chain_a.name(), channel_id);
+		log::info!("ChannelId on Chain {}: {}", chain_b.name(), channel_id_b);
+		config.chain_a.set_channel_whitelist(channel_id, port_id);
+		config.chain_b.set_channel_whitelist(channel_id_b, port_id_b);
+
+		Ok(config)
+	}
+
 	pub async fn save_config(&self, new_config: &Config) -> Result<()> {
 		let path_a = self.out_config_a.as_ref().cloned().unwrap_or_else(|| self.config_a.clone());
 		let path_b = self.out_config_b.as_ref().cloned().unwrap_or_else(|| self.config_b.clone());
@@ -276,6 +509,19 @@ impl Cmd {
 	}
 }
 
+/// Spawns the relayer in light mode, forcing client updates on every finality notification so that
+/// the handshake state can be proven on the counterparty as soon as possible.
+fn spawn_handshake_relay<A: Chain, B: Chain>(
+	chain_a: A,
+	chain_b: B,
+) -> tokio::task::JoinHandle<()> {
+	tokio::task::spawn(async move {
+		relay(chain_a, chain_b, None, None, Some(Mode::Light), Some(ClientRefresh::Always), None)
+			.await
+			.unwrap();
+	})
+}
+
 async fn write_config(path: String, config: &AnyConfig) -> Result<()> {
 	tokio::fs::write(path.parse::<PathBuf>()?, toml::to_string(config)?)
 		.await
@@ -0,0 +1,502 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::chain::{AnyChain, AnyConfig, CoreConfig};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use ibc::core::{
+	ics02_client::client_state::ClientState,
+	ics03_connection::connection::{self, ConnectionEnd},
+	ics04_channel::channel::{self, ChannelEnd},
+	ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+};
+use pallet_ibc::light_clients::AnyClientState;
+use primitives::{Chain, IbcProvider};
+use serde::de::DeserializeOwned;
+use std::{collections::HashSet, fmt::Display, net::SocketAddr, path::PathBuf, time::Duration};
+
+/// How long to wait for an endpoint to accept a connection.
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Parser)]
+pub struct DoctorCmd {
+	/// Relayer chain A config path.
+	#[clap(long)]
+	config_a: String,
+	/// Relayer chain B config path.
+	#[clap(long)]
+	config_b: String,
+	/// Relayer core config path.
+	#[clap(long)]
+	config_core: String,
+	/// Asset id on chain A whose relayer balance must be non-zero. Defaults to the asset of chain
+	/// A's wallet in the core config.
+	#[clap(long)]
+	asset_a: Option<String>,
+	/// Asset id on chain B whose relayer balance must be non-zero. Defaults to the asset of chain
+	/// B's wallet in the core config.
+	#[clap(long)]
+	asset_b: Option<String>,
+}
+
+/// Outcome of the checks. Every check runs, so that all problems are reported at once.
+#[derive(Default)]
+struct Report {
+	problems: Vec<String>,
+}
+
+impl Report {
+	fn ok(&mut self, message: impl Display) {
+		println!("[ ok ] {message}");
+	}
+
+	fn skip(&mut self, message: impl Display) {
+		println!("[skip] {message}");
+	}
+
+	fn problem(&mut self, message: impl Display) {
+		println!("[FAIL] {message}");
+		self.problems.push(message.to_string());
+	}
+
+	fn finish(self) -> Result<()> {
+		if self.problems.is_empty() {
+			println!("\nNo problems found");
+			return Ok(())
+		}
+		println!("\n{} problem(s) found:", self.problems.len());
+		for problem in &self.problems {
+			println!("  - {problem}");
+		}
+		Err(anyhow!("{} problem(s) found", self.problems.len()))
+	}
+}
+
+impl DoctorCmd {
+	pub async fn run(&self) -> Result<()> {
+		let mut report = Report::default();
+
+		let config_a = load_config::<AnyConfig>(&self.config_a, &mut report).await;
+		let config_b = load_config::<AnyConfig>(&self.config_b, &mut report).await;
+		let core = load_config::<CoreConfig>(&self.config_core, &mut report).await;
+		if let Some(core) = &core {
+			check_core_config(core, &mut report);
+		}
+
+		// Fall back to the assets of the monitored wallets
+		let wallet_asset = |config: &Option<AnyConfig>| {
+			let wallet = core.as_ref()?.wallets.get(config.as_ref()?.name())?;
+			Some(wallet.asset_id.clone())
+		};
+		let asset_a = self.asset_a.clone().or_else(|| wallet_asset(&config_a));
+		let asset_b = self.asset_b.clone().or_else(|| wallet_asset(&config_b));
+
+		let chain_a = check_chain(config_a, asset_a.as_deref(), &mut report).await;
+		let chain_b = check_chain(config_b, asset_b.as_deref(), &mut report).await;
+		match (chain_a, chain_b) {
+			(Some((config_a, chain_a)), Some((config_b, chain_b))) => {
+				let a = (&config_a, &chain_a);
+				let b = (&config_b, &chain_b);
+				check_client(a, b, &mut report).await;
+				check_client(b, a, &mut report).await;
+				check_connection(a, b, &mut report).await;
+				check_channels(a, b, &mut report).await;
+				check_channels(b, a, &mut report).await;
+			},
+			_ =>
+				report.skip("Client, connection and channel checks, both chains must be reachable"),
+		}
+
+		report.finish()
+	}
+}
+
+/// Runs the checks that only need a single chain, returning the chain if it's reachable.
+async fn check_chain(
+	config: Option<AnyConfig>,
+	asset_id: Option<&str>,
+	report: &mut Report,
+) -> Option<(AnyConfig, AnyChain)> {
+	let config = config?;
+	check_chain_config(&config, report);
+	check_endpoints(&config, report).await;
+	let chain = connect(&config, report).await?;
+	check_balance(&config, &chain, asset_id, report).await;
+	Some((config, chain))
+}
+
+async fn load_config<T: DeserializeOwned>(path: &str, report: &mut Report) -> Option<T> {
+	let result = async {
+		let path: PathBuf = path.parse()?;
+		let file_content = tokio::fs::read_to_string(path).await?;
+		Ok::<_, anyhow::Error>(toml::from_str(&file_content)?)
+	}
+	.await;
+	match result {
+		Ok(config) => {
+			report.ok(format!("Loaded config {path}"));
+			Some(config)
+		},
+		Err(e) => {
+			report.problem(format!("Failed to load config {path}: {e}"));
+			None
+		},
+	}
+}
+
+fn check_core_config(core: &CoreConfig, report: &mut Report) {
+	if let Some(endpoint) = &core.prometheus_endpoint {
+		match endpoint.parse::<SocketAddr>() {
+			Ok(_) => report.ok(format!("Prometheus endpoint {endpoint}")),
+			Err(e) => report.problem(format!(
+				"Prometheus endpoint {endpoint} is not a socket address, metrics won't be served: {e}"
+			)),
+		}
+	}
+	if let Err(e) = core.client_refresh.validate() {
+		report.problem(format!("{e}, clients may expire"));
+	}
+}
+
+fn check_chain_config(config: &AnyConfig, report: &mut Report) {
+	let name = config.name();
+	if config.client_id().is_none() {
+		report.problem(format!("{name}: client_id is not set, run create-clients first"));
+	}
+	if config.connection_id().is_none() {
+		report.problem(format!("{name}: connection_id is not set, run create-connection first"));
+	}
+
+	let mut seen = HashSet::new();
+	for (channel_id, port_id) in config.channel_whitelist() {
+		if !seen.insert((channel_id, port_id)) {
+			report.problem(format!(
+				"{name}: channel {channel_id}/{port_id} is whitelisted more than once"
+			));
+		}
+	}
+}
+
+/// Checks that every `*_url` of the chain config accepts connections.
+async fn check_endpoints(config: &AnyConfig, report: &mut Report) {
+	let name = config.name();
+	let mut urls = vec![];
+	if let Ok(config) = serde_json::to_value(config) {
+		collect_urls("", &config, &mut urls);
+	}
+
+	for (key, url) in urls {
+		let address = match socket_address(&url) {
+			Some(address) => address,
+			None => {
+				report.problem(format!("{name}: {key} {url} is not a valid url"));
+				continue
+			},
+		};
+		match tokio::time::timeout(ENDPOINT_TIMEOUT, tokio::net::TcpStream::connect(&address)).await
+		{
+			Ok(Ok(_)) => report.ok(format!("{name}: {key} {url} is reachable")),
+			Ok(Err(e)) => report.problem(format!("{name}: {key} {url} is unreachable: {e}")),
+			Err(_) => report.problem(format!(
+				"{name}: {key} {url} didn't accept a connection within {ENDPOINT_TIMEOUT:?}"
+			)),
+		}
+	}
+}
+
+/// Collects the `*_url` and `*_urls` fields of a config, including those of fallback endpoints.
+fn collect_urls(key: &str, value: &serde_json::Value, urls: &mut Vec<(String, String)>) {
+	// list elements are keyed by `field[i]`
+	let field = key
+		.trim_end_matches(']')
+		.trim_end_matches(char::is_numeric)
+		.trim_end_matches('[');
+	match value {
+		serde_json::Value::String(url) if field.ends_with("_url") || field.ends_with("_urls") =>
+			urls.push((key.to_string(), url.clone())),
+		serde_json::Value::Array(values) =>
+			for (i, value) in values.iter().enumerate() {
+				collect_urls(&format!("{key}[{i}]"), value, urls);
+			},
+		serde_json::Value::Object(fields) =>
+			for (field, value) in fields {
+				let key = if key.is_empty() { field.clone() } else { format!("{key}.{field}") };
+				collect_urls(&key, value, urls);
+			},
+		_ => {},
+	}
+}
+
+/// Returns `host:port` of a url, using the scheme's default port if there's none.
+fn socket_address(url: &str) -> Option<String> {
+	let (scheme, rest) = url.split_once("://")?;
+	let authority = rest.split('/').next().filter(|authority| !authority.is_empty())?;
+	if authority
+		.rsplit_once(':')
+		.map_or(false, |(_, port)| port.parse::<u16>().is_ok())
+	{
+		return Some(authority.to_string())
+	}
+	let port = match scheme {
+		"ws" | "http" => 80,
+		"wss" | "https" => 443,
+		_ => return None,
+	};
+	Some(format!("{authority}:{port}"))
+}
+
+async fn connect(config: &AnyConfig, report: &mut Report) -> Option<AnyChain> {
+	let name = config.name();
+	let chain = match config.clone().into_client().await {
+		Ok(chain) => chain,
+		Err(e) => {
+			report.problem(format!("{name}: failed to connect: {e}"));
+			return None
+		},
+	};
+	match chain.latest_height_and_timestamp().await {
+		Ok((height, timestamp)) => {
+			report.ok(format!("{name}: latest finalized height {height} at {timestamp}"));
+			Some(chain)
+		},
+		Err(e) => {
+			report.problem(format!("{name}: failed to query the latest height: {e}"));
+			None
+		},
+	}
+}
+
+async fn check_balance(
+	config: &AnyConfig,
+	chain: &AnyChain,
+	asset_id: Option<&str>,
+	report: &mut Report,
+) {
+	let name = config.name();
+	let asset_id = match asset_id {
+		Some(asset_id) => asset_id,
+		None => {
+			report.skip(format!("{name}: relayer balance, no asset id was given"));
+			return
+		},
+	};
+	let result = async {
+		Ok::<_, anyhow::Error>(chain.query_ibc_balance(chain.parse_asset_id(asset_id)?).await?)
+	}
+	.await;
+	match result {
+		Ok(coins) if coins.iter().any(|coin| !coin.amount.as_u256().is_zero()) =>
+			report.ok(format!(
+				"{name}: relayer balance {}",
+				coins.iter().map(|coin| coin.to_string()).collect::<Vec<_>>().join(", ")
+			)),
+		Ok(_) =>
+			report.problem(format!("{name}: relayer account has no balance of asset {asset_id}")),
+		Err(e) => report.problem(format!("{name}: failed to query the relayer balance: {e}")),
+	}
+}
+
+/// Checks that `source`'s light client exists on `sink`, tracks `source` and hasn't expired.
+async fn check_client(
+	(source_config, source): (&AnyConfig, &AnyChain),
+	(sink_config, sink): (&AnyConfig, &AnyChain),
+	report: &mut Report,
+) {
+	let (source_name, sink_name) = (source_config.name(), sink_config.name());
+	let client_id = match source_config.client_id() {
+		Some(client_id) => client_id.clone(),
+		None => return,
+	};
+	let result = async {
+		let (source_height, _) = source.latest_height_and_timestamp().await?;
+		let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+		let client_state = sink
+			.query_client_state(sink_height, client_id.clone())
+			.await?
+			.client_state
+			.ok_or_else(|| anyhow!("client {client_id} not found on {sink_name}"))?;
+		let client_state = AnyClientState::try_from(client_state)?;
+		let client_height = client_state.latest_height();
+
+		if client_height.revision_number != source_height.revision_number ||
+			client_height > source_height
+		{
+			return Err(anyhow!(
+				"client {client_id} on {sink_name} is at height {client_height}, which doesn't belong to {source_name} at height {source_height}"
+			))
+		}
+		if let Some(frozen_height) = client_state.frozen_height() {
+			return Err(anyhow!("client {client_id} on {sink_name} is frozen at {frozen_height}"))
+		}
+		if let Some(trusting_period) = client_state.trusting_period() {
+			let (_, update_time) =
+				sink.query_client_update_time_and_height(client_id.clone(), client_height).await?;
+			let elapsed = sink_timestamp.duration_since(&update_time).unwrap_or_default();
+			if elapsed >= trusting_period {
+				return Err(anyhow!(
+					"client {client_id} on {sink_name} expired, it was last updated {}s ago",
+					elapsed.as_secs()
+				))
+			}
+		}
+		Ok(client_height)
+	}
+	.await;
+
+	match result {
+		Ok(client_height) => report.ok(format!(
+			"{source_name}: client {client_id} on {sink_name} is active at height {client_height}"
+		)),
+		Err(e) => report.problem(format!("{source_name}: {e}")),
+	}
+}
+
+/// Checks that the configured connections of both chains are open and point at each other.
+async fn check_connection(
+	(config_a, chain_a): (&AnyConfig, &AnyChain),
+	(config_b, chain_b): (&AnyConfig, &AnyChain),
+	report: &mut Report,
+) {
+	let (name_a, name_b) = (config_a.name(), config_b.name());
+	let (connection_id, client_id_a, client_id_b) =
+		match (config_a.connection_id(), config_a.client_id(), config_b.client_id()) {
+			(Some(connection_id), Some(client_id_a), Some(client_id_b)) =>
+				(connection_id.clone(), client_id_a, client_id_b),
+			_ => return,
+		};
+	let result = async {
+		let connection = query_connection(chain_a, connection_id.clone()).await?;
+		if connection.state() != &connection::State::Open {
+			return Err(anyhow!("connection {connection_id} is {}", connection.state().as_str()))
+		}
+		if connection.client_id() != client_id_b {
+			return Err(anyhow!(
+				"connection {connection_id} uses client {}, but {name_b}'s client is {client_id_b}",
+				connection.client_id()
+			))
+		}
+		if connection.counterparty().client_id() != client_id_a {
+			return Err(anyhow!(
+				"connection {connection_id} has counterparty client {}, but {name_a}'s client is {client_id_a}",
+				connection.counterparty().client_id()
+			))
+		}
+		let counterparty_id = connection
+			.counterparty()
+			.connection_id()
+			.ok_or_else(|| anyhow!("connection {connection_id} has no counterparty connection"))?;
+		if Some(counterparty_id) != config_b.connection_id() {
+			return Err(anyhow!(
+				"connection {connection_id} has counterparty {counterparty_id}, but {name_b} is configured with {:?}",
+				config_b.connection_id()
+			))
+		}
+		let counterparty = query_connection(chain_b, counterparty_id.clone()).await?;
+		if counterparty.state() != &connection::State::Open ||
+			counterparty.counterparty().connection_id() != Some(&connection_id)
+		{
+			return Err(anyhow!(
+				"counterparty connection {counterparty_id} on {name_b} is {} with counterparty {:?}",
+				counterparty.state().as_str(),
+				counterparty.counterparty().connection_id()
+			))
+		}
+		Ok(counterparty_id.clone())
+	}
+	.await;
+
+	match result {
+		Ok(counterparty_id) => report.ok(format!(
+			"{name_a}: connection {connection_id} is open with {counterparty_id} on {name_b}"
+		)),
+		Err(e) => report.problem(format!("{name_a}: {e}")),
+	}
+}
+
+/// Checks that the whitelisted channels of `chain_a` are open on the configured connection and
+/// whitelisted on `chain_b`.
+async fn check_channels(
+	(config_a, chain_a): (&AnyConfig, &AnyChain),
+	(config_b, chain_b): (&AnyConfig, &AnyChain),
+	report: &mut Report,
+) {
+	let (name_a, name_b) = (config_a.name(), config_b.name());
+	let whitelist = config_a.channel_whitelist().iter().cloned().collect::<HashSet<_>>();
+	for (channel_id, port_id) in whitelist {
+		let result = async {
+			let channel = query_channel(chain_a, &channel_id, &port_id).await?;
+			if channel.state() != &channel::State::Open {
+				return Err(anyhow!("channel {channel_id}/{port_id} is {}", channel.state()))
+			}
+			let connection_id = channel.connection_hops().get(0);
+			if connection_id != config_a.connection_id() {
+				return Err(anyhow!(
+					"channel {channel_id}/{port_id} is on connection {connection_id:?}, but {name_a} is configured with {:?}",
+					config_a.connection_id()
+				))
+			}
+			let counterparty_port_id = channel.counterparty().port_id();
+			let counterparty_channel_id = channel.counterparty().channel_id().ok_or_else(|| {
+				anyhow!("channel {channel_id}/{port_id} has no counterparty channel")
+			})?;
+			let counterparty =
+				query_channel(chain_b, counterparty_channel_id, counterparty_port_id).await?;
+			if counterparty.state() != &channel::State::Open {
+				return Err(anyhow!(
+					"counterparty channel {counterparty_channel_id}/{counterparty_port_id} on {name_b} is {}",
+					counterparty.state()
+				))
+			}
+			let counterparty_key = (*counterparty_channel_id, counterparty_port_id.clone());
+			if !config_b.channel_whitelist().contains(&counterparty_key) {
+				return Err(anyhow!(
+					"counterparty channel {counterparty_channel_id}/{counterparty_port_id} is not whitelisted on {name_b}, its packets won't be relayed"
+				))
+			}
+			Ok(counterparty_key)
+		}
+		.await;
+
+		match result {
+			Ok((counterparty_channel_id, counterparty_port_id)) => report.ok(format!(
+				"{name_a}: channel {channel_id}/{port_id} is open with {counterparty_channel_id}/{counterparty_port_id} on {name_b}"
+			)),
+			Err(e) => report.problem(format!("{name_a}: {e}")),
+		}
+	}
+}
+
+async fn query_connection(chain: &AnyChain, connection_id: ConnectionId) -> Result<ConnectionEnd> {
+	let (height, _) = chain.latest_height_and_timestamp().await?;
+	let connection = chain
+		.query_connection_end(height, connection_id.clone())
+		.await?
+		.connection
+		.ok_or_else(|| anyhow!("connection {connection_id} not found on {}", chain.name()))?;
+	Ok(ConnectionEnd::try_from(connection)?)
+}
+
+async fn query_channel(
+	chain: &AnyChain,
+	channel_id: &ChannelId,
+	port_id: &PortId,
+) -> Result<ChannelEnd> {
+	let (height, _) = chain.latest_height_and_timestamp().await?;
+	let channel = chain
+		.query_channel_end(height, *channel_id, port_id.clone())
+		.await?
+		.channel
+		.ok_or_else(|| anyhow!("channel {channel_id}/{port_id} not found on {}", chain.name()))?;
+	Ok(ChannelEnd::try_from(channel)?)
+}
@@ -0,0 +1,323 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::chain::{AnyChain, AnyConfig};
+use anyhow::{anyhow, Result};
+use clap::{ArgEnum, Parser};
+use ibc::{
+	core::{
+		ics02_client::{client_consensus::ConsensusState, client_state::ClientState},
+		ics03_connection::connection::ConnectionEnd,
+		ics04_channel::channel::ChannelEnd,
+		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+	},
+	Height,
+};
+use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState};
+use primitives::{query_undelivered_acks, query_undelivered_sequences, IbcProvider};
+use serde_json::{json, Map, Value};
+use std::{path::PathBuf, str::FromStr};
+
+#[derive(Debug, Clone, Parser)]
+pub struct QueryCmd {
+	/// Relayer chain config path.
+	#[clap(long)]
+	config: String,
+	/// Height to query at, formatted as `{revision_number}-{revision_height}`. Defaults to the
+	/// latest finalized height.
+	#[clap(long)]
+	height: Option<String>,
+	/// Output format.
+	#[clap(long, arg_enum, default_value = "table")]
+	output: OutputFormat,
+	#[clap(subcommand)]
+	query: Query,
+}
+
+#[derive(Debug, Clone, Copy, ArgEnum)]
+pub enum OutputFormat {
+	Json,
+	Table,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub enum Query {
+	#[clap(name = "client-state", about = "Query the state of a light client")]
+	ClientState {
+		#[clap(long)]
+		client_id: String,
+	},
+	#[clap(name = "consensus-state", about = "Query a consensus state of a light client")]
+	ConsensusState {
+		#[clap(long)]
+		client_id: String,
+		/// Consensus height, formatted as `{revision_number}-{revision_height}`. Defaults to the
+		/// latest height of the client.
+		#[clap(long)]
+		consensus_height: Option<String>,
+	},
+	#[clap(name = "connection", about = "Query a connection end")]
+	Connection {
+		#[clap(long)]
+		connection_id: String,
+	},
+	#[clap(name = "channel", about = "Query a channel end")]
+	Channel {
+		#[clap(long)]
+		port_id: String,
+		#[clap(long)]
+		channel_id: String,
+	},
+	#[clap(name = "packet-commitments", about = "Query the sequences of all packet commitments")]
+	PacketCommitments {
+		#[clap(long)]
+		port_id: String,
+		#[clap(long)]
+		channel_id: String,
+	},
+	#[clap(
+		name = "unreceived-packets",
+		about = "Query packets sent on the channel that were not received by the counterparty"
+	)]
+	UnreceivedPackets {
+		#[clap(long)]
+		port_id: String,
+		#[clap(long)]
+		channel_id: String,
+		/// Counterparty chain config path.
+		#[clap(long)]
+		counterparty_config: String,
+	},
+	#[clap(
+		name = "unreceived-acks",
+		about = "Query acknowledgements written on the channel that were not received by the counterparty"
+	)]
+	UnreceivedAcks {
+		#[clap(long)]
+		port_id: String,
+		#[clap(long)]
+		channel_id: String,
+		/// Counterparty chain config path.
+		#[clap(long)]
+		counterparty_config: String,
+	},
+}
+
+impl QueryCmd {
+	pub async fn run(&self) -> Result<()> {
+		let chain = chain_from_config(&self.config).await?;
+		let at = match &self.height {
+			Some(height) => parse_height(height)?,
+			None => chain.latest_height_and_timestamp().await?.0,
+		};
+		let output = self.query(&chain, at).await?;
+		match self.output {
+			OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+			OutputFormat::Table => print_table(&output),
+		}
+		Ok(())
+	}
+
+	async fn query(&self, chain: &AnyChain, at: Height) -> Result<Map<String, Value>> {
+		let value = match &self.query {
+			Query::ClientState { client_id } => {
+				let client_id = ClientId::from_str(client_id)?;
+				let client_state = chain
+					.query_client_state(at, client_id.clone())
+					.await?
+					.client_state
+					.ok_or_else(|| anyhow!("Client state for {client_id} not found"))?;
+				let client_state = AnyClientState::try_from(client_state)?;
+				json!({
+					"client_id": client_id.to_string(),
+					"client_type": client_state.client_type(),
+					"latest_height": client_state.latest_height().to_string(),
+					"frozen_height": client_state.frozen_height().map(|h| h.to_string()),
+					"trusting_period_secs": client_state.trusting_period().map(|p| p.as_secs()),
+				})
+			},
+			Query::ConsensusState { client_id, consensus_height } => {
+				let client_id = ClientId::from_str(client_id)?;
+				let consensus_height = match consensus_height {
+					Some(height) => parse_height(height)?,
+					None => {
+						let client_state = chain
+							.query_client_state(at, client_id.clone())
+							.await?
+							.client_state
+							.ok_or_else(|| anyhow!("Client state for {client_id} not found"))?;
+						AnyClientState::try_from(client_state)?.latest_height()
+					},
+				};
+				let consensus_state = chain
+					.query_client_consensus(at, client_id.clone(), consensus_height)
+					.await?
+					.consensus_state
+					.ok_or_else(|| {
+						anyhow!("Consensus state for {client_id} at {consensus_height} not found")
+					})?;
+				let consensus_state = AnyConsensusState::try_from(consensus_state)?;
+				json!({
+					"client_id": client_id.to_string(),
+					"consensus_height": consensus_height.to_string(),
+					"timestamp": consensus_state.timestamp().to_string(),
+					"root": hex::encode(consensus_state.root().bytes.as_slice()),
+				})
+			},
+			Query::Connection { connection_id } => {
+				let connection_id = ConnectionId::from_str(connection_id)?;
+				let connection = chain
+					.query_connection_end(at, connection_id.clone())
+					.await?
+					.connection
+					.ok_or_else(|| anyhow!("Connection {connection_id} not found"))?;
+				let connection = ConnectionEnd::try_from(connection)?;
+				json!({
+					"connection_id": connection_id.to_string(),
+					"state": connection.state().as_str(),
+					"client_id": connection.client_id().to_string(),
+					"counterparty_client_id": connection.counterparty().client_id().to_string(),
+					"counterparty_connection_id":
+						connection.counterparty().connection_id().map(|id| id.to_string()),
+					"delay_period_secs": connection.delay_period().as_secs(),
+					"versions": connection
+						.versions()
+						.iter()
+						.map(|version| version.to_string())
+						.collect::<Vec<_>>(),
+				})
+			},
+			Query::Channel { port_id, channel_id } => {
+				let (port_id, channel_id) = parse_channel(port_id, channel_id)?;
+				let channel = chain
+					.query_channel_end(at, channel_id, port_id.clone())
+					.await?
+					.channel
+					.ok_or_else(|| anyhow!("Channel {channel_id}/{port_id} not found"))?;
+				let channel = ChannelEnd::try_from(channel)?;
+				json!({
+					"port_id": port_id.to_string(),
+					"channel_id": channel_id.to_string(),
+					"state": channel.state().as_string(),
+					"ordering": channel.ordering().as_str(),
+					"counterparty_port_id": channel.counterparty().port_id().to_string(),
+					"counterparty_channel_id":
+						channel.counterparty().channel_id().map(|id| id.to_string()),
+					"connection_hops": channel
+						.connection_hops()
+						.iter()
+						.map(|id| id.to_string())
+						.collect::<Vec<_>>(),
+					"version": channel.version().to_string(),
+				})
+			},
+			Query::PacketCommitments { port_id, channel_id } => {
+				let (port_id, channel_id) = parse_channel(port_id, channel_id)?;
+				let mut sequences =
+					chain.query_packet_commitments(at, channel_id, port_id.clone()).await?;
+				sequences.sort_unstable();
+				json!({
+					"port_id": port_id.to_string(),
+					"channel_id": channel_id.to_string(),
+					"sequences": sequences,
+				})
+			},
+			Query::UnreceivedPackets { port_id, channel_id, counterparty_config } => {
+				let (port_id, channel_id) = parse_channel(port_id, channel_id)?;
+				let counterparty = chain_from_config(counterparty_config).await?;
+				let counterparty_height = counterparty.latest_height_and_timestamp().await?.0;
+				let mut sequences = query_undelivered_sequences(
+					at,
+					counterparty_height,
+					channel_id,
+					port_id.clone(),
+					chain,
+					&counterparty,
+				)
+				.await?;
+				sequences.sort_unstable();
+				json!({
+					"port_id": port_id.to_string(),
+					"channel_id": channel_id.to_string(),
+					"sequences": sequences,
+				})
+			},
+			Query::UnreceivedAcks { port_id, channel_id, counterparty_config } => {
+				let (port_id, channel_id) = parse_channel(port_id, channel_id)?;
+				let counterparty = chain_from_config(counterparty_config).await?;
+				let counterparty_height = counterparty.latest_height_and_timestamp().await?.0;
+				let mut sequences = query_undelivered_acks(
+					at,
+					counterparty_height,
+					channel_id,
+					port_id.clone(),
+					chain,
+					&counterparty,
+				)
+				.await?;
+				sequences.sort_unstable();
+				json!({
+					"port_id": port_id.to_string(),
+					"channel_id": channel_id.to_string(),
+					"sequences": sequences,
+				})
+			},
+		};
+
+		let mut output = Map::new();
+		output.insert("height".to_string(), Value::String(at.to_string()));
+		match value {
+			Value::Object(fields) => output.extend(fields),
+			_ => unreachable!("queries always produce an object"),
+		}
+		Ok(output)
+	}
+}
+
+async fn chain_from_config(path: &str) -> Result<AnyChain> {
+	let path: PathBuf = path.parse()?;
+	let file_content = tokio::fs::read_to_string(path).await?;
+	let config: AnyConfig = toml::from_str(&file_content)?;
+	Ok(config.into_client().await?)
+}
+
+fn parse_height(height: &str) -> Result<Height> {
+	Height::from_str(height).map_err(|e| anyhow!("Invalid height {height}: {e}"))
+}
+
+fn parse_channel(port_id: &str, channel_id: &str) -> Result<(PortId, ChannelId)> {
+	Ok((PortId::from_str(port_id)?, ChannelId::from_str(channel_id)?))
+}
+
+/// Prints every field on its own row, with nested values rendered as compact json.
+fn print_table(output: &Map<String, Value>) {
+	let width = output.keys().map(|key| key.len()).max().unwrap_or_default();
+	for (key, value) in output {
+		let value = match value {
+			Value::String(s) => s.clone(),
+			Value::Null => "-".to_string(),
+			Value::Array(values) if values.is_empty() => "-".to_string(),
+			Value::Array(values) => values
+				.iter()
+				.map(|value| match value {
+					Value::String(s) => s.clone(),
+					value => value.to_string(),
+				})
+				.collect::<Vec<_>>()
+				.join(", "),
+			value => value.to_string(),
+		};
+		println!("{key:<width$}  {value}");
+	}
+}
@@ -14,7 +14,7 @@
 
 #[cfg(feature = "testing")]
 use crate::send_packet_relay::packet_relay_status;
-use crate::Mode;
+use crate::{policy::PacketPolicy, Mode};
 use codec::Encode;
 use ibc::{
 	core::{
@@ -63,6 +63,7 @@ pub async fn parse_events(
 	sink: &mut impl Chain,
 	events: Vec<IbcEvent>,
 	mode: Option<Mode>,
+	packet_policy: &PacketPolicy,
 ) -> Result<Vec<Any>, anyhow::Error> {
 	let mut messages = vec![];
 	// 1. translate events to messages
@@ -491,6 +492,19 @@ pub async fn parse_events(
 					continue
 				}
 
+				if let Err(e) = packet_policy.check_channel(
+					source.name(),
+					&channel_id,
+					&port_id,
+					channel_end.ordering,
+				) {
+					log::warn!(target: "hyperspace", "Skipping packet {}: {:?}", packet.sequence, e);
+					continue
+				}
+				if !packet_policy.allows(source.name(), &packet) {
+					continue
+				}
+
 				let packet_commitment_response = source
 					.query_packet_commitment(send_packet.height, &port_id, &channel_id, seq)
 					.await?;
@@ -511,7 +525,15 @@ pub async fn parse_events(
 				let value = msg.encode_vec()?;
 				let msg = Any { value, type_url: msg.type_url() };
 				messages.push(msg);
-				log::debug!(target: "hyperspace", "Sending packet {:?}", packet);
+				tracing::debug!(
+					target: "hyperspace",
+					chain = source.name(),
+					channel = %packet.source_channel,
+					port = %packet.source_port,
+					sequence = %packet.sequence,
+					"Sending packet {:?}",
+					packet
+				);
 			},
 			IbcEvent::WriteAcknowledgement(write_ack) => {
 				let port_id = &write_ack.packet.destination_port.clone();
@@ -556,6 +578,14 @@ pub async fn parse_events(
 					.expect("Proof height should be present");
 				let proof_height =
 					Height::new(proof_height.revision_number, proof_height.revision_height);
+				tracing::debug!(
+					target: "hyperspace",
+					chain = source.name(),
+					channel = %packet.source_channel,
+					port = %packet.source_port,
+					sequence = %packet.sequence,
+					"Sending acknowledgement"
+				);
 				let msg = MsgAcknowledgement {
 					packet,
 					acknowledgement: acknowledgement.into(),
@@ -0,0 +1,632 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resumes connection and channel handshakes that were left half-finished, e.g. because the
+//! relayer was stopped before all of Try/Ack/Confirm were relayed.
+
+use crate::{events::parse_events, policy::PacketPolicy, Mode};
+use anyhow::anyhow;
+use ibc::{
+	core::{
+		ics02_client::client_state::ClientState,
+		ics03_connection::{
+			connection::{self, ConnectionEnd, IdentifiedConnectionEnd},
+			events as connection_events,
+		},
+		ics04_channel::{
+			channel::{self, ChannelEnd, IdentifiedChannelEnd},
+			events as channel_events,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+	},
+	events::IbcEvent,
+	Height,
+};
+use pallet_ibc::light_clients::AnyClientState;
+use primitives::Chain;
+use std::{collections::HashMap, time::Duration};
+
+/// How long to wait for the counterparty light client to catch up with the handshake state.
+const CLIENT_UPDATE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// How often the relayer checks the handshakes of the configured connection and channels for
+/// progress.
+pub const HANDSHAKE_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Handshake state of one end of a connection or channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeState {
+	Init,
+	TryOpen,
+	Open,
+}
+
+/// Handshake message that can be submitted to move a connection or channel forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeStep {
+	OpenTry,
+	OpenAck,
+	OpenConfirm,
+}
+
+/// What remains to be done to open a connection or channel between chains A and B.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NextStep {
+	/// Both ends are open.
+	Complete,
+	/// The step must be proven on chain A and submitted to chain B.
+	OnB(HandshakeStep),
+	/// The step must be proven on chain B and submitted to chain A.
+	OnA(HandshakeStep),
+}
+
+impl NextStep {
+	/// Returns the next step given the state of the end on chain A and, if it exists, the state of
+	/// its counterparty end on chain B. Returns `None` for states that can't be resumed.
+	pub fn from_states(a: HandshakeState, b: Option<HandshakeState>) -> Option<Self> {
+		use HandshakeState::*;
+		let step = match (a, b) {
+			(Open, Some(Open)) => NextStep::Complete,
+			(Init, None) => NextStep::OnB(HandshakeStep::OpenTry),
+			(Init, Some(TryOpen)) => NextStep::OnA(HandshakeStep::OpenAck),
+			(TryOpen, Some(Init)) => NextStep::OnB(HandshakeStep::OpenAck),
+			(Open, Some(TryOpen)) => NextStep::OnB(HandshakeStep::OpenConfirm),
+			(TryOpen, Some(Open)) => NextStep::OnA(HandshakeStep::OpenConfirm),
+			_ => return None,
+		};
+		Some(step)
+	}
+}
+
+impl TryFrom<connection::State> for HandshakeState {
+	type Error = anyhow::Error;
+
+	fn try_from(state: connection::State) -> Result<Self, Self::Error> {
+		match state {
+			connection::State::Init => Ok(HandshakeState::Init),
+			connection::State::TryOpen => Ok(HandshakeState::TryOpen),
+			connection::State::Open => Ok(HandshakeState::Open),
+			connection::State::Uninitialized => Err(anyhow!("Connection is uninitialized")),
+		}
+	}
+}
+
+impl TryFrom<channel::State> for HandshakeState {
+	type Error = anyhow::Error;
+
+	fn try_from(state: channel::State) -> Result<Self, Self::Error> {
+		match state {
+			channel::State::Init => Ok(HandshakeState::Init),
+			channel::State::TryOpen => Ok(HandshakeState::TryOpen),
+			channel::State::Open => Ok(HandshakeState::Open),
+			channel::State::Closed => Err(anyhow!("Channel is closed")),
+		}
+	}
+}
+
+/// Handshake status of a connection, as seen from both chains.
+pub struct ConnectionStatus {
+	pub next_step: NextStep,
+	/// Connection id on chain A and the height it was queried at.
+	pub connection_a: (ConnectionId, ConnectionEnd, Height),
+	/// Counterparty connection id on chain B and the height it was queried at, if it exists.
+	pub connection_b: Option<(ConnectionId, ConnectionEnd, Height)>,
+}
+
+/// Handshake status of a channel, as seen from both chains.
+pub struct ChannelStatus {
+	pub next_step: NextStep,
+	/// Channel on chain A and the height it was queried at.
+	pub channel_a: (PortId, ChannelId, ChannelEnd, Height),
+	/// Counterparty channel on chain B and the height it was queried at, if it exists.
+	pub channel_b: Option<(PortId, ChannelId, ChannelEnd, Height)>,
+}
+
+/// Reads both ends of the connection `connection_id` on chain A and its counterparty on chain B.
+pub async fn connection_status(
+	chain_a: &impl Chain,
+	chain_b: &impl Chain,
+	connection_id: ConnectionId,
+) -> Result<ConnectionStatus, anyhow::Error> {
+	let (height_a, _) = chain_a.latest_height_and_timestamp().await?;
+	let (height_b, _) = chain_b.latest_height_and_timestamp().await?;
+	let connection_a = query_connection(chain_a, height_a, connection_id.clone()).await?;
+
+	let connection_b = match connection_a.counterparty().connection_id() {
+		Some(counterparty_id) => Some((
+			counterparty_id.clone(),
+			query_connection(chain_b, height_b, counterparty_id.clone()).await?,
+		)),
+		// chain A doesn't learn the counterparty id before the ack, look for a connection on
+		// chain B that points to it
+		None => chain_b
+			.query_connection_using_client(
+				height_b.revision_height as u32,
+				connection_a.counterparty().client_id().to_string(),
+			)
+			.await?
+			.into_iter()
+			.filter_map(|connection| IdentifiedConnectionEnd::try_from(connection).ok())
+			.find(|connection| {
+				connection.connection_end.counterparty().connection_id() == Some(&connection_id)
+			})
+			.map(|connection| (connection.connection_id, connection.connection_end)),
+	};
+
+	let state_b = match &connection_b {
+		Some((_, connection_b)) => Some(HandshakeState::try_from(*connection_b.state())?),
+		None => None,
+	};
+	let next_step =
+		NextStep::from_states(HandshakeState::try_from(*connection_a.state())?, state_b)
+			.ok_or_else(|| {
+				anyhow!(
+					"Connection {connection_id} on {} can't be resumed from its current state",
+					chain_a.name()
+				)
+			})?;
+
+	Ok(ConnectionStatus {
+		next_step,
+		connection_a: (connection_id, connection_a, height_a),
+		connection_b: connection_b.map(|(id, end)| (id, end, height_b)),
+	})
+}
+
+/// Reads both ends of the channel `channel_id` on chain A and its counterparty on chain B.
+pub async fn channel_status(
+	chain_a: &impl Chain,
+	chain_b: &impl Chain,
+	port_id: PortId,
+	channel_id: ChannelId,
+) -> Result<ChannelStatus, anyhow::Error> {
+	let (height_a, _) = chain_a.latest_height_and_timestamp().await?;
+	let (height_b, _) = chain_b.latest_height_and_timestamp().await?;
+	let channel_a = query_channel(chain_a, height_a, port_id.clone(), channel_id).await?;
+	let counterparty_port_id = channel_a.counterparty().port_id().clone();
+
+	let channel_b = match channel_a.counterparty().channel_id() {
+		Some(counterparty_id) => Some((
+			*counterparty_id,
+			query_channel(chain_b, height_b, counterparty_port_id.clone(), *counterparty_id)
+				.await?,
+		)),
+		// chain A doesn't learn the counterparty id before the ack, look for a channel on
+		// chain B that points to it
+		None => {
+			let connection_id = channel_a
+				.connection_hops()
+				.get(0)
+				.ok_or_else(|| anyhow!("Channel {channel_id} has no connection hops"))?;
+			let connection = query_connection(chain_a, height_a, connection_id.clone()).await?;
+			let counterparty_connection_id =
+				connection.counterparty().connection_id().ok_or_else(|| {
+					anyhow!("Connection {connection_id} of channel {channel_id} is not open")
+				})?;
+			chain_b
+				.query_connection_channels(height_b, counterparty_connection_id)
+				.await?
+				.channels
+				.into_iter()
+				.filter_map(|channel| IdentifiedChannelEnd::try_from(channel).ok())
+				.find(|channel| {
+					channel.port_id == counterparty_port_id &&
+						channel.channel_end.counterparty().port_id() == &port_id &&
+						channel.channel_end.counterparty().channel_id() == Some(&channel_id)
+				})
+				.map(|channel| (channel.channel_id, channel.channel_end))
+		},
+	};
+
+	let state_b = match &channel_b {
+		Some((_, channel_b)) => Some(HandshakeState::try_from(*channel_b.state())?),
+		None => None,
+	};
+	let next_step = NextStep::from_states(HandshakeState::try_from(*channel_a.state())?, state_b)
+		.ok_or_else(|| {
+		anyhow!(
+			"Channel {channel_id}/{port_id} on {} can't be resumed from its current state",
+			chain_a.name()
+		)
+	})?;
+
+	Ok(ChannelStatus {
+		next_step,
+		channel_a: (port_id, channel_id, channel_a, height_a),
+		channel_b: channel_b.map(|(id, end)| (counterparty_port_id, id, end, height_b)),
+	})
+}
+
+/// Submits the handshake message missing from the connection `connection_id` on chain A, if any,
+/// and returns the step that was submitted. The relayer must be running for the light clients to
+/// be updated and for the remaining steps to be relayed.
+pub async fn complete_connection(
+	chain_a: &mut impl Chain,
+	chain_b: &mut impl Chain,
+	connection_id: ConnectionId,
+) -> Result<NextStep, anyhow::Error> {
+	let status = connection_status(chain_a, chain_b, connection_id).await?;
+	log::info!(
+		target: "hyperspace",
+		"Connection {} on {}: {:?}",
+		status.connection_a.0,
+		chain_a.name(),
+		status.next_step
+	);
+	match status.next_step {
+		NextStep::Complete => {},
+		NextStep::OnB(step) => {
+			let (id, end, height) = status.connection_a;
+			submit_step(chain_a, chain_b, height, |at| connection_event(step, at, id, &end))
+				.await?;
+		},
+		NextStep::OnA(step) => {
+			let (id, end, height) = status.connection_b.expect("counterparty exists on chain B");
+			submit_step(chain_b, chain_a, height, |at| connection_event(step, at, id, &end))
+				.await?;
+		},
+	}
+	Ok(status.next_step)
+}
+
+/// Submits the handshake message missing from the channel `channel_id` on chain A, if any, and
+/// returns the step that was submitted. The relayer must be running for the light clients to be
+/// updated and for the remaining steps to be relayed.
+pub async fn complete_channel(
+	chain_a: &mut impl Chain,
+	chain_b: &mut impl Chain,
+	port_id: PortId,
+	channel_id: ChannelId,
+) -> Result<NextStep, anyhow::Error> {
+	let status = channel_status(chain_a, chain_b, port_id, channel_id).await?;
+	log::info!(
+		target: "hyperspace",
+		"Channel {}/{} on {}: {:?}",
+		status.channel_a.1,
+		status.channel_a.0,
+		chain_a.name(),
+		status.next_step
+	);
+	match status.next_step {
+		NextStep::Complete => {},
+		NextStep::OnB(step) => {
+			let (port_id, channel_id, end, height) = status.channel_a;
+			submit_step(chain_a, chain_b, height, |at| {
+				channel_event(step, at, port_id, channel_id, &end)
+			})
+			.await?;
+		},
+		NextStep::OnA(step) => {
+			let (port_id, channel_id, end, height) =
+				status.channel_b.expect("counterparty exists on chain B");
+			submit_step(chain_b, chain_a, height, |at| {
+				channel_event(step, at, port_id, channel_id, &end)
+			})
+			.await?;
+		},
+	}
+	Ok(status.next_step)
+}
+
+/// Checks the handshakes between the chains every `interval`, the first time right away. Never
+/// returns.
+pub async fn monitor_handshakes<A: Chain, B: Chain>(chain_a: A, chain_b: B, interval: Duration) {
+	let mut monitor = HandshakeMonitor::default();
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		monitor.check(&chain_a, &chain_b).await;
+	}
+}
+
+/// Reports handshakes of the configured connection and whitelisted channels that made no progress
+/// between two consecutive checks.
+#[derive(Default)]
+pub struct HandshakeMonitor {
+	pending: HashMap<String, NextStep>,
+}
+
+impl HandshakeMonitor {
+	/// Checks the handshakes of both chains, warning about the ones that are stuck. Returns the
+	/// stuck handshakes, as `<chain>/<connection id>` or `<chain>/<channel id>/<port id>`.
+	pub async fn check<A: Chain, B: Chain>(&mut self, chain_a: &A, chain_b: &B) -> Vec<String> {
+		let mut pending = HashMap::new();
+		let mut stuck = Vec::new();
+		self.check_direction(chain_a, chain_b, &mut pending, &mut stuck).await;
+		self.check_direction(chain_b, chain_a, &mut pending, &mut stuck).await;
+		self.pending = pending;
+		stuck
+	}
+
+	async fn check_direction<A: Chain, B: Chain>(
+		&self,
+		chain_a: &A,
+		chain_b: &B,
+		pending: &mut HashMap<String, NextStep>,
+		stuck: &mut Vec<String>,
+	) {
+		if let Some(connection_id) = chain_a.connection_id() {
+			let key = format!("{}/{connection_id}", chain_a.name());
+			let status = connection_status(chain_a, chain_b, connection_id.clone())
+				.await
+				.map(|status| status.next_step);
+			self.report(key, status, pending, stuck, || {
+				format!(
+					"hyperspace complete-connection --connection-id {connection_id} (with {}'s config as chain A)",
+					chain_a.name()
+				)
+			});
+		}
+
+		for (channel_id, port_id) in chain_a.channel_whitelist() {
+			let key = format!("{}/{channel_id}/{port_id}", chain_a.name());
+			let status = channel_status(chain_a, chain_b, port_id.clone(), channel_id)
+				.await
+				.map(|status| status.next_step);
+			self.report(key, status, pending, stuck, || {
+				format!(
+					"hyperspace complete-channel --port-id {port_id} --channel-id {channel_id} (with {}'s config as chain A)",
+					chain_a.name()
+				)
+			});
+		}
+	}
+
+	fn report(
+		&self,
+		key: String,
+		status: Result<NextStep, anyhow::Error>,
+		pending: &mut HashMap<String, NextStep>,
+		stuck: &mut Vec<String>,
+		command: impl FnOnce() -> String,
+	) {
+		match status {
+			Ok(NextStep::Complete) => {},
+			Ok(next_step) => {
+				if self.pending.get(&key) == Some(&next_step) {
+					log::warn!(
+						target: "hyperspace",
+						"Handshake of {key} is stuck waiting for {next_step:?}, run `{}` to resume it",
+						command()
+					);
+					stuck.push(key.clone());
+				}
+				pending.insert(key, next_step);
+			},
+			Err(e) => {
+				log::debug!(target: "hyperspace", "Failed to check handshake of {key}: {e:?}");
+			},
+		}
+	}
+}
+
+/// Waits for `source`'s light client on `sink` to reach `height`, then proves the handshake state
+/// of `source` at the client's latest height and submits the resulting message to `sink`.
+async fn submit_step<A: Chain, B: Chain>(
+	source: &mut A,
+	sink: &mut B,
+	height: Height,
+	event_at: impl FnOnce(Height) -> IbcEvent,
+) -> Result<(), anyhow::Error> {
+	let client_height =
+		tokio::time::timeout(CLIENT_UPDATE_TIMEOUT, wait_for_client_height(source, sink, height))
+			.await
+			.map_err(|_| {
+				anyhow!(
+					"{}'s client on {} didn't reach height {height} within {CLIENT_UPDATE_TIMEOUT:?}",
+					source.name(),
+					sink.name()
+				)
+			})??;
+	let messages = parse_events(
+		source,
+		sink,
+		vec![event_at(client_height)],
+		Some(Mode::Light),
+		&PacketPolicy::default(),
+	)
+	.await?;
+	log::info!(
+		target: "hyperspace",
+		"Submitting handshake messages to {}: {:?}",
+		sink.name(),
+		messages.iter().map(|msg| &msg.type_url).collect::<Vec<_>>()
+	);
+	sink.submit(messages).await?;
+	Ok(())
+}
+
+async fn wait_for_client_height(
+	source: &impl Chain,
+	sink: &impl Chain,
+	height: Height,
+) -> Result<Height, anyhow::Error> {
+	loop {
+		let (sink_height, _) = sink.latest_height_and_timestamp().await?;
+		let client_state = sink
+			.query_client_state(sink_height, source.client_id())
+			.await?
+			.client_state
+			.ok_or_else(|| anyhow!("Client state for {} not found", source.client_id()))?;
+		let client_height = AnyClientState::try_from(client_state)?.latest_height();
+		if client_height >= height {
+			return Ok(client_height)
+		}
+		tokio::time::sleep(source.expected_block_time()).await;
+	}
+}
+
+/// Builds the event that `parse_events` turns into `step`.
+fn connection_event(
+	step: HandshakeStep,
+	height: Height,
+	connection_id: ConnectionId,
+	connection: &ConnectionEnd,
+) -> IbcEvent {
+	let attributes = connection_events::Attributes {
+		height,
+		connection_id: Some(connection_id),
+		client_id: connection.client_id().clone(),
+		counterparty_connection_id: connection.counterparty().connection_id().cloned(),
+		counterparty_client_id: connection.counterparty().client_id().clone(),
+	};
+	match step {
+		HandshakeStep::OpenTry =>
+			IbcEvent::OpenInitConnection(connection_events::OpenInit(attributes)),
+		HandshakeStep::OpenAck =>
+			IbcEvent::OpenTryConnection(connection_events::OpenTry(attributes)),
+		HandshakeStep::OpenConfirm =>
+			IbcEvent::OpenAckConnection(connection_events::OpenAck(attributes)),
+	}
+}
+
+/// Builds the event that `parse_events` turns into `step`.
+fn channel_event(
+	step: HandshakeStep,
+	height: Height,
+	port_id: PortId,
+	channel_id: ChannelId,
+	channel: &ChannelEnd,
+) -> IbcEvent {
+	let connection_id = channel.connection_hops()[0].clone();
+	let counterparty_port_id = channel.counterparty().port_id().clone();
+	let counterparty_channel_id = channel.counterparty().channel_id().cloned();
+	match step {
+		HandshakeStep::OpenTry => IbcEvent::OpenInitChannel(channel_events::OpenInit {
+			height,
+			port_id,
+			channel_id: Some(channel_id),
+			connection_id,
+			counterparty_port_id,
+			counterparty_channel_id,
+		}),
+		HandshakeStep::OpenAck => IbcEvent::OpenTryChannel(channel_events::OpenTry {
+			height,
+			port_id,
+			channel_id: Some(channel_id),
+			connection_id,
+			counterparty_port_id,
+			counterparty_channel_id,
+		}),
+		HandshakeStep::OpenConfirm => IbcEvent::OpenAckChannel(channel_events::OpenAck {
+			height,
+			port_id,
+			channel_id: Some(channel_id),
+			counterparty_channel_id,
+			connection_id,
+			counterparty_port_id,
+		}),
+	}
+}
+
+async fn query_connection(
+	chain: &impl Chain,
+	at: Height,
+	connection_id: ConnectionId,
+) -> Result<ConnectionEnd, anyhow::Error> {
+	let connection = chain
+		.query_connection_end(at, connection_id.clone())
+		.await?
+		.connection
+		.ok_or_else(|| anyhow!("Connection {connection_id} not found on {}", chain.name()))?;
+	Ok(ConnectionEnd::try_from(connection)?)
+}
+
+async fn query_channel(
+	chain: &impl Chain,
+	at: Height,
+	port_id: PortId,
+	channel_id: ChannelId,
+) -> Result<ChannelEnd, anyhow::Error> {
+	let channel = chain
+		.query_channel_end(at, channel_id, port_id.clone())
+		.await?
+		.channel
+		.ok_or_else(|| anyhow!("Channel {channel_id}/{port_id} not found on {}", chain.name()))?;
+	Ok(ChannelEnd::try_from(channel)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::{
+		core::ics03_connection::{
+			connection::Counterparty, msgs::conn_open_init::MsgConnectionOpenInit,
+		},
+		protobuf::Protobuf,
+		tx_msg::Msg,
+	};
+	use ibc_proto::google::protobuf::Any;
+	use primitives::{mock::MockChain, utils::create_clients, IbcProvider, KeyProvider};
+	use HandshakeState::*;
+
+	#[test]
+	fn next_step_moves_the_handshake_forward() {
+		let next_step = NextStep::from_states;
+		assert_eq!(next_step(Init, None), Some(NextStep::OnB(HandshakeStep::OpenTry)));
+		assert_eq!(next_step(Init, Some(TryOpen)), Some(NextStep::OnA(HandshakeStep::OpenAck)));
+		assert_eq!(next_step(TryOpen, Some(Init)), Some(NextStep::OnB(HandshakeStep::OpenAck)));
+		assert_eq!(next_step(Open, Some(TryOpen)), Some(NextStep::OnB(HandshakeStep::OpenConfirm)));
+		assert_eq!(next_step(TryOpen, Some(Open)), Some(NextStep::OnA(HandshakeStep::OpenConfirm)));
+		assert_eq!(next_step(Open, Some(Open)), Some(NextStep::Complete));
+	}
+
+	#[test]
+	fn next_step_is_none_for_states_that_cant_be_resumed() {
+		for (a, b) in [
+			(TryOpen, None),
+			(Open, None),
+			(Init, Some(Init)),
+			(TryOpen, Some(TryOpen)),
+			(Init, Some(Open)),
+			(Open, Some(Init)),
+		] {
+			assert_eq!(NextStep::from_states(a, b), None, "{a:?} {b:?}");
+		}
+	}
+
+	#[tokio::test]
+	async fn monitor_reports_handshakes_that_make_no_progress() {
+		let mut chain_a = MockChain::new("mock-a");
+		let mut chain_b = MockChain::new("mock-b");
+		for chain in [&chain_a, &chain_b] {
+			chain.finalize(chain.produce_block());
+		}
+		let (client_a, client_b) = create_clients(&mut chain_a, &mut chain_b).await.unwrap();
+		chain_a.set_client_id(client_a);
+		chain_b.set_client_id(client_b);
+		let mut monitor = HandshakeMonitor::default();
+		assert!(monitor.check(&chain_a, &chain_b).await.is_empty());
+
+		let msg = MsgConnectionOpenInit {
+			client_id: chain_b.client_id(),
+			counterparty: Counterparty::new(chain_a.client_id(), None, chain_b.connection_prefix()),
+			version: Some(Default::default()),
+			delay_period: Duration::ZERO,
+			signer: chain_a.account_id(),
+		};
+		let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+		let tx_id = chain_a.submit(vec![msg]).await.unwrap();
+		let connection_id = chain_a.query_connection_id_from_tx_hash(tx_id).await.unwrap();
+		chain_a.set_connection_id(connection_id.clone());
+		chain_a.finalize(chain_a.produce_block());
+
+		// the first check only records the step the handshake waits for
+		assert!(monitor.check(&chain_a, &chain_b).await.is_empty());
+		assert_eq!(
+			monitor.check(&chain_a, &chain_b).await,
+			vec![format!("mock-a/{connection_id}")]
+		);
+	}
+}
@@ -14,22 +14,33 @@
 
 #![warn(unused_variables)]
 
-use futures::{future::ready, StreamExt};
-use primitives::Chain;
+use futures::{future::ready, Stream, StreamExt};
+use primitives::{endpoints::SubscriptionStalled, Chain};
+use std::{pin::Pin, time::Instant};
 
 pub mod chain;
+pub mod client_refresh;
 pub mod command;
 pub mod events;
+pub mod handshake;
 pub mod logging;
 mod macros;
 pub mod packets;
+pub mod policy;
 pub mod queue;
 pub mod substrate;
+pub mod wallet;
 
+use client_refresh::{ClientRefresh, ClientRefreshScheduler};
 use events::{has_packet_events, parse_events};
 use futures::TryFutureExt;
 use ibc::events::IbcEvent;
 use metrics::handler::MetricsHandler;
+use policy::PacketPolicy;
+
+/// A chain whose finality notifications stop arriving for this long is reconnected, possibly to
+/// another endpoint.
+const FINALITY_STALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
 
 #[derive(Copy, Debug, Clone)]
 pub enum Mode {
@@ -45,11 +56,17 @@ pub async fn relay<A, B>(
 	mut chain_a_metrics: Option<MetricsHandler>,
 	mut chain_b_metrics: Option<MetricsHandler>,
 	mode: Option<Mode>,
+	client_refresh: Option<ClientRefresh>,
+	packet_policy: Option<PacketPolicy>,
 ) -> Result<(), anyhow::Error>
 where
 	A: Chain,
 	B: Chain,
 {
+	let client_refresh = client_refresh.unwrap_or_default();
+	let packet_policy = packet_policy.unwrap_or_default();
+	let (mut chain_a_client_refresh, mut chain_b_client_refresh) =
+		(ClientRefreshScheduler::new(client_refresh), ClientRefreshScheduler::new(client_refresh));
 	let (mut chain_a_finality, mut chain_b_finality) =
 		(chain_a.finality_notifications().await?, chain_b.finality_notifications().await?);
 
@@ -59,7 +76,8 @@ where
 		let (mut messages, events) = chain_a.fetch_mandatory_updates(&chain_b).await?;
 		// we use light mode because channel state will be queried during the full relay operation
 		let parsed_messages =
-			parse_events(&mut chain_a, &mut chain_b, events, Some(Mode::Light)).await?;
+			parse_events(&mut chain_a, &mut chain_b, events, Some(Mode::Light), &packet_policy)
+				.await?;
 		messages.extend(parsed_messages);
 		log::info!(target: "hyperspace",
 			"Syncing Chain {}'s light client on chain {} {:#?}",
@@ -74,7 +92,8 @@ where
 		let (mut messages, events) = chain_b.fetch_mandatory_updates(&chain_a).await?;
 		// we use light mode because channel state will be queried during the full relay operation
 		let parsed_messages =
-			parse_events(&mut chain_b, &mut chain_a, events, Some(Mode::Light)).await?;
+			parse_events(&mut chain_b, &mut chain_a, events, Some(Mode::Light), &packet_policy)
+				.await?;
 		messages.extend(parsed_messages);
 		log::info!(target: "hyperspace",
 			"Syncing Chain {}'s light client on chain {} {:#?}",
@@ -85,18 +104,229 @@ where
 		queue::flush_message_batch(messages, chain_b_metrics.as_ref(), &chain_a).await?;
 	}
 
+	let (mut chain_a_last_finality, mut chain_b_last_finality) = (Instant::now(), Instant::now());
+
 	// loop forever
 	loop {
 		tokio::select! {
+			// reconnect chains whose finality notifications stopped arriving
+			_ = tokio::time::sleep_until((chain_a_last_finality.min(chain_b_last_finality) + FINALITY_STALL_TIMEOUT).into()) => {
+				if chain_a_last_finality.elapsed() >= FINALITY_STALL_TIMEOUT {
+					resubscribe_stalled(&mut chain_a, &mut chain_a_finality).await;
+					chain_a_last_finality = Instant::now();
+				}
+				if chain_b_last_finality.elapsed() >= FINALITY_STALL_TIMEOUT {
+					resubscribe_stalled(&mut chain_b, &mut chain_b_finality).await;
+					chain_b_last_finality = Instant::now();
+				}
+			}
 			// new finality event from chain A
 			result = chain_a_finality.next() => {
-				process_finality_event!(chain_a, chain_b, chain_a_metrics, mode, result, chain_a_finality, chain_b_finality)
+				chain_a_last_finality = Instant::now();
+				process_finality_event!(chain_a, chain_b, chain_a_metrics, chain_a_client_refresh, packet_policy, mode, result, chain_a_finality, chain_b_finality)
 			}
 			// new finality event from chain B
 			result = chain_b_finality.next() => {
-				process_finality_event!(chain_b, chain_a, chain_b_metrics, mode, result, chain_b_finality, chain_a_finality)
+				chain_b_last_finality = Instant::now();
+				process_finality_event!(chain_b, chain_a, chain_b_metrics, chain_b_client_refresh, packet_policy, mode, result, chain_b_finality, chain_a_finality)
+			}
+		}
+	}
+}
+
+/// Relays the ibc events and the packets that became ready with a finality event of `source`,
+/// then submits the packet timeouts back to `source`.
+async fn handle_finality_event<A: Chain, B: Chain>(
+	source: &mut A,
+	sink: &mut B,
+	metrics: &mut Option<MetricsHandler>,
+	client_refresh: &mut ClientRefreshScheduler,
+	packet_policy: &PacketPolicy,
+	mode: Option<Mode>,
+	finality_event: A::FinalityEvent,
+) -> Result<(), anyhow::Error> {
+	log::info!("Received finality notification from {}", source.name());
+	let sink_initial_rpc_call_delay = sink.rpc_call_delay();
+	let source_initial_rpc_call_delay = source.rpc_call_delay();
+	// TODO: make better error handling
+	let mut had_error = false;
+
+	let updates = match source.query_latest_ibc_events(finality_event, &*sink).await {
+		Ok(resp) => resp,
+		Err(err) => {
+			log::error!(
+				"Failed to fetch IBC events for finality event for {} {:?}",
+				source.name(),
+				err
+			);
+			match sink.handle_error(&err).and_then(|_| source.handle_error(&err)).await {
+				Ok(_) => {},
+				Err(e) => log::error!("Failed to handle error for {} {:?}", sink.name(), e),
+			}
+			return Ok(())
+		},
+	};
+	log::trace!(target: "hyperspace", "Received updates count: {}", updates.len());
+	// query packets that can now be sent, at this sink height because of connection
+	// delay.
+	let (ready_packets, timeout_msgs) = crate::packets::query_ready_and_timed_out_packets(
+		&*source,
+		&*sink,
+		packet_policy,
+		metrics.as_ref(),
+	)
+	.await?;
+
+	// force a client update if the client on the sink is close to expiring
+	let refresh_due = match client_refresh.is_refresh_due(&*source, &*sink, metrics.as_mut()).await
+	{
+		Ok(due) => due,
+		Err(e) => {
+			log::warn!(target: "hyperspace", "Failed to check client expiry for {} {:?}", source.name(), e);
+			false
+		},
+	};
+	let updates_count = updates.len();
+
+	let mut msgs = Vec::new();
+	let mut has_client_update = false;
+
+	for (i, (msg_update_client, events, update_type)) in updates.into_iter().enumerate() {
+		// only the latest update is needed to refresh the client
+		let force_refresh = refresh_due && i + 1 == updates_count;
+		if let Some(metrics) = metrics.as_mut() {
+			if let Err(e) = metrics.handle_events(events.as_slice()).await {
+				log::error!("Failed to handle metrics for {} {:?}", source.name(), e);
 			}
 		}
+		let event_types = events.iter().map(|ev| ev.event_type()).collect::<Vec<_>>();
+		let mut messages =
+			match parse_events(&mut *source, &mut *sink, events, mode, packet_policy).await {
+				Ok(msgs) => msgs,
+				Err(e) => {
+					log::error!("Failed to parse events for {} {:?}", source.name(), e);
+					match sink.handle_error(&e).and_then(|_| source.handle_error(&e)).await {
+						Ok(_) => {},
+						Err(e) => log::error!("Failed to handle error for {} {:?}", sink.name(), e),
+					}
+					had_error = true;
+					continue
+				},
+			};
+		log::trace!(target: "hyperspace", "Received messages count: {}, timeouts count: {}, is the update optional: {}, has undelivered packets: {}", messages.len(), timeout_msgs.len(), update_type.is_optional(), source.has_undelivered_sequences());
+
+		// We want to send client update if packet messages exist but where not sent due
+		// to a connection delay even if client update message is optional
+		match (
+			// TODO: we actually man send only when timeout of some packet has reached,
+			// not when we have *any* undelivered packets. But this requires rewriting
+			// `find_suitable_proof_height_for_client` function, that uses binary
+			// search, which won't work in this case
+			update_type.is_optional() && !source.has_undelivered_sequences() && !force_refresh,
+			has_packet_events(&event_types),
+			messages.is_empty(),
+		) {
+			(true, false, true) => {
+				// skip sending ibc messages if no new events
+				log::info!("Skipping finality notification for {}", sink.name());
+				continue
+			},
+			(false, _, true) if force_refresh =>
+				log::info!("Sending client refresh update message for {}", sink.name()),
+			(false, _, true) =>
+				log::info!("Sending mandatory client update message for {}", sink.name()),
+			_ => log::info!("Received finalized events from: {} {event_types:#?}", source.name()),
+		};
+		msgs.push(msg_update_client);
+		msgs.append(&mut messages);
+		has_client_update = true;
+	}
+	msgs.extend(ready_packets);
+
+	if !msgs.is_empty() {
+		if let Some(metrics) = metrics.as_ref() {
+			metrics.handle_messages(msgs.as_slice()).await;
+		}
+		let type_urls = msgs.iter().map(|msg| msg.type_url.as_str()).collect::<Vec<_>>();
+		log::info!("Submitting messages to {}: {type_urls:#?}", sink.name());
+		match queue::flush_message_batch(msgs, metrics.as_ref(), &*sink).await {
+			Ok(_) => {
+				log::trace!(target: "hyperspace", "Successfully submitted messages to {}", sink.name());
+				if has_client_update {
+					client_refresh.on_client_updated();
+				}
+			},
+			Err(e) => {
+				log::error!(
+					target:"hyperspace",
+					"Failed to submit messages to {} {:?}",
+					sink.name(),
+					e
+				);
+				match sink.handle_error(&e).and_then(|_| source.handle_error(&e)).await {
+					Ok(_) => {},
+					Err(e) => log::error!("Failed to handle error for {} {:?}", sink.name(), e),
+				}
+				had_error = true;
+			},
+		}
+	}
+
+	if !timeout_msgs.is_empty() {
+		if let Some(metrics) = metrics.as_ref() {
+			metrics.handle_timeouts(timeout_msgs.as_slice()).await;
+		}
+		let type_urls = timeout_msgs.iter().map(|msg| msg.type_url.as_str()).collect::<Vec<_>>();
+		log::info!("Submitting timeout messages to {}: {type_urls:#?}", source.name());
+		match queue::flush_message_batch(timeout_msgs, metrics.as_ref(), &*source).await {
+			Ok(_) => {
+				log::trace!(target: "hyperspace", "Successfully submitted timeout messages to {}", source.name());
+			},
+			Err(e) => {
+				log::error!(
+					target:"hyperspace",
+					"Failed to submit timeout messages to {} {:?}",
+					source.name(),
+					e
+				);
+				match sink.handle_error(&e).and_then(|_| source.handle_error(&e)).await {
+					Ok(_) => {},
+					Err(e) => log::error!("Failed to handle error for {} {:?}", sink.name(), e),
+				}
+				had_error = true;
+			},
+		}
+	}
+	if !had_error {
+		sink.set_rpc_call_delay(sink_initial_rpc_call_delay);
+		source.set_rpc_call_delay(source_initial_rpc_call_delay);
+	}
+	Ok(())
+}
+
+/// Lets `chain` recover from a stalled finality subscription, e.g. by failing over to another
+/// endpoint, then subscribes again. Keeps the old subscription if that fails.
+async fn resubscribe_stalled<C: Chain>(
+	chain: &mut C,
+	finality: &mut Pin<Box<dyn Stream<Item = C::FinalityEvent> + Send + Sync>>,
+) {
+	log::warn!(
+		target: "hyperspace",
+		"No finality notification from {} in {FINALITY_STALL_TIMEOUT:?}, reconnecting",
+		chain.name()
+	);
+	let error = anyhow::Error::new(SubscriptionStalled(FINALITY_STALL_TIMEOUT));
+	if let Err(e) = chain.handle_error(&error).await {
+		log::error!(target: "hyperspace", "Failed to reconnect {} {:?}", chain.name(), e);
+	}
+	match chain.finality_notifications().await {
+		Ok(stream) => *finality = stream,
+		Err(e) => log::error!(
+			target: "hyperspace",
+			"Failed to get finality notifications for {} {:?}",
+			chain.name(),
+			e
+		),
 	}
 }
 
@@ -12,11 +12,69 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use log::LevelFilter;
+//! Log and trace collection. Records emitted with the `log` macros are forwarded to `tracing`, so
+//! they carry the fields of the spans they're emitted in (finality event, channel, packet, ...).
 
+use clap::ArgEnum;
+use opentelemetry::{
+	sdk::{trace, Resource},
+	KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+#[derive(Debug, Clone, Copy, Default, ArgEnum)]
+pub enum LogFormat {
+	#[default]
+	Text,
+	Json,
+}
+
+/// Sets up human readable logs, filtered with `RUST_LOG`.
 pub fn setup_logging() {
-	env_logger::builder()
-		.filter_module("hyper", LevelFilter::Info)
-		.format_module_path(false)
-		.init();
+	setup_tracing(LogFormat::Text, None).expect("failed to set up logging")
+}
+
+/// Sets up logs in the given format, filtered with `RUST_LOG`, and exports spans to the
+/// OpenTelemetry collector listening on `otlp_endpoint` over gRPC, if any.
+///
+/// Must be called from within a tokio runtime when exporting spans.
+pub fn setup_tracing(format: LogFormat, otlp_endpoint: Option<&str>) -> anyhow::Result<()> {
+	let fmt_layer = match format {
+		LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+		LogFormat::Json => tracing_subscriber::fmt::layer()
+			.json()
+			.with_current_span(true)
+			.with_span_list(true)
+			.boxed(),
+	};
+	let otlp_layer = match otlp_endpoint {
+		Some(endpoint) => {
+			let tracer = opentelemetry_otlp::new_pipeline()
+				.tracing()
+				.with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+				.with_trace_config(
+					trace::config().with_resource(Resource::new([KeyValue::new(
+						"service.name",
+						"hyperspace",
+					)])),
+				)
+				.install_batch(opentelemetry::runtime::Tokio)?;
+			Some(tracing_opentelemetry::layer().with_tracer(tracer))
+		},
+		None => None,
+	};
+	let filter = EnvFilter::from_default_env().add_directive("hyper=info".parse()?);
+
+	tracing_subscriber::registry()
+		.with(fmt_layer)
+		.with(otlp_layer)
+		.with(filter)
+		.try_init()?;
+	Ok(())
+}
+
+/// Exports the spans that haven't been exported yet. Should be called before exiting.
+pub fn shutdown_tracing() {
+	opentelemetry::global::shutdown_tracer_provider();
 }
@@ -14,7 +14,7 @@
 
 #[macro_export]
 macro_rules! process_finality_event {
-	($source:ident, $sink:ident, $metrics:expr, $mode:ident, $result:ident, $stream_source:ident, $stream_sink:ident) => {
+	($source:ident, $sink:ident, $metrics:expr, $client_refresh:ident, $packet_policy:ident, $mode:ident, $result:ident, $stream_source:ident, $stream_sink:ident) => {
 		match $result {
 			// stream closed
 			None => {
@@ -39,148 +39,20 @@ macro_rules! process_finality_event {
 				};
 			},
 			Some(finality_event) => {
-				log::info!("=======================================================");
-				log::info!("Received finality notification from {}", $source.name());
-				let sink_initial_rpc_call_delay = $sink.rpc_call_delay();
-				let source_initial_rpc_call_delay = $source.rpc_call_delay();
-				// TODO: make better error handling
-				let mut had_error = false;
-
-				let updates = match $source.query_latest_ibc_events(finality_event, &$sink).await {
-					Ok(resp) => resp,
-					Err(err) => {
-						log::error!(
-							"Failed to fetch IBC events for finality event for {} {:?}",
-							$source.name(),
-							err
-						);
-						match $sink.handle_error(&err).and_then(|_| $source.handle_error(&err)).await {
-							Ok(_) => {},
-							Err(e) => log::error!("Failed to handle error for {} {:?}", $sink.name(), e),
-						}
-						continue
-					},
-				};
-				log::trace!(target: "hyperspace", "Received updates count: {}", updates.len());
-				// query packets that can now be sent, at this sink height because of connection
-				// delay.
-				let (ready_packets, timeout_msgs) =
-					crate::packets::query_ready_and_timed_out_packets(&$source, &$sink).await?;
-
-				let mut msgs = Vec::new();
-
-				for (msg_update_client, events, update_type) in updates {
-					if let Some(metrics) = $metrics.as_mut() {
-						if let Err(e) = metrics.handle_events(events.as_slice()).await {
-							log::error!("Failed to handle metrics for {} {:?}", $source.name(), e);
-						}
-					}
-					let event_types = events.iter().map(|ev| ev.event_type()).collect::<Vec<_>>();
-					let mut messages =
-						match parse_events(&mut $source, &mut $sink, events, $mode).await {
-							Ok(msgs) => msgs,
-							Err(e) => {
-								log::error!("Failed to parse events for {} {:?}", $source.name(), e);
-								match $sink.handle_error(&e).and_then(|_| $source.handle_error(&e)).await {
-									Ok(_) => {},
-									Err(e) => log::error!("Failed to handle error for {} {:?}", $sink.name(), e),
-								}
-								had_error = true;
-								continue
-							},
-					};
-					log::trace!(target: "hyperspace", "Received messages count: {}, timeouts count: {}, is the update optional: {}, has undelivered packets: {}", messages.len(), timeout_msgs.len(), update_type.is_optional(), $source.has_undelivered_sequences());
-
-					// We want to send client update if packet messages exist but where not sent due
-					// to a connection delay even if client update message is optional
-					match (
-						// TODO: we actually man send only when timeout of some packet has reached,
-						// not when we have *any* undelivered packets. But this requires rewriting
-						// `find_suitable_proof_height_for_client` function, that uses binary
-						// search, which won't work in this case
-						update_type.is_optional() && !$source.has_undelivered_sequences(),
-						has_packet_events(&event_types),
-						messages.is_empty(),
-					) {
-						(true, false, true) => {
-							// skip sending ibc messages if no new events
-							log::info!("Skipping finality notification for {}", $sink.name());
-							continue
-						},
-						(false, _, true) => log::info!(
-							"Sending mandatory client update message for {}",
-							$sink.name()
-						),
-						_ => log::info!(
-							"Received finalized events from: {} {event_types:#?}",
-							$source.name()
-						),
-					};
-					msgs.push(msg_update_client);
-					msgs.append(&mut messages);
-				}
-				msgs.extend(ready_packets);
-
-				if !msgs.is_empty() {
-					if let Some(metrics) = $metrics.as_ref() {
-						metrics.handle_messages(msgs.as_slice()).await;
-					}
-					let type_urls =
-						msgs.iter().map(|msg| msg.type_url.as_str()).collect::<Vec<_>>();
-					log::info!("Submitting messages to {}: {type_urls:#?}", $sink.name());
-					match queue::flush_message_batch(msgs, $metrics.as_ref(), &$sink).await {
-						Ok(_) => {
-							log::trace!(target: "hyperspace", "Successfully submitted messages to {}", $sink.name());
-						},
-						Err(e) => {
-							log::error!(
-								target:"hyperspace",
-								"Failed to submit messages to {} {:?}",
-								$sink.name(),
-								e
-							);
-							match $sink.handle_error(&e).and_then(|_| $source.handle_error(&e)).await {
-								Ok(_) => {},
-								Err(e) => log::error!("Failed to handle error for {} {:?}", $sink.name(), e),
-							}
-							had_error = true;
-						},
-					}
-				}
-
-				if !timeout_msgs.is_empty() {
-					if let Some(metrics) = $metrics.as_ref() {
-						metrics.handle_timeouts(timeout_msgs.as_slice()).await;
-					}
-					let type_urls =
-						timeout_msgs.iter().map(|msg| msg.type_url.as_str()).collect::<Vec<_>>();
-					log::info!(
-						"Submitting timeout messages to {}: {type_urls:#?}",
-						$source.name()
-					);
-					match queue::flush_message_batch(timeout_msgs, $metrics.as_ref(), &$source).await {
-						Ok(_) => {
-							log::trace!(target: "hyperspace", "Successfully submitted timeout messages to {}", $source.name());
-						},
-						Err(e) => {
-							log::error!(
-								target:"hyperspace",
-								"Failed to submit timeout messages to {} {:?}",
-								$source.name(),
-								e
-							);
-							match $sink.handle_error(&e).and_then(|_| $source.handle_error(&e)).await {
-								Ok(_) => {},
-								Err(e) => log::error!("Failed to handle error for {} {:?}", $sink.name(), e),
-							}
-							had_error = true;
-						},
-					}
-				}
-				if !had_error {
-					$sink.set_rpc_call_delay(sink_initial_rpc_call_delay);
-					$source.set_rpc_call_delay(source_initial_rpc_call_delay);
-				}
+				let span = tracing::info_span!("finality_event", source = $source.name(), sink = $sink.name());
+				tracing::Instrument::instrument(
+					handle_finality_event(
+						&mut $source,
+						&mut $sink,
+						&mut $metrics,
+						&mut $client_refresh,
+						&$packet_policy,
+						$mode,
+						finality_event,
+					),
+					span,
+				)
+				.await?;
 			},
 		}
 	};
@@ -818,6 +690,24 @@ macro_rules! chains {
 				}
 			}
 
+			async fn migrate_wasm_client(
+				&self,
+				client_id: ClientId,
+				code_id: Vec<u8>,
+				migrate_msg: Vec<u8>,
+			) -> Result<WasmClientMigration, Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain
+							.migrate_wasm_client(client_id, code_id, migrate_msg)
+							.await
+							.map_err(AnyError::$name),
+					)*
+					Self::Wasm(c) => c.inner.migrate_wasm_client(client_id, code_id, migrate_msg).await,
+				}
+			}
+
 			async fn on_undelivered_sequences(&self, seqs: &[u64]) -> Result<(), Self::Error> {
 				match self {
 					$(
@@ -1009,6 +899,17 @@ macro_rules! chains {
 				}
 			}
 
+			fn transaction_fee(&self, tx_id: &Self::TransactionId) -> Option<u128> {
+				match (self, tx_id) {
+					$(
+						$(#[$($meta)*])*
+						(Self::$name(chain), AnyTransactionId::$name(tx_id)) => chain.transaction_fee(tx_id),
+					)*
+					(Self::Wasm(c), tx_id) => c.inner.transaction_fee(tx_id),
+					_ => None,
+				}
+			}
+
 			async fn query_client_message(
 				&self,
 				update: UpdateClient,
@@ -1151,6 +1052,23 @@ macro_rules! chains {
 			}
 		}
 
+		impl AnyChain {
+			/// Parses an asset id of this chain, e.g. to query balances with [`IbcProvider::query_ibc_balance`].
+			pub fn parse_asset_id(&self, asset_id: &str) -> anyhow::Result<AnyAssetId> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(_) => Ok(AnyAssetId::$name(
+							asset_id
+								.parse()
+								.map_err(|_| anyhow::anyhow!("Invalid asset id: {asset_id}"))?,
+						)),
+					)*
+					Self::Wasm(c) => c.inner.parse_asset_id(asset_id),
+				}
+			}
+		}
+
 		impl AnyConfig {
 			pub async fn into_client(self) -> anyhow::Result<AnyChain> {
 				let maybe_wasm_code_id = self.wasm_code_id();
@@ -1200,6 +1118,42 @@ macro_rules! chains {
 				}
 			}
 
+			pub fn name(&self) -> &str {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => &chain.name,
+					)*
+				}
+			}
+
+			pub fn client_id(&self) -> Option<&ClientId> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain.client_id.as_ref(),
+					)*
+				}
+			}
+
+			pub fn connection_id(&self) -> Option<&ConnectionId> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain.connection_id.as_ref(),
+					)*
+				}
+			}
+
+			pub fn channel_whitelist(&self) -> &[(ChannelId, PortId)] {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => &chain.channel_whitelist,
+					)*
+				}
+			}
+
 			pub fn wasm_code_id(&self) -> Option<CodeId> {
 				let maybe_code_id = match self {
 					$(
@@ -14,87 +14,192 @@
 
 #[cfg(feature = "testing")]
 use crate::send_packet_relay::packet_relay_status;
+use futures::{stream, StreamExt, TryStreamExt};
+use metrics::handler::MetricsHandler;
 use rand::Rng;
 use sp_runtime::Either::{Left, Right};
-use std::{sync::Arc, time::Duration};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
 use tokio::{task::JoinSet, time::sleep};
-
-use crate::packets::utils::{
-	construct_ack_message, construct_recv_message, construct_timeout_message,
-	get_timeout_proof_height, verify_delay_passed, VerifyDelayOn,
+use tracing::Instrument;
+
+use crate::{
+	packets::{
+		cache::PacketScanCache,
+		utils::{
+			construct_ack_message, construct_recv_message, construct_timeout_message,
+			get_timeout_proof_height, verify_delay_passed, VerifyDelayOn,
+		},
+	},
+	policy::PacketPolicy,
 };
 use ibc::{
 	core::{
 		ics02_client::client_state::ClientState as ClientStateT,
-		ics03_connection::connection::ConnectionEnd,
-		ics04_channel::channel::{ChannelEnd, State},
+		ics04_channel::channel::State,
+		ics24_host::identifier::{ChannelId, PortId},
 	},
+	timestamp::Timestamp,
 	Height,
 };
 use ibc_proto::google::protobuf::Any;
-use pallet_ibc::light_clients::AnyClientState;
 use primitives::{
 	error::Error, find_suitable_proof_height_for_client, packet_info_to_packet,
 	query_undelivered_acks, query_undelivered_sequences, Chain,
 };
 
+pub mod cache;
 pub mod connection_delay;
 pub mod utils;
 
 pub const PROCESS_PACKETS_BATCH_SIZE: usize = 100;
 pub const MAX_PACKETS_TO_PROCESS: usize = 1000;
+/// Maximum number of whitelisted channels scanned for packets at the same time.
+pub const MAX_CONCURRENT_CHANNEL_SCANS: usize = 8;
 
 /// Returns a tuple of messages, with the first item being packets that are ready to be sent to the
 /// sink chain. And the second item being packet timeouts that should be sent to the source.
+///
+/// Up to [`MAX_CONCURRENT_CHANNEL_SCANS`] whitelisted channels are scanned concurrently, sharing
+/// the channel ends, connection ends and client states they query. Packets that `packet_policy`
+/// doesn't allow are not received, but are still timed out.
 pub async fn query_ready_and_timed_out_packets(
 	source: &impl Chain,
 	sink: &impl Chain,
+	packet_policy: &PacketPolicy,
+	metrics: Option<&MetricsHandler>,
 ) -> Result<(Vec<Any>, Vec<Any>), anyhow::Error> {
-	let mut messages = vec![];
-	let mut timeout_messages = vec![];
+	scan_channels(source, sink, packet_policy, metrics, &PacketScanCache::default()).await
+}
+
+/// Scans the whitelisted channels of `source`, looking up chain state through `cache`.
+async fn scan_channels(
+	source: &impl Chain,
+	sink: &impl Chain,
+	packet_policy: &PacketPolicy,
+	metrics: Option<&MetricsHandler>,
+	cache: &PacketScanCache,
+) -> Result<(Vec<Any>, Vec<Any>), anyhow::Error> {
+	let scan_start = Instant::now();
 	let (source_height, source_timestamp) = source.latest_height_and_timestamp().await?;
 	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
 	let channel_whitelist = source.channel_whitelist();
+	let channels_count = channel_whitelist.len();
+	let scan = ChannelScan {
+		source: Arc::new(source.clone()),
+		sink: Arc::new(sink.clone()),
+		source_height,
+		source_timestamp,
+		sink_height,
+		sink_timestamp,
+		packet_policy,
+		cache,
+	};
+
+	let results = stream::iter(channel_whitelist)
+		.map(|(channel_id, port_id)| {
+			let scan = &scan;
+			let span = tracing::info_span!(
+				"channel_scan",
+				chain = source.name(),
+				channel = %channel_id,
+				port = %port_id
+			);
+			async move {
+				let channel_scan_start = Instant::now();
+				let result = scan.query_channel(channel_id, port_id.clone()).await;
+				if let Some(metrics) = metrics {
+					metrics.handle_channel_scan(
+						&channel_id,
+						&port_id,
+						channel_scan_start.elapsed(),
+					);
+				}
+				result
+			}
+			.instrument(span)
+		})
+		.buffer_unordered(MAX_CONCURRENT_CHANNEL_SCANS)
+		.try_collect::<Vec<_>>()
+		.await?;
+
+	let mut messages = vec![];
+	let mut timeout_messages = vec![];
+	for (ready, timed_out) in results {
+		messages.extend(ready);
+		timeout_messages.extend(timed_out);
+	}
+
+	let scan_duration = scan_start.elapsed();
+	log::debug!(target: "hyperspace", "Scanned {} channels on {} in {:?}", channels_count, source.name(), scan_duration);
+	if let Some(metrics) = metrics {
+		metrics.handle_packet_scan(scan_duration);
+	}
+
+	Ok((messages, timeout_messages))
+}
+
+/// A scan of the whitelisted channels of `source` at fixed heights of both chains.
+struct ChannelScan<'a, A, B> {
+	source: Arc<A>,
+	sink: Arc<B>,
+	source_height: Height,
+	source_timestamp: Timestamp,
+	sink_height: Height,
+	sink_timestamp: Timestamp,
+	packet_policy: &'a PacketPolicy,
+	cache: &'a PacketScanCache,
+}
 
-	// TODO: parallelize this
-	for (channel_id, port_id) in channel_whitelist {
-		let source_channel_response = match source
-			.query_channel_end(source_height, channel_id, port_id.clone())
+impl<A: Chain, B: Chain> ChannelScan<'_, A, B> {
+	/// Returns the packets of the channel that are ready to be sent to the sink, and the packet
+	/// timeouts that should be sent to the source.
+	async fn query_channel(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<(Vec<Any>, Vec<Any>), anyhow::Error> {
+		let mut messages = vec![];
+		let mut timeout_messages = vec![];
+		let (source, sink) = (&*self.source, &*self.sink);
+		let (source_height, source_timestamp) = (self.source_height, self.source_timestamp);
+		let (sink_height, sink_timestamp) = (self.sink_height, self.sink_timestamp);
+
+		let source_channel_end = match self
+			.cache
+			.source
+			.channel_end(source, source_height, channel_id, port_id.clone())
 			.await
 		{
-			Ok(response) => response,
+			Ok(channel_end) => channel_end,
 			Err(e) => {
 				log::warn!(target: "hyperspace", "Failed to query channel end for {:?}/{:?}: {:?}", channel_id, port_id.clone(), e);
-				continue
+				return Ok((messages, timeout_messages))
 			},
 		};
-		let source_channel_end =
-			ChannelEnd::try_from(source_channel_response.channel.ok_or_else(|| {
-				Error::Custom(format!(
-					"ChannelEnd not found for {:?}/{:?}",
-					channel_id,
-					port_id.clone()
-				))
-			})?)?;
 		// we're only interested in open or closed channels
 		if !matches!(source_channel_end.state, State::Open | State::Closed) {
 			log::trace!(target: "hyperspace", "Skipping channel {:?}/{:?} because it is not open or closed", channel_id, port_id.clone());
-			continue
+			return Ok((messages, timeout_messages))
+		}
+		if let Err(e) = self.packet_policy.check_channel(
+			source.name(),
+			&channel_id,
+			&port_id,
+			source_channel_end.ordering,
+		) {
+			log::warn!(target: "hyperspace", "Skipping channel {:?}/{:?}: {:?}", channel_id, port_id, e);
+			return Ok((messages, timeout_messages))
 		}
 		let connection_id = source_channel_end
 			.connection_hops
 			.get(0)
 			.ok_or_else(|| Error::Custom("Channel end missing connection id".to_string()))?
 			.clone();
-		let connection_response =
-			source.query_connection_end(source_height, connection_id.clone()).await?;
 		let source_connection_end =
-			ConnectionEnd::try_from(connection_response.connection.ok_or_else(|| {
-				Error::Custom(format!(
-					"[query_ready_and_timed_out_packets] ConnectionEnd not found for {:?}",
-					connection_id
-				))
-			})?)?;
+			self.cache.source.connection_end(source, source_height, connection_id).await?;
 
 		let sink_channel_id = source_channel_end
 			.counterparty()
@@ -106,58 +211,20 @@ pub async fn query_ready_and_timed_out_packets(
 			})?
 			.clone();
 		let sink_port_id = source_channel_end.counterparty().port_id.clone();
-		let sink_channel_response = sink
-			.query_channel_end(sink_height, sink_channel_id, sink_port_id.clone())
+		let sink_channel_end = self
+			.cache
+			.sink
+			.channel_end(sink, sink_height, sink_channel_id, sink_port_id.clone())
 			.await?;
 
-		let sink_channel_end =
-			ChannelEnd::try_from(sink_channel_response.channel.ok_or_else(|| {
-				Error::Custom(format!(
-					"Failed to convert to concrete channel end from raw channel end",
-				))
-			})?)?;
-
 		let next_sequence_recv = sink
 			.query_next_sequence_recv(sink_height, &sink_port_id, &sink_channel_id)
 			.await?;
 
 		let source_client_state_on_sink =
-			sink.query_client_state(sink_height, source.client_id()).await?;
-		let source_client_state_on_sink = AnyClientState::try_from(
-			source_client_state_on_sink.client_state.ok_or_else(|| {
-				Error::Custom(format!(
-					"Client state for {} should exist on {}",
-					source.name(),
-					sink.name()
-				))
-			})?,
-		)
-		.map_err(|_| {
-			Error::Custom(format!(
-				"Invalid Client state for {} should found on {}",
-				source.name(),
-				sink.name()
-			))
-		})?;
-
+			self.cache.sink.client_state(sink, sink_height, source.client_id()).await?;
 		let sink_client_state_on_source =
-			source.query_client_state(source_height, sink.client_id()).await?;
-		let sink_client_state_on_source = AnyClientState::try_from(
-			sink_client_state_on_source.client_state.ok_or_else(|| {
-				Error::Custom(format!(
-					"Client state for {} should exist on {}",
-					source.name(),
-					sink.name()
-				))
-			})?,
-		)
-		.map_err(|_| {
-			Error::Custom(format!(
-				"Invalid Client state for {} should found on {}",
-				source.name(),
-				sink.name()
-			))
-		})?;
+			self.cache.source.client_state(source, source_height, sink.client_id()).await?;
 		let latest_sink_height_on_source = sink_client_state_on_source.latest_height();
 		let latest_source_height_on_sink = source_client_state_on_sink.latest_height();
 
@@ -179,10 +246,12 @@ pub async fn query_ready_and_timed_out_packets(
 
 		let send_packets = source.query_send_packets(channel_id, port_id.clone(), seqs).await?;
 		let mut timeout_packets_join_set: JoinSet<Result<_, anyhow::Error>> = JoinSet::new();
-		let source = Arc::new(source.clone());
-		let sink = Arc::new(sink.clone());
+		let source = self.source.clone();
+		let sink = self.sink.clone();
 		for send_packets in send_packets.chunks(PROCESS_PACKETS_BATCH_SIZE) {
 			for send_packet in send_packets.to_owned() {
+				let allowed =
+					self.packet_policy.allows(source.name(), &packet_info_to_packet(&send_packet));
 				let source_connection_end = source_connection_end.clone();
 				let sink_channel_end = sink_channel_end.clone();
 				let source_connection_end = source_connection_end.clone();
@@ -191,6 +260,7 @@ pub async fn query_ready_and_timed_out_packets(
 				let duration = Duration::from_millis(
 					rand::thread_rng().gen_range(1..source.rpc_call_delay().as_millis() as u64),
 				);
+				let span = tracing::info_span!("packet", sequence = send_packet.sequence);
 				timeout_packets_join_set.spawn(async move {
 					sleep(duration).await;
 					let source = &source;
@@ -255,6 +325,11 @@ pub async fn query_ready_and_timed_out_packets(
 						log::trace!(target: "hyperspace", "Skipping packet as it has not timed out: {:?}", packet);
 					}
 
+					// packets the policy doesn't allow are only timed out, so that senders get refunded
+					if !allowed {
+						return Ok(None)
+					}
+
 					// If packet has not timed out but channel is closed on sink we skip
 					// Since we have no reference point for when this channel was closed so we can't
 					// calculate connection delays yet
@@ -319,7 +394,7 @@ pub async fn query_ready_and_timed_out_packets(
 
 					let msg = construct_recv_message(&**source, &**sink, packet, proof_height).await?;
 					Ok(Some(Right(msg)))
-				});
+				}.instrument(span));
 			}
 		}
 
@@ -334,7 +409,7 @@ pub async fn query_ready_and_timed_out_packets(
 		// Get acknowledgement messages
 		if source_channel_end.state == State::Closed {
 			log::trace!(target: "hyperspace", "Skipping acknowledgements for channel {:?} as channel is closed on source", channel_id);
-			continue
+			return Ok((messages, timeout_messages))
 		}
 
 		// query acknowledgements that are waiting for connection delay.
@@ -361,6 +436,8 @@ pub async fn query_ready_and_timed_out_packets(
 				let duration1 = Duration::from_millis(
 					rand::thread_rng().gen_range(1..source.rpc_call_delay().as_millis() as u64),
 				);
+				let span =
+					tracing::info_span!("acknowledgement", sequence = acknowledgement.sequence);
 				acknowledgements_join_set.spawn(async move {
 					sleep(duration1).await;
 					let source = &source;
@@ -426,7 +503,7 @@ pub async fn query_ready_and_timed_out_packets(
 					let msg = construct_ack_message(&**source, &**sink, packet, ack, proof_height).await?;
 					// messages.push(msg)
 					Ok(Some(msg))
-				});
+				}.instrument(span));
 			}
 		}
 
@@ -434,7 +511,203 @@ pub async fn query_ready_and_timed_out_packets(
 			let Some(msg) = result?? else { continue };
 			messages.push(msg)
 		}
+
+		Ok((messages, timeout_messages))
 	}
+}
 
-	Ok((messages, timeout_messages))
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::{
+		applications::transfer::{msgs::transfer::MsgTransfer, Amount, PrefixedCoin},
+		core::{
+			ics03_connection::{
+				connection::Counterparty, msgs::conn_open_init::MsgConnectionOpenInit,
+			},
+			ics04_channel::{
+				channel::{self, ChannelEnd, Order},
+				msgs::{chan_open_init::MsgChannelOpenInit, recv_packet},
+				Version,
+			},
+		},
+		protobuf::Protobuf,
+		tx_msg::Msg,
+	};
+	use primitives::{
+		mock::MockChain,
+		utils::{create_channel, create_clients, create_connection},
+		IbcProvider, KeyProvider, TestProvider,
+	};
+	const DENOM: &str = "stake";
+
+	/// Two mock chains with clients of each other.
+	async fn setup_chains() -> (MockChain, MockChain) {
+		let mut chain_a = MockChain::new("mock-a");
+		let mut chain_b = MockChain::new("mock-b");
+		for chain in [&chain_a, &chain_b] {
+			chain.finalize(chain.produce_block());
+		}
+		let (client_a, client_b) = create_clients(&mut chain_a, &mut chain_b).await.unwrap();
+		chain_a.set_client_id(client_a);
+		chain_b.set_client_id(client_b);
+		for chain in [&chain_a, &chain_b] {
+			chain.finalize(chain.produce_block());
+		}
+		(chain_a, chain_b)
+	}
+
+	/// Starts a channel handshake on `chain_a`, leaving the channel in the `Init` state.
+	async fn init_channel(chain_a: &MockChain, chain_b: &MockChain) -> ChannelId {
+		let msg = MsgConnectionOpenInit {
+			client_id: chain_b.client_id(),
+			counterparty: Counterparty::new(chain_a.client_id(), None, chain_b.connection_prefix()),
+			version: Some(Default::default()),
+			delay_period: Duration::ZERO,
+			signer: chain_a.account_id(),
+		};
+		let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+		let tx_id = chain_a.submit(vec![msg]).await.unwrap();
+		let connection_id = chain_a.query_connection_id_from_tx_hash(tx_id).await.unwrap();
+
+		let channel = ChannelEnd::new(
+			State::Init,
+			Order::Unordered,
+			channel::Counterparty::new(PortId::transfer(), None),
+			vec![connection_id],
+			Version::new("ics20-1".to_string()),
+		);
+		let msg = MsgChannelOpenInit::new(PortId::transfer(), channel, chain_a.account_id());
+		let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+		let tx_id = chain_a.submit(vec![msg]).await.unwrap();
+		let (channel_id, _) = chain_a.query_channel_id_from_tx_hash(tx_id).await.unwrap();
+		chain_a.finalize(chain_a.produce_block());
+		channel_id
+	}
+
+	/// Opens `count` transfer channels between the chains over a single connection, returning the
+	/// channel ids on `chain_a`. Block production and the relayer are stopped afterwards.
+	async fn open_channels(
+		chain_a: &mut MockChain,
+		chain_b: &mut MockChain,
+		count: usize,
+	) -> Vec<ChannelId> {
+		let handles = [
+			chain_a.spawn_block_production(Duration::from_millis(100), 0),
+			chain_b.spawn_block_production(Duration::from_millis(100), 0),
+			tokio::spawn({
+				let (chain_a, chain_b) = (chain_a.clone(), chain_b.clone());
+				async move {
+					crate::relay(chain_a, chain_b, None, None, None, None, None).await.unwrap();
+				}
+			}),
+		];
+		let (connection_id, _) = create_connection(chain_a, chain_b, Duration::ZERO).await.unwrap();
+		let mut channel_ids = vec![];
+		for _ in 0..count {
+			let (channel_id, _) = create_channel(
+				chain_a,
+				chain_b,
+				connection_id.clone(),
+				PortId::transfer(),
+				"ics20-1".to_string(),
+				Order::Unordered,
+			)
+			.await
+			.unwrap();
+			channel_ids.push(channel_id);
+		}
+		for handle in handles {
+			handle.abort();
+		}
+		channel_ids
+	}
+
+	#[tokio::test]
+	async fn scan_shares_chain_state_between_concurrent_channel_scans() {
+		let (chain_a, chain_b) = setup_chains().await;
+		let (mut chain_a, mut chain_b) =
+			(chain_a.with_balance(DENOM, 1_000_000), chain_b.with_balance(DENOM, 1_000_000));
+		let channel_ids = open_channels(&mut chain_a, &mut chain_b, 2).await;
+		chain_a.set_channel_whitelist(
+			channel_ids.iter().map(|channel_id| (*channel_id, PortId::transfer())).collect(),
+		);
+
+		let (sink_height, sink_timestamp) = chain_b.latest_height_and_timestamp().await.unwrap();
+		for channel_id in &channel_ids {
+			for _ in 0..3 {
+				let msg = MsgTransfer {
+					source_port: PortId::transfer(),
+					source_channel: *channel_id,
+					token: PrefixedCoin {
+						denom: DENOM.parse().unwrap(),
+						amount: Amount::from(100),
+					},
+					sender: chain_a.account_id(),
+					receiver: chain_b.account_id(),
+					timeout_height: sink_height.add(1_000),
+					timeout_timestamp: (sink_timestamp + Duration::from_secs(60 * 60)).unwrap(),
+					memo: "".to_string(),
+				};
+				chain_a.send_transfer(msg).await.unwrap();
+			}
+		}
+		let height = chain_a.produce_block();
+		chain_a.finalize(height);
+
+		// Update the client on the sink so that the packets can be proven
+		let finalized = chain_a.finalized_height();
+		let updates = chain_a
+			.query_latest_ibc_events(finalized, &chain_b)
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|(msg, ..)| msg)
+			.collect::<Vec<_>>();
+		chain_b.submit(updates).await.unwrap();
+		chain_b.finalize(chain_b.produce_block());
+
+		let cache = PacketScanCache::default();
+		let (messages, timeouts) =
+			scan_channels(&chain_a, &chain_b, &PacketPolicy::default(), None, &cache)
+				.await
+				.unwrap();
+		assert_eq!(messages.len(), 6);
+		assert!(messages.iter().all(|msg| msg.type_url == recv_packet::TYPE_URL));
+		assert!(timeouts.is_empty());
+		// Both scans share the source connection end and the client states of both chains
+		assert_eq!(cache.hits(), 3);
+	}
+
+	#[tokio::test]
+	async fn scan_skips_channels_that_dont_exist() {
+		let (mut chain_a, chain_b) = setup_chains().await;
+		chain_a.set_channel_whitelist(vec![(ChannelId::new(7), PortId::transfer())]);
+
+		let (messages, timeouts) =
+			query_ready_and_timed_out_packets(&chain_a, &chain_b, &PacketPolicy::default(), None)
+				.await
+				.unwrap();
+		assert!(messages.is_empty());
+		assert!(timeouts.is_empty());
+	}
+
+	#[tokio::test]
+	async fn scan_skips_channels_that_are_not_open() {
+		let (mut chain_a, chain_b) = setup_chains().await;
+		let channel_id = init_channel(&chain_a, &chain_b).await;
+		// Channels sharing a connection are scanned concurrently
+		chain_a.set_channel_whitelist(vec![
+			(channel_id, PortId::transfer()),
+			(channel_id, PortId::transfer()),
+			(ChannelId::new(7), PortId::transfer()),
+		]);
+
+		let (messages, timeouts) =
+			query_ready_and_timed_out_packets(&chain_a, &chain_b, &PacketPolicy::default(), None)
+				.await
+				.unwrap();
+		assert!(messages.is_empty());
+		assert!(timeouts.is_empty());
+	}
 }
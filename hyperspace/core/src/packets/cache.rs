@@ -0,0 +1,268 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::Future;
+use ibc::{
+	core::{
+		ics03_connection::connection::ConnectionEnd,
+		ics04_channel::channel::ChannelEnd,
+		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+	},
+	Height,
+};
+use pallet_ibc::light_clients::AnyClientState;
+use primitives::{error::Error, Chain};
+use std::{
+	collections::HashMap,
+	hash::Hash,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+};
+use tokio::sync::OnceCell;
+
+/// Query results indexed by key. Concurrent lookups of the same key share a single query, and
+/// failed queries aren't cached so that the next lookup retries them.
+struct QueryCache<K, V> {
+	entries: Mutex<HashMap<K, Arc<OnceCell<V>>>>,
+	/// Number of lookups answered without running their query.
+	hits: AtomicUsize,
+}
+
+impl<K, V> Default for QueryCache<K, V> {
+	fn default() -> Self {
+		Self { entries: Mutex::new(HashMap::new()), hits: AtomicUsize::new(0) }
+	}
+}
+
+impl<K: Eq + Hash, V: Clone> QueryCache<K, V> {
+	/// Returns the value cached for `key`, running `query` if there's none yet and no other
+	/// lookup of `key` is in flight.
+	async fn get_or_query(
+		&self,
+		key: K,
+		query: impl Future<Output = Result<V, anyhow::Error>>,
+	) -> Result<V, anyhow::Error> {
+		let cell = self.entries.lock().unwrap().entry(key).or_default().clone();
+		let mut queried = false;
+		let value = cell
+			.get_or_try_init(|| {
+				queried = true;
+				query
+			})
+			.await
+			.cloned()?;
+		if !queried {
+			self.hits.fetch_add(1, Ordering::Relaxed);
+		}
+		Ok(value)
+	}
+
+	/// Number of lookups that were answered by a cached or in-flight query.
+	fn hits(&self) -> usize {
+		self.hits.load(Ordering::Relaxed)
+	}
+}
+
+/// Channel ends, connection ends and client states of a chain queried at a single height.
+///
+/// A cache lives for one packet scan, i.e. one finality event, so channels sharing a connection or
+/// client only query them once and nothing outlives the height it was queried at.
+#[derive(Default)]
+pub struct ChainStateCache {
+	channel_ends: QueryCache<(ChannelId, PortId), ChannelEnd>,
+	connection_ends: QueryCache<ConnectionId, ConnectionEnd>,
+	client_states: QueryCache<ClientId, AnyClientState>,
+}
+
+impl ChainStateCache {
+	/// Returns the channel end, querying it from `chain` at `at` on the first call.
+	pub async fn channel_end(
+		&self,
+		chain: &impl Chain,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<ChannelEnd, anyhow::Error> {
+		let query = async {
+			let response = chain.query_channel_end(at, channel_id, port_id.clone()).await?;
+			let channel_end = ChannelEnd::try_from(response.channel.ok_or_else(|| {
+				Error::Custom(format!(
+					"ChannelEnd not found for {:?}/{:?} on {}",
+					channel_id,
+					port_id,
+					chain.name()
+				))
+			})?)?;
+			Ok::<_, anyhow::Error>(channel_end)
+		};
+		self.channel_ends.get_or_query((channel_id, port_id.clone()), query).await
+	}
+
+	/// Returns the connection end, querying it from `chain` at `at` on the first call.
+	pub async fn connection_end(
+		&self,
+		chain: &impl Chain,
+		at: Height,
+		connection_id: ConnectionId,
+	) -> Result<ConnectionEnd, anyhow::Error> {
+		let query = async {
+			let response = chain.query_connection_end(at, connection_id.clone()).await?;
+			let connection_end =
+				ConnectionEnd::try_from(response.connection.ok_or_else(|| {
+					Error::Custom(format!(
+						"ConnectionEnd not found for {:?} on {}",
+						connection_id,
+						chain.name()
+					))
+				})?)?;
+			Ok::<_, anyhow::Error>(connection_end)
+		};
+		self.connection_ends.get_or_query(connection_id.clone(), query).await
+	}
+
+	/// Returns the state of the client on `chain`, querying it at `at` on the first call.
+	pub async fn client_state(
+		&self,
+		chain: &impl Chain,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<AnyClientState, anyhow::Error> {
+		let query = async {
+			let response = chain.query_client_state(at, client_id.clone()).await?;
+			let client_state =
+				AnyClientState::try_from(response.client_state.ok_or_else(|| {
+					Error::Custom(format!(
+						"Client state for {} should exist on {}",
+						client_id,
+						chain.name()
+					))
+				})?)
+				.map_err(|_| {
+					Error::Custom(format!(
+						"Invalid client state for {} found on {}",
+						client_id,
+						chain.name()
+					))
+				})?;
+			Ok::<_, anyhow::Error>(client_state)
+		};
+		self.client_states.get_or_query(client_id.clone(), query).await
+	}
+
+	/// Number of lookups that didn't have to query the chain.
+	pub fn hits(&self) -> usize {
+		self.channel_ends.hits() + self.connection_ends.hits() + self.client_states.hits()
+	}
+}
+
+/// The caches of both chains of a packet scan.
+#[derive(Default)]
+pub struct PacketScanCache {
+	pub source: ChainStateCache,
+	pub sink: ChainStateCache,
+}
+
+impl PacketScanCache {
+	/// Number of lookups on either chain that didn't have to query the chain.
+	pub fn hits(&self) -> usize {
+		self.source.hits() + self.sink.hits()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::core::ics02_client::client_state::ClientState as _;
+	use primitives::{mock::MockChain, utils::create_clients, IbcProvider};
+	use std::time::Duration;
+
+	/// Returns `value` after a short delay, counting how many times it ran.
+	async fn counted_query(
+		queries: &AtomicUsize,
+		value: Result<u64, &str>,
+	) -> Result<u64, anyhow::Error> {
+		queries.fetch_add(1, Ordering::SeqCst);
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		value.map_err(|e| anyhow::anyhow!("{e}"))
+	}
+
+	#[tokio::test]
+	async fn query_cache_queries_each_key_once() {
+		let cache = QueryCache::<u8, u64>::default();
+		let queries = AtomicUsize::new(0);
+		assert_eq!(cache.get_or_query(0, counted_query(&queries, Ok(1))).await.unwrap(), 1);
+		assert_eq!(cache.get_or_query(0, counted_query(&queries, Ok(2))).await.unwrap(), 1);
+		assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+		assert_eq!(cache.get_or_query(1, counted_query(&queries, Ok(2))).await.unwrap(), 2);
+		assert_eq!(queries.load(Ordering::SeqCst), 2);
+		assert_eq!(cache.hits(), 1);
+	}
+
+	#[tokio::test]
+	async fn query_cache_shares_in_flight_queries() {
+		let cache = QueryCache::<u8, u64>::default();
+		let queries = AtomicUsize::new(0);
+		let results = futures::future::join_all(
+			(0..8).map(|_| cache.get_or_query(0, counted_query(&queries, Ok(1)))),
+		)
+		.await;
+		assert!(results.into_iter().all(|result| result.unwrap() == 1));
+		assert_eq!(queries.load(Ordering::SeqCst), 1);
+		assert_eq!(cache.hits(), 7);
+	}
+
+	#[tokio::test]
+	async fn query_cache_retries_failed_queries() {
+		let cache = QueryCache::<u8, u64>::default();
+		let queries = AtomicUsize::new(0);
+		assert!(cache.get_or_query(0, counted_query(&queries, Err("timeout"))).await.is_err());
+		assert_eq!(cache.get_or_query(0, counted_query(&queries, Ok(1))).await.unwrap(), 1);
+		assert_eq!(queries.load(Ordering::SeqCst), 2);
+		assert_eq!(cache.hits(), 0);
+	}
+
+	#[tokio::test]
+	async fn chain_state_cache_returns_the_chain_state() {
+		let mut chain_a = MockChain::new("mock-a");
+		let mut chain_b = MockChain::new("mock-b");
+		for chain in [&chain_a, &chain_b] {
+			chain.finalize(chain.produce_block());
+		}
+		let (client_a, _) = create_clients(&mut chain_a, &mut chain_b).await.unwrap();
+		chain_b.finalize(chain_b.produce_block());
+		let (height, _) = chain_b.latest_height_and_timestamp().await.unwrap();
+
+		let cache = ChainStateCache::default();
+		let client_state = cache.client_state(&chain_b, height, client_a.clone()).await.unwrap();
+		let expected = AnyClientState::try_from(
+			chain_b
+				.query_client_state(height, client_a)
+				.await
+				.unwrap()
+				.client_state
+				.unwrap(),
+		)
+		.unwrap();
+		assert_eq!(client_state.latest_height(), expected.latest_height());
+
+		assert!(cache
+			.channel_end(&chain_b, height, ChannelId::new(0), PortId::transfer())
+			.await
+			.is_err());
+		assert!(cache.connection_end(&chain_b, height, ConnectionId::new(0)).await.is_err());
+	}
+}
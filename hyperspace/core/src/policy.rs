@@ -0,0 +1,259 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Restricts which packets of a whitelisted channel are relayed, based on their ICS-20 transfer
+//! data, so that the relayer only pays fees for the traffic it's meant to relay.
+
+use ibc::{
+	applications::transfer::packet::PacketData,
+	core::{
+		ics04_channel::{channel::Order, packet::Packet},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Conditions a packet sent on a channel must meet to be relayed. Empty lists don't restrict
+/// anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PacketRule {
+	/// Name of the chain the packets are sent from.
+	pub chain: String,
+	pub channel_id: ChannelId,
+	pub port_id: PortId,
+	/// Denominations that are relayed, including their trace path, e.g.
+	/// `transfer/channel-0/uatom`.
+	#[serde(default)]
+	pub allowed_denoms: Vec<String>,
+	/// Minimum amount of a denomination that is relayed.
+	#[serde(default)]
+	pub min_amounts: HashMap<String, u128>,
+	#[serde(default)]
+	pub allowed_senders: Vec<String>,
+	#[serde(default)]
+	pub denied_senders: Vec<String>,
+	#[serde(default)]
+	pub allowed_receivers: Vec<String>,
+	#[serde(default)]
+	pub denied_receivers: Vec<String>,
+	/// Packets whose memo contains any of these strings are not relayed.
+	#[serde(default)]
+	pub denied_memo_substrings: Vec<String>,
+}
+
+impl PacketRule {
+	fn applies_to(&self, chain: &str, packet: &Packet) -> bool {
+		self.chain == chain &&
+			self.channel_id == packet.source_channel &&
+			self.port_id == packet.source_port
+	}
+
+	/// Returns the reason the packet must not be relayed, if any.
+	fn check(&self, packet: &Packet) -> Result<(), String> {
+		let data: PacketData = serde_json::from_slice(&packet.data)
+			.map_err(|e| format!("packet data is not an ICS-20 transfer: {e}"))?;
+		let denom = data.token.denom.to_string();
+		if !self.allowed_denoms.is_empty() && !self.allowed_denoms.contains(&denom) {
+			return Err(format!("denom {denom} is not allowed"))
+		}
+		if let Some(min_amount) = self.min_amounts.get(&denom) {
+			let amount = data.token.amount.as_u256();
+			if amount < (*min_amount).into() {
+				return Err(format!("amount {amount} is below the minimum of {min_amount}"))
+			}
+		}
+		let (sender, receiver) = (data.sender.as_ref(), data.receiver.as_ref());
+		if !is_allowed(sender, &self.allowed_senders, &self.denied_senders) {
+			return Err(format!("sender {sender} is not allowed"))
+		}
+		if !is_allowed(receiver, &self.allowed_receivers, &self.denied_receivers) {
+			return Err(format!("receiver {receiver} is not allowed"))
+		}
+		if let Some(pattern) = self
+			.denied_memo_substrings
+			.iter()
+			.find(|pattern| data.memo.contains(pattern.as_str()))
+		{
+			return Err(format!("memo contains {pattern:?}"))
+		}
+		Ok(())
+	}
+}
+
+fn is_allowed(account: &str, allowed: &[String], denied: &[String]) -> bool {
+	(allowed.is_empty() || allowed.iter().any(|a| a == account)) &&
+		!denied.iter().any(|d| d == account)
+}
+
+/// Rules applied to packets before they're relayed. Packets sent on channels without a rule are
+/// always relayed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PacketPolicy {
+	rules: Vec<PacketRule>,
+}
+
+impl PacketPolicy {
+	pub fn new(rules: Vec<PacketRule>) -> Self {
+		Self { rules }
+	}
+
+	/// Rejects rules on the channel if it's ordered: a skipped packet would block every later
+	/// packet of an ordered channel.
+	pub fn check_channel(
+		&self,
+		chain: &str,
+		channel_id: &ChannelId,
+		port_id: &PortId,
+		ordering: Order,
+	) -> Result<(), anyhow::Error> {
+		let has_rule = self.rules.iter().any(|rule| {
+			rule.chain == chain && &rule.channel_id == channel_id && &rule.port_id == port_id
+		});
+		anyhow::ensure!(
+			!has_rule || ordering != Order::Ordered,
+			"packet policy rules are not supported on ordered channel {channel_id}/{port_id} of {chain}"
+		);
+		Ok(())
+	}
+
+	/// Returns true if the packet sent from `chain` may be relayed. The packet must satisfy every
+	/// rule of its channel; packets that aren't ICS-20 transfers don't satisfy any rule.
+	pub fn allows(&self, chain: &str, packet: &Packet) -> bool {
+		for rule in self.rules.iter().filter(|rule| rule.applies_to(chain, packet)) {
+			if let Err(reason) = rule.check(packet) {
+				log::debug!(
+					target: "hyperspace",
+					"Skipping packet {} on {}/{} from {}: {}",
+					packet.sequence,
+					packet.source_channel,
+					packet.source_port,
+					chain,
+					reason
+				);
+				return false
+			}
+		}
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	const SENDER: &str = "cosmos1sender";
+	const RECEIVER: &str = "5receiver";
+
+	fn rule() -> PacketRule {
+		PacketRule {
+			chain: "picasso".to_string(),
+			channel_id: ChannelId::new(0),
+			port_id: PortId::transfer(),
+			allowed_denoms: vec![],
+			min_amounts: HashMap::new(),
+			allowed_senders: vec![],
+			denied_senders: vec![],
+			allowed_receivers: vec![],
+			denied_receivers: vec![],
+			denied_memo_substrings: vec![],
+		}
+	}
+
+	fn transfer(denom: &str, amount: u128, memo: &str) -> Packet {
+		let data = json!({
+			"denom": denom,
+			"amount": amount.to_string(),
+			"sender": SENDER,
+			"receiver": RECEIVER,
+			"memo": memo,
+		});
+		Packet {
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			data: serde_json::to_vec(&data).unwrap(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn empty_rule_allows_any_transfer() {
+		assert!(rule().check(&transfer("transfer/channel-0/uatom", 1, "")).is_ok());
+		assert!(rule()
+			.check(&Packet { data: b"not json".to_vec(), ..Default::default() })
+			.is_err());
+	}
+
+	#[test]
+	fn rule_checks_denoms_and_amounts() {
+		let rule = PacketRule {
+			allowed_denoms: vec!["1".to_string(), "uatom".to_string()],
+			min_amounts: HashMap::from([("1".to_string(), 1000)]),
+			..rule()
+		};
+		assert!(rule.check(&transfer("1", 1000, "")).is_ok());
+		assert!(rule.check(&transfer("1", 999, "")).is_err());
+		assert!(rule.check(&transfer("uatom", 1, "")).is_ok());
+		assert!(rule.check(&transfer("transfer/channel-0/uatom", 1000, "")).is_err());
+	}
+
+	#[test]
+	fn rule_checks_accounts() {
+		let allowed = PacketRule {
+			allowed_senders: vec![SENDER.to_string()],
+			allowed_receivers: vec![RECEIVER.to_string()],
+			..rule()
+		};
+		assert!(allowed.check(&transfer("1", 1, "")).is_ok());
+		let other_sender = PacketRule { allowed_senders: vec!["other".to_string()], ..rule() };
+		assert!(other_sender.check(&transfer("1", 1, "")).is_err());
+		let denied_sender = PacketRule { denied_senders: vec![SENDER.to_string()], ..rule() };
+		assert!(denied_sender.check(&transfer("1", 1, "")).is_err());
+		let denied_receiver = PacketRule { denied_receivers: vec![RECEIVER.to_string()], ..rule() };
+		assert!(denied_receiver.check(&transfer("1", 1, "")).is_err());
+	}
+
+	#[test]
+	fn rule_checks_memos() {
+		let rule = PacketRule { denied_memo_substrings: vec!["wasm".to_string()], ..rule() };
+		assert!(rule.check(&transfer("1", 1, "")).is_ok());
+		assert!(rule.check(&transfer("1", 1, r#"{"wasm":{}}"#)).is_err());
+	}
+
+	#[test]
+	fn policy_applies_rules_of_the_packet_channel_only() {
+		let policy =
+			PacketPolicy::new(vec![PacketRule { allowed_denoms: vec!["1".to_string()], ..rule() }]);
+		assert!(policy.allows("picasso", &transfer("1", 1, "")));
+		assert!(!policy.allows("picasso", &transfer("2", 1, "")));
+		// rules of other chains and channels don't apply
+		assert!(policy.allows("composable", &transfer("2", 1, "")));
+		let packet = Packet { source_channel: ChannelId::new(1), ..transfer("2", 1, "") };
+		assert!(policy.allows("picasso", &packet));
+		assert!(PacketPolicy::default().allows("picasso", &transfer("2", 1, "")));
+	}
+
+	#[test]
+	fn policy_rejects_rules_on_ordered_channels() {
+		let policy = PacketPolicy::new(vec![rule()]);
+		let (channel_id, port_id) = (ChannelId::new(0), PortId::transfer());
+		assert!(policy.check_channel("picasso", &channel_id, &port_id, Order::Unordered).is_ok());
+		assert!(policy.check_channel("picasso", &channel_id, &port_id, Order::Ordered).is_err());
+		assert!(policy
+			.check_channel("picasso", &ChannelId::new(1), &port_id, Order::Ordered)
+			.is_ok());
+	}
+}
@@ -32,7 +32,7 @@ pub async fn flush_message_batch(
 	log::debug!(target: "hyperspace", "Outgoing messages weight: {} block max weight: {}", batch_weight, block_max_weight);
 	let ratio = (batch_weight / block_max_weight) as usize;
 	if ratio == 0 {
-		sink.submit(msgs).await?;
+		submit(msgs, metrics, sink).await?;
 		return Ok(())
 	}
 
@@ -53,8 +53,28 @@ pub async fn flush_message_batch(
 	// TODO: return number of failed messages and record it to metrics
 	for batch in msgs.chunks(chunk_size) {
 		// send out batches.
-		sink.submit(batch.to_vec()).await?;
+		submit(batch.to_vec(), metrics, sink).await?;
 	}
 
 	Ok(())
 }
+
+/// Submits a single transaction to the sink chain and records the fee it paid.
+#[tracing::instrument(
+	name = "message_batch",
+	skip_all,
+	fields(chain = sink.name(), messages = msgs.len(), tx_id = tracing::field::Empty)
+)]
+async fn submit(
+	msgs: Vec<Any>,
+	metrics: Option<&MetricsHandler>,
+	sink: &impl Chain,
+) -> Result<(), anyhow::Error> {
+	let tx_id = sink.submit(msgs.clone()).await?;
+	tracing::Span::current().record("tx_id", tracing::field::debug(&tx_id));
+	log::debug!(target: "hyperspace", "Submitted {} messages to {}", msgs.len(), sink.name());
+	if let (Some(metrics), Some(fee)) = (metrics, sink.transaction_fee(&tx_id)) {
+		metrics.handle_transaction_fee(sink.name(), fee, &msgs);
+	}
+	Ok(())
+}
@@ -866,6 +866,14 @@ macro_rules! define_asset_id {
 			}
 		}
 
+		impl core::str::FromStr for $name {
+			type Err = core::num::ParseIntError;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				Ok(Self::from(s.parse::<u128>()?))
+			}
+		}
+
 		impl Clone for $name {
 			fn clone(&self) -> Self {
 				use $ty as CurrencyId;
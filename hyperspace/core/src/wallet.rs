@@ -0,0 +1,144 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watches the balance of the relayer account on a chain, so that the relayer doesn't run out of
+//! funds for fees unnoticed.
+
+use metrics::data::Metrics;
+use primitives::Chain;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletConfig {
+	/// Asset the relayer pays fees with, as accepted by `IbcProvider::query_ibc_balance`.
+	pub asset_id: String,
+	/// Balance, in the smallest unit of the asset, below which an alert is raised.
+	#[serde(default)]
+	pub low_balance_threshold: Option<u128>,
+	/// How often, in seconds, the balance is queried.
+	#[serde(default = "default_check_interval_secs")]
+	pub check_interval_secs: u64,
+}
+
+fn default_check_interval_secs() -> u64 {
+	60
+}
+
+impl WalletConfig {
+	/// Rejects settings the balance monitor can't run with.
+	pub fn validate(&self) -> Result<(), anyhow::Error> {
+		anyhow::ensure!(
+			self.check_interval_secs > 0,
+			"wallet check_interval_secs must be positive"
+		);
+		Ok(())
+	}
+}
+
+/// Periodically queries the relayer's balance of `asset_id` on `chain`, records it in `metrics`
+/// and warns while it's below the configured threshold. Never returns.
+pub async fn monitor_balance<C: Chain>(
+	chain: C,
+	asset_id: C::AssetId,
+	config: WalletConfig,
+	metrics: Option<Metrics>,
+) {
+	let mut interval = tokio::time::interval(Duration::from_secs(config.check_interval_secs));
+	loop {
+		interval.tick().await;
+		let coins = match chain.query_ibc_balance(asset_id.clone()).await {
+			Ok(coins) => coins,
+			Err(e) => {
+				log::warn!(target: "hyperspace", "Failed to query the relayer balance on {}: {e:?}", chain.name());
+				continue
+			},
+		};
+
+		let mut balance = 0u128;
+		for coin in &coins {
+			let amount = u128::try_from(coin.amount.as_u256()).unwrap_or(u128::MAX);
+			balance = balance.saturating_add(amount);
+			if let Some(metrics) = &metrics {
+				metrics.update_relayer_balance(&coin.denom.to_string(), amount as f64);
+			}
+		}
+		log::debug!(target: "hyperspace", "Relayer balance on {}: {balance}", chain.name());
+
+		let is_low = config.low_balance_threshold.map_or(false, |threshold| balance < threshold);
+		if is_low {
+			log::warn!(
+				target: "hyperspace",
+				"Relayer balance on {} is low: {balance} of asset {}, threshold is {}",
+				chain.name(),
+				config.asset_id,
+				config.low_balance_threshold.unwrap_or_default(),
+			);
+		}
+		if let Some(metrics) = &metrics {
+			metrics.set_relayer_balance_low(is_low);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::mock::MockChain;
+	use prometheus::Registry;
+
+	async fn monitor(balance: u128, low_balance_threshold: Option<u128>) -> Metrics {
+		let chain = MockChain::new("mock").with_balance("stake", balance);
+		let metrics = Metrics::register("mock", &Registry::new()).unwrap();
+		let config = WalletConfig {
+			asset_id: "stake".to_string(),
+			low_balance_threshold,
+			check_interval_secs: 1,
+		};
+		let handle = tokio::spawn(monitor_balance(
+			chain,
+			"stake".to_string(),
+			config,
+			Some(metrics.clone()),
+		));
+		// the first check happens right away
+		tokio::time::sleep(Duration::from_millis(500)).await;
+		handle.abort();
+		metrics
+	}
+
+	#[tokio::test]
+	async fn monitor_balance_records_the_balance() {
+		let metrics = monitor(100, None).await;
+		assert_eq!(metrics.relayer_balance.with_label_values(&["stake"]).get(), 100.0);
+		assert_eq!(metrics.relayer_balance_low.get(), 0);
+	}
+
+	#[tokio::test]
+	async fn monitor_balance_raises_the_low_balance_alert() {
+		assert_eq!(monitor(100, Some(1000)).await.relayer_balance_low.get(), 1);
+		assert_eq!(monitor(1000, Some(1000)).await.relayer_balance_low.get(), 0);
+	}
+
+	#[test]
+	fn zero_check_interval_is_rejected() {
+		let config = WalletConfig {
+			asset_id: "stake".to_string(),
+			low_balance_threshold: None,
+			check_interval_secs: 0,
+		};
+		assert!(config.validate().is_err());
+		assert!(WalletConfig { check_interval_secs: 1, ..config }.validate().is_ok());
+	}
+}
@@ -19,7 +19,9 @@ use ibc_proto::{
 };
 use pallet_ibc::light_clients::AnyClientMessage;
 use primitives::{
-	mock::LocalClientTypes, Chain, IbcProvider, LightClientSync, MisbehaviourHandler,
+	endpoints::{is_connection_error, SubscriptionStalled},
+	mock::LocalClientTypes,
+	Chain, IbcProvider, LightClientSync, MisbehaviourHandler,
 };
 use prost::Message;
 use std::{pin::Pin, time::Duration};
@@ -146,9 +148,13 @@ where
 	}
 
 	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Error> {
-		let hash = self.submit_call(messages).await?;
+		let (hash, fee) = self.submit_call(messages).await?;
 		log::debug!(target: "hyperspace_cosmos", "Submitted. Tx hash: {}", hash);
-		Ok(Self::TransactionId { hash })
+		Ok(Self::TransactionId { hash, fee })
+	}
+
+	fn transaction_fee(&self, tx_id: &Self::TransactionId) -> Option<u128> {
+		tx_id.fee
 	}
 
 	async fn query_client_message(
@@ -244,20 +250,15 @@ where
 		let err_str = if let Some(rpc_err) = error.downcast_ref::<Error>() {
 			match rpc_err {
 				Error::RpcError(s) => s.clone(),
-				_ => "".to_string(),
+				e => e.to_string(),
 			}
 		} else {
 			error.to_string()
 		};
 		log::debug!(target: "hyperspace_cosmos", "Handling error: {err_str}");
 
-		if err_str.contains("dispatch task is gone") {
-			let (rpc_client, ws_driver) = WebSocketClient::new(self.rpc_url.clone())
-				.await
-				.map_err(|e| Error::RpcError(format!("{:?}", e)))?;
-			tokio::spawn(ws_driver.run());
-			log::info!(target: "hyperspace_cosmos", "Reconnected to cosmos chain");
-			self.rpc_client = rpc_client;
+		if error.downcast_ref::<SubscriptionStalled>().is_some() || is_connection_error(&err_str) {
+			self.reconnect(true).await?;
 		}
 
 		Ok(())
@@ -2,7 +2,7 @@
 use super::{
 	key_provider::KeyEntry,
 	light_client::LightClient,
-	tx::{broadcast_tx, confirm_tx, sign_tx, simulate_tx},
+	tx::{broadcast_tx, confirm_tx, sign_tx, simulate_tx, tx_fee},
 };
 use crate::error::Error;
 use bech32::ToBase32;
@@ -26,19 +26,20 @@ use ics07_tendermint::{
 	merkle::convert_tm_to_ics_merkle_proof,
 };
 use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
-use primitives::{IbcProvider, KeyProvider, UpdateType};
+use primitives::{endpoints::EndpointPool, IbcProvider, KeyProvider, UpdateType};
 use prost::Message;
 use quick_cache::sync::Cache;
 use ripemd::Ripemd160;
 use serde::{Deserialize, Serialize};
 use std::{
+	fmt::{Display, Formatter},
 	str::FromStr,
 	sync::{Arc, Mutex},
 };
 use tendermint::{block::Height as TmHeight, Hash};
 use tendermint_light_client::components::io::{AtHeight, Io};
 use tendermint_light_client_verifier::types::{LightBlock, ValidatorSet};
-use tendermint_rpc::{endpoint::abci_query::AbciQuery, Client, Url, WebSocketClient};
+use tendermint_rpc::{endpoint::abci_query::AbciQuery, Client, HttpClient, Url, WebSocketClient};
 use tokio::task::JoinSet;
 
 const DEFAULT_FEE_DENOM: &str = "stake";
@@ -130,6 +131,8 @@ pub struct CosmosClient<H> {
 	pub grpc_url: Url,
 	/// Websocket chain ws client
 	pub websocket_url: Url,
+	/// Nodes of the chain and their health
+	pub endpoints: EndpointPool<CosmosEndpoint>,
 	/// Chain Id
 	pub chain_id: ChainId,
 	/// Light client id on counterparty chain
@@ -152,6 +155,8 @@ pub struct CosmosClient<H> {
 	pub fee_amount: String,
 	/// Fee amount
 	pub gas_limit: u64,
+	/// Deposit, in the fee denom, of the governance proposals submitted by the relayer
+	pub proposal_deposit: Option<String>,
 	/// Maximun transaction size
 	pub max_tx_size: usize,
 	/// Finality protocol to use, eg Tenderminet
@@ -180,6 +185,9 @@ pub struct CosmosClientConfig {
 	pub grpc_url: Url,
 	/// websocket url for cosmos
 	pub websocket_url: Url,
+	/// Nodes to fail over to when the node above is unhealthy
+	#[serde(default)]
+	pub fallback_endpoints: Vec<CosmosEndpoint>,
 	/// Cosmos chain Id
 	pub chain_id: String,
 	/// Light client id on counterparty chain
@@ -197,6 +205,10 @@ pub struct CosmosClientConfig {
 	/// Fee amount
 	#[serde(default = "default_gas_limit")]
 	pub gas_limit: u64,
+	/// Deposit, in the fee denom, of the governance proposals submitted by the relayer, e.g. to
+	/// migrate wasm clients. Proposals are submitted without a deposit if this is omitted.
+	#[serde(default)]
+	pub proposal_deposit: Option<String>,
 	/// Store prefix
 	pub store_prefix: String,
 	/// Maximun transaction size
@@ -230,25 +242,76 @@ pub struct CosmosClientConfig {
 	pub mnemonic: String,
 }
 
-impl<H> CosmosClient<H>
-where
-	Self: KeyProvider,
-	H: Clone + Send + Sync + 'static,
-{
-	/// Initializes a [`CosmosClient`] given a [`CosmosClientConfig`]
-	pub async fn new(config: CosmosClientConfig) -> Result<Self, Error> {
-		let (rpc_client, rpc_driver) = WebSocketClient::new(config.websocket_url.clone())
+/// The urls of a single cosmos node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosmosEndpoint {
+	/// rpc url for cosmos
+	pub rpc_url: Url,
+	/// grpc url for cosmos
+	pub grpc_url: Url,
+	/// websocket url for cosmos
+	pub websocket_url: Url,
+}
+
+impl Display for CosmosEndpoint {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.rpc_url)
+	}
+}
+
+/// Clients connected to a single cosmos node.
+struct CosmosConnection {
+	rpc_client: WebSocketClient,
+	grpc_client: tonic::transport::Channel,
+	light_client: LightClient,
+}
+
+impl CosmosConnection {
+	async fn new(endpoint: CosmosEndpoint) -> Result<Self, Error> {
+		let (rpc_client, rpc_driver) = WebSocketClient::new(endpoint.websocket_url)
 			.await
 			.map_err(|e| Error::RpcError(format!("{:?}", e)))?;
 		tokio::spawn(rpc_driver.run());
-		let grpc_client = tonic::transport::Endpoint::new(config.grpc_url.to_string())
+		let grpc_client = tonic::transport::Endpoint::new(endpoint.grpc_url.to_string())
 			.map_err(|e| Error::RpcError(format!("{:?}", e)))?
 			.connect()
 			.await
 			.map_err(|e| Error::RpcError(format!("{:?}", e)))?;
+		let light_client = LightClient::init_light_client(endpoint.rpc_url).await?;
+		Ok(Self { rpc_client, grpc_client, light_client })
+	}
+}
+
+/// Asks a cosmos node for its health.
+async fn check_health(endpoint: CosmosEndpoint) -> Result<(), Error> {
+	HttpClient::new(endpoint.rpc_url)
+		.map_err(|e| Error::RpcError(format!("{:?}", e)))?
+		.health()
+		.await
+		.map_err(|e| Error::RpcError(format!("{:?}", e)))
+}
+
+impl<H> CosmosClient<H>
+where
+	Self: KeyProvider,
+	H: Clone + Send + Sync + 'static,
+{
+	/// Initializes a [`CosmosClient`] given a [`CosmosClientConfig`]
+	pub async fn new(config: CosmosClientConfig) -> Result<Self, Error> {
+		let endpoints = EndpointPool::new(
+			CosmosEndpoint {
+				rpc_url: config.rpc_url,
+				grpc_url: config.grpc_url,
+				websocket_url: config.websocket_url,
+			},
+			config.fallback_endpoints,
+		);
+		endpoints.spawn_health_checks(check_health);
+		let CosmosConnection { rpc_client, grpc_client, light_client } =
+			endpoints.connect(CosmosConnection::new).await?;
+		let endpoint = endpoints.active();
 
 		let chain_id = ChainId::from(config.chain_id);
-		let light_client = LightClient::init_light_client(config.rpc_url.clone()).await?;
 		let commitment_prefix = CommitmentPrefix::try_from(config.store_prefix.as_bytes().to_vec())
 			.map_err(|e| Error::from(format!("Invalid store prefix {:?}", e)))?;
 
@@ -263,9 +326,10 @@ where
 			chain_id,
 			rpc_client,
 			grpc_client,
-			rpc_url: config.rpc_url,
-			grpc_url: config.grpc_url,
-			websocket_url: config.websocket_url,
+			rpc_url: endpoint.rpc_url,
+			grpc_url: endpoint.grpc_url,
+			websocket_url: endpoint.websocket_url,
+			endpoints,
 			client_id: Arc::new(Mutex::new(config.client_id)),
 			connection_id: Arc::new(Mutex::new(config.connection_id)),
 			channel_whitelist: Arc::new(Mutex::new(config.channel_whitelist)),
@@ -275,6 +339,7 @@ where
 			fee_denom: config.fee_denom,
 			fee_amount: config.fee_amount,
 			gas_limit: config.gas_limit,
+			proposal_deposit: config.proposal_deposit,
 			max_tx_size: config.max_tx_size,
 			keybase,
 			_phantom: std::marker::PhantomData,
@@ -284,6 +349,27 @@ where
 		})
 	}
 
+	/// Reconnects to the chain. If `failed`, the current node is reported unhealthy first, so
+	/// that the connection fails over to a fallback node.
+	pub async fn reconnect(&mut self, failed: bool) -> Result<(), Error> {
+		if failed {
+			self.endpoints.record_failure();
+			self.endpoints.failover();
+		}
+		let CosmosConnection { rpc_client, grpc_client, light_client } =
+			self.endpoints.connect(CosmosConnection::new).await?;
+		let endpoint = self.endpoints.active();
+		log::info!(target: "hyperspace_cosmos", "Reconnected to {} at {endpoint}", self.name);
+
+		self.rpc_client = rpc_client;
+		self.grpc_client = grpc_client;
+		self.light_client = light_client;
+		self.rpc_url = endpoint.rpc_url;
+		self.grpc_url = endpoint.grpc_url;
+		self.websocket_url = endpoint.websocket_url;
+		Ok(())
+	}
+
 	pub fn client_id(&self) -> ClientId {
 		self.client_id
 			.lock()
@@ -297,6 +383,13 @@ where
 		*self.client_id.lock().unwrap() = Some(client_id);
 	}
 
+	/// Address of the gov module, the authority of gov-gated messages.
+	pub fn gov_module_address(&self) -> Result<String, Error> {
+		let hash = sha2::Sha256::digest(b"gov");
+		bech32::encode(&self.account_prefix, hash[..20].to_base32(), bech32::Variant::Bech32)
+			.map_err(|e| Error::from(format!("Failed to encode the gov module address: {e}")))
+	}
+
 	/// Construct a tendermint client state to be submitted to the counterparty chain
 	pub async fn construct_tendermint_client_state(
 		&self,
@@ -324,7 +417,9 @@ where
 		}
 	}
 
-	pub async fn submit_call(&self, messages: Vec<Any>) -> Result<Hash, Error> {
+	/// Signs, simulates and broadcasts a transaction with the given messages, and waits for it to
+	/// be committed. Returns the transaction hash and the fee paid in `fee_denom`, if reported.
+	pub async fn submit_call(&self, messages: Vec<Any>) -> Result<(Hash, Option<u128>), Error> {
 		let _lock = self.tx_mutex.lock().await;
 		let account_info = self.query_account().await?;
 
@@ -347,7 +442,9 @@ where
 		log::debug!(target: "hyperspace_cosmos", "🤝 Transaction sent with hash: {:?}", hash);
 
 		// wait for confirmation
-		confirm_tx(&self.rpc_client, hash).await
+		let response = confirm_tx(&self.rpc_client, hash).await?;
+		let fee = tx_fee(&response.tx_result.events, &self.fee_denom);
+		Ok((response.hash, fee))
 	}
 
 	pub async fn fetch_light_block_with_cache(
@@ -29,13 +29,14 @@ use ibc::{
 	},
 	events::IbcEvent,
 	protobuf::Protobuf,
+	signer::Signer,
 	timestamp::Timestamp,
 	tx_msg::Msg,
 	Height,
 };
 use ibc_primitives::PacketInfo as IbcPacketInfo;
 use ibc_proto::{
-	cosmos::bank::v1beta1::QueryBalanceRequest,
+	cosmos::{bank::v1beta1::QueryBalanceRequest, base::v1beta1::Coin, gov::v1::MsgSubmitProposal},
 	google::protobuf::Any,
 	ibc::core::{
 		channel::v1::{
@@ -57,11 +58,13 @@ use ibc_rpc::PacketInfo;
 use ics07_tendermint::{
 	client_message::ClientMessage, client_state::ClientState, consensus_state::ConsensusState,
 };
-use ics08_wasm::msg::MsgPushNewWasmCode;
+use ics08_wasm::msg::{MsgMigrateContract, MsgPushNewWasmCode};
 use pallet_ibc::light_clients::{
 	AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager,
 };
-use primitives::{mock::LocalClientTypes, Chain, IbcProvider, KeyProvider, UpdateType};
+use primitives::{
+	mock::LocalClientTypes, Chain, IbcProvider, KeyProvider, UpdateType, WasmClientMigration,
+};
 use prost::Message;
 use std::{pin::Pin, str::FromStr, time::Duration};
 use tendermint::block::Height as TmHeight;
@@ -74,6 +77,8 @@ use tendermint_rpc::{
 };
 use tokio::{task::JoinSet, time::sleep};
 
+const SUBMIT_PROPOSAL_TYPE_URL: &str = "/cosmos.gov.v1.MsgSubmitProposal";
+
 #[derive(Clone, Debug)]
 pub enum FinalityEvent {
 	Tendermint { from: TmHeight, to: TmHeight },
@@ -82,6 +87,8 @@ pub enum FinalityEvent {
 #[derive(Clone, Debug)]
 pub struct TransactionId<Hash> {
 	pub hash: Hash,
+	/// Fee paid for the transaction, in the fee denom.
+	pub fee: Option<u128>,
 }
 
 #[async_trait::async_trait]
@@ -1265,6 +1272,45 @@ where
 
 		Ok(code_id)
 	}
+
+	async fn migrate_wasm_client(
+		&self,
+		client_id: ClientId,
+		code_id: Vec<u8>,
+		migrate_msg: Vec<u8>,
+	) -> Result<WasmClientMigration, Self::Error> {
+		// Only the gov module can migrate clients, so the migration is submitted as a proposal
+		// signed by the relayer, which migrates the client once it passes.
+		let authority = Signer::from_str(&self.gov_module_address()?)
+			.map_err(|e| Error::from(format!("Invalid gov module address: {e}")))?;
+		let title = format!("Migrate {client_id} to code {}", hex::encode(&code_id));
+		let msg = MsgMigrateContract { client_id, code_id, migrate_msg, signer: authority };
+		let proposal = MsgSubmitProposal {
+			messages: vec![msg.into()],
+			initial_deposit: self
+				.proposal_deposit
+				.iter()
+				.map(|amount| Coin { denom: self.fee_denom.clone(), amount: amount.clone() })
+				.collect(),
+			proposer: self.account_id().to_string(),
+			metadata: String::new(),
+			summary: title.clone(),
+			title,
+		};
+		let msg =
+			Any { type_url: SUBMIT_PROPOSAL_TYPE_URL.to_string(), value: proposal.encode_to_vec() };
+		let hash = self.submit(vec![msg]).await?;
+		let response = self.wait_for_tx_result(hash).await?;
+		let proposal_id = response
+			.tx_result
+			.events
+			.iter()
+			.filter(|event| event.kind == "submit_proposal")
+			.flat_map(|event| event.attributes.iter())
+			.find(|attribute| attribute.key == "proposal_id")
+			.map(|attribute| attribute.value.clone());
+		Ok(WasmClientMigration::Proposed { proposal_id })
+	}
 }
 
 impl<H> CosmosClient<H>
@@ -21,7 +21,7 @@ where
 {
 	/// Initiate an ibc transfer on chain.
 	async fn send_transfer(&self, msg: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
-		let hash = self.submit_call(vec![msg.to_any()]).await?;
+		let (hash, _) = self.submit_call(vec![msg.to_any()]).await?;
 		log::info!(target: "hyperspace_cosmos", "🤝 Transfer transaction confirmed with hash: {:?}", hash);
 		Ok(())
 	}
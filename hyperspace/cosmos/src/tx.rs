@@ -19,7 +19,7 @@ use ibc_proto::{
 	google::protobuf::Any,
 };
 use prost::Message;
-use tendermint::Hash;
+use tendermint::{abci::Event, Hash};
 use tendermint_rpc::{
 	endpoint::tx::Response as TxResponse, query::Query, Client, Order, Url, WebSocketClient,
 };
@@ -90,7 +90,7 @@ pub async fn broadcast_tx(rpc_client: &WebSocketClient, tx_bytes: Vec<u8>) -> Re
 	Ok(response.hash)
 }
 
-pub async fn confirm_tx(rpc_client: &WebSocketClient, tx_hash: Hash) -> Result<Hash, Error> {
+pub async fn confirm_tx(rpc_client: &WebSocketClient, tx_hash: Hash) -> Result<TxResponse, Error> {
 	let start_time = tokio::time::Instant::now();
 	let timeout = Duration::from_millis(30000);
 	const WAIT_BACKOFF: Duration = Duration::from_millis(300);
@@ -129,7 +129,20 @@ pub async fn confirm_tx(rpc_client: &WebSocketClient, tx_hash: Hash) -> Result<H
 			tx_hash, response_code
 		)))
 	}
-	Ok(response.hash)
+	Ok(response)
+}
+
+/// Returns the amount of `denom` paid as fee by a transaction, read from the `fee` attribute of
+/// the `tx` event among its `events`.
+pub fn tx_fee(events: &[Event], denom: &str) -> Option<u128> {
+	events
+		.iter()
+		.filter(|event| event.kind == "tx")
+		.flat_map(|event| event.attributes.iter())
+		.filter(|attribute| attribute.key == "fee")
+		.flat_map(|attribute| attribute.value.split(','))
+		.filter_map(|coin| coin.trim().strip_suffix(denom)?.parse::<u128>().ok())
+		.reduce(|total, amount| total.saturating_add(amount))
 }
 
 pub fn encoded_tx_metrics(
@@ -150,3 +163,48 @@ pub fn encoded_tx_metrics(
 
 	Ok((total_len, envelope_len))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tendermint::abci::EventAttribute;
+
+	fn event(kind: &str, attributes: &[(&str, &str)]) -> Event {
+		Event {
+			kind: kind.to_string(),
+			attributes: attributes
+				.iter()
+				.map(|(key, value)| EventAttribute {
+					key: key.to_string(),
+					value: value.to_string(),
+					index: true,
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn tx_fee_sums_the_fees_in_the_denom() {
+		let events = vec![
+			event("tx", &[("fee", "100stake,5uatom")]),
+			event("tx", &[("acc_seq", "cosmos1.../2"), ("fee", "20stake")]),
+		];
+		assert_eq!(tx_fee(&events, "stake"), Some(120));
+		assert_eq!(tx_fee(&events, "uatom"), Some(5));
+	}
+
+	#[test]
+	fn tx_fee_is_none_without_a_fee_in_the_denom() {
+		let events =
+			vec![event("tx", &[("fee", "100uatom")]), event("transfer", &[("fee", "7stake")])];
+		assert_eq!(tx_fee(&events, "stake"), None);
+		assert_eq!(tx_fee(&[], "stake"), None);
+	}
+
+	#[test]
+	fn tx_fee_saturates_instead_of_overflowing() {
+		let fee = format!("{}stake,1stake", u128::MAX);
+		let events = vec![event("tx", &[("fee", &fee)])];
+		assert_eq!(tx_fee(&events, "stake"), Some(u128::MAX));
+	}
+}
@@ -119,9 +119,20 @@ pub struct Metrics {
 	pub gas_cost_for_sent_tx_bundle: Histogram,
 	/// Transaction length (in bytes) for every sent tx bundle.
 	pub transaction_length_for_sent_tx_bundle: Histogram,
+	/// Fees paid for sent tx bundles, in the smallest unit of the paying chain's fee token,
+	/// labelled with the paying chain and the message type.
+	pub fees_paid: CounterVec<F64>,
+
+	/// Balance of the relayer account, labelled with the denomination.
+	pub relayer_balance: GaugeVec<F64>,
+	/// 1 while the relayer balance is below the configured threshold, 0 otherwise.
+	pub relayer_balance_low: Gauge<U64>,
 
 	/// Light client height.
 	pub light_client_height: HashMap<ClientId, LightClientMetrics>,
+	/// Seconds left before the trusting period of this chain's light client on the counterparty
+	/// lapses, negative once it has.
+	pub client_time_to_expiry: HashMap<ClientId, Gauge<I64>>,
 
 	/// Average time between "send packet" events.
 	pub send_packet_event_time: Histogram,
@@ -137,6 +148,10 @@ pub struct Metrics {
 	pub sent_timeout_packet_time: Histogram,
 	/// Average time between client updates.
 	pub sent_update_client_time: Histogram,
+	/// Time it takes to scan all whitelisted channels for packets to relay.
+	pub packet_scan_time: Histogram,
+	/// Time it takes to scan a single channel for packets to relay, labelled with the channel.
+	pub channel_scan_time: HistogramVec,
 
 	/// Metrics prefix.
 	pub prefix: String,
@@ -230,7 +245,35 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			fees_paid: register(
+				CounterVec::new(
+					Opts::new(
+						format!("hyperspace_{}_fees_paid", prefix),
+						"Fees paid for sent tx bundles, in the smallest unit of the fee token",
+					),
+					&["chain", "message_type"],
+				)?,
+				registry,
+			)?,
+			relayer_balance: register(
+				GaugeVec::new(
+					Opts::new(
+						format!("hyperspace_{}_relayer_balance", prefix),
+						"Balance of the relayer account",
+					),
+					&["denom"],
+				)?,
+				registry,
+			)?,
+			relayer_balance_low: register(
+				Gauge::new(
+					format!("hyperspace_{}_relayer_balance_low", prefix),
+					"Whether the relayer balance is below the configured threshold",
+				)?,
+				registry,
+			)?,
 			light_client_height: HashMap::new(),
+			client_time_to_expiry: HashMap::new(),
 			send_packet_event_time: register(
 				Histogram::with_opts(
 					HistogramOpts::new(
@@ -301,6 +344,27 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			packet_scan_time: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						&format!("hyperspace_{}_packet_scan_time", prefix),
+						"Time it takes to scan all whitelisted channels for packets",
+					)
+					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0]),
+				)?,
+				registry,
+			)?,
+			channel_scan_time: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						&format!("hyperspace_{}_channel_scan_time", prefix),
+						"Time it takes to scan a channel for packets",
+					)
+					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0]),
+					&["channel_id", "port_id"],
+				)?,
+				registry,
+			)?,
 			prefix: prefix.to_string(),
 		})
 	}
@@ -350,4 +414,37 @@ impl Metrics {
 			},
 		}
 	}
+
+	/// Records the relayer account's balance of `denom`.
+	pub fn update_relayer_balance(&self, denom: &str, balance: f64) {
+		self.relayer_balance.with_label_values(&[denom]).set(balance);
+	}
+
+	/// Raises or clears the low balance alert.
+	pub fn set_relayer_balance_low(&self, is_low: bool) {
+		self.relayer_balance_low.set(is_low as u64);
+	}
+
+	pub fn update_client_time_to_expiry(
+		&mut self,
+		client_id: &ClientId,
+		time_to_expiry: i64,
+		registry: &Registry,
+	) -> anyhow::Result<()> {
+		if !self.client_time_to_expiry.contains_key(client_id) {
+			let gauge = register(
+				Gauge::with_opts(
+					Opts::new(
+						format!("hyperspace_{}_client_time_to_expiry", self.prefix),
+						"Seconds left before the light client on the counterparty expires",
+					)
+					.const_label("client_id", client_id.to_string()),
+				)?,
+				registry,
+			)?;
+			self.client_time_to_expiry.insert(client_id.clone(), gauge);
+		}
+		self.client_time_to_expiry[client_id].set(time_to_expiry);
+		Ok(())
+	}
 }
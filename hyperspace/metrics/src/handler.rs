@@ -19,7 +19,7 @@ use ibc::{
 			events::{TimeoutOnClosePacket, TimeoutPacket},
 			packet::{Packet, Sequence},
 		},
-		ics24_host::identifier::{ChannelId, PortId},
+		ics24_host::identifier::{ChannelId, ClientId, PortId},
 	},
 	events::IbcEvent,
 };
@@ -29,7 +29,7 @@ use std::{
 	collections::HashMap,
 	ops::DerefMut,
 	sync::{Arc, Mutex},
-	time::Instant,
+	time::{Duration, Instant},
 };
 
 #[derive(Eq, PartialEq, Hash)]
@@ -136,6 +136,16 @@ impl MetricsHandler {
 		Ok(())
 	}
 
+	/// Record how long this chain's light client on the counterparty has left before it expires.
+	pub fn handle_client_expiry(
+		&mut self,
+		client_id: &ClientId,
+		time_to_expiry: i64,
+	) -> anyhow::Result<()> {
+		self.metrics
+			.update_client_time_to_expiry(client_id, time_to_expiry, &self.registry)
+	}
+
 	pub async fn handle_messages(&self, messages: &[Any]) {
 		for message in messages {
 			match message.type_url.as_str() {
@@ -191,6 +201,39 @@ impl MetricsHandler {
 		self.metrics.transaction_length_for_sent_tx_bundle.observe(batch_size as f64);
 	}
 
+	/// Record the fee paid on `chain` for a transaction. The fee is split evenly across the
+	/// transaction's messages.
+	pub fn handle_transaction_fee(&self, chain: &str, fee: u128, messages: &[Any]) {
+		if messages.is_empty() {
+			return
+		}
+		let fee_per_message = fee as f64 / messages.len() as f64;
+		for message in messages {
+			self.metrics
+				.fees_paid
+				.with_label_values(&[chain, message.type_url.as_str()])
+				.inc_by(fee_per_message);
+		}
+	}
+
+	/// Record how long scanning all whitelisted channels for packets took.
+	pub fn handle_packet_scan(&self, duration: Duration) {
+		self.metrics.packet_scan_time.observe(duration.as_millis() as f64);
+	}
+
+	/// Record how long scanning a single channel for packets took.
+	pub fn handle_channel_scan(
+		&self,
+		channel_id: &ChannelId,
+		port_id: &PortId,
+		duration: Duration,
+	) {
+		self.metrics
+			.channel_scan_time
+			.with_label_values(&[&channel_id.to_string(), port_id.as_str()])
+			.observe(duration.as_millis() as f64);
+	}
+
 	pub fn observe_last_packet_time(
 		&self,
 		packet: &Packet,
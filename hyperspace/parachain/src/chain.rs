@@ -36,21 +36,23 @@ use ibc::{
 use ibc_proto::google::protobuf::Any;
 use ics10_grandpa::client_message::{ClientMessage, Misbehaviour, RelayChainHeader};
 use itertools::Itertools;
-use jsonrpsee_ws_client::WsClientBuilder;
 use light_client_common::config::{EventRecordT, RuntimeCall, RuntimeTransactions};
 use pallet_ibc::light_clients::AnyClientMessage;
-use primitives::{mock::LocalClientTypes, Chain, IbcProvider, MisbehaviourHandler};
+use primitives::{
+	endpoints::{is_connection_error, SubscriptionStalled},
+	mock::LocalClientTypes,
+	Chain, IbcProvider, MisbehaviourHandler,
+};
 use sp_core::{twox_128, H256};
 use sp_runtime::{
 	traits::{IdentifyAccount, One, Verify},
 	MultiSignature, MultiSigner,
 };
-use std::{collections::BTreeMap, fmt::Display, pin::Pin, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, fmt::Display, pin::Pin, time::Duration};
 // #[cfg(not(feature = "dali"))]
 // use subxt::config::polkadot::PlainTip as Tip;
 // #[cfg(feature = "dali")]
 // use subxt::config::substrate::AssetTip as Tip;
-use crate::utils::unsafe_cast_to_jsonrpsee_client;
 use subxt::{
 	config::{
 		extrinsic_params::{BaseExtrinsicParamsBuilder, Era},
@@ -148,7 +150,7 @@ where
 		Error,
 	> {
 		match self.finality_protocol {
-			FinalityProtocol::Grandpa => {
+			FinalityProtocol::Grandpa | FinalityProtocol::GrandpaStandalone => {
 				let subscription =
 					GrandpaApiClient::<JustificationNotification, sp_core::H256, u32>::subscribe_justifications(
 						&*self.relay_ws_client,
@@ -222,11 +224,15 @@ where
 		log::debug!(target: "hyperspace_parachain", "Sending message: {messages_urls}");
 
 		let call = T::Tx::ibc_deliver(messages);
-		let (ext_hash, block_hash) = self.submit_call(call).await?;
+		let (ext_hash, block_hash, fee) = self.submit_call(call).await?;
 
 		log::debug!(target: "hyperspace_parachain", "Submitted extrinsic (hash: {:?}) to block {:?}", ext_hash, block_hash);
 
-		Ok(TransactionId { ext_hash, block_hash })
+		Ok(TransactionId { ext_hash, block_hash, fee })
+	}
+
+	fn transaction_fee(&self, tx_id: &Self::TransactionId) -> Option<u128> {
+		tx_id.fee
 	}
 
 	async fn query_client_message(&self, update: UpdateClient) -> Result<AnyClientMessage, Error> {
@@ -331,45 +337,24 @@ where
 		let err_str = if let Some(rpc_err) = error.downcast_ref::<Error>() {
 			match rpc_err {
 				Error::RpcError(s) => s.clone(),
-				_ => "".to_string(),
+				e => e.to_string(),
 			}
 		} else {
 			error.to_string()
 		};
 		log::debug!(target: "hyperspace", "Handling error: {err_str}");
 
+		let stalled = error.downcast_ref::<SubscriptionStalled>().is_some();
 		if err_str.contains("MaxSlotsExceeded") {
 			self.rpc_call_delay = self.rpc_call_delay * 2;
-		} else if err_str.contains("RestartNeeded") || err_str.contains("restart required") {
-			let relay_ws_client = Arc::new(
-				WsClientBuilder::default()
-					.build(&self.relay_chain_rpc_url)
-					.await
-					.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
-			);
-			let para_ws_client = Arc::new(
-				WsClientBuilder::default()
-					.build(&self.parachain_rpc_url)
-					.await
-					.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
-			);
-
-			let para_client = subxt::OnlineClient::from_rpc_client(unsafe {
-				unsafe_cast_to_jsonrpsee_client(&para_ws_client)
-			})
-			.await?;
-			let relay_client = subxt::OnlineClient::from_rpc_client(unsafe {
-				unsafe_cast_to_jsonrpsee_client(&relay_ws_client)
-			})
-			.await?;
-
-			log::info!(target: "hyperspace", "Reconnected to relay chain and parachain");
-
-			self.relay_ws_client = relay_ws_client;
-			self.para_ws_client = para_ws_client;
-			self.relay_client = relay_client;
-			self.para_client = para_client;
-			self.rpc_call_delay = self.rpc_call_delay * 2;
+		} else if stalled || is_connection_error(&err_str) {
+			// finality notifications are read from the relay chain
+			let relay_chain_failed = stalled || !self.relay_ws_client.is_connected();
+			let parachain_failed = !self.para_ws_client.is_connected();
+			self.reconnect(parachain_failed, relay_chain_failed).await?;
+			if !stalled {
+				self.rpc_call_delay = self.rpc_call_delay * 2;
+			}
 		}
 
 		Ok(())
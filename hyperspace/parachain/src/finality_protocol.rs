@@ -67,6 +67,9 @@ use tendermint_proto::Protobuf;
 pub enum FinalityProtocol {
 	Grandpa,
 	Beefy,
+	/// GRANDPA finality of a standalone chain, whose own headers are finalized by its
+	/// authorities. The relay chain rpc url must point to the chain itself.
+	GrandpaStandalone,
 }
 
 /// Finality event for parachains
@@ -118,7 +121,7 @@ impl FinalityProtocol {
 		<T as subxt::Config>::Address: Send + Sync,
 	{
 		match self {
-			FinalityProtocol::Grandpa =>
+			FinalityProtocol::Grandpa | FinalityProtocol::GrandpaStandalone =>
 				query_latest_ibc_events_with_grandpa::<T, C>(source, finality_event, counterparty)
 					.await,
 			FinalityProtocol::Beefy =>
@@ -615,7 +618,12 @@ where
 		headers_with_events.insert(finalized_para_header.number());
 	}
 
-	let ParachainHeadersWithFinalityProof { finality_proof, parachain_headers, .. } = prover
+	let ParachainHeadersWithFinalityProof {
+		finality_proof,
+		parachain_headers,
+		ancestry_proofs,
+		..
+	} = prover
 		.query_finalized_parachain_headers_with_proof::<T::Header>(
 			client_state.latest_relay_height,
 			justification.commit.target_number,
@@ -648,6 +656,7 @@ where
 		finality_proof: codec::Decode::decode(&mut &*finality_proof.encode())
 			.expect("Same struct from different crates,decode should not fail"),
 		parachain_headers: parachain_headers.into(),
+		ancestry_proofs,
 		height: Height::new(source.para_id as u64, finalized_para_height as u64),
 	};
 
@@ -36,14 +36,14 @@ pub mod test_provider;
 
 use error::Error;
 use frame_support::Serialize;
-use serde::Deserialize;
+use serde::{de::IgnoredAny, Deserialize};
 
 use beefy_light_client_primitives::{ClientState, MmrUpdateProof};
 use beefy_prover::Prover;
 use ibc::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
 use ics11_beefy::client_message::ParachainHeader;
 use pallet_mmr_primitives::Proof;
-use sp_core::{ecdsa, ed25519, sr25519, Bytes, Pair, H256};
+use sp_core::{crypto::AccountId32, ecdsa, ed25519, sr25519, Bytes, Pair, H256};
 use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
 use sp_runtime::{
 	traits::{IdentifyAccount, Verify},
@@ -53,21 +53,25 @@ use ss58_registry::Ss58AddressFormat;
 use subxt::config::{Header as HeaderT, Header};
 
 use crate::utils::{fetch_max_extrinsic_weight, unsafe_cast_to_jsonrpsee_client};
-use codec::Decode;
+use codec::{Decode, Encode};
 use ics10_grandpa::consensus_state::ConsensusState as GrandpaConsensusState;
 use ics11_beefy::{
 	client_state::ClientState as BeefyClientState,
 	consensus_state::ConsensusState as BeefyConsensusState,
 };
-use primitives::KeyProvider;
+use primitives::{endpoints::EndpointPool, KeyProvider};
 
 use crate::{finality_protocol::FinalityProtocol, signer::ExtrinsicSigner};
 use grandpa_light_client_primitives::ParachainHeaderProofs;
 use grandpa_prover::GrandpaProver;
 use ibc::timestamp::Timestamp;
 use ics10_grandpa::client_state::ClientState as GrandpaClientState;
+use jsonrpsee::{core::client::ClientT, rpc_params};
 use jsonrpsee_ws_client::WsClientBuilder;
-use light_client_common::config::{AsInner, RuntimeStorage};
+use light_client_common::{
+	config::{AsInner, RuntimeStorage},
+	RelayChain,
+};
 use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
 use sp_keystore::testing::KeyStore;
 use sp_runtime::traits::One;
@@ -86,6 +90,10 @@ pub struct ParachainClient<T: light_client_common::config::Config> {
 	pub parachain_rpc_url: String,
 	/// rpc url for relay chain
 	pub relay_chain_rpc_url: String,
+	/// Parachain rpc endpoints and their health
+	pub parachain_endpoints: EndpointPool<String>,
+	/// Relay chain rpc endpoints and their health
+	pub relay_chain_endpoints: EndpointPool<String>,
 	/// Relay chain rpc client
 	pub relay_client: subxt::OnlineClient<T>,
 	/// Parachain rpc client
@@ -116,6 +124,8 @@ pub struct ParachainClient<T: light_client_common::config::Config> {
 	pub max_extrinsic_weight: u64,
 	/// Finality protocol to use, eg Beefy, Grandpa
 	pub finality_protocol: FinalityProtocol,
+	/// Relay chain and the periods set in the client states of this chain
+	pub relay_chain: RelayChain,
 	/// Delay between parallel RPC calls to be friendly with the node and avoid MaxSlotsExceeded
 	/// error
 	pub rpc_call_delay: Duration,
@@ -168,8 +178,14 @@ pub struct ParachainClientConfig {
 	pub para_id: u32,
 	/// rpc url for parachain
 	pub parachain_rpc_url: String,
-	/// rpc url for relay chain
+	/// rpc url for relay chain, or for the chain itself when using `GrandpaStandalone`
 	pub relay_chain_rpc_url: String,
+	/// Parachain rpc urls to fail over to when `parachain_rpc_url` is unhealthy
+	#[serde(default)]
+	pub parachain_rpc_fallback_urls: Vec<String>,
+	/// Relay chain rpc urls to fail over to when `relay_chain_rpc_url` is unhealthy
+	#[serde(default)]
+	pub relay_chain_rpc_fallback_urls: Vec<String>,
 	/// Light client id on counterparty chain
 	pub client_id: Option<ClientId>,
 	/// Connection Id
@@ -184,6 +200,17 @@ pub struct ParachainClientConfig {
 	pub channel_whitelist: Vec<(ChannelId, PortId)>,
 	/// Finality protocol
 	pub finality_protocol: FinalityProtocol,
+	/// Name of the relay chain, e.g. `Polkadot`, defaults to `Rococo`
+	#[serde(default)]
+	pub relay_chain: Option<String>,
+	/// Unbonding period of the relay chain in seconds, required unless it's a well-known relay
+	/// chain
+	#[serde(default)]
+	pub unbonding_period_secs: Option<u64>,
+	/// Trusting period of the light client in seconds, must be less than the unbonding period.
+	/// Defaults to a third of the unbonding period
+	#[serde(default)]
+	pub trusting_period_secs: Option<u64>,
 	/// Digital signature scheme
 	pub key_type: String,
 	/// All the client states and headers will be wrapped in WASM ones using the WASM code ID.
@@ -197,18 +224,15 @@ where
 {
 	/// Initializes a [`ParachainClient`] given a [`ParachainConfig`]
 	pub async fn new(config: ParachainClientConfig) -> Result<Self, Error> {
-		let relay_ws_client = Arc::new(
-			WsClientBuilder::default()
-				.build(&config.relay_chain_rpc_url)
-				.await
-				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
-		);
-		let para_ws_client = Arc::new(
-			WsClientBuilder::default()
-				.build(&config.parachain_rpc_url)
-				.await
-				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
-		);
+		let relay_chain_endpoints =
+			EndpointPool::new(config.relay_chain_rpc_url, config.relay_chain_rpc_fallback_urls);
+		let parachain_endpoints =
+			EndpointPool::new(config.parachain_rpc_url, config.parachain_rpc_fallback_urls);
+		relay_chain_endpoints.spawn_health_checks(check_ws_health);
+		parachain_endpoints.spawn_health_checks(check_ws_health);
+
+		let relay_ws_client = connect_ws(&relay_chain_endpoints).await?;
+		let para_ws_client = connect_ws(&parachain_endpoints).await?;
 
 		let para_client = subxt::OnlineClient::from_rpc_client(unsafe {
 			unsafe_cast_to_jsonrpsee_client(&para_ws_client)
@@ -222,6 +246,13 @@ where
 
 		let max_extrinsic_weight = fetch_max_extrinsic_weight(&para_client).await?;
 
+		let relay_chain = RelayChain::with_periods(
+			&config.relay_chain.unwrap_or_else(|| RelayChain::default().name),
+			config.unbonding_period_secs.map(Duration::from_secs),
+			config.trusting_period_secs.map(Duration::from_secs),
+		)
+		.map_err(|e| Error::Custom(format!("Invalid relay chain configuration: {e}")))?;
+
 		let key_store: SyncCryptoStorePtr = Arc::new(KeyStore::new());
 		let key_type = KeyType::from_str(&config.key_type)?;
 		let key_type_id = key_type.to_key_type_id();
@@ -254,8 +285,10 @@ where
 
 		Ok(Self {
 			name: config.name,
-			parachain_rpc_url: config.parachain_rpc_url,
-			relay_chain_rpc_url: config.relay_chain_rpc_url,
+			parachain_rpc_url: parachain_endpoints.active(),
+			relay_chain_rpc_url: relay_chain_endpoints.active(),
+			parachain_endpoints,
+			relay_chain_endpoints,
 			para_client,
 			relay_client,
 			para_id: config.para_id,
@@ -271,10 +304,84 @@ where
 			ss58_version: Ss58AddressFormat::from(config.ss58_version),
 			channel_whitelist: Arc::new(Mutex::new(config.channel_whitelist)),
 			finality_protocol: config.finality_protocol,
+			relay_chain,
 			rpc_call_delay: DEFAULT_RPC_CALL_DELAY,
 			maybe_has_undelivered_packets: Default::default(),
 		})
 	}
+
+	/// Reconnects to the parachain and relay chain nodes. The endpoints flagged as failed are
+	/// reported unhealthy first, so that their connection fails over to a fallback endpoint.
+	pub async fn reconnect(
+		&mut self,
+		parachain_failed: bool,
+		relay_chain_failed: bool,
+	) -> Result<(), Error> {
+		for (failed, endpoints) in [
+			(parachain_failed, &self.parachain_endpoints),
+			(relay_chain_failed, &self.relay_chain_endpoints),
+		] {
+			if failed {
+				endpoints.record_failure();
+				endpoints.failover();
+			}
+		}
+
+		let relay_ws_client = connect_ws(&self.relay_chain_endpoints).await?;
+		let para_ws_client = connect_ws(&self.parachain_endpoints).await?;
+
+		let para_client = subxt::OnlineClient::from_rpc_client(unsafe {
+			unsafe_cast_to_jsonrpsee_client(&para_ws_client)
+		})
+		.await?;
+		let relay_client = subxt::OnlineClient::from_rpc_client(unsafe {
+			unsafe_cast_to_jsonrpsee_client(&relay_ws_client)
+		})
+		.await?;
+
+		self.relay_chain_rpc_url = self.relay_chain_endpoints.active();
+		self.parachain_rpc_url = self.parachain_endpoints.active();
+		log::info!(
+			target: "hyperspace",
+			"Reconnected to relay chain at {} and parachain at {}",
+			self.relay_chain_rpc_url,
+			self.parachain_rpc_url
+		);
+
+		self.relay_ws_client = relay_ws_client;
+		self.para_ws_client = para_ws_client;
+		self.relay_client = relay_client;
+		self.para_client = para_client;
+		Ok(())
+	}
+}
+
+/// Opens a websocket connection to the healthiest endpoint of the pool.
+async fn connect_ws(
+	endpoints: &EndpointPool<String>,
+) -> Result<Arc<jsonrpsee_ws_client::WsClient>, Error> {
+	let ws_client = endpoints
+		.connect(|url| async move {
+			WsClientBuilder::default()
+				.build(&url)
+				.await
+				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))
+		})
+		.await?;
+	Ok(Arc::new(ws_client))
+}
+
+/// Connects to a substrate node and asks for its health.
+async fn check_ws_health(url: String) -> Result<(), Error> {
+	let ws_client = WsClientBuilder::default()
+		.build(&url)
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+	ws_client
+		.request::<IgnoredAny, _>("system_health", rpc_params![])
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+	Ok(())
 }
 
 impl<T: light_client_common::config::Config + Send + Sync> ParachainClient<T>
@@ -303,6 +410,7 @@ where
 			para_ws_client,
 			para_id: self.para_id,
 			rpc_call_delay: self.rpc_call_delay,
+			standalone: matches!(self.finality_protocol, FinalityProtocol::GrandpaStandalone),
 		}
 	}
 
@@ -410,7 +518,13 @@ where
 	///
 	/// We retry sending the transaction up to 5 times in the case where the transaction pool might
 	/// reject the transaction because of conflicting nonces.
-	pub async fn submit_call<C: TxPayload>(&self, call: C) -> Result<(T::Hash, T::Hash), Error> {
+	///
+	/// Returns the extrinsic hash, the block hash and the fee paid for the extrinsic, if the
+	/// runtime emitted a `TransactionPayment::TransactionFeePaid` event for it.
+	pub async fn submit_call<C: TxPayload>(
+		&self,
+		call: C,
+	) -> Result<(T::Hash, T::Hash, Option<u128>), Error> {
 		// Try extrinsic submission five times in case of failures
 		let mut count = 0;
 		let progress = loop {
@@ -446,8 +560,30 @@ where
 				.map_err(|e| {
 					Error::from(format!("[submit_call] Failed to wait for in block due to {:?}", e))
 				})??;
-		tx_in_block.wait_for_success().await?;
-		Ok((tx_in_block.extrinsic_hash(), tx_in_block.block_hash()))
+		let events = tx_in_block.wait_for_success().await?;
+		// The extrinsic is already included, so failing to read its fee must not fail the call.
+		let mut fee = None;
+		for event in events.iter() {
+			let event = match event {
+				Ok(event) => event,
+				Err(e) => {
+					log::warn!(target: "hyperspace_parachain", "Failed to decode an event of {:?}: {e}", tx_in_block.extrinsic_hash());
+					continue
+				},
+			};
+			if event.pallet_name() == "TransactionPayment" &&
+				event.variant_name() == "TransactionFeePaid"
+			{
+				// TransactionFeePaid { who, actual_fee, tip }
+				fee = <(AccountId32, u128, u128)>::decode(&mut event.field_bytes())
+					.map(|(_, actual_fee, _)| actual_fee)
+					.map_err(|e| {
+						log::warn!(target: "hyperspace_parachain", "Failed to decode the fee of {:?}: {e}", tx_in_block.extrinsic_hash());
+					})
+					.ok();
+			}
+		}
+		Ok((tx_in_block.extrinsic_hash(), tx_in_block.block_hash(), fee))
 	}
 
 	pub fn client_id(&self) -> ClientId {
@@ -526,7 +662,7 @@ where
 			let block_number = decoded_para_head.number;
 			let client_state = BeefyClientState::<HostFunctionsManager> {
 				chain_id: ChainId::new("relay-chain".to_string(), 0),
-				relay_chain: Default::default(),
+				relay_chain: self.relay_chain.clone(),
 				mmr_root_hash: beefy_state.mmr_root_hash,
 				latest_beefy_height: beefy_state.latest_beefy_height,
 				frozen_height: None,
@@ -587,6 +723,7 @@ where
 			para_ws_client,
 			para_id: self.para_id,
 			rpc_call_delay: self.rpc_call_delay,
+			standalone: matches!(self.finality_protocol, FinalityProtocol::GrandpaStandalone),
 		};
 		let api = self.relay_client.storage();
 		let para_client_api = self.para_client.storage();
@@ -596,22 +733,39 @@ where
 				.await
 				.map_err(|e| Error::from(format!("Error constructing client state: {e}")))?;
 
-			let heads_addr = T::Storage::paras_heads(self.para_id);
-			let head_data = <T::Storage as RuntimeStorage>::HeadData::from_inner(
-				api.at(light_client_state.latest_relay_hash.into())
-					.fetch(&heads_addr)
+			let head_data = if prover.standalone {
+				// the finalized header is the chain's own header
+				self.relay_client
+					.rpc()
+					.header(Some(light_client_state.latest_relay_hash.into()))
 					.await?
 					.ok_or_else(|| {
+						Error::Custom(format!(
+							"Couldn't find header for block {:?}",
+							light_client_state.latest_relay_hash
+						))
+					})?
+					.encode()
+			} else {
+				let heads_addr = T::Storage::paras_heads(self.para_id);
+				<T::Storage as RuntimeStorage>::HeadData::from_inner(
+					api.at(light_client_state.latest_relay_hash.into())
+						.fetch(&heads_addr)
+						.await?
+						.ok_or_else(|| {
 						Error::Custom(format!(
 							"Couldn't find header for ParaId({}) at relay block {:?}",
 							self.para_id, light_client_state.latest_relay_hash
 						))
 					})?,
-			);
+				)
+				.as_ref()
+				.to_vec()
+			};
 			let decoded_para_head = sp_runtime::generic::Header::<
 				u32,
 				sp_runtime::traits::BlakeTwo256,
-			>::decode(&mut &*head_data.as_ref())?;
+			>::decode(&mut &*head_data)?;
 			let block_number = decoded_para_head.number;
 			// we can't use the genesis block to construct the initial state.
 			if block_number == 0 {
@@ -620,7 +774,7 @@ where
 
 			let mut client_state = GrandpaClientState::<HostFunctionsManager>::default();
 
-			client_state.relay_chain = Default::default();
+			client_state.relay_chain = self.relay_chain.clone();
 			client_state.current_authorities = light_client_state.current_authorities;
 			client_state.current_set_id = light_client_state.current_set_id;
 			client_state.latest_relay_hash = light_client_state.latest_relay_hash.into();
@@ -628,6 +782,7 @@ where
 			client_state.latest_para_height = block_number;
 			client_state.para_id = self.para_id;
 			client_state.latest_relay_height = light_client_state.latest_relay_height;
+			client_state.standalone = light_client_state.standalone;
 
 			let subxt_block_number: subxt::rpc::types::BlockNumber = block_number.into();
 			let block_hash =
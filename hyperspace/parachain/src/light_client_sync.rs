@@ -68,7 +68,7 @@ where
 		})?;
 
 		match self.finality_protocol {
-			FinalityProtocol::Grandpa => {
+			FinalityProtocol::Grandpa | FinalityProtocol::GrandpaStandalone => {
 				let prover = self.grandpa_prover();
 				let AnyClientState::Grandpa(client_state) = AnyClientState::decode_recursive(any_client_state, |c| matches!(c, AnyClientState::Grandpa(_)))
 					.ok_or_else(|| Error::Custom(format!("Could not decode client state")))? else { unreachable!() };
@@ -104,7 +104,7 @@ where
 		})?;
 
 		let (messages, events) = match self.finality_protocol {
-			FinalityProtocol::Grandpa => {
+			FinalityProtocol::Grandpa | FinalityProtocol::GrandpaStandalone => {
 				let AnyClientState::Grandpa(client_state) = AnyClientState::decode_recursive(any_client_state, |c| matches!(c, AnyClientState::Grandpa(_)))
 					.ok_or_else(|| Error::Custom(format!("Could not decode client state")))? else { unreachable!() };
 				let latest_hash = self.relay_client.rpc().finalized_head().await?;
@@ -316,7 +316,12 @@ where
 			)
 		})
 		.collect();
-	let ParachainHeadersWithFinalityProof { finality_proof, parachain_headers, .. } = prover
+	let ParachainHeadersWithFinalityProof {
+		finality_proof,
+		parachain_headers,
+		ancestry_proofs,
+		..
+	} = prover
 		.query_finalized_parachain_headers_with_proof::<T::Header>(
 			previous_finalized_height,
 			latest_finalized_height,
@@ -329,6 +334,7 @@ where
 		finality_proof: codec::Decode::decode(&mut &*finality_proof.encode())
 			.expect("Same struct from different crates,decode should not fail"),
 		parachain_headers: parachain_headers.into(),
+		ancestry_proofs,
 		height: Height::new(para_id as u64, finalized_para_height as u64),
 	};
 
@@ -51,7 +51,7 @@ use pallet_ibc::{
 	light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager},
 	HostConsensusProof,
 };
-use primitives::{apply_prefix, Chain, IbcProvider, KeyProvider, UpdateType};
+use primitives::{apply_prefix, Chain, IbcProvider, KeyProvider, UpdateType, WasmClientMigration};
 use sp_core::H256;
 use sp_runtime::{
 	traits::{IdentifyAccount, One, Verify},
@@ -67,6 +67,8 @@ use tokio_stream::wrappers::ReceiverStream;
 pub struct TransactionId<Hash> {
 	pub ext_hash: Hash,
 	pub block_hash: Hash,
+	/// Fee paid for the extrinsic, from its `TransactionPayment::TransactionFeePaid` event.
+	pub fee: Option<u128>,
 }
 
 #[async_trait::async_trait]
@@ -607,7 +609,8 @@ where
 
 	fn client_type(&self) -> ClientType {
 		match self.finality_protocol {
-			FinalityProtocol::Grandpa => GrandpaClientState::<HostFunctionsManager>::client_type(),
+			FinalityProtocol::Grandpa | FinalityProtocol::GrandpaStandalone =>
+				GrandpaClientState::<HostFunctionsManager>::client_type(),
 			FinalityProtocol::Beefy => BeefyClientState::<HostFunctionsManager>::client_type(),
 		}
 	}
@@ -707,7 +710,7 @@ where
 		&self,
 	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
 		match self.finality_protocol {
-			FinalityProtocol::Grandpa => {
+			FinalityProtocol::Grandpa | FinalityProtocol::GrandpaStandalone => {
 				let res = self.construct_grandpa_client_state().await?;
 				Ok(res)
 			},
@@ -723,7 +726,7 @@ where
 		tx_id: Self::TransactionId,
 	) -> Result<ClientId, Self::Error> {
 		// Query newly created client Id
-		let TransactionId { ext_hash, block_hash } = tx_id;
+		let TransactionId { ext_hash, block_hash, .. } = tx_id;
 		let identified_client_state = IbcApiClient::<
 			u32,
 			H256,
@@ -744,7 +747,7 @@ where
 		tx_id: Self::TransactionId,
 	) -> Result<ConnectionId, Self::Error> {
 		// Query newly created connection Id
-		let TransactionId { ext_hash, block_hash } = tx_id;
+		let TransactionId { ext_hash, block_hash, .. } = tx_id;
 		let identified_connection: IdentifiedConnection = IbcApiClient::<
 			u32,
 			H256,
@@ -767,7 +770,7 @@ where
 		tx_id: Self::TransactionId,
 	) -> Result<(ChannelId, PortId), Self::Error> {
 		// Query newly created channel Id
-		let TransactionId { ext_hash, block_hash } = tx_id;
+		let TransactionId { ext_hash, block_hash, .. } = tx_id;
 		let identified_channel: IdentifiedChannel = IbcApiClient::<
 			u32,
 			H256,
@@ -803,4 +806,13 @@ where
 	async fn upload_wasm(&self, _wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
 		Err(Error::Custom("Uploading WASM to parachain is not supported".to_string()))
 	}
+
+	async fn migrate_wasm_client(
+		&self,
+		_client_id: ClientId,
+		_code_id: Vec<u8>,
+		_migrate_msg: Vec<u8>,
+	) -> Result<WasmClientMigration, Self::Error> {
+		Err(Error::Custom("Migrating WASM clients on parachain is not supported".to_string()))
+	}
 }
@@ -72,7 +72,7 @@ where
 
 	pub async fn submit_create_client_msg(&self, msg: Any) -> Result<ClientId, Error> {
 		let call = T::Tx::ibc_deliver(vec![msg]);
-		let (ext_hash, block_hash) = self.submit_call(call).await?;
+		let (ext_hash, block_hash, _) = self.submit_call(call).await?;
 
 		// Query newly created client Id
 		let identified_client_state = IbcApiClient::<
@@ -0,0 +1,383 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Health tracking and failover for chains that are reachable through several RPC endpoints.
+
+use std::{
+	fmt::{Debug, Display},
+	future::Future,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+/// How often the endpoints of a pool with fallbacks are health checked.
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long an endpoint that failed is avoided when picking another one.
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// Weight of a new sample in the moving average of an endpoint's latency.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// Substrings of errors that mean the connection to the node is broken, rather than that a
+/// single request failed.
+const CONNECTION_ERRORS: &[&str] = &[
+	"RestartNeeded",
+	"restart required",
+	"dispatch task is gone",
+	"connection closed",
+	"Connection reset",
+	"Networking or low-level protocol error",
+	"transport error",
+];
+
+/// Passed to [`Chain::handle_error`](crate::Chain::handle_error) when a chain's finality
+/// notifications stopped arriving, so that the chain can move to another endpoint.
+#[derive(Debug, thiserror::Error)]
+#[error("No finality notification received in {0:?}")]
+pub struct SubscriptionStalled(pub Duration);
+
+/// Returns true if `error` means the connection to the node must be re-established.
+pub fn is_connection_error(error: &str) -> bool {
+	CONNECTION_ERRORS.iter().any(|pattern| error.contains(pattern))
+}
+
+struct EndpointHealth<T> {
+	endpoint: T,
+	/// Moving average of the endpoint's latency, if it ever responded.
+	latency: Option<Duration>,
+	last_failure: Option<Instant>,
+}
+
+impl<T> EndpointHealth<T> {
+	fn observe_latency(&mut self, latency: Duration) {
+		self.latency = Some(match self.latency {
+			Some(average) =>
+				average.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING),
+			None => latency,
+		});
+		self.last_failure = None;
+	}
+
+	fn observe_failure(&mut self) {
+		self.last_failure = Some(Instant::now());
+	}
+
+	fn is_cooling_down(&self) -> bool {
+		self.last_failure
+			.map_or(false, |failed_at| failed_at.elapsed() < FAILURE_COOLDOWN)
+	}
+}
+
+struct PoolState<T> {
+	endpoints: Vec<EndpointHealth<T>>,
+	active: usize,
+}
+
+/// The RPC endpoints of a chain in order of preference, together with their observed health.
+/// Clones share the same state.
+#[derive(Clone)]
+pub struct EndpointPool<T> {
+	state: Arc<Mutex<PoolState<T>>>,
+}
+
+impl<T: Clone + Display + Send + 'static> EndpointPool<T> {
+	pub fn new(primary: T, fallbacks: impl IntoIterator<Item = T>) -> Self {
+		let endpoints = std::iter::once(primary)
+			.chain(fallbacks)
+			.map(|endpoint| EndpointHealth { endpoint, latency: None, last_failure: None })
+			.collect();
+		Self { state: Arc::new(Mutex::new(PoolState { endpoints, active: 0 })) }
+	}
+
+	fn len(&self) -> usize {
+		self.state.lock().unwrap().endpoints.len()
+	}
+
+	/// The endpoint currently in use.
+	pub fn active(&self) -> T {
+		let state = self.state.lock().unwrap();
+		state.endpoints[state.active].endpoint.clone()
+	}
+
+	/// Records the latency of a request to the active endpoint.
+	pub fn record_latency(&self, latency: Duration) {
+		let mut state = self.state.lock().unwrap();
+		let active = state.active;
+		state.endpoints[active].observe_latency(latency);
+	}
+
+	/// Records that the active endpoint failed.
+	pub fn record_failure(&self) {
+		let mut state = self.state.lock().unwrap();
+		let active = state.active;
+		state.endpoints[active].observe_failure();
+	}
+
+	/// Switches to the healthiest endpoint other than the active one: endpoints that failed
+	/// recently come last, then the slowest ones. Returns `None` if there's no other endpoint.
+	pub fn failover(&self) -> Option<T> {
+		let mut state = self.state.lock().unwrap();
+		let active = state.active;
+		let next = state
+			.endpoints
+			.iter()
+			.enumerate()
+			.filter(|(i, _)| *i != active)
+			.min_by_key(|(i, health)| {
+				(health.is_cooling_down(), health.latency.unwrap_or(Duration::MAX), *i)
+			})
+			.map(|(i, _)| i)?;
+		log::warn!(
+			target: "hyperspace",
+			"Failing over from {} to {} (latency: {:?})",
+			state.endpoints[active].endpoint,
+			state.endpoints[next].endpoint,
+			state.endpoints[next].latency,
+		);
+		state.active = next;
+		Some(state.endpoints[next].endpoint.clone())
+	}
+
+	/// Connects to the active endpoint, failing over to the other endpoints until a connection
+	/// succeeds. Returns the last error if none does.
+	pub async fn connect<C, E, F, Fut>(&self, mut connect: F) -> Result<C, E>
+	where
+		E: Debug,
+		F: FnMut(T) -> Fut,
+		Fut: Future<Output = Result<C, E>>,
+	{
+		let mut attempts_left = self.len();
+		loop {
+			let endpoint = self.active();
+			let start = Instant::now();
+			match connect(endpoint.clone()).await {
+				Ok(client) => {
+					self.record_latency(start.elapsed());
+					return Ok(client)
+				},
+				Err(e) => {
+					log::warn!(target: "hyperspace", "Failed to connect to {endpoint}: {e:?}");
+					self.record_failure();
+					attempts_left -= 1;
+					if attempts_left == 0 || self.failover().is_none() {
+						return Err(e)
+					}
+				},
+			}
+		}
+	}
+
+	/// Runs `check` against every endpoint each [`HEALTH_CHECK_INTERVAL`], recording latencies
+	/// and failures, until all clones of the pool are dropped. Does nothing without fallbacks.
+	pub fn spawn_health_checks<E, F, Fut>(&self, check: F)
+	where
+		E: Debug,
+		F: Fn(T) -> Fut + Send + 'static,
+		Fut: Future<Output = Result<(), E>> + Send,
+	{
+		if self.len() < 2 {
+			return
+		}
+		let state = Arc::downgrade(&self.state);
+		tokio::spawn(async move {
+			let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+			loop {
+				interval.tick().await;
+				let endpoints = match state.upgrade() {
+					Some(state) => state
+						.lock()
+						.unwrap()
+						.endpoints
+						.iter()
+						.map(|health| health.endpoint.clone())
+						.collect::<Vec<_>>(),
+					None => return,
+				};
+				for (i, endpoint) in endpoints.into_iter().enumerate() {
+					let start = Instant::now();
+					let result = check(endpoint.clone()).await;
+					let pool = match state.upgrade() {
+						Some(pool) => pool,
+						None => return,
+					};
+					let mut pool = pool.lock().unwrap();
+					match result {
+						Ok(()) => pool.endpoints[i].observe_latency(start.elapsed()),
+						Err(e) => {
+							log::debug!(target: "hyperspace", "Health check of {endpoint} failed: {e:?}");
+							pool.endpoints[i].observe_failure();
+						},
+					}
+				}
+			}
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	fn pool() -> EndpointPool<&'static str> {
+		EndpointPool::new("a", ["b", "c"])
+	}
+
+	#[test]
+	fn failover_prefers_fast_endpoints_that_didnt_fail() {
+		let pool = pool();
+		{
+			let mut state = pool.state.lock().unwrap();
+			state.endpoints[1].observe_latency(Duration::from_millis(50));
+			state.endpoints[2].observe_latency(Duration::from_millis(10));
+		}
+		assert_eq!(pool.failover(), Some("c"));
+		assert_eq!(pool.active(), "c");
+
+		// A recent failure outweighs a low latency
+		pool.record_failure();
+		assert_eq!(pool.failover(), Some("b"));
+		assert_eq!(pool.failover(), Some("a"));
+	}
+
+	#[test]
+	fn failover_keeps_the_preference_order_without_observations() {
+		let pool = pool();
+		assert_eq!(pool.failover(), Some("b"));
+		assert_eq!(pool.failover(), Some("a"));
+	}
+
+	#[test]
+	fn failover_needs_another_endpoint() {
+		let pool = EndpointPool::new("a", []);
+		assert_eq!(pool.failover(), None);
+		assert_eq!(pool.active(), "a");
+	}
+
+	#[test]
+	fn latency_is_a_moving_average() {
+		let mut health = EndpointHealth { endpoint: "a", latency: None, last_failure: None };
+		health.observe_latency(Duration::from_millis(100));
+		assert_eq!(health.latency, Some(Duration::from_millis(100)));
+		health.observe_latency(Duration::from_millis(200));
+		assert_eq!(health.latency, Some(Duration::from_millis(120)));
+
+		health.observe_failure();
+		assert!(health.is_cooling_down());
+		health.observe_latency(Duration::from_millis(100));
+		assert!(!health.is_cooling_down());
+	}
+
+	#[tokio::test]
+	async fn connect_fails_over_until_an_endpoint_connects() {
+		let pool = pool();
+		let attempts = AtomicUsize::new(0);
+		let client = pool
+			.connect(|endpoint| {
+				attempts.fetch_add(1, Ordering::SeqCst);
+				async move {
+					match endpoint {
+						"c" => Ok(endpoint),
+						_ => Err(format!("{endpoint} is down")),
+					}
+				}
+			})
+			.await;
+		assert_eq!(client, Ok("c"));
+		assert_eq!(attempts.load(Ordering::SeqCst), 3);
+		assert_eq!(pool.active(), "c");
+
+		// Reconnecting starts from the endpoint that worked
+		let client = pool.connect(|endpoint| async move { Ok::<_, String>(endpoint) }).await;
+		assert_eq!(client, Ok("c"));
+	}
+
+	#[tokio::test]
+	async fn connect_returns_the_last_error_once_every_endpoint_failed() {
+		let pool = pool();
+		let attempts = AtomicUsize::new(0);
+		let client = pool
+			.connect(|endpoint| {
+				attempts.fetch_add(1, Ordering::SeqCst);
+				async move { Err::<(), _>(format!("{endpoint} is down")) }
+			})
+			.await;
+		assert_eq!(client, Err("c is down".to_string()));
+		assert_eq!(attempts.load(Ordering::SeqCst), 3);
+		assert!(pool
+			.state
+			.lock()
+			.unwrap()
+			.endpoints
+			.iter()
+			.all(|health| health.is_cooling_down()));
+	}
+
+	#[tokio::test]
+	async fn health_checks_record_failures_and_latencies() {
+		let pool = pool();
+		let checks = Arc::new(AtomicUsize::new(0));
+		let checks_clone = checks.clone();
+		// The first round of checks runs right away
+		pool.spawn_health_checks(move |endpoint| {
+			let checks = checks_clone.clone();
+			async move {
+				let result = match endpoint {
+					"b" => Err("unreachable"),
+					"c" => {
+						tokio::time::sleep(Duration::from_millis(20)).await;
+						Ok(())
+					},
+					_ => Ok(()),
+				};
+				checks.fetch_add(1, Ordering::SeqCst);
+				result
+			}
+		});
+		while checks.load(Ordering::SeqCst) < 3 {
+			tokio::time::sleep(Duration::from_millis(5)).await;
+		}
+		// Let the last result be recorded
+		tokio::time::sleep(Duration::from_millis(5)).await;
+
+		{
+			let state = pool.state.lock().unwrap();
+			assert!(state.endpoints[0].latency.is_some());
+			assert!(state.endpoints[1].is_cooling_down());
+			assert!(state.endpoints[2].latency.unwrap() >= Duration::from_millis(20));
+		}
+		assert_eq!(pool.failover(), Some("c"));
+	}
+
+	#[tokio::test]
+	async fn health_checks_need_fallbacks() {
+		let pool = EndpointPool::new("a", []);
+		let checks = Arc::new(AtomicUsize::new(0));
+		let checks_clone = checks.clone();
+		pool.spawn_health_checks(move |_| {
+			checks_clone.fetch_add(1, Ordering::SeqCst);
+			async { Ok::<_, String>(()) }
+		});
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		assert_eq!(checks.load(Ordering::SeqCst), 0);
+	}
+
+	#[test]
+	fn detects_connection_errors() {
+		assert!(is_connection_error("Networking or low-level protocol error: Connection reset"));
+		assert!(is_connection_error("The background task been terminated because: RestartNeeded"));
+		assert!(!is_connection_error("Custom error: Client state not found"));
+	}
+}
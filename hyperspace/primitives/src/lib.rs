@@ -61,6 +61,7 @@ use ibc_proto::ibc::core::{
 use ibc_rpc::PacketInfo;
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
 
+pub mod endpoints;
 pub mod error;
 pub mod mock;
 pub mod utils;
@@ -87,6 +88,16 @@ impl UpdateType {
 	}
 }
 
+/// Outcome of [`Chain::migrate_wasm_client`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasmClientMigration {
+	/// The client was migrated.
+	Executed,
+	/// Only governance can migrate clients on the chain, a proposal migrating the client was
+	/// submitted instead.
+	Proposed { proposal_id: Option<String> },
+}
+
 pub fn apply_prefix(mut commitment_prefix: Vec<u8>, path: impl Into<Vec<u8>>) -> Vec<u8> {
 	let path = path.into();
 	commitment_prefix.extend_from_slice(&path);
@@ -364,6 +375,16 @@ pub trait IbcProvider {
 	) -> Result<(ChannelId, PortId), Self::Error>;
 
 	async fn upload_wasm(&self, wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error>;
+
+	/// Points the wasm client `client_id` at the uploaded code with id `code_id`, running the
+	/// `migrate` entry point of the contract with `migrate_msg` if it isn't empty. On chains where
+	/// only governance can migrate clients, this submits a proposal doing the migration instead.
+	async fn migrate_wasm_client(
+		&self,
+		client_id: ClientId,
+		code_id: Vec<u8>,
+		migrate_msg: Vec<u8>,
+	) -> Result<WasmClientMigration, Self::Error>;
 }
 
 /// Provides an interface that allows us run the hyperspace-testsuite
@@ -444,6 +465,10 @@ pub trait Chain:
 	/// Should return the transaction id
 	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Self::Error>;
 
+	/// Returns the fee paid for a transaction returned by [`Chain::submit`], in the smallest unit
+	/// of the chain's fee token, if the chain reports it.
+	fn transaction_fee(&self, tx_id: &Self::TransactionId) -> Option<u128>;
+
 	/// Returns an [`AnyClientMessage`] for an [`UpdateClient`] event
 	async fn query_client_message(
 		&self,
@@ -452,6 +477,9 @@ pub trait Chain:
 
 	async fn get_proof_height(&self, block_height: Height) -> Height;
 
+	/// Recovers from an error returned by this chain, e.g. by reconnecting to another endpoint.
+	/// Also called with [`endpoints::SubscriptionStalled`] when finality notifications stop
+	/// arriving.
 	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error>;
 
 	fn rpc_call_delay(&self) -> Duration;
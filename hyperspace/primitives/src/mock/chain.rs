@@ -0,0 +1,1304 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An in-memory chain backed by [`MockContext`], so the relayer can be exercised end to end in
+//! `cargo test` without any external nodes. Blocks are only produced and finalized when asked to,
+//! either manually through [`MockChain::produce_block`] and [`MockChain::finalize`], or on a timer
+//! through [`MockChain::spawn_block_production`].
+
+use crate::{
+	error::Error, Chain, IbcProvider, KeyProvider, LightClientSync, MisbehaviourHandler,
+	TestProvider, UpdateType, WasmClientMigration,
+};
+use futures::{channel::mpsc, Stream};
+use ibc::{
+	applications::transfer::{
+		acknowledgement::Acknowledgement as TransferAcknowledgement, is_receiver_chain_source,
+		msgs::transfer::MsgTransfer, packet::PacketData, Amount, PrefixedCoin, PrefixedDenom,
+		TracePrefix,
+	},
+	core::{
+		ics02_client::{
+			client_state::{ClientState as ClientStateT, ClientType},
+			events::UpdateClient,
+			msgs::update_client::MsgUpdateAnyClient,
+		},
+		ics03_connection::{connection::IdentifiedConnectionEnd, context::ConnectionReader},
+		ics04_channel::{
+			channel::{Counterparty, IdentifiedChannelEnd, Order},
+			context::{ChannelKeeper, ChannelReader},
+			error::Error as Ics04Error,
+			handler::send_packet::send_packet,
+			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+			packet::{Packet, Sequence},
+			Version,
+		},
+		ics23_commitment::commitment::CommitmentPrefix,
+		ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+		ics26_routing::{
+			context::{
+				Module, ModuleCallbackContext, ModuleId, ModuleOutputBuilder, RouterBuilder,
+			},
+			handler::{deliver, MsgReceipt},
+		},
+	},
+	events::IbcEvent,
+	handler::HandlerOutput,
+	mock::{
+		client_state::{
+			AnyConsensusState as MockAnyConsensusState, MockClientState, MockConsensusState,
+		},
+		context::{MockClientTypes, MockContext, MockIbcStore, MockRouterBuilder},
+		header::{AnyClientMessage as MockAnyClientMessage, MockClientMessage, MockHeader},
+		host::{MockHostBlock, MockHostType},
+	},
+	protobuf::Protobuf,
+	signer::Signer,
+	test_utils::get_dummy_proof,
+	timestamp::Timestamp,
+	tx_msg::Msg,
+	Height,
+};
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::{
+		channel::v1::{
+			QueryChannelResponse, QueryChannelsResponse, QueryNextSequenceReceiveResponse,
+			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
+			QueryPacketReceiptResponse,
+		},
+		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
+		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
+	},
+};
+use ibc_rpc::PacketInfo;
+use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
+use std::{
+	collections::BTreeMap,
+	pin::Pin,
+	str::FromStr,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use tokio::task::JoinHandle;
+
+/// Number of blocks (and store snapshots) kept around for queries and client updates.
+pub const DEFAULT_MAX_HISTORY_SIZE: usize = 10_000;
+/// Number of blocks after which the counterparty client must be updated, even without events.
+pub const DEFAULT_MANDATORY_UPDATE_INTERVAL: u64 = 10;
+/// Revision number of every mock chain.
+pub const MOCK_REVISION_NUMBER: u64 = 0;
+
+pub type MockIbcContext = MockContext<MockClientTypes>;
+
+type Balances = Arc<Mutex<BTreeMap<String, u128>>>;
+
+/// Mock chain state that isn't tracked by the ibc store.
+#[derive(Default)]
+struct MockChainState {
+	/// Latest finalized block.
+	finalized_height: u64,
+	/// Ibc store as it was at the end of every sealed block.
+	snapshots: BTreeMap<u64, Arc<MockIbcStore<MockClientTypes>>>,
+	/// Events emitted in each block.
+	block_events: BTreeMap<u64, Vec<IbcEvent>>,
+	/// Events emitted by each submitted transaction, indexed by transaction id.
+	transactions: Vec<Vec<IbcEvent>>,
+	/// Sent packets, indexed by source port, source channel and sequence.
+	send_packets: BTreeMap<(PortId, ChannelId, u64), PacketInfo>,
+	/// Received packets, indexed by destination port, destination channel and sequence.
+	recv_packets: BTreeMap<(PortId, ChannelId, u64), PacketInfo>,
+	finality_subscribers: Vec<mpsc::UnboundedSender<Height>>,
+	event_subscribers: Vec<mpsc::UnboundedSender<IbcEvent>>,
+	block_subscribers: Vec<mpsc::UnboundedSender<u64>>,
+}
+
+/// An in-memory chain implementing every relayer trait on top of [`MockContext`].
+///
+/// Clones share the same underlying chain, so a clone can be handed to the relayer while the
+/// test keeps driving block production. Proofs are never checked by the mock light client, so
+/// every proof returned is a dummy.
+#[derive(Clone)]
+pub struct MockChain {
+	name: String,
+	account: Signer,
+	// Lock order: `context`, then `state`.
+	context: Arc<Mutex<MockIbcContext>>,
+	state: Arc<Mutex<MockChainState>>,
+	/// Token balances of `account`, indexed by denom.
+	balances: Balances,
+	client_id: Option<ClientId>,
+	connection_id: Option<ConnectionId>,
+	channel_whitelist: Vec<(ChannelId, PortId)>,
+	maybe_has_undelivered_packets: Arc<Mutex<bool>>,
+	mandatory_update_interval: u64,
+	rpc_call_delay: Duration,
+}
+
+impl MockChain {
+	/// Create a new chain at height 1, with an ics20 module bound to the `transfer` port and a
+	/// ping module bound to the `ping` port.
+	pub fn new(name: impl Into<String>) -> Self {
+		let name = name.into();
+		let balances = Balances::default();
+		let router = MockRouterBuilder::default()
+			.add_route(
+				ModuleId::from_str("transfer").expect("Module id is valid"),
+				MockTransferModule { balances: balances.clone() },
+			)
+			.and_then(|builder| {
+				builder.add_route(
+					ModuleId::from_str("ping").expect("Module id is valid"),
+					MockPingModule,
+				)
+			})
+			.expect("Module ids are unique")
+			.build();
+		let mut context = MockIbcContext::new(
+			ChainId::new(name.clone(), MOCK_REVISION_NUMBER),
+			MockHostType::Mock,
+			DEFAULT_MAX_HISTORY_SIZE,
+			Height::new(MOCK_REVISION_NUMBER, 1),
+		)
+		.with_router(router);
+		context.scope_port_to_module(
+			PortId::transfer(),
+			ModuleId::from_str("transfer").expect("Module id is valid"),
+		);
+		context.scope_port_to_module(
+			PortId::from_str("ping").expect("Port id is valid"),
+			ModuleId::from_str("ping").expect("Module id is valid"),
+		);
+
+		Self {
+			account: Signer::from_str(&format!("{name}-relayer")).expect("Signer is not empty"),
+			name,
+			context: Arc::new(Mutex::new(context)),
+			state: Default::default(),
+			balances,
+			client_id: None,
+			connection_id: None,
+			channel_whitelist: vec![],
+			maybe_has_undelivered_packets: Default::default(),
+			mandatory_update_interval: DEFAULT_MANDATORY_UPDATE_INTERVAL,
+			rpc_call_delay: Duration::from_millis(10),
+		}
+	}
+
+	/// Mint `amount` of `denom` to the chain's account.
+	pub fn with_balance(self, denom: impl Into<String>, amount: u128) -> Self {
+		*self.balances.lock().unwrap().entry(denom.into()).or_default() += amount;
+		self
+	}
+
+	/// Set the number of blocks after which a client update is always sent to the counterparty.
+	pub fn with_mandatory_update_interval(mut self, interval: u64) -> Self {
+		self.mandatory_update_interval = interval;
+		self
+	}
+
+	/// Seal the pending block, returning its height. Transactions submitted afterwards go into
+	/// the next block.
+	pub fn produce_block(&self) -> u64 {
+		let mut context = self.context.lock().unwrap();
+		let height = context.latest_height().revision_height;
+		let snapshot = Arc::new(context.ibc_store.lock().unwrap().clone());
+		context.advance_host_chain_height();
+
+		let mut state = self.state.lock().unwrap();
+		state.snapshots.insert(height, snapshot);
+		if let Some(pruned) = height.checked_sub(DEFAULT_MAX_HISTORY_SIZE as u64) {
+			state.snapshots.remove(&pruned);
+			state.block_events.remove(&pruned);
+		}
+		state.block_subscribers.retain(|sender| sender.unbounded_send(height).is_ok());
+		height
+	}
+
+	/// Finalize every sealed block up to `height`, notifying finality and event subscribers.
+	pub fn finalize(&self, height: u64) {
+		let mut state = self.state.lock().unwrap();
+		let sealed = state.snapshots.keys().next_back().copied().unwrap_or_default();
+		let height = height.min(sealed);
+		if height <= state.finalized_height {
+			return
+		}
+
+		let events = state
+			.block_events
+			.range(state.finalized_height + 1..=height)
+			.flat_map(|(_, events)| events.clone())
+			.collect::<Vec<_>>();
+		state.finalized_height = height;
+		let finalized = Height::new(MOCK_REVISION_NUMBER, height);
+		state
+			.finality_subscribers
+			.retain(|sender| sender.unbounded_send(finalized).is_ok());
+		for event in events {
+			state
+				.event_subscribers
+				.retain(|sender| sender.unbounded_send(event.clone()).is_ok());
+		}
+	}
+
+	/// Produce a block every `block_time`, finalizing blocks `finality_lag` blocks behind the
+	/// latest sealed block.
+	pub fn spawn_block_production(
+		&self,
+		block_time: Duration,
+		finality_lag: u64,
+	) -> JoinHandle<()> {
+		let chain = self.clone();
+		tokio::spawn(async move {
+			let mut interval = tokio::time::interval(block_time);
+			loop {
+				interval.tick().await;
+				let height = chain.produce_block();
+				chain.finalize(height.saturating_sub(finality_lag));
+			}
+		})
+	}
+
+	/// Latest finalized block height.
+	pub fn finalized_height(&self) -> Height {
+		Height::new(MOCK_REVISION_NUMBER, self.state.lock().unwrap().finalized_height)
+	}
+
+	/// The ibc store as it was at the end of block `at`, or the live store if no block at or
+	/// below `at` was sealed yet.
+	fn store_at(&self, at: Height) -> Arc<MockIbcStore<MockClientTypes>> {
+		let context = self.context.lock().unwrap();
+		let state = self.state.lock().unwrap();
+		state
+			.snapshots
+			.range(..=at.revision_height)
+			.next_back()
+			.map(|(_, store)| store.clone())
+			.unwrap_or_else(|| Arc::new(context.ibc_store.lock().unwrap().clone()))
+	}
+
+	/// Index the packets among `events` and record them as a transaction in the pending block.
+	fn record_transaction(&self, context: &MockIbcContext, events: Vec<IbcEvent>) -> usize {
+		let height = context.latest_height().revision_height;
+		let store = context.ibc_store.lock().unwrap();
+		let mut state = self.state.lock().unwrap();
+		for event in &events {
+			match event {
+				IbcEvent::SendPacket(ev) => {
+					let packet = &ev.packet;
+					let key = (
+						packet.source_port.clone(),
+						packet.source_channel,
+						u64::from(packet.sequence),
+					);
+					let order = store.channels.get(&(key.0.clone(), key.1)).map(|c| c.ordering);
+					state.send_packets.insert(key, packet_info(packet, height, order, None));
+				},
+				IbcEvent::WriteAcknowledgement(ev) => {
+					let packet = &ev.packet;
+					let key = (
+						packet.destination_port.clone(),
+						packet.destination_channel,
+						u64::from(packet.sequence),
+					);
+					let order = store.channels.get(&(key.0.clone(), key.1)).map(|c| c.ordering);
+					state
+						.recv_packets
+						.insert(key, packet_info(packet, height, order, Some(ev.ack.clone())));
+				},
+				_ => {},
+			}
+		}
+		state.block_events.entry(height).or_default().extend(events.iter().cloned());
+		state.transactions.push(events);
+		state.transactions.len() - 1
+	}
+
+	fn transaction_events(&self, tx_id: usize) -> Result<Vec<IbcEvent>, Error> {
+		self.state
+			.lock()
+			.unwrap()
+			.transactions
+			.get(tx_id)
+			.cloned()
+			.ok_or_else(|| Error::Custom(format!("Unknown transaction {tx_id} on {}", self.name)))
+	}
+
+	/// Send a packet from the chain's account, as the source module would.
+	fn send_packet(
+		&self,
+		source_port: PortId,
+		source_channel: ChannelId,
+		data: Vec<u8>,
+		timeout_height: Height,
+		timeout_timestamp: Timestamp,
+	) -> Result<(), Error> {
+		let mut context = self.context.lock().unwrap();
+		let key = (source_port.clone(), source_channel);
+		let channel_end = context.channel_end(&key)?;
+		let sequence = context.get_next_sequence_send(&key)?;
+		let destination_channel = channel_end.counterparty().channel_id.ok_or_else(|| {
+			Error::Custom(format!("Channel {source_channel} has no counterparty channel"))
+		})?;
+		let packet = Packet {
+			sequence,
+			source_port,
+			source_channel,
+			destination_port: channel_end.counterparty().port_id.clone(),
+			destination_channel,
+			data,
+			timeout_height,
+			timeout_timestamp,
+		};
+
+		let HandlerOutput { result, events, .. } = send_packet(&*context, packet)?;
+		context.store_packet_result(result)?;
+		self.record_transaction(&context, events);
+		Ok(())
+	}
+}
+
+fn packet_info(
+	packet: &Packet,
+	height: u64,
+	order: Option<Order>,
+	ack: Option<Vec<u8>>,
+) -> PacketInfo {
+	PacketInfo {
+		height: Some(height),
+		sequence: packet.sequence.into(),
+		source_port: packet.source_port.to_string(),
+		source_channel: packet.source_channel.to_string(),
+		destination_port: packet.destination_port.to_string(),
+		destination_channel: packet.destination_channel.to_string(),
+		channel_order: (order.unwrap_or_default() as u8).to_string(),
+		data: packet.data.clone(),
+		timeout_height: packet.timeout_height.into(),
+		timeout_timestamp: packet.timeout_timestamp.nanoseconds(),
+		ack,
+	}
+}
+
+fn header_at(context: &MockIbcContext, height: u64) -> Result<MockHeader, Error> {
+	match context.host_block(Height::new(MOCK_REVISION_NUMBER, height)) {
+		Some(MockHostBlock::Mock(header)) => Ok(header.clone()),
+		None => Err(Error::Custom(format!("Block {height} is not available"))),
+	}
+}
+
+#[async_trait::async_trait]
+impl IbcProvider for MockChain {
+	type FinalityEvent = Height;
+	type TransactionId = usize;
+	type AssetId = String;
+	type Error = Error;
+
+	async fn query_latest_ibc_events<T>(
+		&mut self,
+		finality_event: Self::FinalityEvent,
+		counterparty: &T,
+	) -> Result<Vec<(Any, Vec<IbcEvent>, UpdateType)>, anyhow::Error>
+	where
+		T: Chain,
+	{
+		let (counterparty_height, ..) = counterparty.latest_height_and_timestamp().await?;
+		let client_state = counterparty
+			.query_client_state(counterparty_height, self.client_id())
+			.await?
+			.client_state
+			.ok_or_else(|| Error::Custom("Client state not found on counterparty".to_string()))?;
+		let latest_client_height =
+			AnyClientState::try_from(client_state)?.latest_height().revision_height;
+		let finalized_height = finality_event.revision_height;
+		if finalized_height <= latest_client_height {
+			return Ok(vec![])
+		}
+		let update_required =
+			self.is_update_required(finalized_height, latest_client_height).await?;
+
+		// The counterparty needs a consensus state at the height of every event it is sent proofs
+		// for, so only blocks with events and the finalized block get an update.
+		let context = self.context.lock().unwrap();
+		let state = self.state.lock().unwrap();
+		let mut updates = vec![];
+		for height in (latest_client_height + 1)..=finalized_height {
+			let events = state.block_events.get(&height).cloned().unwrap_or_default();
+			let is_finalized_block = height == finalized_height;
+			if events.is_empty() && !is_finalized_block {
+				continue
+			}
+
+			let msg = MsgUpdateAnyClient::<MockClientTypes> {
+				client_id: self.client_id(),
+				client_message: MockAnyClientMessage::Mock(MockClientMessage::Header(header_at(
+					&context, height,
+				)?)),
+				signer: counterparty.account_id(),
+			};
+			let update_type = if is_finalized_block && update_required {
+				UpdateType::Mandatory
+			} else {
+				UpdateType::Optional
+			};
+			let value = msg.encode_vec()?;
+			updates.push((Any { type_url: msg.type_url(), value }, events, update_type));
+		}
+
+		Ok(updates)
+	}
+
+	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
+		let (sender, receiver) = mpsc::unbounded();
+		self.state.lock().unwrap().event_subscribers.push(sender);
+		Box::pin(receiver)
+	}
+
+	async fn query_client_consensus(
+		&self,
+		at: Height,
+		client_id: ClientId,
+		consensus_height: Height,
+	) -> Result<QueryConsensusStateResponse, Self::Error> {
+		let store = self.store_at(at);
+		let consensus_state = store
+			.clients
+			.get(&client_id)
+			.and_then(|record| record.consensus_states.get(&consensus_height))
+			.cloned()
+			.ok_or_else(|| {
+				Error::Custom(format!(
+					"Consensus state for {client_id} at {consensus_height} not found at {at}"
+				))
+			})?;
+		Ok(QueryConsensusStateResponse {
+			consensus_state: Some(consensus_state.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_client_state(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<QueryClientStateResponse, Self::Error> {
+		let store = self.store_at(at);
+		let client_state = store
+			.clients
+			.get(&client_id)
+			.and_then(|record| record.client_state.clone())
+			.ok_or_else(|| Error::Custom(format!("Client {client_id} not found at {at}")))?;
+		Ok(QueryClientStateResponse {
+			client_state: Some(client_state.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_connection_end(
+		&self,
+		at: Height,
+		connection_id: ConnectionId,
+	) -> Result<QueryConnectionResponse, Self::Error> {
+		let store = self.store_at(at);
+		let connection = store.connections.get(&connection_id).cloned().ok_or_else(|| {
+			Error::Custom(format!("Connection {connection_id} not found at {at}"))
+		})?;
+		Ok(QueryConnectionResponse {
+			connection: Some(connection.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_channel_end(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryChannelResponse, Self::Error> {
+		let store = self.store_at(at);
+		let channel =
+			store.channels.get(&(port_id.clone(), channel_id)).cloned().ok_or_else(|| {
+				Error::Custom(format!("Channel {port_id}/{channel_id} not found at {at}"))
+			})?;
+		Ok(QueryChannelResponse {
+			channel: Some(channel.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_proof(&self, _at: Height, _keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
+		Ok(get_dummy_proof())
+	}
+
+	async fn query_packet_commitment(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
+		let store = self.store_at(at);
+		let commitment = store
+			.packet_commitment
+			.get(&(port_id.clone(), *channel_id, Sequence::from(seq)))
+			.cloned()
+			.map(|commitment| commitment.into_vec())
+			.unwrap_or_default();
+		Ok(QueryPacketCommitmentResponse {
+			commitment,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_packet_acknowledgement(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
+		let store = self.store_at(at);
+		let acknowledgement = store
+			.packet_acknowledgement
+			.get(&(port_id.clone(), *channel_id, Sequence::from(seq)))
+			.cloned()
+			.map(|commitment| commitment.into_vec())
+			.unwrap_or_default();
+		Ok(QueryPacketAcknowledgementResponse {
+			acknowledgement,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_next_sequence_recv(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
+		let store = self.store_at(at);
+		let next_sequence_receive = store
+			.next_sequence_recv
+			.get(&(port_id.clone(), *channel_id))
+			.copied()
+			.map(u64::from)
+			.unwrap_or(1);
+		Ok(QueryNextSequenceReceiveResponse {
+			next_sequence_receive,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_packet_receipt(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketReceiptResponse, Self::Error> {
+		let store = self.store_at(at);
+		let received =
+			store
+				.packet_receipt
+				.contains_key(&(port_id.clone(), *channel_id, Sequence::from(seq)));
+		Ok(QueryPacketReceiptResponse {
+			received,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		let context = self.context.lock().unwrap();
+		let height = self.state.lock().unwrap().finalized_height;
+		let timestamp = header_at(&context, height)?.timestamp;
+		Ok((Height::new(MOCK_REVISION_NUMBER, height), timestamp))
+	}
+
+	async fn query_packet_commitments(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		let store = self.store_at(at);
+		Ok(store
+			.packet_commitment
+			.keys()
+			.filter(|(port, channel, _)| *port == port_id && *channel == channel_id)
+			.map(|(.., seq)| u64::from(*seq))
+			.collect())
+	}
+
+	async fn query_packet_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		let store = self.store_at(at);
+		Ok(store
+			.packet_acknowledgement
+			.keys()
+			.filter(|(port, channel, _)| *port == port_id && *channel == channel_id)
+			.map(|(.., seq)| u64::from(*seq))
+			.collect())
+	}
+
+	async fn query_unreceived_packets(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		let store = self.store_at(at);
+		Ok(seqs
+			.into_iter()
+			.filter(|seq| {
+				!store.packet_receipt.contains_key(&(
+					port_id.clone(),
+					channel_id,
+					Sequence::from(*seq),
+				))
+			})
+			.collect())
+	}
+
+	async fn on_undelivered_sequences(&self, seqs: &[u64]) -> Result<(), Self::Error> {
+		*self.maybe_has_undelivered_packets.lock().unwrap() = !seqs.is_empty();
+		Ok(())
+	}
+
+	fn has_undelivered_sequences(&self) -> bool {
+		*self.maybe_has_undelivered_packets.lock().unwrap()
+	}
+
+	async fn query_unreceived_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		// An acknowledgement is unreceived for as long as the packet commitment exists
+		let store = self.store_at(at);
+		Ok(seqs
+			.into_iter()
+			.filter(|seq| {
+				store.packet_commitment.contains_key(&(
+					port_id.clone(),
+					channel_id,
+					Sequence::from(*seq),
+				))
+			})
+			.collect())
+	}
+
+	fn channel_whitelist(&self) -> Vec<(ChannelId, PortId)> {
+		self.channel_whitelist.clone()
+	}
+
+	async fn query_connection_channels(
+		&self,
+		at: Height,
+		connection_id: &ConnectionId,
+	) -> Result<QueryChannelsResponse, Self::Error> {
+		let store = self.store_at(at);
+		let channels = store
+			.connection_channels
+			.get(connection_id)
+			.into_iter()
+			.flatten()
+			.filter_map(|(port_id, channel_id)| {
+				let channel_end = store.channels.get(&(port_id.clone(), *channel_id))?.clone();
+				Some(IdentifiedChannelEnd::new(port_id.clone(), *channel_id, channel_end).into())
+			})
+			.collect();
+		Ok(QueryChannelsResponse { channels, pagination: None, height: Some(at.into()) })
+	}
+
+	async fn query_send_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		let state = self.state.lock().unwrap();
+		Ok(seqs
+			.into_iter()
+			.filter_map(|seq| state.send_packets.get(&(port_id.clone(), channel_id, seq)).cloned())
+			.collect())
+	}
+
+	async fn query_recv_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		let state = self.state.lock().unwrap();
+		Ok(seqs
+			.into_iter()
+			.filter_map(|seq| state.recv_packets.get(&(port_id.clone(), channel_id, seq)).cloned())
+			.collect())
+	}
+
+	fn expected_block_time(&self) -> Duration {
+		self.context.lock().unwrap().block_time
+	}
+
+	async fn query_client_update_time_and_height(
+		&self,
+		client_id: ClientId,
+		client_height: Height,
+	) -> Result<(Height, Timestamp), Self::Error> {
+		let context = self.context.lock().unwrap();
+		let store = context.ibc_store.lock().unwrap();
+		let key = (client_id, client_height);
+		match (store.client_processed_heights.get(&key), store.client_processed_times.get(&key)) {
+			(Some(height), Some(timestamp)) => Ok((*height, *timestamp)),
+			_ => Err(Error::Custom(format!(
+				"Update time and height for {} at {} not found",
+				key.0, key.1
+			))),
+		}
+	}
+
+	async fn query_host_consensus_state_proof(
+		&self,
+		_client_state: &AnyClientState,
+	) -> Result<Option<Vec<u8>>, Self::Error> {
+		// The mock host looks its consensus states up in the block history.
+		Ok(Some(vec![]))
+	}
+
+	async fn query_ibc_balance(
+		&self,
+		asset_id: Self::AssetId,
+	) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		let amount = self.balances.lock().unwrap().get(&asset_id).copied().unwrap_or_default();
+		let denom = PrefixedDenom::from_str(&asset_id).map_err(|e| Error::Custom(e.to_string()))?;
+		let amount =
+			Amount::from_str(&amount.to_string()).map_err(|e| Error::Custom(e.to_string()))?;
+		Ok(vec![PrefixedCoin { denom, amount }])
+	}
+
+	fn connection_prefix(&self) -> CommitmentPrefix {
+		self.context.lock().unwrap().commitment_prefix()
+	}
+
+	fn client_id(&self) -> ClientId {
+		self.client_id.clone().expect("Client id should have been set")
+	}
+
+	fn set_client_id(&mut self, client_id: ClientId) {
+		self.client_id = Some(client_id);
+	}
+
+	fn connection_id(&self) -> Option<ConnectionId> {
+		self.connection_id.clone()
+	}
+
+	fn set_channel_whitelist(&mut self, channel_whitelist: Vec<(ChannelId, PortId)>) {
+		self.channel_whitelist = channel_whitelist;
+	}
+
+	fn add_channel_to_whitelist(&mut self, channel: (ChannelId, PortId)) {
+		self.channel_whitelist.push(channel);
+	}
+
+	fn set_connection_id(&mut self, connection_id: ConnectionId) {
+		self.connection_id = Some(connection_id);
+	}
+
+	fn client_type(&self) -> ClientType {
+		MockClientState::client_type()
+	}
+
+	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		let context = self.context.lock().unwrap();
+		Ok(header_at(&context, block_number)?.timestamp.nanoseconds())
+	}
+
+	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
+		let context = self.context.lock().unwrap();
+		let clients = context.ibc_store.lock().unwrap().clients.keys().cloned().collect();
+		Ok(clients)
+	}
+
+	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
+		let context = self.context.lock().unwrap();
+		let channels = context
+			.ibc_store
+			.lock()
+			.unwrap()
+			.channels
+			.keys()
+			.map(|(port_id, channel_id)| (*channel_id, port_id.clone()))
+			.collect();
+		Ok(channels)
+	}
+
+	async fn query_connection_using_client(
+		&self,
+		height: u32,
+		client_id: String,
+	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		let store = self.store_at(Height::new(MOCK_REVISION_NUMBER, height as u64));
+		Ok(store
+			.connections
+			.iter()
+			.filter(|(_, connection_end)| connection_end.client_id().as_str() == client_id)
+			.map(|(connection_id, connection_end)| {
+				IdentifiedConnectionEnd::new(connection_id.clone(), connection_end.clone()).into()
+			})
+			.collect())
+	}
+
+	async fn is_update_required(
+		&self,
+		latest_height: u64,
+		latest_client_height_on_counterparty: u64,
+	) -> Result<bool, Self::Error> {
+		Ok(latest_height.saturating_sub(latest_client_height_on_counterparty) >=
+			self.mandatory_update_interval)
+	}
+
+	async fn initialize_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		let context = self.context.lock().unwrap();
+		let height = self.state.lock().unwrap().finalized_height;
+		let header = header_at(&context, height)?;
+		let client_state = MockClientState::new(MockClientMessage::Header(header.clone()));
+		Ok((
+			AnyClientState::Mock(client_state),
+			AnyConsensusState::Mock(MockConsensusState::new(header)),
+		))
+	}
+
+	async fn query_client_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ClientId, Self::Error> {
+		self.transaction_events(tx_id)?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::CreateClient(ev) => Some(ev.client_id().clone()),
+				_ => None,
+			})
+			.ok_or_else(|| Error::Custom(format!("No CreateClient event in transaction {tx_id}")))
+	}
+
+	async fn query_connection_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ConnectionId, Self::Error> {
+		self.transaction_events(tx_id)?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::OpenInitConnection(ev) => ev.connection_id().cloned(),
+				_ => None,
+			})
+			.ok_or_else(|| {
+				Error::Custom(format!("No OpenInitConnection event in transaction {tx_id}"))
+			})
+	}
+
+	async fn query_channel_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<(ChannelId, PortId), Self::Error> {
+		self.transaction_events(tx_id)?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::OpenInitChannel(ev) =>
+					ev.channel_id().map(|channel_id| (*channel_id, ev.port_id().clone())),
+				_ => None,
+			})
+			.ok_or_else(|| {
+				Error::Custom(format!("No OpenInitChannel event in transaction {tx_id}"))
+			})
+	}
+
+	async fn upload_wasm(&self, _wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
+		Err(Error::Custom("Wasm clients are not supported by the mock chain".to_string()))
+	}
+
+	async fn migrate_wasm_client(
+		&self,
+		_client_id: ClientId,
+		_code_id: Vec<u8>,
+		_migrate_msg: Vec<u8>,
+	) -> Result<WasmClientMigration, Self::Error> {
+		Err(Error::Custom("Wasm clients are not supported by the mock chain".to_string()))
+	}
+}
+
+impl KeyProvider for MockChain {
+	fn account_id(&self) -> Signer {
+		self.account.clone()
+	}
+}
+
+#[async_trait::async_trait]
+impl MisbehaviourHandler for MockChain {
+	async fn check_for_misbehaviour<C: Chain>(
+		&self,
+		_counterparty: &C,
+		_client_message: AnyClientMessage,
+	) -> Result<(), anyhow::Error> {
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl LightClientSync for MockChain {
+	async fn is_synced<C: Chain>(&self, _counterparty: &C) -> Result<bool, anyhow::Error> {
+		Ok(true)
+	}
+
+	async fn fetch_mandatory_updates<C: Chain>(
+		&self,
+		_counterparty: &C,
+	) -> Result<(Vec<Any>, Vec<IbcEvent>), anyhow::Error> {
+		Ok((vec![], vec![]))
+	}
+}
+
+#[async_trait::async_trait]
+impl Chain for MockChain {
+	fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn block_max_weight(&self) -> u64 {
+		u64::MAX
+	}
+
+	async fn estimate_weight(&self, _msg: Vec<Any>) -> Result<u64, Self::Error> {
+		Ok(0)
+	}
+
+	async fn finality_notifications(
+		&self,
+	) -> Result<Pin<Box<dyn Stream<Item = Self::FinalityEvent> + Send + Sync>>, Self::Error> {
+		let (sender, receiver) = mpsc::unbounded();
+		self.state.lock().unwrap().finality_subscribers.push(sender);
+		Ok(Box::pin(receiver))
+	}
+
+	/// Messages are delivered atomically in the pending block: if any of them fails, the store
+	/// and balances are rolled back.
+	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Self::Error> {
+		let mut context = self.context.lock().unwrap();
+		let store_backup = context.ibc_store.lock().unwrap().clone();
+		let balances_backup = self.balances.lock().unwrap().clone();
+		let mut events = vec![];
+		for message in messages {
+			match deliver(&mut *context, message) {
+				Ok(MsgReceipt { events: message_events, .. }) => events.extend(message_events),
+				Err(e) => {
+					*context.ibc_store.lock().unwrap() = store_backup;
+					*self.balances.lock().unwrap() = balances_backup;
+					return Err(Error::Custom(format!("Transaction failed on {}: {e}", self.name)))
+				},
+			}
+		}
+
+		Ok(self.record_transaction(&context, events))
+	}
+
+	/// Transactions on the mock chain are free.
+	fn transaction_fee(&self, _tx_id: &Self::TransactionId) -> Option<u128> {
+		None
+	}
+
+	async fn query_client_message(
+		&self,
+		update: UpdateClient,
+	) -> Result<AnyClientMessage, Self::Error> {
+		let context = self.context.lock().unwrap();
+		let store = context.ibc_store.lock().unwrap();
+		let consensus_state = store
+			.clients
+			.get(update.client_id())
+			.and_then(|record| record.consensus_states.get(&update.consensus_height()))
+			.ok_or_else(|| {
+				Error::Custom(format!(
+					"Consensus state for {} at {} not found",
+					update.client_id(),
+					update.consensus_height()
+				))
+			})?;
+		match consensus_state {
+			MockAnyConsensusState::Mock(consensus_state) => Ok(AnyClientMessage::Mock(
+				MockClientMessage::Header(consensus_state.header.clone()),
+			)),
+		}
+	}
+
+	async fn get_proof_height(&self, block_height: Height) -> Height {
+		block_height
+	}
+
+	async fn handle_error(&mut self, _error: &anyhow::Error) -> Result<(), anyhow::Error> {
+		Ok(())
+	}
+
+	fn rpc_call_delay(&self) -> Duration {
+		self.rpc_call_delay
+	}
+
+	fn set_rpc_call_delay(&mut self, delay: Duration) {
+		self.rpc_call_delay = delay;
+	}
+}
+
+#[async_trait::async_trait]
+impl TestProvider for MockChain {
+	async fn send_transfer(&self, params: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
+		let denom = params.token.denom.to_string();
+		let amount = params.token.amount.as_u256().as_u128();
+		{
+			let mut balances = self.balances.lock().unwrap();
+			let balance =
+				balances.get_mut(&denom).filter(|balance| **balance >= amount).ok_or_else(
+					|| Error::Custom(format!("Insufficient {denom} balance on {}", self.name)),
+				)?;
+			*balance -= amount;
+		}
+
+		let data = PacketData {
+			token: params.token,
+			sender: params.sender,
+			receiver: params.receiver,
+			memo: params.memo,
+		};
+		let data = serde_json::to_vec(&data).map_err(|e| Error::Custom(e.to_string()))?;
+		self.send_packet(
+			params.source_port,
+			params.source_channel,
+			data,
+			params.timeout_height,
+			params.timeout_timestamp,
+		)
+		.map_err(|e| {
+			*self.balances.lock().unwrap().entry(denom).or_default() += amount;
+			e
+		})
+	}
+
+	async fn send_ordered_packet(
+		&self,
+		channel_id: ChannelId,
+		timeout: pallet_ibc::Timeout,
+	) -> Result<(), Self::Error> {
+		// Offsets are relative to the latest counterparty state known to the channel's client.
+		let port_id = PortId::from_str("ping").expect("Port id is valid");
+		let (latest_height, latest_timestamp) = {
+			let context = self.context.lock().unwrap();
+			let store = context.ibc_store.lock().unwrap();
+			let record = store
+				.channels
+				.get(&(port_id.clone(), channel_id))
+				.and_then(|channel_end| channel_end.connection_hops.first())
+				.and_then(|connection_id| store.connections.get(connection_id))
+				.and_then(|connection_end| store.clients.get(connection_end.client_id()))
+				.ok_or_else(|| Error::Custom(format!("Client for {channel_id} not found")))?;
+			let latest_height = record
+				.client_state
+				.as_ref()
+				.map(|client_state| client_state.latest_height())
+				.unwrap_or_default();
+			let latest_timestamp = record
+				.consensus_states
+				.get(&latest_height)
+				.map(|consensus_state| match consensus_state {
+					MockAnyConsensusState::Mock(consensus_state) => consensus_state.timestamp(),
+				})
+				.unwrap_or_default();
+			(latest_height, latest_timestamp)
+		};
+
+		let (timeout_height, timeout_timestamp) = match timeout {
+			pallet_ibc::Timeout::Offset { timestamp, height } => (
+				height.map(|offset| latest_height.add(offset)).unwrap_or_default(),
+				timestamp
+					.map(|offset| latest_timestamp + Duration::from_secs(offset))
+					.transpose()
+					.map_err(|e| Error::Custom(e.to_string()))?
+					.unwrap_or_default(),
+			),
+			pallet_ibc::Timeout::Absolute { timestamp, height } => (
+				height
+					.map(|height| Height::new(latest_height.revision_number, height))
+					.unwrap_or_default(),
+				timestamp
+					.map(Timestamp::from_nanoseconds)
+					.transpose()
+					.map_err(|e| Error::Custom(e.to_string()))?
+					.unwrap_or_default(),
+			),
+		};
+
+		self.send_packet(port_id, channel_id, b"ping".to_vec(), timeout_height, timeout_timestamp)
+	}
+
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		let (sender, receiver) = mpsc::unbounded();
+		self.state.lock().unwrap().block_subscribers.push(sender);
+		Box::pin(receiver)
+	}
+
+	async fn increase_counters(&mut self) -> Result<(), Self::Error> {
+		let context = self.context.lock().unwrap();
+		let mut store = context.ibc_store.lock().unwrap();
+		store.client_ids_counter += 1;
+		store.connection_ids_counter += 1;
+		store.channel_ids_counter += 1;
+		Ok(())
+	}
+}
+
+/// Ics20 stand-in which credits and debits the chain's account directly instead of escrowing
+/// and minting.
+struct MockTransferModule {
+	balances: Balances,
+}
+
+impl MockTransferModule {
+	fn refund(&self, packet: &Packet) -> Result<(), Ics04Error> {
+		let PacketData { token, .. } = decode_packet_data(packet)?;
+		*self.balances.lock().unwrap().entry(token.denom.to_string()).or_default() +=
+			token.amount.as_u256().as_u128();
+		Ok(())
+	}
+}
+
+fn decode_packet_data(packet: &Packet) -> Result<PacketData, Ics04Error> {
+	serde_json::from_slice(&packet.data)
+		.map_err(|e| Ics04Error::app_module(format!("Invalid transfer packet data: {e}")))
+}
+
+impl Module for MockTransferModule {
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		Ok(counterparty_version.clone())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<GenericAcknowledgement, Ics04Error> {
+		let PacketData { token, .. } = decode_packet_data(packet)?;
+		let mut denom = token.denom;
+		if is_receiver_chain_source(packet.source_port.clone(), packet.source_channel, &denom) {
+			denom.remove_trace_prefix(&TracePrefix::new(
+				packet.source_port.clone(),
+				packet.source_channel,
+			));
+		} else {
+			denom.add_trace_prefix(TracePrefix::new(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+			));
+		}
+		*self.balances.lock().unwrap().entry(denom.to_string()).or_default() +=
+			token.amount.as_u256().as_u128();
+
+		Ok(GenericAcknowledgement::from_bytes(
+			TransferAcknowledgement::success().to_string().into_bytes(),
+		))
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &GenericAcknowledgement,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let acknowledgement =
+			serde_json::from_slice::<TransferAcknowledgement>(acknowledgement.as_ref())
+				.map_err(|e| Ics04Error::app_module(format!("Invalid acknowledgement: {e}")))?;
+		if !acknowledgement.is_successful() {
+			self.refund(packet)?;
+		}
+		Ok(())
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.refund(packet)
+	}
+}
+
+/// Acknowledges every packet it receives, for testing ordered channels.
+struct MockPingModule;
+
+impl Module for MockPingModule {
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		Ok(counterparty_version.clone())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<GenericAcknowledgement, Ics04Error> {
+		Ok(GenericAcknowledgement::from_bytes(b"pong".to_vec()))
+	}
+}
@@ -15,6 +15,12 @@
 use ibc::core::ics02_client::context::ClientTypes;
 use pallet_ibc::light_clients::{AnyClient, AnyClientMessage, AnyClientState, AnyConsensusState};
 
+#[cfg(feature = "mocks")]
+mod chain;
+
+#[cfg(feature = "mocks")]
+pub use chain::*;
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct LocalClientTypes;
 
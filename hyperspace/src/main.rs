@@ -15,20 +15,24 @@ use anyhow::Result;
 use clap::Parser;
 use hyperspace_core::{
 	command::{Cli, Subcommand},
-	logging,
+	logging::{self, setup_tracing},
 };
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	logging::setup_logging();
 	let cli = Cli::parse();
+	setup_tracing(cli.log_format, cli.otlp_endpoint.as_deref())?;
 
-	match &cli.subcommand {
+	let result = match &cli.subcommand {
 		Subcommand::Relay(cmd) => cmd.run().await,
 		Subcommand::UploadWasm(cmd) => {
 			let new_config = cmd.run().await?;
 			cmd.save_config(&new_config).await
 		},
+		Subcommand::MigrateWasmClient(cmd) => {
+			let new_config = cmd.run().await?;
+			cmd.save_config(&new_config).await
+		},
 		Subcommand::CreateClients(cmd) => {
 			let new_config = cmd.create_clients().await?;
 			cmd.save_config(&new_config).await
@@ -41,6 +45,18 @@ async fn main() -> Result<()> {
 			let new_config = cmd.create_channel().await?;
 			cmd.save_config(&new_config).await
 		},
+		Subcommand::CompleteConnection(cmd) => {
+			let new_config = cmd.complete_connection().await?;
+			cmd.save_config(&new_config).await
+		},
+		Subcommand::CompleteChannel(cmd) => {
+			let new_config = cmd.complete_channel().await?;
+			cmd.save_config(&new_config).await
+		},
 		Subcommand::Fish(cmd) => cmd.fish().await,
-	}
+		Subcommand::Query(cmd) => cmd.run().await,
+		Subcommand::Doctor(cmd) => cmd.run().await,
+	};
+	logging::shutdown_tracing();
+	result
 }
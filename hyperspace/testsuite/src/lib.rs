@@ -62,7 +62,7 @@ where
 	let client_b_clone = chain_b.clone();
 	// Start relayer loop
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -508,7 +508,7 @@ pub async fn ibc_messaging_packet_height_timeout_with_connection_delay<A, B>(
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -542,7 +542,7 @@ pub async fn ibc_messaging_packet_timestamp_timeout_with_connection_delay<A, B>(
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -580,7 +580,7 @@ pub async fn ibc_messaging_with_connection_delay<A, B>(
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -619,7 +619,7 @@ where
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -653,7 +653,7 @@ pub async fn ibc_messaging_packet_timeout_on_channel_close<A, B>(
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -676,7 +676,7 @@ where
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
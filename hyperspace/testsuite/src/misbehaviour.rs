@@ -198,6 +198,7 @@ where
 	let grandpa_header = GrandpaHeader {
 		finality_proof,
 		parachain_headers,
+		ancestry_proofs: Default::default(),
 		height: Height::new(client_state.para_id as u64, parachain_header.number as u64),
 	};
 	let client_message = AnyClientMessage::Grandpa(ClientMessage::Header(grandpa_header));
@@ -51,7 +51,7 @@ where
 	let client_b_clone = chain_b.clone();
 	// Start relayer loop
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -253,7 +253,7 @@ pub async fn ibc_messaging_ordered_packet_with_connection_delay<A, B>(
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -290,7 +290,7 @@ pub async fn ibc_messaging_ordered_packet_timeout<A, B>(
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None, None, None)
 			.await
 			.unwrap()
 	});
@@ -0,0 +1,91 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::StreamExt;
+use hyperspace_core::logging;
+use hyperspace_primitives::{mock::MockChain, utils::create_clients, IbcProvider, TestProvider};
+use hyperspace_testsuite::{
+	ibc_channel_close, ibc_messaging_packet_height_timeout_with_connection_delay,
+	ibc_messaging_packet_timeout_on_channel_close,
+	ibc_messaging_packet_timestamp_timeout_with_connection_delay,
+	ibc_messaging_with_connection_delay,
+};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const BLOCK_TIME: Duration = Duration::from_millis(100);
+const DENOM: &str = "stake";
+
+async fn setup_clients() -> (MockChain, MockChain, Vec<JoinHandle<()>>) {
+	log::info!(target: "hyperspace", "=========================== Starting Test ===========================");
+	let mut chain_a = MockChain::new("mock-a").with_balance(DENOM, 1_000_000_000_000);
+	let mut chain_b = MockChain::new("mock-b").with_balance(DENOM, 1_000_000_000_000);
+
+	let handles = vec![
+		chain_a.spawn_block_production(BLOCK_TIME, 0),
+		chain_b.spawn_block_production(BLOCK_TIME, 0),
+	];
+	// Wait for both chains to finalize a block
+	for chain in [&chain_a, &chain_b] {
+		chain.subscribe_blocks().await.take(2).collect::<Vec<_>>().await;
+	}
+
+	// We need to make difference between the chains' counters to ensure that
+	// proper values are used for source/sink client, connection, channel (etc.) ids.
+	chain_a.increase_counters().await.unwrap();
+
+	let (client_a, client_b) = create_clients(&mut chain_a, &mut chain_b).await.unwrap();
+	log::info!(target: "hyperspace", "Client IDs: {client_a}, {client_b}");
+	chain_a.set_client_id(client_a);
+	chain_b.set_client_id(client_b);
+	(chain_a, chain_b, handles)
+}
+
+#[tokio::test]
+async fn mock_to_mock_ibc_messaging_full_integration_test() {
+	logging::setup_logging();
+	let (mut chain_a, mut chain_b, handles) = setup_clients().await;
+
+	// no timeouts + connection delay
+	ibc_messaging_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		DENOM.to_string(),
+		DENOM.to_string(),
+	)
+	.await;
+
+	// timeouts + connection delay
+	ibc_messaging_packet_height_timeout_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		DENOM.to_string(),
+	)
+	.await;
+	ibc_messaging_packet_timestamp_timeout_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		DENOM.to_string(),
+	)
+	.await;
+
+	// channel closing semantics
+	ibc_messaging_packet_timeout_on_channel_close(&mut chain_a, &mut chain_b, DENOM.to_string())
+		.await;
+	ibc_channel_close(&mut chain_a, &mut chain_b).await;
+
+	for handle in handles {
+		handle.abort();
+	}
+}
@@ -75,12 +75,17 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		para_id: args.para_id,
 		parachain_rpc_url: args.chain_a,
 		relay_chain_rpc_url: args.relay_chain.clone(),
+		parachain_rpc_fallback_urls: vec![],
+		relay_chain_rpc_fallback_urls: vec![],
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_a.as_bytes().to_vec().into(),
 		ss58_version: 42,
 		channel_whitelist: vec![],
 		finality_protocol: FinalityProtocol::Grandpa,
+		relay_chain: None,
+		unbonding_period_secs: None,
+		trusting_period_secs: None,
 		private_key: "//Alice".to_string(),
 		key_type: "sr25519".to_string(),
 		wasm_code_id: None,
@@ -91,6 +96,7 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		rpc_url: args.chain_b.clone().parse().unwrap(),
 		grpc_url: args.cosmos_grpc.clone().parse().unwrap(),
 		websocket_url: args.cosmos_ws.clone().parse().unwrap(),
+		fallback_endpoints: vec![],
 		chain_id: "ibcgo-1".to_string(),
 		client_id: None,
 		connection_id: None,
@@ -98,6 +104,7 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		fee_denom: "stake".to_string(),
 		fee_amount: "4000".to_string(),
 		gas_limit: (i64::MAX - 1) as u64,
+		proposal_deposit: None,
 		store_prefix: args.connection_prefix_b,
 		max_tx_size: 200000,
 		mnemonic:
@@ -64,12 +64,17 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		para_id: args.para_id_a,
 		parachain_rpc_url: args.chain_a,
 		relay_chain_rpc_url: args.relay_chain.clone(),
+		parachain_rpc_fallback_urls: vec![],
+		relay_chain_rpc_fallback_urls: vec![],
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_b.as_bytes().to_vec().into(),
 		ss58_version: 42,
 		channel_whitelist: vec![],
 		finality_protocol: FinalityProtocol::Grandpa,
+		relay_chain: None,
+		unbonding_period_secs: None,
+		trusting_period_secs: None,
 		private_key: "//Alice".to_string(),
 		key_type: "sr25519".to_string(),
 		wasm_code_id: None,
@@ -79,6 +84,8 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		para_id: args.para_id_b,
 		parachain_rpc_url: args.chain_b,
 		relay_chain_rpc_url: args.relay_chain,
+		parachain_rpc_fallback_urls: vec![],
+		relay_chain_rpc_fallback_urls: vec![],
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_b.as_bytes().to_vec().into(),
@@ -86,6 +93,9 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		ss58_version: 42,
 		channel_whitelist: vec![],
 		finality_protocol: FinalityProtocol::Grandpa,
+		relay_chain: None,
+		unbonding_period_secs: None,
+		trusting_period_secs: None,
 		key_type: "sr25519".to_string(),
 		wasm_code_id: None,
 	};
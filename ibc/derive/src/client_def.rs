@@ -130,15 +130,17 @@ impl State {
 					)
 					.ok_or_else(|| #error::client_args_type_mismatch(client_type))?;
 
-					let client_state = #trait_::update_state_on_misbehaviour(client, client_state, client_message)?;
+					let client_state = #trait_::update_state_on_misbehaviour(client, ctx, client_id, client_state, client_message)?;
 					Ok(Self::ClientState::#variant_ident(client_state))
 				}
 			}
 		});
 
 		quote! {
-			fn update_state_on_misbehaviour(
+			fn update_state_on_misbehaviour<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
 				&self,
+				ctx: &Ctx,
+				client_id: #crate_::core::ics24_host::identifier::ClientId,
 				client_state: Self::ClientState,
 				client_message: Self::ClientMessage,
 			) -> ::core::result::Result<Self::ClientState, #error> {
@@ -319,12 +321,13 @@ impl State {
 					#trait_::verify_client_consensus_state(
 						client,
 						ctx,
+						client_id,
 						client_state,
 						height,
 						prefix,
 						proof,
 						root,
-						client_id,
+						counterparty_client_id,
 						consensus_height,
 						expected_consensus_state,
 					)
@@ -336,12 +339,13 @@ impl State {
 			fn verify_client_consensus_state<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
 				&self,
 				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
 				client_state: &Self::ClientState,
 				height: #crate_::core::ics02_client::height::Height,
 				prefix: &#crate_::core::ics23_commitment::commitment::CommitmentPrefix,
 				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
 				root: &#crate_::core::ics23_commitment::commitment::CommitmentRoot,
-				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+				counterparty_client_id: &#crate_::core::ics24_host::identifier::ClientId,
 				consensus_height: #crate_::core::ics02_client::height::Height,
 				expected_consensus_state: &Ctx::AnyConsensusState,
 			) -> ::core::result::Result<(), #error> {
@@ -476,12 +480,13 @@ impl State {
 					#trait_::verify_client_full_state(
 						client,
 						ctx,
+						client_id,
 						client_state,
 						height,
 						prefix,
 						proof,
 						root,
-						client_id,
+						counterparty_client_id,
 						client_state_on_counterparty,
 					)
 				}
@@ -492,12 +497,13 @@ impl State {
 			fn verify_client_full_state<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
 				&self,
 				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
 				client_state: &Self::ClientState,
 				height: #crate_::core::ics02_client::height::Height,
 				prefix: &#crate_::core::ics23_commitment::commitment::CommitmentPrefix,
 				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
 				root: &#crate_::core::ics23_commitment::commitment::CommitmentRoot,
-				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+				counterparty_client_id: &#crate_::core::ics24_host::identifier::ClientId,
 				client_state_on_counterparty: &Ctx::AnyClientState,
 			) -> ::core::result::Result<(), #error> {
 				match self {
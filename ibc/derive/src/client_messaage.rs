@@ -38,6 +38,27 @@ impl State {
 		}
 	}
 
+	pub fn impl_fn_maybe_header_height(&self) -> proc_macro2::TokenStream {
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			let trait_ = &self.current_impl_trait;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(message) => #trait_::maybe_header_height(message),
+			}
+		});
+
+		let crate_ = &self.crate_ident;
+		quote! {
+			fn maybe_header_height(&self) -> ::core::option::Option<#crate_::core::ics02_client::height::Height> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
 	pub fn impl_client_message(&mut self) -> proc_macro2::TokenStream {
 		let crate_ = &self.crate_ident;
 		let this = &self.self_ident;
@@ -50,12 +71,14 @@ impl State {
 
 		let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
 
+		let fn_maybe_header_height = self.impl_fn_maybe_header_height();
 		let fn_downcast = self.impl_fn_downcast();
 		let fn_wrap = self.impl_fn_wrap();
 		let fn_encode_to_vec = self.impl_fn_encode_to_vec();
 
 		quote! {
 			impl #impl_generics #trait_ for #this #ty_generics #where_clause {
+				#fn_maybe_header_height
 				#fn_downcast
 				#fn_wrap
 				#fn_encode_to_vec
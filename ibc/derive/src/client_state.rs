@@ -100,6 +100,31 @@ impl State {
 		}
 	}
 
+	fn impl_fn_status(&self) -> proc_macro2::TokenStream {
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			let trait_ = &self.current_impl_trait;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(state) => #trait_::status(state, ctx, client_id),
+			}
+		});
+
+		let crate_ = &self.crate_ident;
+		quote! {
+			fn status<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
+				&self,
+				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+			) -> #crate_::core::ics02_client::client_state::Status {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
 	fn impl_fn_frozen_height(&self) -> proc_macro2::TokenStream {
 		let cases = self.clients.iter().map(|client| {
 			let variant_ident = &client.variant_ident;
@@ -121,6 +146,31 @@ impl State {
 		}
 	}
 
+	fn impl_fn_with_frozen_height(&self) -> proc_macro2::TokenStream {
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			let trait_ = &self.current_impl_trait;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(state) => #trait_::with_frozen_height(state, height).map(Self::#variant_ident),
+			}
+		});
+
+		let crate_ = &self.crate_ident;
+		let error = &self.current_impl_error;
+		quote! {
+			fn with_frozen_height(
+				self,
+				height: #crate_::core::ics02_client::height::Height,
+			) -> ::core::result::Result<Self, #error> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
 	fn impl_fn_upgrade(&self) -> proc_macro2::TokenStream {
 		let crate_ = &self.crate_ident;
 		let cases = self.clients.iter().map(|client| {
@@ -191,7 +241,9 @@ impl State {
 		let fn_client_type = self.impl_fn_client_type();
 		let fn_client_def = self.impl_fn_client_def();
 		let fn_latest_height = self.impl_fn_latest_height();
+		let fn_status = self.impl_fn_status();
 		let fn_frozen_height = self.impl_fn_frozen_height();
+		let fn_with_frozen_height = self.impl_fn_with_frozen_height();
 		let fn_upgrade = self.impl_fn_upgrade();
 		let fn_expired = self.impl_fn_expired();
 		let fn_downcast = self.impl_fn_downcast();
@@ -208,7 +260,9 @@ impl State {
 				#fn_client_type
 				#fn_client_def
 				#fn_latest_height
+				#fn_status
 				#fn_frozen_height
+				#fn_with_frozen_height
 				#fn_upgrade
 				#fn_expired
 				#fn_downcast
@@ -41,7 +41,11 @@ struct ClientData {
 	pub inner_ty_path: TypePath,
 	pub client_state_path: TypePath,
 	pub attrs: Vec<syn::Attribute>,
-	pub proto_ty_url: Option<Ident>,
+	/// Type urls the variant is decoded from. The first one is used to encode the variant,
+	/// unless there are several, in which case the inner type picks the url itself.
+	pub proto_ty_urls: Vec<Ident>,
+	/// Type url the variant is always encoded with, overriding the urls above.
+	pub proto_encode_url: Option<Ident>,
 	pub proto_decode_error: Option<Ident>,
 }
 
@@ -50,7 +54,8 @@ impl ClientData {
 		variant_ident: Ident,
 		inner_ty_path: TypePath,
 		attrs: Vec<syn::Attribute>,
-		proto_ty_url: Option<Ident>,
+		proto_ty_urls: Vec<Ident>,
+		proto_encode_url: Option<Ident>,
 		proto_decode_error: Option<Ident>,
 	) -> Self {
 		let client_state_path =
@@ -60,7 +65,8 @@ impl ClientData {
 			inner_ty_path,
 			client_state_path,
 			attrs,
-			proto_ty_url,
+			proto_ty_urls,
+			proto_encode_url,
 			proto_decode_error,
 		}
 	}
@@ -126,7 +132,8 @@ fn client_data_with_proto_attrs(variant: &syn::Variant) -> ClientData {
 		Type::Path(p) => p.clone(),
 		_ => panic!("Only path types are supported"),
 	};
-	let mut proto_url = None;
+	let mut proto_urls = Vec::new();
+	let mut proto_encode_url = None;
 	let mut proto_decode_error = None;
 	let attrs = variant
 		.attrs
@@ -141,11 +148,13 @@ fn client_data_with_proto_attrs(variant: &syn::Variant) -> ClientData {
 							let ident = &nv.path.segments.first().unwrap().ident;
 							if let syn::Lit::Str(lit) = nv.lit {
 								if ident == "proto_url" {
+									proto_urls.push(Ident::new(&lit.value(), lit.span()));
+								} else if ident == "proto_encode_url" {
 									assert!(
-										proto_url.is_none(),
-										"Only one proto type url is allowed"
+										proto_encode_url.is_none(),
+										"Only one proto encode type url is allowed"
 									);
-									proto_url = Some(Ident::new(&lit.value(), lit.span()));
+									proto_encode_url = Some(Ident::new(&lit.value(), lit.span()));
 								} else if ident == "proto_decode_err" {
 									assert!(
 										proto_decode_error.is_none(),
@@ -163,7 +172,14 @@ fn client_data_with_proto_attrs(variant: &syn::Variant) -> ClientData {
 		.cloned()
 		.collect();
 
-	ClientData::new(variant.ident.clone(), client_def_path, attrs, proto_url, proto_decode_error)
+	ClientData::new(
+		variant.ident.clone(),
+		client_def_path,
+		attrs,
+		proto_urls,
+		proto_encode_url,
+		proto_decode_error,
+	)
 }
 
 impl State {
@@ -23,9 +23,12 @@ impl State {
 		let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
 		let crate_ = &self.crate_ident;
 		let error = quote!(#crate_::core::ics02_client::error::Error);
+		let ibc_proto =
+			generate_crate_access_2018("ibc-proto").expect("'ibc-proto' crate not found");
+		let proto_any = quote! { #ibc_proto::google::protobuf::Any };
 
 		let cases = self.clients.iter().filter_map(|client| {
-			let type_url = client.proto_ty_url.as_ref()?;
+			let (type_url, other_type_urls) = client.proto_ty_urls.split_first()?;
 			let decode_err = client.proto_decode_error.clone().unwrap_or_else(|| {
 				let string_without_any = &this.to_string()[3..];
 				syn::parse_str(&format!("decode_raw_{}", string_without_any.to_case(Case::Snake)))
@@ -34,17 +37,24 @@ impl State {
 			let variant_ident = &client.variant_ident;
 			let attrs = &client.attrs;
 			let inner_ty = &client.inner_ty_path;
+			let other_cases = (!other_type_urls.is_empty()).then(|| {
+				quote! {
+					#(#attrs)*
+					#(#other_type_urls)|* => Ok(Self::#variant_ident(
+						<#inner_ty as ::core::convert::TryFrom<#proto_any>>::try_from(value)
+							.map_err(#error::from)?,
+					)),
+				}
+			});
 			Some(quote! {
 				#(#attrs)*
 				#type_url => Ok(Self::#variant_ident(
 					<#inner_ty>::decode_vec(&value.value)
 						.map_err(#error::#decode_err)?,
 				)),
+				#other_cases
 			})
 		});
-		let ibc_proto =
-			generate_crate_access_2018("ibc-proto").expect("'ibc-proto' crate not found");
-		let proto_any = quote! { #ibc_proto::google::protobuf::Any };
 
 		// TODO: fix up error variants used in decoding
 		quote! {
@@ -74,7 +84,13 @@ impl State {
 		let cases = self.clients.iter().filter_map(|client| {
 			let variant_ident = &client.variant_ident;
 			let attrs = &client.attrs;
-			let type_url = client.proto_ty_url.as_ref()?;
+			let type_url = client.proto_encode_url.as_ref().or(client.proto_ty_urls.first())?;
+			if client.proto_encode_url.is_none() && client.proto_ty_urls.len() > 1 {
+				return Some(quote! {
+					#(#attrs)*
+					#this ::<#gen_params> ::#variant_ident(value) => #proto_any::from(value),
+				})
+			}
 			Some(quote! {
 				#(#attrs)*
 				#this ::<#gen_params> ::#variant_ident(value) => #proto_any {
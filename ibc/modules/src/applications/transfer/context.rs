@@ -72,11 +72,11 @@ where
 			.map_err(|_| Ics20Error::parse_account_failure())
 	}
 
-	/// Returns true iff send is enabled.
-	fn is_send_enabled(&self) -> bool;
+	/// Returns true iff send is enabled on the given channel.
+	fn is_send_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool;
 
-	/// Returns true iff receive is enabled.
-	fn is_receive_enabled(&self) -> bool;
+	/// Returns true iff receive is enabled on the given channel.
+	fn is_receive_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool;
 
 	/// Returns a hash of the prefixed denom.
 	/// Implement only if the host chain supports hashed denominations.
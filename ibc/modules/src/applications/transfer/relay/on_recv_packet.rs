@@ -27,7 +27,7 @@ pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 	packet: &Packet,
 	data: PacketData,
 ) -> Result<(), Ics20Error> {
-	if !ctx.is_receive_enabled() {
+	if !ctx.is_receive_enabled(&packet.destination_port, &packet.destination_channel) {
 		return Err(Ics20Error::receive_disabled())
 	}
 
@@ -34,7 +34,7 @@ where
 	Ctx: Ics20Context,
 	C: TryInto<PrefixedCoin>,
 {
-	if !ctx.is_send_enabled() {
+	if !ctx.is_send_enabled(&msg.source_port, &msg.source_channel) {
 		return Err(Error::send_disabled())
 	}
 
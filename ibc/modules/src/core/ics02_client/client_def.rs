@@ -72,8 +72,10 @@ pub trait ClientDef: Clone {
 		client_msg: Self::ClientMessage,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error>;
 
-	fn update_state_on_misbehaviour(
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
 		&self,
+		ctx: &Ctx,
+		client_id: ClientId,
 		client_state: Self::ClientState,
 		client_msg: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error>;
@@ -112,20 +114,22 @@ pub trait ClientDef: Clone {
 	/// Verification functions as specified in:
 	/// <https://github.com/cosmos/ibc/tree/master/spec/core/ics-002-client-semantics>
 	///
-	/// Verify a `proof` that the consensus state of a given client (at height `consensus_height`)
-	/// matches the input `consensus_state`. The parameter `counterparty_height` represent the
-	/// height of the counterparty chain that this proof assumes (i.e., the height at which this
-	/// proof was computed).
+	/// Verify a `proof` that the consensus state of the counterparty's client
+	/// `counterparty_client_id` (at height `consensus_height`) matches the input
+	/// `consensus_state`. The parameter `counterparty_height` represent the height of the
+	/// counterparty chain that this proof assumes (i.e., the height at which this proof was
+	/// computed).
 	#[allow(clippy::too_many_arguments)]
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		ctx: &Ctx,
+		client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
 		root: &CommitmentRoot,
-		client_id: &ClientId,
+		counterparty_client_id: &ClientId,
 		consensus_height: Height,
 		expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error>;
@@ -161,17 +165,19 @@ pub trait ClientDef: Clone {
 		expected_channel_end: &ChannelEnd,
 	) -> Result<(), Error>;
 
-	/// Verify the client state for this chain that it is stored on the counterparty chain.
+	/// Verify the client state for this chain that it is stored on the counterparty chain under
+	/// `counterparty_client_id`.
 	#[allow(clippy::too_many_arguments)]
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		ctx: &Ctx,
+		client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
 		root: &CommitmentRoot,
-		client_id: &ClientId,
+		counterparty_client_id: &ClientId,
 		expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Error>;
 
@@ -12,10 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::Height;
 use alloc::vec::Vec;
 
 /// Abstract of consensus state update information
 pub trait ClientMessage: Clone + core::fmt::Debug + Send + Sync {
+	/// Height of the header, if the message is a header.
+	fn maybe_header_height(&self) -> Option<Height> {
+		None
+	}
+
 	fn downcast<T: Clone + 'static>(self) -> Option<T>
 	where
 		Self: 'static,
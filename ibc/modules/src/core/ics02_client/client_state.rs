@@ -14,7 +14,7 @@
 
 use crate::{
 	core::{
-		ics02_client::client_def::ClientDef,
+		ics02_client::{client_def::ClientDef, error::Error},
 		ics24_host::identifier::{ChainId, ClientId},
 		ics26_routing::context::ReaderContext,
 	},
@@ -74,6 +74,15 @@ pub trait ClientState: Clone + Debug + Send + Sync {
 	/// Frozen height of the client
 	fn frozen_height(&self) -> Option<Height>;
 
+	/// Returns the client frozen at the given height, e.g. by governance. Clients that can't be
+	/// frozen return an error.
+	fn with_frozen_height(self, _height: Height) -> Result<Self, Error> {
+		Err(Error::implementation_specific(format!(
+			"{} clients can't be frozen",
+			self.client_type()
+		)))
+	}
+
 	/// Helper function to verify the upgrade client procedure.
 	/// Resets all fields except the blockchain-specific ones,
 	/// and updates the given fields.
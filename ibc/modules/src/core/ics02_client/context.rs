@@ -31,7 +31,7 @@ use crate::{
 	timestamp::Timestamp,
 	Height,
 };
-use alloc::{string::String, vec::Vec};
+use alloc::{format, string::String, vec::Vec};
 use core::fmt::Debug;
 
 /// Defines the read-only part of ICS2 (client functions) context.
@@ -102,6 +102,48 @@ pub trait ClientReader: ClientKeeper {
 	/// The value of this counter should increase only via method
 	/// `ClientKeeper::increase_client_counter`.
 	fn client_counter(&self) -> Result<u64, Error>;
+
+	/// Runs the light client contract with the given code id for the `08-wasm` client
+	/// `client_id`, passing it the JSON encoded `ExecuteMsg` `msg`, and returns the data of the
+	/// contract's response. Hosts that don't run light client contracts return an error.
+	fn execute_wasm_contract(
+		&self,
+		client_id: &ClientId,
+		_code_id: &[u8],
+		_msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		Err(Error::implementation_specific(format!(
+			"light client contracts can't run on this host, {client_id} can't be verified"
+		)))
+	}
+
+	/// Like [`Self::execute_wasm_contract`], for recovering the `08-wasm` client
+	/// `subject_client_id` with `substitute_client_id`: the contract sees the storage of the
+	/// subject under `subject/` and the storage of the substitute under `substitute/`.
+	fn execute_wasm_substitute_contract(
+		&self,
+		subject_client_id: &ClientId,
+		_substitute_client_id: &ClientId,
+		_code_id: &[u8],
+		_msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		Err(Error::implementation_specific(format!(
+			"light client contracts can't run on this host, {subject_client_id} can't be recovered"
+		)))
+	}
+
+	/// Runs the `query` entry point of the light client contract of the `08-wasm` client
+	/// `client_id` with the JSON encoded `QueryMsg` `msg`, and returns the contract's response.
+	fn query_wasm_contract(
+		&self,
+		client_id: &ClientId,
+		_code_id: &[u8],
+		_msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		Err(Error::implementation_specific(format!(
+			"light client contracts can't run on this host, {client_id} can't be queried"
+		)))
+	}
 }
 
 pub trait ClientTypes {
@@ -119,7 +119,12 @@ where
 	};
 
 	if found_misbehaviour {
-		let client_state = client_def.update_state_on_misbehaviour(client_state, client_message)?;
+		let client_state = client_def.update_state_on_misbehaviour(
+			ctx,
+			client_id.clone(),
+			client_state,
+			client_message,
+		)?;
 		let result = ClientResult::Update(Result {
 			client_id,
 			client_state,
@@ -111,6 +111,7 @@ pub fn verify_client_proof<Ctx: ReaderContext>(
 	client_def
 		.verify_client_full_state(
 			ctx,
+			client_id,
 			&client_state,
 			height,
 			connection_end.counterparty().prefix(),
@@ -157,6 +158,7 @@ pub fn verify_consensus_proof<Ctx: ReaderContext>(
 	client
 		.verify_client_consensus_state(
 			ctx,
+			client_id,
 			&client_state,
 			height,
 			connection_end.counterparty().prefix(),
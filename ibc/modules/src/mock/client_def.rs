@@ -91,17 +91,18 @@ impl ClientDef for MockClient {
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		_client_state: &Self::ClientState,
 		_height: Height,
 		prefix: &CommitmentPrefix,
 		_proof: &CommitmentProofBytes,
 		_root: &CommitmentRoot,
-		client_id: &ClientId,
+		counterparty_client_id: &ClientId,
 		consensus_height: Height,
 		_expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error> {
 		let client_prefixed_path = Path::ClientConsensusState(ClientConsensusStatePath {
-			client_id: client_id.clone(),
+			client_id: counterparty_client_id.clone(),
 			epoch: consensus_height.revision_number,
 			height: consensus_height.revision_height,
 		})
@@ -146,12 +147,13 @@ impl ClientDef for MockClient {
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		_client_state: &Self::ClientState,
 		_height: Height,
 		_prefix: &CommitmentPrefix,
 		_proof: &CommitmentProofBytes,
 		_root: &CommitmentRoot,
-		_client_id: &ClientId,
+		_counterparty_client_id: &ClientId,
 		_expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Error> {
 		Ok(())
@@ -251,8 +253,10 @@ impl ClientDef for MockClient {
 		Ok(())
 	}
 
-	fn update_state_on_misbehaviour(
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
 		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
 		client_state: Self::ClientState,
 		_client_msg: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
@@ -144,6 +144,10 @@ impl ClientState for MockClientState {
 		self.frozen_height
 	}
 
+	fn with_frozen_height(self, height: Height) -> Result<Self, Error> {
+		Ok(Self { frozen_height: Some(height), ..self })
+	}
+
 	fn upgrade(self, _upgrade_height: Height, _upgrade_options: (), _chain_id: ChainId) -> Self {
 		self.upgrade(_upgrade_height, _upgrade_options, _chain_id)
 	}
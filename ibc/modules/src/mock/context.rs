@@ -138,7 +138,9 @@ where
 }
 
 /// A manual clone impl is provided because the tests are oblivious to the fact that the `ibc_store`
-/// is a shared ptr.
+/// is a shared ptr. The router is not cloned: the routing handlers clone the context to hand a
+/// read-only view to module callbacks, and `MockRouter::get_route_mut` needs the modules to stay
+/// uniquely owned by the original context. Clones therefore have no routes.
 impl<C: HostBlockType> Clone for MockContext<C> {
 	fn clone(&self) -> Self {
 		let ibc_store = {
@@ -152,7 +154,7 @@ impl<C: HostBlockType> Clone for MockContext<C> {
 			history: self.history.clone(),
 			block_time: self.block_time,
 			ibc_store,
-			router: self.router.clone(),
+			router: Default::default(),
 			_phantom: Default::default(),
 		}
 	}
@@ -1408,6 +1410,13 @@ mod tests {
 			.map(|(.., result)| result)
 			.collect::<Result<Vec<_>, _>>()
 			.unwrap();
+
+		// clones don't share the modules, which the original can still borrow mutably
+		let module_id = ModuleId::from_str("foomodule").unwrap();
+		let cloned_ctx = ctx.clone();
+		assert!(!cloned_ctx.router.has_route(&module_id));
+		assert!(ctx.router.has_route(&module_id));
+		assert!(ctx.router.get_route_mut(&module_id).is_some());
 	}
 }
 
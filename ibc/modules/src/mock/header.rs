@@ -73,8 +73,33 @@ impl MockClientMessage {
 }
 
 impl ClientMessage for MockClientMessage {
+	fn maybe_header_height(&self) -> Option<Height> {
+		match self {
+			MockClientMessage::Header(header) => Some(header.height),
+			MockClientMessage::Misbehaviour(_) => None,
+		}
+	}
+
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		unreachable!()
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<Any> for MockClientMessage {}
+
+impl TryFrom<Any> for MockClientMessage {
+	type Error = Error;
+
+	fn try_from(value: Any) -> Result<Self, Self::Error> {
+		match AnyClientMessage::try_from(value)? {
+			AnyClientMessage::Mock(client_message) => Ok(client_message),
+		}
+	}
+}
+
+impl From<MockClientMessage> for Any {
+	fn from(client_message: MockClientMessage) -> Self {
+		AnyClientMessage::Mock(client_message).into()
 	}
 }
 
@@ -419,11 +419,11 @@ impl<C: HostBlockType> Ics20Reader for DummyTransferModule<C> {
 		Ok(PortId::transfer())
 	}
 
-	fn is_send_enabled(&self) -> bool {
+	fn is_send_enabled(&self, _port_id: &PortId, _channel_id: &ChannelId) -> bool {
 		true
 	}
 
-	fn is_receive_enabled(&self) -> bool {
+	fn is_receive_enabled(&self, _port_id: &PortId, _channel_id: &ChannelId) -> bool {
 		true
 	}
 }
@@ -96,13 +96,20 @@ impl CompileCmd {
 	) {
 		println!("[info ] Compiling IBC .proto files to Rust into '{}'...", out_dir.display());
 
+		let root = env!("CARGO_MANIFEST_DIR");
+		// Definitions in this repository replace the ibc-go ones at the same path, for messages
+		// the ibc-go fork doesn't define yet
+		let definitions = PathBuf::from(format!("{}/../proto/definitions", root));
+
 		// Paths
 		let proto_paths = [
 			// ibc-go proto files
 			format!("{}/proto/ibc", ibc_dir.display()),
+			format!("{}/ibc", definitions.display()),
 		];
 
 		let proto_includes_paths = [
+			format!("{}", definitions.display()),
 			format!("{}", gogo.display()),
 			format!("{}", google.display()),
 			format!("{}/proto", cosmos_proto.display()),
@@ -129,6 +136,12 @@ impl CompileCmd {
 					.collect(),
 			);
 		}
+		let ibc_protos = ibc_dir.join("proto");
+		protos.retain(|proto| {
+			proto
+				.strip_prefix(&ibc_protos)
+				.map_or(true, |relative| !definitions.join(relative).exists())
+		});
 
 		println!("Found the following protos:");
 		// Show which protos will be compiled
@@ -14,6 +14,23 @@ pub struct MsgPushNewWasmCodeResponse {
 	#[prost(bytes = "vec", tag = "1")]
 	pub code_id: ::prost::alloc::vec::Vec<u8>,
 }
+/// Message type to migrate a wasm client to new code
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgMigrateContract {
+	#[prost(string, tag = "1")]
+	pub signer: ::prost::alloc::string::String,
+	#[prost(string, tag = "2")]
+	pub client_id: ::prost::alloc::string::String,
+	#[prost(bytes = "vec", tag = "3")]
+	pub checksum: ::prost::alloc::vec::Vec<u8>,
+	#[prost(bytes = "vec", tag = "4")]
+	pub msg: ::prost::alloc::vec::Vec<u8>,
+}
+/// Response in case of successful handling
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgMigrateContractResponse {}
 /// Generated client implementations.
 #[cfg(feature = "client")]
 pub mod msg_client {
@@ -97,6 +114,23 @@ pub mod msg_client {
 			);
 			self.inner.unary(request.into_request(), path, codec).await
 		}
+		/// MigrateContract defines a rpc handler method for MigrateContract.
+		pub async fn migrate_contract(
+			&mut self,
+			request: impl tonic::IntoRequest<super::MsgMigrateContract>,
+		) -> Result<tonic::Response<super::MsgMigrateContractResponse>, tonic::Status> {
+			self.inner.ready().await.map_err(|e| {
+				tonic::Status::new(
+					tonic::Code::Unknown,
+					format!("Service was not ready: {}", e.into()),
+				)
+			})?;
+			let codec = tonic::codec::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static(
+				"/ibc.lightclients.wasm.v1.Msg/MigrateContract",
+			);
+			self.inner.unary(request.into_request(), path, codec).await
+		}
 	}
 }
 /// Generated server implementations.
@@ -112,6 +146,11 @@ pub mod msg_server {
 			&self,
 			request: tonic::Request<super::MsgPushNewWasmCode>,
 		) -> Result<tonic::Response<super::MsgPushNewWasmCodeResponse>, tonic::Status>;
+		/// MigrateContract defines a rpc handler method for MigrateContract.
+		async fn migrate_contract(
+			&self,
+			request: tonic::Request<super::MsgMigrateContract>,
+		) -> Result<tonic::Response<super::MsgMigrateContractResponse>, tonic::Status>;
 	}
 	/// Msg defines the ibc/wasm Msg service.
 	#[derive(Debug)]
@@ -198,6 +237,37 @@ pub mod msg_server {
 					};
 					Box::pin(fut)
 				},
+				"/ibc.lightclients.wasm.v1.Msg/MigrateContract" => {
+					#[allow(non_camel_case_types)]
+					struct MigrateContractSvc<T: Msg>(pub Arc<T>);
+					impl<T: Msg> tonic::server::UnaryService<super::MsgMigrateContract> for MigrateContractSvc<T> {
+						type Response = super::MsgMigrateContractResponse;
+						type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+						fn call(
+							&mut self,
+							request: tonic::Request<super::MsgMigrateContract>,
+						) -> Self::Future {
+							let inner = self.0.clone();
+							let fut = async move { (*inner).migrate_contract(request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let inner = inner.0;
+						let method = MigrateContractSvc(inner);
+						let codec = tonic::codec::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+							accept_compression_encodings,
+							send_compression_encodings,
+						);
+						let res = grpc.unary(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				},
 				_ => Box::pin(async move {
 					Ok(http::Response::builder()
 						.status(200)
@@ -21,7 +21,11 @@
 extern crate alloc;
 extern crate core;
 
-use alloc::{string::ToString, vec, vec::Vec};
+use alloc::{
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
 use anyhow::anyhow;
 use codec::Compact;
 use core::{
@@ -116,22 +120,27 @@ where
 	Ok(())
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
-pub enum RelayChain {
-	Polkadot = 0,
-	Kusama = 1,
-	Rococo = 2,
+/// The relay chain tracked by a light client, together with the periods the client uses. The
+/// periods of the well-known relay chains are used unless they're configured.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RelayChain {
+	/// Name of the relay chain, e.g. `Polkadot`
+	pub name: String,
+	/// Period after which the stake of validators is released
+	pub unbonding_period: Duration,
+	/// Period within which the client must be updated, must be less than the unbonding period
+	pub trusting_period: Duration,
 }
 
 impl Default for RelayChain {
 	fn default() -> Self {
-		RelayChain::Rococo
+		RelayChain::rococo()
 	}
 }
 
 impl Display for RelayChain {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		write!(f, "{}", self.as_str())
+		write!(f, "{}", self.name)
 	}
 }
 
@@ -141,48 +150,143 @@ const KUSAMA_UNBONDING_PERIOD: u64 = 7;
 // number of seconds in a day
 const DAY: u64 = 24 * 60 * 60;
 
+// Ids of the well-known relay chains in the legacy `RelayChain` protobuf enum
+const POLKADOT_ID: i32 = 0;
+const KUSAMA_ID: i32 = 1;
+const ROCOCO_ID: i32 = 2;
+const CUSTOM_RELAY_CHAIN_ID: i32 = 3;
+
 impl RelayChain {
-	/// Yields the Order as a string
-	pub fn as_str(&self) -> &'static str {
-		match self {
-			Self::Polkadot => "Polkadot",
-			Self::Kusama => "Kusama",
-			Self::Rococo => "Rococo",
-		}
+	/// Creates a relay chain with the given periods.
+	pub fn new(
+		name: impl Into<String>,
+		unbonding_period: Duration,
+		trusting_period: Duration,
+	) -> Result<Self, anyhow::Error> {
+		let relay_chain = Self { name: name.into(), unbonding_period, trusting_period };
+		relay_chain.validate()?;
+		Ok(relay_chain)
+	}
+
+	fn well_known(name: &str, days: u64) -> Self {
+		let unbonding_period = Duration::from_secs(days * DAY);
+		// Trusting period is 1/3 of unbonding period
+		let trusting_period = unbonding_period.checked_div(3).unwrap();
+		Self { name: name.to_string(), unbonding_period, trusting_period }
+	}
+
+	pub fn polkadot() -> Self {
+		Self::well_known("Polkadot", POLKADOT_UNBONDING_PERIOD)
+	}
+
+	pub fn kusama() -> Self {
+		Self::well_known("Kusama", KUSAMA_UNBONDING_PERIOD)
+	}
+
+	pub fn rococo() -> Self {
+		Self::well_known("Rococo", KUSAMA_UNBONDING_PERIOD)
+	}
+
+	/// Yields the name of the relay chain
+	pub fn as_str(&self) -> &str {
+		&self.name
+	}
+
+	/// Returns true if both relay chains have the same name, regardless of their periods.
+	pub fn is_same_chain(&self, other: &Self) -> bool {
+		self.name.eq_ignore_ascii_case(&other.name)
 	}
 
-	// Parses the Order out from a i32.
+	// Parses a well-known relay chain out from its id in the legacy protobuf enum.
 	pub fn from_i32(nr: i32) -> Result<Self, anyhow::Error> {
 		match nr {
-			0 => Ok(Self::Polkadot),
-			1 => Ok(Self::Kusama),
-			2 => Ok(Self::Rococo),
+			POLKADOT_ID => Ok(Self::polkadot()),
+			KUSAMA_ID => Ok(Self::kusama()),
+			ROCOCO_ID => Ok(Self::rococo()),
 			id => Err(anyhow!("Unknown relay chain {id}")),
 		}
 	}
 
-	pub fn unbonding_period(&self) -> Duration {
-		match self {
-			Self::Polkadot => Duration::from_secs(POLKADOT_UNBONDING_PERIOD * DAY),
-			Self::Kusama | Self::Rococo => Duration::from_secs(KUSAMA_UNBONDING_PERIOD * DAY),
+	/// Id of the relay chain in the legacy protobuf enum, which only knows about the well-known
+	/// relay chains.
+	pub fn to_i32(&self) -> i32 {
+		match self.name.to_lowercase().as_str() {
+			"polkadot" => POLKADOT_ID,
+			"kusama" => KUSAMA_ID,
+			"rococo" => ROCOCO_ID,
+			_ => CUSTOM_RELAY_CHAIN_ID,
+		}
+	}
+
+	/// Creates a relay chain, falling back to the periods of the well-known relay chain with the
+	/// same name. The trusting period defaults to a third of the unbonding period.
+	pub fn with_periods(
+		name: &str,
+		unbonding_period: Option<Duration>,
+		trusting_period: Option<Duration>,
+	) -> Result<Self, anyhow::Error> {
+		let known = Self::from_str(name).ok();
+		let unbonding_period = unbonding_period
+			.or_else(|| known.as_ref().map(|known| known.unbonding_period))
+			.ok_or_else(|| anyhow!("Missing unbonding period for relay chain {name}"))?;
+		let trusting_period =
+			trusting_period.unwrap_or_else(|| unbonding_period.checked_div(3).unwrap());
+		let name = known.map_or_else(|| name.to_string(), |known| known.name);
+		Self::new(name, unbonding_period, trusting_period)
+	}
+
+	/// Reads the relay chain of a client state from its name and periods in seconds. Client
+	/// states encoded before relay chains were configurable only have the legacy enum id, so they
+	/// get the well-known relay chain's name and periods.
+	pub fn from_proto(id: i32, params: Option<(String, u64, u64)>) -> Result<Self, anyhow::Error> {
+		match params {
+			Some((name, unbonding_period, trusting_period)) => Self::new(
+				name,
+				Duration::from_secs(unbonding_period),
+				Duration::from_secs(trusting_period),
+			),
+			None => Self::from_i32(id),
 		}
 	}
 
+	/// Checks that the relay chain has a name and that its trusting period is non-zero and
+	/// shorter than its unbonding period.
+	pub fn validate(&self) -> Result<(), anyhow::Error> {
+		if self.name.is_empty() {
+			Err(anyhow!("Relay chain name can't be empty"))?
+		}
+		if self.trusting_period.is_zero() {
+			Err(anyhow!("Trusting period of {} can't be zero", self.name))?
+		}
+		if self.trusting_period >= self.unbonding_period {
+			Err(anyhow!(
+				"Trusting period {:?} of {} should be less than its unbonding period {:?}",
+				self.trusting_period,
+				self.name,
+				self.unbonding_period
+			))?
+		}
+		Ok(())
+	}
+
+	pub fn unbonding_period(&self) -> Duration {
+		self.unbonding_period
+	}
+
 	pub fn trusting_period(&self) -> Duration {
-		let unbonding_period = self.unbonding_period();
-		// Trusting period is 1/3 of unbonding period
-		unbonding_period.checked_div(3).unwrap()
+		self.trusting_period
 	}
 }
 
 impl FromStr for RelayChain {
 	type Err = anyhow::Error;
 
+	/// Parses one of the well-known relay chains.
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		match s.to_lowercase().trim_start_matches("order_") {
-			"polkadot" => Ok(Self::Polkadot),
-			"kusama" => Ok(Self::Kusama),
-			"rococo" => Ok(Self::Rococo),
+			"polkadot" => Ok(Self::polkadot()),
+			"kusama" => Ok(Self::kusama()),
+			"rococo" => Ok(Self::rococo()),
 			_ => Err(anyhow!("Unknown relay chain {s}")),
 		}
 	}
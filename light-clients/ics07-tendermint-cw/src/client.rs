@@ -297,10 +297,10 @@ impl<'a, H: Clone> Context<'a, H> {
 	}
 
 	pub fn encode_consensus_state(consensus_state: ConsensusState) -> Vec<u8> {
-		let wasm_consensus_state = ics08_wasm::consensus_state::ConsensusState {
+		let wasm_consensus_state = ics08_wasm::consensus_state::ConsensusState::<FakeInner> {
 			data: consensus_state.to_any().encode_to_vec(),
 			timestamp: consensus_state.timestamp().nanoseconds(),
-			inner: Box::new(FakeInner),
+			inner: None,
 		};
 		wasm_consensus_state.to_any().encode_to_vec()
 	}
@@ -239,7 +239,12 @@ fn process_message(
 				.map_err(|e| ContractError::Tendermint(e.to_string()))?;
 			let msg = UpdateStateOnMisbehaviourMsg::try_from(msg_raw)?;
 			client
-				.update_state_on_misbehaviour(client_state, msg.client_message)
+				.update_state_on_misbehaviour(
+					ctx,
+					client_id.clone(),
+					client_state,
+					msg.client_message,
+				)
 				.map_err(|e| ContractError::Tendermint(e.to_string()))
 				.and_then(|cs| {
 					ctx.store_client_state(client_id, cs)
@@ -222,8 +222,10 @@ impl ClientDef for FakeInner {
 		unimplemented!()
 	}
 
-	fn update_state_on_misbehaviour(
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
 		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
 		_client_state: Self::ClientState,
 		_client_msg: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
@@ -267,12 +269,13 @@ impl ClientDef for FakeInner {
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		_client_state: &Self::ClientState,
 		_height: Height,
 		_prefix: &CommitmentPrefix,
 		_proof: &CommitmentProofBytes,
 		_root: &CommitmentRoot,
-		_client_id: &ClientId,
+		_counterparty_client_id: &ClientId,
 		_consensus_height: Height,
 		_expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error> {
@@ -313,12 +316,13 @@ impl ClientDef for FakeInner {
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		_client_state: &Self::ClientState,
 		_height: Height,
 		_prefix: &CommitmentPrefix,
 		_proof: &CommitmentProofBytes,
 		_root: &CommitmentRoot,
-		_client_id: &ClientId,
+		_counterparty_client_id: &ClientId,
 		_expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Error> {
 		unimplemented!()
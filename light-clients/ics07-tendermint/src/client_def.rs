@@ -223,8 +223,10 @@ where
 		Ok((client_state.with_header(header), ConsensusUpdateResult::Single(cs)))
 	}
 
-	fn update_state_on_misbehaviour(
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
 		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
 		client_state: Self::ClientState,
 		client_message: Self::ClientMessage,
 	) -> Result<Self::ClientState, Ics02Error> {
@@ -350,19 +352,20 @@ where
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
 		root: &CommitmentRoot,
-		client_id: &ClientId,
+		counterparty_client_id: &ClientId,
 		consensus_height: Height,
 		expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Ics02Error> {
 		client_state.verify_height(height)?;
 
 		let path = ClientConsensusStatePath {
-			client_id: client_id.clone(),
+			client_id: counterparty_client_id.clone(),
 			epoch: consensus_height.revision_number,
 			height: consensus_height.revision_height,
 		};
@@ -412,17 +415,18 @@ where
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
 		root: &CommitmentRoot,
-		client_id: &ClientId,
+		counterparty_client_id: &ClientId,
 		expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Ics02Error> {
 		client_state.verify_height(height)?;
 
-		let path = ClientStatePath(client_id.clone());
+		let path = ClientStatePath(counterparty_client_id.clone());
 		let value = expected_client_state.encode_to_vec().map_err(Ics02Error::encode)?;
 		verify_membership::<H, _>(client_state, prefix, proof, root, path, value)
 	}
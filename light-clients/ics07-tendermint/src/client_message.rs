@@ -55,6 +55,13 @@ pub enum ClientMessage {
 }
 
 impl ics02_client::client_message::ClientMessage for ClientMessage {
+	fn maybe_header_height(&self) -> Option<Height> {
+		match self {
+			ClientMessage::Header(header) => Some(header.height()),
+			ClientMessage::Misbehaviour(_) => None,
+		}
+	}
+
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
 		self.encode_vec()
 	}
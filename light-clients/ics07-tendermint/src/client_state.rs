@@ -282,6 +282,10 @@ where
 		self.frozen_height()
 	}
 
+	fn with_frozen_height(self, height: Height) -> Result<Self, Ics02Error> {
+		self.with_frozen_height(height).map_err(Into::into)
+	}
+
 	fn upgrade(
 		self,
 		upgrade_height: Height,
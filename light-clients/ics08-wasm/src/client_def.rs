@@ -14,39 +14,166 @@
 // limitations under the License.
 
 use crate::{
-	client_message::ClientMessage, client_state::ClientState, consensus_state::ConsensusState,
+	client_message::ClientMessage,
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+	contract::{
+		CheckSubstituteAndUpdateStateMsg, ClientMessageMsg, ClientStateRaw, ConsensusStateRaw,
+		ContractResult, ExecuteMsg, MerklePath, QueryMsg, StatusMsg, StatusResponse,
+		VerifyMembershipMsg, VerifyNonMembershipMsg, VerifyUpgradeAndUpdateStateMsg,
+	},
+};
+use alloc::{
+	string::{String, ToString},
+	vec::Vec,
 };
-use alloc::{boxed::Box, vec::Vec};
 use core::{
 	fmt::{Debug, Display},
 	marker::PhantomData,
+	time::Duration,
 };
 use ibc::{
 	core::{
 		ics02_client::{
 			client_consensus::ConsensusState as IbcConsensusState,
 			client_def::{ClientDef, ConsensusUpdateResult},
-			client_state::ClientState as IbcClientState,
+			client_state::{ClientState as IbcClientState, Status},
 			error::Error,
 		},
 		ics03_connection::connection::ConnectionEnd,
 		ics04_channel::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
+			context::ChannelReader,
 			packet::Sequence,
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
+				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+			},
+		},
 		ics26_routing::context::ReaderContext,
 	},
+	protobuf::Protobuf,
 	Height,
 };
 use ibc_proto::google::protobuf::Any;
 
+/// The client definition of `08-wasm` clients. Every method is run by the light client contract of
+/// the client, the client, consensus states and messages it passes around are opaque to the host.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct WasmClient<AnyClient, AnyClientState, AnyConsensusState> {
-	pub inner: Box<AnyClient>,
-	pub _phantom: PhantomData<(AnyClientState, AnyConsensusState)>,
+	pub _phantom: PhantomData<(AnyClient, AnyClientState, AnyConsensusState)>,
+}
+
+/// Parses the response of the light client contract to an [`ExecuteMsg`].
+fn contract_result(data: Vec<u8>) -> Result<ContractResult, Error> {
+	let result = serde_json::from_slice::<ContractResult>(&data).map_err(|e| {
+		Error::implementation_specific(format!("invalid light client contract result: {e}"))
+	})?;
+	if !result.is_valid {
+		return Err(Error::implementation_specific(result.error_msg))
+	}
+	Ok(result)
+}
+
+/// Runs `msg` on the light client contract of the client.
+fn execute_contract<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	code_id: &[u8],
+	msg: ExecuteMsg,
+) -> Result<ContractResult, Error> {
+	let msg =
+		serde_json::to_vec(&msg).map_err(|e| Error::implementation_specific(e.to_string()))?;
+	contract_result(ctx.execute_wasm_contract(client_id, code_id, &msg)?)
+}
+
+/// Returns the status of the client reported by its light client contract, or
+/// [`Status::Unknown`] if the contract can't be queried.
+pub(crate) fn query_status<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	code_id: &[u8],
+) -> Status {
+	let msg = serde_json::to_vec(&QueryMsg::Status(StatusMsg {})).unwrap_or_default();
+	let response = match ctx.query_wasm_contract(client_id, code_id, &msg) {
+		Ok(response) => response,
+		Err(_) => return Status::Unknown,
+	};
+	match serde_json::from_slice::<StatusResponse>(&response).map(|r| r.status) {
+		Ok(status) => match &*status {
+			"Active" => Status::Active,
+			"Frozen" => Status::Frozen,
+			"Expired" => Status::Expired,
+			"Unauthorized" => Status::Unauthorized,
+			_ => Status::Unknown,
+		},
+		Err(_) => Status::Unknown,
+	}
+}
+
+/// Reads back the client state written by the light client contract of the client.
+fn stored_client_state<Ctx: ReaderContext, S: Clone + 'static>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+) -> Result<S, Error> {
+	ctx.client_state(client_id)?.downcast::<S>().ok_or_else(|| {
+		Error::implementation_specific(format!("client {client_id} is not a wasm client"))
+	})
+}
+
+/// Reads back the client state and the latest consensus state written by the light client
+/// contract of the client. Both are wasm states, whose data the host doesn't need to decode.
+fn stored_state<Ctx: ReaderContext, S: IbcClientState + 'static>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+) -> Result<(S, ConsensusUpdateResult<Ctx>), Error> {
+	let client_state = stored_client_state::<_, S>(ctx, client_id)?;
+	let consensus_state = ctx.consensus_state(client_id, client_state.latest_height())?;
+	Ok((client_state, ConsensusUpdateResult::Single(consensus_state)))
+}
+
+/// Verifies the (non-)membership of `value` at `path` with the light client contract of the
+/// client, `value` being `None` for non-membership.
+fn verify_membership<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	code_id: &[u8],
+	height: Height,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	path: String,
+	value: Option<Vec<u8>>,
+	delay_period: Duration,
+) -> Result<(), Error> {
+	let path = MerklePath {
+		key_path: vec![String::from_utf8_lossy(prefix.as_bytes()).into_owned(), path],
+	};
+	let proof = proof.as_bytes().to_vec();
+	let delay_block_period = ctx.block_delay(delay_period);
+	let delay_time_period = delay_period.as_nanos() as u64;
+	let msg = match value {
+		Some(value) => ExecuteMsg::VerifyMembership(VerifyMembershipMsg {
+			proof,
+			path,
+			value,
+			height,
+			delay_block_period,
+			delay_time_period,
+		}),
+		None => ExecuteMsg::VerifyNonMembership(VerifyNonMembershipMsg {
+			proof,
+			path,
+			height,
+			delay_block_period,
+			delay_time_period,
+		}),
+	};
+	execute_contract(ctx, client_id, code_id, msg).map(|_| ())
 }
 
 impl<AnyClient, AnyClientState, AnyConsensusState> ClientDef
@@ -65,6 +192,9 @@ where
 	<AnyConsensusState as TryFrom<Any>>::Error: Display,
 	AnyClient::ClientMessage: TryFrom<Any>,
 	<AnyClient::ClientMessage as TryFrom<Any>>::Error: Display,
+	AnyClient: 'static,
+	AnyClientState: 'static,
+	AnyConsensusState: 'static,
 {
 	type ClientMessage = ClientMessage<AnyClient::ClientMessage>;
 	type ClientState = ClientState<AnyClient, AnyClientState, AnyConsensusState>;
@@ -77,12 +207,10 @@ where
 		client_state: Self::ClientState,
 		client_msg: Self::ClientMessage,
 	) -> Result<(), Error> {
-		self.inner.verify_client_message(
-			ctx,
-			client_id,
-			*client_state.inner,
-			client_msg.into_inner(),
-		)
+		let msg = ExecuteMsg::VerifyClientMessage(ClientMessageMsg {
+			client_message: (&client_msg).into(),
+		});
+		execute_contract(ctx, &client_id, &client_state.code_id, msg).map(|_| ())
 	}
 
 	fn update_state<Ctx: ReaderContext>(
@@ -92,37 +220,24 @@ where
 		client_state: Self::ClientState,
 		client_msg: Self::ClientMessage,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		let (inner_client_state, inner_consensus_update_result) = self.inner.update_state(
-			ctx,
-			client_id,
-			*client_state.inner,
-			client_msg.into_inner(),
-		)?;
-		let client_state = ClientState {
-			data: client_state.data.clone(),
-			code_id: client_state.code_id.clone(),
-			inner: Box::new(inner_client_state),
-			latest_height: client_state.latest_height,
-			_phantom: PhantomData,
-		};
-		Ok((client_state, inner_consensus_update_result))
+		let msg =
+			ExecuteMsg::UpdateState(ClientMessageMsg { client_message: (&client_msg).into() });
+		execute_contract(ctx, &client_id, &client_state.code_id, msg)?;
+		stored_state(ctx, &client_id)
 	}
 
-	fn update_state_on_misbehaviour(
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
 		&self,
+		ctx: &Ctx,
+		client_id: ClientId,
 		client_state: Self::ClientState,
 		client_msg: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
-		let inner_client_state = self
-			.inner
-			.update_state_on_misbehaviour(*client_state.inner, client_msg.into_inner())?;
-		Ok(ClientState {
-			data: client_state.data.clone(),
-			code_id: client_state.code_id.clone(),
-			inner: Box::new(inner_client_state),
-			latest_height: client_state.latest_height,
-			_phantom: PhantomData,
-		})
+		let msg = ExecuteMsg::UpdateStateOnMisbehaviour(ClientMessageMsg {
+			client_message: (&client_msg).into(),
+		});
+		execute_contract(ctx, &client_id, &client_state.code_id, msg)?;
+		stored_client_state(ctx, &client_id)
 	}
 
 	fn check_for_misbehaviour<Ctx: ReaderContext>(
@@ -132,12 +247,11 @@ where
 		client_state: Self::ClientState,
 		client_msg: Self::ClientMessage,
 	) -> Result<bool, Error> {
-		self.inner.check_for_misbehaviour(
-			ctx,
-			client_id,
-			*client_state.inner,
-			client_msg.into_inner(),
-		)
+		let msg = ExecuteMsg::CheckForMisbehaviour(ClientMessageMsg {
+			client_message: (&client_msg).into(),
+		});
+		execute_contract(ctx, &client_id, &client_state.code_id, msg)
+			.map(|result| result.found_misbehaviour)
 	}
 
 	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
@@ -150,28 +264,21 @@ where
 		proof_upgrade_client: Vec<u8>,
 		proof_upgrade_consensus_state: Vec<u8>,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		self.inner
-			.verify_upgrade_and_update_state(
-				ctx,
-				client_id,
-				&old_client_state.inner,
-				&upgrade_client_state.inner,
-				&upgrade_consensus_state.inner,
-				proof_upgrade_client,
-				proof_upgrade_consensus_state,
-			)
-			.map(|(client_state, result)| {
-				(
-					ClientState {
-						inner: Box::new(client_state),
-						data: old_client_state.data.clone(),
-						code_id: old_client_state.code_id.clone(),
-						latest_height: old_client_state.latest_height.clone(),
-						_phantom: Default::default(),
-					},
-					result,
-				)
-			})
+		let msg = ExecuteMsg::VerifyUpgradeAndUpdateState(VerifyUpgradeAndUpdateStateMsg {
+			upgrade_client_state: ClientStateRaw {
+				data: upgrade_client_state.data.clone(),
+				code_id: upgrade_client_state.code_id.clone(),
+				latest_height: upgrade_client_state.latest_height,
+			},
+			upgrade_consensus_state: ConsensusStateRaw {
+				data: upgrade_consensus_state.data.clone(),
+				timestamp: upgrade_consensus_state.timestamp,
+			},
+			proof_upgrade_client,
+			proof_upgrade_consensus_state,
+		});
+		execute_contract(ctx, &client_id, &old_client_state.code_id, msg)?;
+		stored_state(ctx, &client_id)
 	}
 
 	fn check_substitute_and_update_state<Ctx: ReaderContext>(
@@ -180,51 +287,51 @@ where
 		subject_client_id: ClientId,
 		substitute_client_id: ClientId,
 		old_client_state: Self::ClientState,
-		substitute_client_state: Self::ClientState,
+		_substitute_client_state: Self::ClientState,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		let (inner_client_state, inner_consensus_update_result) =
-			self.inner.check_substitute_and_update_state(
-				ctx,
-				subject_client_id,
-				substitute_client_id,
-				*old_client_state.inner,
-				*substitute_client_state.inner,
-			)?;
-		let client_state = ClientState {
-			data: old_client_state.data.clone(),
-			code_id: old_client_state.code_id.clone(),
-			inner: Box::new(inner_client_state),
-			latest_height: old_client_state.latest_height,
-			_phantom: PhantomData,
-		};
-		Ok((client_state, inner_consensus_update_result))
+		let msg = ExecuteMsg::CheckSubstituteAndUpdateState(CheckSubstituteAndUpdateStateMsg {});
+		let msg =
+			serde_json::to_vec(&msg).map_err(|e| Error::implementation_specific(e.to_string()))?;
+		contract_result(ctx.execute_wasm_substitute_contract(
+			&subject_client_id,
+			&substitute_client_id,
+			&old_client_state.code_id,
+			&msg,
+		)?)?;
+		stored_state(ctx, &subject_client_id)
 	}
 
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		ctx: &Ctx,
+		client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
-		client_id: &ClientId,
+		_root: &CommitmentRoot,
+		counterparty_client_id: &ClientId,
 		consensus_height: Height,
 		expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_client_consensus_state(
-				ctx,
-				&client_state.inner,
-				height,
-				prefix,
-				proof,
-				root,
-				client_id,
-				consensus_height,
-				expected_consensus_state,
-			)
-			.map_err(|e| e.into())
+		let path = ClientConsensusStatePath {
+			client_id: counterparty_client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		}
+		.to_string();
+		let value = expected_consensus_state.encode_to_vec().map_err(Error::encode)?;
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			prefix,
+			proof,
+			path,
+			Some(value),
+			Duration::ZERO,
+		)
 	}
 
 	fn verify_connection_state<Ctx: ReaderContext>(
@@ -235,23 +342,23 @@ where
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
+		_root: &CommitmentRoot,
 		connection_id: &ConnectionId,
 		expected_connection_end: &ConnectionEnd,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_connection_state(
-				ctx,
-				client_id,
-				&client_state.inner,
-				height,
-				prefix,
-				proof,
-				root,
-				connection_id,
-				expected_connection_end,
-			)
-			.map_err(|e| e.into())
+		let path = ConnectionsPath(connection_id.clone()).to_string();
+		let value = expected_connection_end.encode_vec().map_err(Error::encode)?;
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			prefix,
+			proof,
+			path,
+			Some(value),
+			Duration::ZERO,
+		)
 	}
 
 	fn verify_channel_state<Ctx: ReaderContext>(
@@ -262,50 +369,51 @@ where
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
+		_root: &CommitmentRoot,
 		port_id: &PortId,
 		channel_id: &ChannelId,
 		expected_channel_end: &ChannelEnd,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_channel_state(
-				ctx,
-				client_id,
-				&client_state.inner,
-				height,
-				prefix,
-				proof,
-				root,
-				port_id,
-				channel_id,
-				expected_channel_end,
-			)
-			.map_err(|e| e.into())
+		let path = ChannelEndsPath(port_id.clone(), *channel_id).to_string();
+		let value = expected_channel_end.encode_vec().map_err(Error::encode)?;
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			prefix,
+			proof,
+			path,
+			Some(value),
+			Duration::ZERO,
+		)
 	}
 
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		ctx: &Ctx,
+		client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
-		client_id: &ClientId,
+		_root: &CommitmentRoot,
+		counterparty_client_id: &ClientId,
 		expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_client_full_state(
-				ctx,
-				&client_state.inner,
-				height,
-				prefix,
-				proof,
-				root,
-				client_id,
-				expected_client_state,
-			)
-			.map_err(|e| e.into())
+		let path = ClientStatePath(counterparty_client_id.clone()).to_string();
+		let value = expected_client_state.encode_to_vec().map_err(Error::encode)?;
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			prefix,
+			proof,
+			path,
+			Some(value),
+			Duration::ZERO,
+		)
 	}
 
 	fn verify_packet_data<Ctx: ReaderContext>(
@@ -316,27 +424,25 @@ where
 		height: Height,
 		connection_end: &ConnectionEnd,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
+		_root: &CommitmentRoot,
 		port_id: &PortId,
 		channel_id: &ChannelId,
 		sequence: Sequence,
 		commitment: PacketCommitment,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_packet_data(
-				ctx,
-				client_id,
-				&client_state.inner,
-				height,
-				connection_end,
-				proof,
-				root,
-				port_id,
-				channel_id,
-				sequence,
-				commitment,
-			)
-			.map_err(|e| e.into())
+		let path = CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence }
+			.to_string();
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			connection_end.counterparty().prefix(),
+			proof,
+			path,
+			Some(commitment.into_vec()),
+			connection_end.delay_period(),
+		)
 	}
 
 	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
@@ -347,27 +453,25 @@ where
 		height: Height,
 		connection_end: &ConnectionEnd,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
+		_root: &CommitmentRoot,
 		port_id: &PortId,
 		channel_id: &ChannelId,
 		sequence: Sequence,
 		ack: AcknowledgementCommitment,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_packet_acknowledgement(
-				ctx,
-				client_id,
-				&client_state.inner,
-				height,
-				connection_end,
-				proof,
-				root,
-				port_id,
-				channel_id,
-				sequence,
-				ack,
-			)
-			.map_err(|e| e.into())
+		let path =
+			AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence }.to_string();
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			connection_end.counterparty().prefix(),
+			proof,
+			path,
+			Some(ack.into_vec()),
+			connection_end.delay_period(),
+		)
 	}
 
 	fn verify_next_sequence_recv<Ctx: ReaderContext>(
@@ -378,25 +482,25 @@ where
 		height: Height,
 		connection_end: &ConnectionEnd,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
+		_root: &CommitmentRoot,
 		port_id: &PortId,
 		channel_id: &ChannelId,
 		sequence: Sequence,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_next_sequence_recv(
-				ctx,
-				client_id,
-				&client_state.inner,
-				height,
-				connection_end,
-				proof,
-				root,
-				port_id,
-				channel_id,
-				sequence,
-			)
-			.map_err(|e| e.into())
+		// Sequences are committed to in big endian by ibc-go, which light client contracts follow.
+		let path = SeqRecvsPath(port_id.clone(), *channel_id).to_string();
+		let value = u64::from(sequence).to_be_bytes().to_vec();
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			connection_end.counterparty().prefix(),
+			proof,
+			path,
+			Some(value),
+			connection_end.delay_period(),
+		)
 	}
 
 	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
@@ -407,24 +511,23 @@ where
 		height: Height,
 		connection_end: &ConnectionEnd,
 		proof: &CommitmentProofBytes,
-		root: &CommitmentRoot,
+		_root: &CommitmentRoot,
 		port_id: &PortId,
 		channel_id: &ChannelId,
 		sequence: Sequence,
 	) -> Result<(), Error> {
-		self.inner
-			.verify_packet_receipt_absence(
-				ctx,
-				client_id,
-				&client_state.inner,
-				height,
-				connection_end,
-				proof,
-				root,
-				port_id,
-				channel_id,
-				sequence,
-			)
-			.map_err(|e| e.into())
+		let path = ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence }
+			.to_string();
+		verify_membership(
+			ctx,
+			client_id,
+			&client_state.code_id,
+			height,
+			connection_end.counterparty().prefix(),
+			proof,
+			path,
+			None,
+			connection_end.delay_period(),
+		)
 	}
 }
@@ -46,17 +46,17 @@ pub enum ClientMessage<AnyClientMessage> {
 }
 
 impl<AnyClientMessage> ClientMessage<AnyClientMessage> {
-	pub fn inner(&self) -> &AnyClientMessage {
+	pub fn inner(&self) -> Option<&AnyClientMessage> {
 		match self {
-			ClientMessage::Header(h) => &h.inner,
-			ClientMessage::Misbehaviour(m) => &m.inner,
+			ClientMessage::Header(h) => h.inner.as_deref(),
+			ClientMessage::Misbehaviour(m) => m.inner.as_deref(),
 		}
 	}
 
-	pub fn into_inner(self) -> AnyClientMessage {
+	pub fn into_inner(self) -> Option<AnyClientMessage> {
 		match self {
-			ClientMessage::Header(h) => *h.inner,
-			ClientMessage::Misbehaviour(m) => *m.inner,
+			ClientMessage::Header(h) => h.inner.map(|inner| *inner),
+			ClientMessage::Misbehaviour(m) => m.inner.map(|inner| *inner),
 		}
 	}
 }
@@ -67,6 +67,13 @@ where
 	AnyClientMessage: TryFrom<Any>,
 	<AnyClientMessage as TryFrom<Any>>::Error: Display,
 {
+	fn maybe_header_height(&self) -> Option<Height> {
+		match self {
+			ClientMessage::Header(header) => Some(header.height),
+			ClientMessage::Misbehaviour(_) => None,
+		}
+	}
+
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
 		self.encode_vec()
 	}
@@ -138,9 +145,11 @@ where
 	type Error = String;
 
 	fn try_from(raw: RawMisbehaviour) -> Result<Self, Self::Error> {
-		let any = Any::decode(&mut &raw.data[..]).map_err(|e| e.to_string())?;
-		let inner = AnyClientMessage::try_from(any).map_err(|e| e.to_string())?;
-		Ok(Self { inner: Box::new(inner), data: raw.data })
+		let inner = Any::decode(&mut &raw.data[..])
+			.ok()
+			.and_then(|any| AnyClientMessage::try_from(any).ok())
+			.map(Box::new);
+		Ok(Self { inner, data: raw.data })
 	}
 }
 
@@ -160,14 +169,13 @@ where
 	type Error = String;
 
 	fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
-		let any = Any::decode(&mut &raw.data[..])
-			.map_err(|e| format!("failed to decode raw header into Any: {}", e.to_string()))?;
-		let inner = AnyClientMessage::try_from(any).map_err(|e| {
-			format!("failed to decode raw header into AnyClientMessage: {}", e.to_string())
-		})?;
+		let inner = Any::decode(&mut &raw.data[..])
+			.ok()
+			.and_then(|any| AnyClientMessage::try_from(any).ok())
+			.map(Box::new);
 
 		let header = Self {
-			inner: Box::new(inner),
+			inner,
 			data: raw.data,
 			height: raw
 				.height
@@ -182,9 +190,10 @@ where
 #[cfg_attr(not(feature = "cosmwasm"), derive(Clone, Debug, PartialEq))]
 #[derive(Eq)]
 pub struct Header<AnyClientMessage> {
+	/// The native header `data` decodes to, if the host knows its client type.
 	#[cfg_attr(feature = "cosmwasm", serde(skip))]
 	#[cfg_attr(feature = "cosmwasm", schemars(skip))]
-	pub inner: Box<AnyClientMessage>,
+	pub inner: Option<Box<AnyClientMessage>>,
 	#[cfg_attr(feature = "cosmwasm", schemars(with = "String"))]
 	#[cfg_attr(feature = "cosmwasm", serde(with = "Base64", default))]
 	pub data: Bytes,
@@ -195,7 +204,8 @@ pub struct Header<AnyClientMessage> {
 pub struct Misbehaviour<AnyClientMessage> {
 	// #[schemars(with = "String")]
 	// #[serde(with = "Base64", default)]
-	pub inner: Box<AnyClientMessage>,
+	/// The native misbehaviour `data` decodes to, if the host knows its client type.
+	pub inner: Option<Box<AnyClientMessage>>,
 	pub data: Bytes,
 }
 
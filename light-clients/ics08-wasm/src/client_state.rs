@@ -15,7 +15,10 @@
 
 #[cfg(feature = "cosmwasm")]
 use crate::msg::Base64;
-use crate::{client_def::WasmClient, Bytes};
+use crate::{
+	client_def::{query_status, WasmClient},
+	Bytes,
+};
 use alloc::{
 	boxed::Box,
 	string::{String, ToString},
@@ -31,10 +34,13 @@ use cosmwasm_schema::cw_serde;
 use ibc::{
 	core::{
 		ics02_client::{
-			client_consensus::ConsensusState as IbcConsensusState, client_def::ClientDef,
-			client_state::ClientState as IbcClientState,
+			client_consensus::ConsensusState as IbcConsensusState,
+			client_def::ClientDef,
+			client_state::{ClientState as IbcClientState, Status},
+			error::Error,
 		},
-		ics24_host::identifier::ChainId,
+		ics24_host::identifier::{ChainId, ClientId},
+		ics26_routing::context::ReaderContext,
 	},
 	protobuf::Protobuf,
 	Height,
@@ -57,9 +63,11 @@ pub struct ClientState<AnyClient, AnyClientState, AnyConsensusState> {
 	#[cfg_attr(feature = "cosmwasm", serde(with = "Base64", default))]
 	pub code_id: Bytes,
 	pub latest_height: Height,
+	/// The native client state `data` decodes to, if the host knows its client type. The client
+	/// is only ever run by its contract, this is for relayers inspecting the wrapped state.
 	#[cfg_attr(feature = "cosmwasm", serde(skip))]
 	#[cfg_attr(feature = "cosmwasm", schemars(skip))]
-	pub inner: Box<AnyClientState>,
+	pub inner: Option<Box<AnyClientState>>,
 	#[cfg_attr(feature = "cosmwasm", serde(skip))]
 	#[cfg_attr(feature = "cosmwasm", schemars(skip))]
 	pub _phantom: PhantomData<(AnyConsensusState, AnyClient)>,
@@ -86,13 +94,11 @@ where
 	type ClientDef = WasmClient<AnyClient, AnyClientState, AnyConsensusState>;
 
 	fn chain_id(&self) -> ChainId {
-		// self.inner.chain_id()
-		self.inner.chain_id()
+		self.inner.as_ref().map(|inner| inner.chain_id()).unwrap_or_default()
 	}
 
 	fn client_def(&self) -> Self::ClientDef {
-		let inner = self.inner.client_def();
-		WasmClient { inner: Box::new(inner), _phantom: Default::default() }
+		WasmClient { _phantom: Default::default() }
 	}
 
 	fn client_type(&self) -> String {
@@ -103,8 +109,25 @@ where
 		self.latest_height
 	}
 
+	fn status<Ctx: ReaderContext>(&self, ctx: &Ctx, client_id: &ClientId) -> Status {
+		query_status(ctx, client_id, &self.code_id)
+	}
+
 	fn frozen_height(&self) -> Option<Height> {
-		self.inner.frozen_height()
+		self.inner.as_ref().and_then(|inner| inner.frozen_height())
+	}
+
+	fn with_frozen_height(self, height: Height) -> Result<Self, Error> {
+		let inner = self.inner.ok_or_else(|| {
+			Error::implementation_specific(
+				"the client state of the wasm client is only known to its contract".to_string(),
+			)
+		})?;
+		let inner = inner.with_frozen_height(height)?;
+		let data = inner
+			.encode_to_vec()
+			.map_err(|e| Error::implementation_specific(e.to_string()))?;
+		Ok(Self { data, inner: Some(Box::new(inner)), ..self })
 	}
 
 	fn upgrade(
@@ -113,12 +136,15 @@ where
 		upgrade_options: Self::UpgradeOptions,
 		chain_id: ChainId,
 	) -> Self {
-		let inner = self.inner.upgrade(upgrade_height, *upgrade_options, chain_id);
-		Self { inner: Box::new(inner), ..self }
+		let inner = self
+			.inner
+			.map(|inner| Box::new(inner.upgrade(upgrade_height, *upgrade_options, chain_id)));
+		Self { inner, ..self }
 	}
 
 	fn expired(&self, elapsed: Duration) -> bool {
-		self.inner.expired(elapsed)
+		// The contract checks the expiry of clients it doesn't expose.
+		self.inner.as_ref().map_or(false, |inner| inner.expired(elapsed))
 	}
 
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
@@ -162,12 +188,14 @@ where
 	type Error = String;
 
 	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
-		let any = Any::decode(&mut &raw.data[..]).map_err(|e| e.to_string())?;
-		let inner = AnyClientState::try_from(any).map_err(|e| e.to_string())?;
+		let inner = Any::decode(&mut &raw.data[..])
+			.ok()
+			.and_then(|any| AnyClientState::try_from(any).ok())
+			.map(Box::new);
 		Ok(Self {
 			data: raw.data,
 			code_id: raw.code_id,
-			inner: Box::new(inner),
+			inner,
 			latest_height: raw
 				.latest_height
 				.map(|h| Height::new(h.revision_number, h.revision_height))
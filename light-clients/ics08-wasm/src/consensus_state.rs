@@ -42,6 +42,9 @@ use prost::Message;
 
 pub const WASM_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ConsensusState";
 
+/// Root of the consensus states the host can't decode, whose proofs only their contract verifies.
+static OPAQUE_ROOT: CommitmentRoot = CommitmentRoot { bytes: Vec::new() };
+
 #[cfg_attr(feature = "cosmwasm", cw_serde)]
 #[cfg_attr(not(feature = "cosmwasm"), derive(Clone, Debug, PartialEq))]
 #[derive(Eq)]
@@ -50,9 +53,10 @@ pub struct ConsensusState<AnyConsensusState> {
 	#[cfg_attr(feature = "cosmwasm", serde(with = "Base64", default))]
 	pub data: Bytes,
 	pub timestamp: u64,
+	/// The native consensus state `data` decodes to, if the host knows its client type.
 	#[cfg_attr(feature = "cosmwasm", serde(skip))]
 	#[cfg_attr(feature = "cosmwasm", schemars(skip))]
-	pub inner: Box<AnyConsensusState>,
+	pub inner: Option<Box<AnyConsensusState>>,
 }
 
 impl<AnyConsensusState: IbcConsensusState> IbcConsensusState for ConsensusState<AnyConsensusState>
@@ -64,7 +68,7 @@ where
 	type Error = Infallible;
 
 	fn root(&self) -> &CommitmentRoot {
-		unimplemented!()
+		self.inner.as_ref().map_or(&OPAQUE_ROOT, |inner| inner.root())
 	}
 
 	fn timestamp(&self) -> Timestamp {
@@ -100,12 +104,11 @@ where
 	type Error = String;
 
 	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
-		let any = Any::decode(&mut &raw.data[..])
-			.map_err(|e| format!("failed to decode ConsensusState::data into Any: {}", e))?;
-		let inner = AnyConsensusState::try_from(any).map_err(|e| {
-			format!("failed to decode ConsensusState::data into ConsensusState: {}", e)
-		})?;
-		Ok(Self { data: raw.data, timestamp: raw.timestamp, inner: Box::new(inner) })
+		let inner = Any::decode(&mut &raw.data[..])
+			.ok()
+			.and_then(|any| AnyConsensusState::try_from(any).ok())
+			.map(Box::new);
+		Ok(Self { data: raw.data, timestamp: raw.timestamp, inner })
 	}
 }
 
@@ -0,0 +1,147 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Messages passed to the light client contract of an `08-wasm` client, in the JSON format of the
+//! `ExecuteMsg` and `QueryMsg` of `ics10-grandpa-cw` and `ics07-tendermint-cw`.
+
+use crate::{client_message::ClientMessage, msg::Base64, Bytes};
+use alloc::{string::String, vec::Vec};
+use ibc::Height;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+	VerifyMembership(VerifyMembershipMsg),
+	VerifyNonMembership(VerifyNonMembershipMsg),
+	VerifyClientMessage(ClientMessageMsg),
+	CheckForMisbehaviour(ClientMessageMsg),
+	UpdateStateOnMisbehaviour(ClientMessageMsg),
+	UpdateState(ClientMessageMsg),
+	CheckSubstituteAndUpdateState(CheckSubstituteAndUpdateStateMsg),
+	VerifyUpgradeAndUpdateState(VerifyUpgradeAndUpdateStateMsg),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+	Status(StatusMsg),
+}
+
+#[derive(Serialize)]
+pub struct StatusMsg {}
+
+/// The response of a contract to [`QueryMsg::Status`].
+#[derive(Deserialize)]
+pub struct StatusResponse {
+	pub status: String,
+}
+
+#[derive(Serialize)]
+pub struct MerklePath {
+	/// The commitment prefix followed by the path.
+	pub key_path: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyMembershipMsg {
+	#[serde(with = "Base64")]
+	pub proof: Bytes,
+	pub path: MerklePath,
+	#[serde(with = "Base64")]
+	pub value: Bytes,
+	pub height: Height,
+	pub delay_block_period: u64,
+	pub delay_time_period: u64,
+}
+
+#[derive(Serialize)]
+pub struct VerifyNonMembershipMsg {
+	#[serde(with = "Base64")]
+	pub proof: Bytes,
+	pub path: MerklePath,
+	pub height: Height,
+	pub delay_block_period: u64,
+	pub delay_time_period: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientMessageRaw {
+	Header {
+		#[serde(with = "Base64")]
+		data: Bytes,
+		height: Height,
+	},
+	Misbehaviour {
+		#[serde(with = "Base64")]
+		data: Bytes,
+	},
+}
+
+impl<AnyClientMessage> From<&ClientMessage<AnyClientMessage>> for ClientMessageRaw {
+	fn from(client_message: &ClientMessage<AnyClientMessage>) -> Self {
+		match client_message {
+			ClientMessage::Header(header) =>
+				Self::Header { data: header.data.clone(), height: header.height },
+			ClientMessage::Misbehaviour(misbehaviour) =>
+				Self::Misbehaviour { data: misbehaviour.data.clone() },
+		}
+	}
+}
+
+#[derive(Serialize)]
+pub struct ClientMessageMsg {
+	pub client_message: ClientMessageRaw,
+}
+
+#[derive(Serialize)]
+pub struct CheckSubstituteAndUpdateStateMsg {}
+
+#[derive(Serialize)]
+pub struct ClientStateRaw {
+	#[serde(with = "Base64")]
+	pub data: Bytes,
+	#[serde(with = "Base64")]
+	pub code_id: Bytes,
+	pub latest_height: Height,
+}
+
+#[derive(Serialize)]
+pub struct ConsensusStateRaw {
+	#[serde(with = "Base64")]
+	pub data: Bytes,
+	pub timestamp: u64,
+}
+
+#[derive(Serialize)]
+pub struct VerifyUpgradeAndUpdateStateMsg {
+	pub upgrade_client_state: ClientStateRaw,
+	pub upgrade_consensus_state: ConsensusStateRaw,
+	#[serde(with = "Base64")]
+	pub proof_upgrade_client: Bytes,
+	#[serde(with = "Base64")]
+	pub proof_upgrade_consensus_state: Bytes,
+}
+
+/// The data of a contract's response to an [`ExecuteMsg`].
+#[derive(Deserialize)]
+pub struct ContractResult {
+	pub is_valid: bool,
+	pub error_msg: String,
+	#[serde(default)]
+	pub data: Option<Vec<u8>>,
+	pub found_misbehaviour: bool,
+}
@@ -17,16 +17,20 @@ use crate::Bytes;
 use alloc::string::ToString;
 use core::str::FromStr;
 use ibc::{
+	core::{ics02_client::error::Error, ics24_host::identifier::ClientId},
 	protobuf::Protobuf,
 	signer::{Signer, SignerError},
 };
 use ibc_proto::{
-	google::protobuf::Any, ibc::lightclients::wasm::v1::MsgPushNewWasmCode as RawMsgPushNewWasmCode,
+	google::protobuf::Any,
+	ibc::lightclients::wasm::v1::{
+		MsgMigrateContract as RawMsgMigrateContract, MsgPushNewWasmCode as RawMsgPushNewWasmCode,
+	},
 };
-#[cfg(feature = "cosmwasm")]
 use serde::{Deserializer, Serializer};
 
 pub const WASM_PUSH_WASM_CODE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.MsgPushNewWasmCode";
+pub const WASM_MIGRATE_CONTRACT_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.MsgMigrateContract";
 
 #[derive(Clone, PartialEq, Debug, Eq)]
 pub struct MsgPushNewWasmCode {
@@ -59,9 +63,54 @@ impl From<MsgPushNewWasmCode> for Any {
 	}
 }
 
+/// Points a wasm client at new contract code, identified by its sha256 `code_id`, and runs the
+/// `migrate` entry point of the contract with the JSON encoded `migrate_msg` if it isn't empty.
+#[derive(Clone, PartialEq, Debug, Eq)]
+pub struct MsgMigrateContract {
+	pub client_id: ClientId,
+	pub code_id: Bytes,
+	pub migrate_msg: Bytes,
+	pub signer: Signer,
+}
+
+impl Protobuf<RawMsgMigrateContract> for MsgMigrateContract {}
+
+impl From<MsgMigrateContract> for RawMsgMigrateContract {
+	fn from(value: MsgMigrateContract) -> Self {
+		Self {
+			signer: value.signer.to_string(),
+			client_id: value.client_id.to_string(),
+			checksum: value.code_id,
+			msg: value.migrate_msg,
+		}
+	}
+}
+
+impl TryFrom<RawMsgMigrateContract> for MsgMigrateContract {
+	type Error = Error;
+
+	fn try_from(value: RawMsgMigrateContract) -> Result<Self, Self::Error> {
+		Ok(Self {
+			client_id: ClientId::from_str(&value.client_id)
+				.map_err(Error::invalid_client_identifier)?,
+			code_id: value.checksum,
+			migrate_msg: value.msg,
+			signer: Signer::from_str(&value.signer).map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgMigrateContract> for Any {
+	fn from(value: MsgMigrateContract) -> Self {
+		Any {
+			type_url: WASM_MIGRATE_CONTRACT_TYPE_URL.to_string(),
+			value: value.encode_vec().expect("MsgMigrateContract encoding should always succeed"),
+		}
+	}
+}
+
 pub struct Base64;
 
-#[cfg(feature = "cosmwasm")]
 impl Base64 {
 	pub fn serialize<S: Serializer>(v: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
 		ibc_proto::base64::serialize(v, serializer)
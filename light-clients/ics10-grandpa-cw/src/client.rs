@@ -227,6 +227,18 @@ impl<'a, H: HostFunctions<Header = RelayChainHeader>> ClientKeeper for Context<'
 		);
 		let mut consensus_states = ConsensusStates::new(self.storage_mut());
 		consensus_states.insert(height, encoded);
+		CONSENSUS_STATES_HEIGHTS
+			.update(self.storage_mut(), client_id.as_bytes().to_owned(), |heights| {
+				let mut heights = heights.unwrap_or_default();
+				heights.insert(height);
+				Ok::<_, cosmwasm_std::StdError>(heights)
+			})
+			.map_err(|e| {
+				Error::implementation_specific(format!(
+					"[store_consensus_state]: error saving consensus state height {}",
+					e
+				))
+			})?;
 		Ok(())
 	}
 
@@ -309,10 +321,10 @@ impl<'a, H: Clone> Context<'a, H> {
 	}
 
 	pub fn encode_consensus_state(consensus_state: ConsensusState) -> Vec<u8> {
-		let wasm_consensus_state = ics08_wasm::consensus_state::ConsensusState {
+		let wasm_consensus_state = ics08_wasm::consensus_state::ConsensusState::<FakeInner> {
 			data: consensus_state.to_any().encode_to_vec(),
 			timestamp: consensus_state.timestamp().nanoseconds(),
-			inner: Box::new(FakeInner),
+			inner: None,
 		};
 		wasm_consensus_state.to_any().encode_to_vec()
 	}
@@ -19,10 +19,10 @@ use crate::{
 	log,
 	msg::{
 		CheckForMisbehaviourMsg, CheckSubstituteAndUpdateStateMsg, ClientStateCallResponse,
-		ContractResult, ExecuteMsg, ExportMetadataMsg, InitializeState, InstantiateMsg, QueryMsg,
-		QueryResponse, StatusMsg, UpdateStateMsg, UpdateStateOnMisbehaviourMsg,
-		VerifyClientMessage, VerifyMembershipMsg, VerifyNonMembershipMsg,
-		VerifyUpgradeAndUpdateStateMsg,
+		ClientTypeMsg, ContractResult, ExecuteMsg, ExportMetadataMsg, GenesisMetadata,
+		GetLatestHeightsMsg, InitializeState, InstantiateMsg, QueryMsg, QueryResponse, StatusMsg,
+		UpdateStateMsg, UpdateStateOnMisbehaviourMsg, VerifyClientMessage, VerifyMembershipMsg,
+		VerifyNonMembershipMsg, VerifyUpgradeAndUpdateStateMsg,
 	},
 	state::{get_client_state, get_consensus_state},
 	Bytes,
@@ -31,7 +31,9 @@ use byteorder::{ByteOrder, LittleEndian};
 use core::hash::Hasher;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+	to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage,
+};
 use cw_storage_plus::{Item, Map};
 use digest::Digest;
 use grandpa_light_client_primitives::justification::AncestryChain;
@@ -72,7 +74,8 @@ pub const CLIENT_COUNTER: Item<u32> = Item::new("client_counter");
 pub const HOST_CONSENSUS_STATE: Map<u64, ConsensusState> = Map::new("host_consensus_state");
 pub const CONSENSUS_STATES_HEIGHTS: Map<Bytes, BTreeSet<Height>> =
 	Map::new("consensus_states_heights");
-pub const GRANDPA_HEADER_HASHES_STORAGE: Item<Vec<H256>> = Item::new("grandpa_header_hashes");
+pub const GRANDPA_HEADER_HASHES_KEY: &str = "grandpa_header_hashes";
+pub const GRANDPA_HEADER_HASHES_STORAGE: Item<Vec<H256>> = Item::new(GRANDPA_HEADER_HASHES_KEY);
 pub const GRANDPA_HEADER_HASHES_SET_STORAGE: Map<Vec<u8>, ()> =
 	Map::new("grandpa_header_hashes_set");
 
@@ -132,9 +135,9 @@ pub fn execute(
 	msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
 	let client = GrandpaClient::<HostFunctions>::default();
+	let client_id = client_id(&env)?;
 	let mut ctx = Context::<HostFunctions>::new(deps, env);
-	let client_id = ClientId::from_str("08-wasm-0").expect("client id is valid");
-	let result = process_message(msg, client, &mut ctx, client_id.clone());
+	let result = process_message(msg, client, &mut ctx, client_id);
 	let data = match result {
 		Ok(res) => res,
 		Err(ContractError::Grandpa(e)) => to_binary(&ContractResult::error(e))?,
@@ -231,7 +234,12 @@ fn process_message(
 				.map_err(|e| ContractError::Grandpa(e.to_string()))?;
 			let msg = UpdateStateOnMisbehaviourMsg::try_from(msg_raw)?;
 			client
-				.update_state_on_misbehaviour(client_state, msg.client_message)
+				.update_state_on_misbehaviour(
+					ctx,
+					client_id.clone(),
+					client_state,
+					msg.client_message,
+				)
 				.map_err(|e| ContractError::Grandpa(e.to_string()))
 				.and_then(|cs| {
 					ctx.store_client_state(client_id, cs)
@@ -247,13 +255,18 @@ fn process_message(
 
 			let finalized_headers = match &msg.client_message {
 				ClientMessage::Header(header) => {
-					use finality_grandpa::Chain;
 					let ancestry = AncestryChain::<RelayChainHeader>::new(
 						&header.finality_proof.unknown_headers,
 					);
 					let from = client_state.latest_relay_hash;
-					let mut finalized =
-						ancestry.ancestry(from, header.finality_proof.block).map_err(|_| {
+					let mut finalized = ancestry
+						.sparse_ancestry::<HostFunctions>(
+							client_state.latest_relay_height,
+							from,
+							header.finality_proof.block,
+							&header.ancestry_proofs,
+						)
+						.map_err(|_| {
 							ContractError::Grandpa(format!("[update_state] Invalid ancestry!"))
 						})?;
 					finalized.reverse();
@@ -284,7 +297,7 @@ fn process_message(
 			// all fields should be the same as in the old state, except for the `relay_chain`,
 			// `para_id`, `latest_para_height`, `latest_relay_height`, `frozen_height`,
 			// `current_authorities`, `current_set_id`
-			old_client_state.relay_chain = substitute_client_state.relay_chain;
+			old_client_state.relay_chain = substitute_client_state.relay_chain.clone();
 			old_client_state.para_id = substitute_client_state.para_id;
 			old_client_state.latest_para_height = substitute_client_state.latest_para_height;
 			old_client_state.latest_relay_height = substitute_client_state.latest_relay_height;
@@ -345,16 +358,27 @@ fn process_message(
 	Ok(result??)
 }
 
+/// The id of the client the contract is run for. The host passes it as the contract address.
+fn client_id(env: &Env) -> Result<ClientId, ContractError> {
+	ClientId::from_str(env.contract.address.as_str()).map_err(ContractError::from)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-	let client_id = ClientId::from_str("08-wasm-0").expect("client id is valid");
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+	let client_id = client_id(&env).map_err(|e| StdError::generic_err(e.to_string()))?;
 	match msg {
-		QueryMsg::ClientTypeMsg(_) => unimplemented!("ClientTypeMsg"),
-		QueryMsg::GetLatestHeightsMsg(_) => unimplemented!("GetLatestHeightsMsg"),
+		QueryMsg::ClientTypeMsg(ClientTypeMsg {}) =>
+			to_binary(&QueryResponse::client_type(ClientState::<HostFunctions>::client_type())),
+		QueryMsg::GetLatestHeightsMsg(GetLatestHeightsMsg {}) => {
+			let heights = CONSENSUS_STATES_HEIGHTS
+				.load(deps.storage, client_id.as_bytes().to_vec())
+				.unwrap_or_default();
+			to_binary(&QueryResponse::heights(heights.into_iter().rev().collect()))
+		},
 		QueryMsg::ExportMetadata(ExportMetadataMsg {}) =>
-			to_binary(&QueryResponse::genesis_metadata(None)),
+			to_binary(&QueryResponse::genesis_metadata(export_metadata(deps.storage, &client_id))),
 		QueryMsg::Status(StatusMsg {}) => {
-			let client_state = match get_client_state::<HostFunctions>(deps) {
+			let client_state = match get_client_state::<HostFunctions>(deps, &client_id) {
 				Ok(client_state) => client_state,
 				Err(_) => return to_binary(&QueryResponse::status("Unknown".to_string())),
 			};
@@ -372,6 +396,28 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 	}
 }
 
+/// The storage entries a new chain needs to import the client at genesis, besides its client and
+/// consensus states: the hashes of the finalized relay chain headers, used to check misbehaviour
+/// reports, and the heights of the consensus states.
+fn export_metadata(storage: &dyn Storage, client_id: &ClientId) -> Option<Vec<GenesisMetadata>> {
+	let raw_entry =
+		|key: &[u8]| storage.get(key).map(|value| GenesisMetadata { key: key.to_vec(), value });
+	let header_hashes = GRANDPA_HEADER_HASHES_STORAGE.load(storage).unwrap_or_default();
+	let metadata =
+		raw_entry(GRANDPA_HEADER_HASHES_KEY.as_bytes())
+			.into_iter()
+			.chain(header_hashes.iter().filter_map(|hash| {
+				raw_entry(&GRANDPA_HEADER_HASHES_SET_STORAGE.key(hash.0.to_vec()))
+			}))
+			.chain(raw_entry(&CONSENSUS_STATES_HEIGHTS.key(client_id.as_bytes().to_vec())))
+			.collect::<Vec<_>>();
+	if metadata.is_empty() {
+		None
+	} else {
+		Some(metadata)
+	}
+}
+
 fn store_client_and_consensus_states<H>(
 	ctx: &mut Context<H>,
 	client_id: ClientId,
@@ -446,3 +492,103 @@ pub extern "C" fn ext_hashing_twox_64_version_1(data: i64) -> i32 {
 	let out_ptr = Box::leak(hash).as_ptr();
 	out_ptr as i32
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cosmwasm_std::{
+		from_binary,
+		testing::{mock_dependencies, mock_env},
+		Addr,
+	};
+
+	/// An env of the contract run for the client `client_id`.
+	fn client_env(client_id: &str) -> Env {
+		let mut env = mock_env();
+		env.contract.address = Addr::unchecked(client_id);
+		env
+	}
+
+	fn query_response(deps: Deps, client_id: &str, msg: QueryMsg) -> QueryResponse {
+		from_binary(&query(deps, client_env(client_id), msg).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn queries_the_client_type() {
+		let deps = mock_dependencies();
+		let response =
+			query_response(deps.as_ref(), "08-wasm-3", QueryMsg::ClientTypeMsg(ClientTypeMsg {}));
+		assert_eq!(response.client_type, Some(ClientState::<HostFunctions>::client_type()));
+	}
+
+	#[test]
+	fn queries_the_consensus_state_heights_of_the_client_latest_first() {
+		let mut deps = mock_dependencies();
+		let heights = [1, 5, 3].into_iter().map(|height| Height::new(0, height)).collect();
+		CONSENSUS_STATES_HEIGHTS
+			.save(deps.as_mut().storage, b"08-wasm-3".to_vec(), &heights)
+			.unwrap();
+		CONSENSUS_STATES_HEIGHTS
+			.save(deps.as_mut().storage, b"08-wasm-0".to_vec(), &[Height::new(0, 9)].into())
+			.unwrap();
+
+		let query_heights = |client_id| {
+			query_response(
+				deps.as_ref(),
+				client_id,
+				QueryMsg::GetLatestHeightsMsg(GetLatestHeightsMsg {}),
+			)
+			.heights
+		};
+		assert_eq!(
+			query_heights("08-wasm-3"),
+			Some(vec![Height::new(0, 5), Height::new(0, 3), Height::new(0, 1)])
+		);
+		assert_eq!(query_heights("08-wasm-0"), Some(vec![Height::new(0, 9)]));
+		assert_eq!(query_heights("08-wasm-7"), Some(vec![]));
+	}
+
+	#[test]
+	fn exports_the_metadata_of_the_client() {
+		let mut deps = mock_dependencies();
+		let export = |deps: Deps, client_id| {
+			query_response(deps, client_id, QueryMsg::ExportMetadata(ExportMetadataMsg {}))
+				.genesis_metadata
+		};
+		assert_eq!(export(deps.as_ref(), "08-wasm-3"), None);
+
+		let hash = H256::repeat_byte(1);
+		GRANDPA_HEADER_HASHES_STORAGE.save(deps.as_mut().storage, &vec![hash]).unwrap();
+		GRANDPA_HEADER_HASHES_SET_STORAGE
+			.save(deps.as_mut().storage, hash.0.to_vec(), &())
+			.unwrap();
+		CONSENSUS_STATES_HEIGHTS
+			.save(deps.as_mut().storage, b"08-wasm-3".to_vec(), &[Height::new(0, 1)].into())
+			.unwrap();
+
+		let keys = |metadata: Option<Vec<GenesisMetadata>>| {
+			metadata.unwrap().into_iter().map(|entry| entry.key).collect::<Vec<_>>()
+		};
+		let header_hash_keys = vec![
+			GRANDPA_HEADER_HASHES_KEY.as_bytes().to_vec(),
+			GRANDPA_HEADER_HASHES_SET_STORAGE.key(hash.0.to_vec()).to_vec(),
+		];
+		assert_eq!(
+			keys(export(deps.as_ref(), "08-wasm-3")),
+			[
+				header_hash_keys.clone(),
+				vec![CONSENSUS_STATES_HEIGHTS.key(b"08-wasm-3".to_vec()).to_vec()]
+			]
+			.concat()
+		);
+		// the consensus state heights of other clients aren't exported
+		assert_eq!(keys(export(deps.as_ref(), "08-wasm-0")), header_hash_keys);
+	}
+
+	#[test]
+	fn queries_the_status_of_a_client_without_a_client_state() {
+		let deps = mock_dependencies();
+		let response = query_response(deps.as_ref(), "08-wasm-3", QueryMsg::Status(StatusMsg {}));
+		assert_eq!(response.status, "Unknown");
+	}
+}
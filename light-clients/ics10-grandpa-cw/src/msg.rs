@@ -60,15 +60,28 @@ pub struct QueryResponse {
 	pub status: String,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub genesis_metadata: Option<Vec<GenesisMetadata>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub client_type: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub heights: Option<Vec<Height>>,
 }
 
 impl QueryResponse {
 	pub fn status(status: String) -> Self {
-		Self { status, genesis_metadata: None }
+		Self { status, genesis_metadata: None, client_type: None, heights: None }
 	}
 
 	pub fn genesis_metadata(genesis_metadata: Option<Vec<GenesisMetadata>>) -> Self {
-		Self { status: "".to_string(), genesis_metadata }
+		Self { genesis_metadata, ..Self::status("".to_string()) }
+	}
+
+	pub fn client_type(client_type: String) -> Self {
+		Self { client_type: Some(client_type), ..Self::status("".to_string()) }
+	}
+
+	/// The heights of the stored consensus states, latest first.
+	pub fn heights(heights: Vec<Height>) -> Self {
+		Self { heights: Some(heights), ..Self::status("".to_string()) }
 	}
 }
 
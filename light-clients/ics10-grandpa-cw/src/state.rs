@@ -25,10 +25,13 @@ use ics10_grandpa::client_state::ClientState;
 use prost::Message;
 
 /// Retrieves raw bytes from storage and deserializes them into [`ClientState`]
-pub fn get_client_state<H: Clone>(deps: Deps) -> Result<ClientState<H>, Error> {
+pub fn get_client_state<H: Clone>(
+	deps: Deps,
+	client_id: &ClientId,
+) -> Result<ClientState<H>, Error> {
 	deps.storage
 		.get(&format!("clientState").into_bytes())
-		.ok_or_else(|| Error::unknown_client_state_type("08-wasm-0".to_string()))
+		.ok_or_else(|| Error::client_not_found(client_id.clone()))
 		.and_then(deserialize_client_state)
 }
 
@@ -25,7 +25,7 @@ use core::marker::PhantomData;
 use finality_grandpa::Chain;
 use grandpa_client_primitives::{
 	justification::{find_scheduled_change, AncestryChain, GrandpaJustification},
-	ParachainHeadersWithFinalityProof,
+	ParachainHeaderProofs, ParachainHeadersWithFinalityProof,
 };
 use ibc::{
 	core::{
@@ -93,6 +93,7 @@ where
 					finality_proof: header.finality_proof,
 					parachain_headers: header.parachain_headers,
 					latest_para_height: header.height.revision_height as u32,
+					ancestry_proofs: header.ancestry_proofs,
 				};
 
 				grandpa_client::verify_parachain_headers_with_grandpa_finality_proof::<
@@ -223,7 +224,12 @@ where
 		let from = client_state.latest_relay_hash;
 
 		let finalized = ancestry
-			.ancestry(from, header.finality_proof.block)
+			.sparse_ancestry::<H>(
+				client_state.latest_relay_height,
+				from,
+				header.finality_proof.block,
+				&header.ancestry_proofs,
+			)
 			.map_err(|_| Error::Custom(format!("[update_state] Invalid ancestry!")))?;
 		let mut finalized_sorted = finalized.clone();
 		finalized_sorted.sort();
@@ -239,11 +245,8 @@ where
 				Error::Custom(format!("No relay chain header found for hash: {relay_hash:?}"))
 			})?;
 
-			let (height, consensus_state) = ConsensusState::from_header::<H>(
-				parachain_header_proof,
-				client_state.para_id,
-				header.state_root.clone(),
-			)?;
+			let (height, consensus_state) =
+				consensus_state_from_header::<H>(&client_state, parachain_header_proof, header)?;
 
 			// Skip duplicate consensus states
 			if ctx.consensus_state(&client_id, height).is_ok() {
@@ -300,8 +303,10 @@ where
 		Ok((client_state, ConsensusUpdateResult::Batch(consensus_states)))
 	}
 
-	fn update_state_on_misbehaviour(
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
 		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
 		mut client_state: Self::ClientState,
 		_client_message: Self::ClientMessage,
 	) -> Result<Self::ClientState, Ics02Error> {
@@ -335,11 +340,8 @@ where
 				Error::Custom(format!("No relay chain header found for hash: {relay_hash:?}"))
 			})?;
 
-			let (height, consensus_state) = ConsensusState::from_header::<H>(
-				parachain_header_proof,
-				client_state.para_id,
-				header.state_root.clone(),
-			)?;
+			let (height, consensus_state) =
+				consensus_state_from_header::<H>(&client_state, parachain_header_proof, header)?;
 
 			match ctx.maybe_consensus_state(&client_id, height)? {
 				Some(cs) => {
@@ -466,18 +468,19 @@ where
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
 		root: &CommitmentRoot,
-		client_id: &ClientId,
+		counterparty_client_id: &ClientId,
 		consensus_height: Height,
 		expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Ics02Error> {
 		client_state.verify_height(height)?;
 		let path = ClientConsensusStatePath {
-			client_id: client_id.clone(),
+			client_id: counterparty_client_id.clone(),
 			epoch: consensus_height.revision_number,
 			height: consensus_height.revision_height,
 		};
@@ -531,16 +534,17 @@ where
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		client_state: &Self::ClientState,
 		height: Height,
 		prefix: &CommitmentPrefix,
 		proof: &CommitmentProofBytes,
 		root: &CommitmentRoot,
-		client_id: &ClientId,
+		counterparty_client_id: &ClientId,
 		expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Ics02Error> {
 		client_state.verify_height(height)?;
-		let path = ClientStatePath(client_id.clone());
+		let path = ClientStatePath(counterparty_client_id.clone());
 		let value = expected_client_state.encode_to_vec().map_err(Ics02Error::encode)?;
 		verify_membership::<H::BlakeTwo256, _>(prefix, proof, root, path, value)
 			.map_err(Error::Anyhow)?;
@@ -665,3 +669,19 @@ where
 		Ok(())
 	}
 }
+
+/// Builds the consensus state for a finalized header: the parachain header proven in its state for
+/// parachains, or the header itself for standalone chains.
+fn consensus_state_from_header<H>(
+	client_state: &ClientState<H>,
+	header_proof: ParachainHeaderProofs,
+	header: &RelayChainHeader,
+) -> Result<(Height, ConsensusState), Error>
+where
+	H: grandpa_client_primitives::HostFunctions,
+{
+	if client_state.standalone {
+		return ConsensusState::from_standalone_header(header_proof, client_state.para_id, header)
+	}
+	ConsensusState::from_header::<H>(header_proof, client_state.para_id, header.state_root)
+}
@@ -20,11 +20,12 @@ use crate::{
 		Misbehaviour as RawMisbehaviour,
 	},
 };
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::{collections::BTreeMap, string::ToString, vec::Vec};
 use anyhow::anyhow;
 use codec::{Decode, Encode};
 use grandpa_client_primitives::{FinalityProof, ParachainHeaderProofs};
 use ibc::Height;
+use ibc_proto::google::protobuf::Any;
 use sp_core::H256;
 use sp_runtime::traits::BlakeTwo256;
 use tendermint_proto::Protobuf;
@@ -47,6 +48,9 @@ pub struct Header {
 	/// finalzed at the relay chain height. We check for this parachain header finalization
 	/// via state proofs. Also contains extrinsic proof for timestamp.
 	pub parachain_headers: BTreeMap<H256, ParachainHeaderProofs>,
+	/// Proofs that link the relay chain headers of the finality proof that skip blocks, see
+	/// [`grandpa_client_primitives::ParachainHeadersWithFinalityProof::ancestry_proofs`].
+	pub ancestry_proofs: BTreeMap<H256, Vec<Vec<u8>>>,
 	/// Lazily initialized height
 	pub height: Height,
 }
@@ -78,6 +82,13 @@ pub enum ClientMessage {
 }
 
 impl ibc::core::ics02_client::client_message::ClientMessage for ClientMessage {
+	fn maybe_header_height(&self) -> Option<Height> {
+		match self {
+			ClientMessage::Header(header) => Some(header.height()),
+			ClientMessage::Misbehaviour(_) => None,
+		}
+	}
+
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
 		self.encode_vec()
 	}
@@ -117,6 +128,22 @@ impl TryFrom<RawHeader> for Header {
 			})
 			.collect::<Result<_, Error>>()?;
 
+		let ancestry_proofs = raw_header
+			.ancestry_proofs
+			.into_iter()
+			.map(|ancestry_proof| {
+				let block = if ancestry_proof.relay_hash.len() == 32 {
+					H256::from_slice(&*ancestry_proof.relay_hash)
+				} else {
+					Err(anyhow!(
+						"Invalid hash type with length: {}",
+						ancestry_proof.relay_hash.len()
+					))?
+				};
+				Ok((block, ancestry_proof.proof))
+			})
+			.collect::<Result<_, Error>>()?;
+
 		let unknown_headers = finality_proof
 			.unknown_headers
 			.into_iter()
@@ -133,6 +160,7 @@ impl TryFrom<RawHeader> for Header {
 				unknown_headers,
 			},
 			parachain_headers,
+			ancestry_proofs,
 			height: Height::new(raw_header.para_id as u64, raw_header.para_height as u64),
 		})
 	}
@@ -152,6 +180,14 @@ impl From<Header> for RawHeader {
 				}),
 			})
 			.collect();
+		let ancestry_proofs = header
+			.ancestry_proofs
+			.into_iter()
+			.map(|(hash, proof)| proto::AncestryProof {
+				relay_hash: hash.as_bytes().to_vec(),
+				proof,
+			})
+			.collect();
 		let finality_proof = proto::FinalityProof {
 			block: header.finality_proof.block.as_bytes().to_vec(),
 			justification: header.finality_proof.justification,
@@ -168,6 +204,7 @@ impl From<Header> for RawHeader {
 			parachain_headers,
 			para_id: header.height.revision_number as u32,
 			para_height: header.height.revision_height as u32,
+			ancestry_proofs,
 		}
 	}
 }
@@ -225,3 +262,40 @@ impl From<ClientMessage> for RawClientMessage {
 		}
 	}
 }
+
+impl TryFrom<Any> for ClientMessage {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		let msg = match &*any.type_url {
+			GRANDPA_CLIENT_MESSAGE_TYPE_URL =>
+				<Self as Protobuf<RawClientMessage>>::decode_vec(&any.value)
+					.map_err(|e| anyhow!("{e:?}"))?,
+			GRANDPA_HEADER_TYPE_URL =>
+				Self::Header(Header::decode_vec(&any.value).map_err(|e| anyhow!("{e:?}"))?),
+			GRANDPA_MISBEHAVIOUR_TYPE_URL => Self::Misbehaviour(
+				Misbehaviour::decode_vec(&any.value).map_err(|e| anyhow!("{e:?}"))?,
+			),
+			_ => Err(anyhow!("Unknown type: {}", any.type_url))?,
+		};
+
+		Ok(msg)
+	}
+}
+
+impl From<ClientMessage> for Any {
+	fn from(msg: ClientMessage) -> Self {
+		match msg {
+			ClientMessage::Header(header) => Any {
+				value: header.encode_vec().expect("failed to encode ClientMessage.header"),
+				type_url: GRANDPA_HEADER_TYPE_URL.to_string(),
+			},
+			ClientMessage::Misbehaviour(misbehaviour) => Any {
+				value: misbehaviour
+					.encode_vec()
+					.expect("failed to encode ClientMessage.misbehaviour"),
+				type_url: GRANDPA_MISBEHAVIOUR_TYPE_URL.to_string(),
+			},
+		}
+	}
+}
@@ -17,7 +17,10 @@ use crate::{
 	client_def::GrandpaClient,
 	client_message::RelayChainHeader,
 	error::Error,
-	proto::{Authority as RawAuthority, ClientState as RawClientState},
+	proto::{
+		Authority as RawAuthority, ClientState as RawClientState,
+		RelayChainParams as RawRelayChainParams,
+	},
 };
 use alloc::{format, string::ToString, vec::Vec};
 use anyhow::anyhow;
@@ -45,7 +48,7 @@ pub const GRANDPA_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.grandpa.v1.Cl
 
 #[derive(PartialEq, Clone, Debug, Default, Eq)]
 pub struct ClientState<H> {
-	/// Relay chain
+	/// Relay chain and the periods of the client
 	pub relay_chain: RelayChain,
 	// Latest relay chain height
 	pub latest_relay_height: u32,
@@ -61,6 +64,10 @@ pub struct ClientState<H> {
 	pub current_set_id: u64,
 	/// authorities for the current round
 	pub current_authorities: AuthorityList,
+	/// Whether the client tracks a standalone chain whose own headers are finalized by GRANDPA,
+	/// instead of a parachain. The relay chain fields then describe the chain itself, and
+	/// `para_id` is used as its revision number.
+	pub standalone: bool,
 	/// phantom type.
 	pub _phantom: PhantomData<H>,
 }
@@ -74,6 +81,7 @@ impl<H> From<ClientState<H>> for grandpa_client_primitives::ClientState {
 			latest_relay_height: client_state.latest_relay_height,
 			latest_para_height: client_state.latest_para_height,
 			para_id: client_state.para_id,
+			standalone: client_state.standalone,
 		}
 	}
 }
@@ -116,6 +124,9 @@ impl<H> ClientState<H> {
 	}
 
 	pub fn chain_id(&self) -> ChainId {
+		if self.standalone {
+			return ChainId::new("standalone".to_string(), self.para_id as u64)
+		}
 		ChainId::new(self.relay_chain.to_string(), self.para_id as u64)
 	}
 
@@ -140,10 +151,18 @@ impl<H> ClientState<H> {
 		self
 	}
 
+	pub fn unbonding_period(&self) -> Duration {
+		self.relay_chain.unbonding_period()
+	}
+
+	pub fn trusting_period(&self) -> Duration {
+		self.relay_chain.trusting_period()
+	}
+
 	/// Check if the state is expired when `elapsed` time has passed since the latest consensus
 	/// state timestamp
 	pub fn expired(&self, elapsed: Duration) -> bool {
-		elapsed > self.relay_chain.trusting_period()
+		elapsed > self.trusting_period()
 	}
 
 	pub fn with_frozen_height(self, h: Height) -> Result<Self, Error> {
@@ -210,6 +229,13 @@ where
 		self.frozen_height()
 	}
 
+	fn with_frozen_height(
+		self,
+		height: Height,
+	) -> Result<Self, ibc::core::ics02_client::error::Error> {
+		self.with_frozen_height(height).map_err(Into::into)
+	}
+
 	fn upgrade(
 		self,
 		upgrade_height: Height,
@@ -242,7 +268,11 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 			})
 			.collect::<Result<_, Error>>()?;
 
-		let relay_chain = RelayChain::from_i32(raw.relay_chain)?;
+		let relay_chain = RelayChain::from_proto(
+			raw.relay_chain,
+			raw.relay_chain_params
+				.map(|params| (params.name, params.unbonding_period, params.trusting_period)),
+		)?;
 		if raw.latest_relay_hash.len() != 32 {
 			Err(anyhow!("Invalid ed25519 public key lenght: {}", raw.latest_relay_hash.len()))?
 		}
@@ -259,6 +289,7 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 			current_authorities,
 			latest_relay_hash,
 			latest_relay_height: raw.latest_relay_height,
+			standalone: raw.standalone,
 			_phantom: Default::default(),
 		})
 	}
@@ -273,7 +304,12 @@ impl<H> From<ClientState<H>> for RawClientState {
 			frozen_height: client_state
 				.frozen_height
 				.map(|frozen_height| frozen_height.revision_height),
-			relay_chain: client_state.relay_chain as i32,
+			relay_chain: client_state.relay_chain.to_i32(),
+			relay_chain_params: Some(RawRelayChainParams {
+				name: client_state.relay_chain.name.clone(),
+				unbonding_period: client_state.relay_chain.unbonding_period.as_secs(),
+				trusting_period: client_state.relay_chain.trusting_period.as_secs(),
+			}),
 			para_id: client_state.para_id,
 			latest_para_height: client_state.latest_para_height,
 			current_authorities: client_state
@@ -285,6 +321,7 @@ impl<H> From<ClientState<H>> for RawClientState {
 					weight,
 				})
 				.collect(),
+			standalone: client_state.standalone,
 		}
 	}
 }
@@ -76,18 +76,38 @@ impl ConsensusState {
 		let parachain_header =
 			generic::Header::<u32, BlakeTwo256>::decode(&mut &parachain_header_bytes[..])?;
 		let root = parachain_header.state_root.0.to_vec();
-
-		let timestamp = decode_timestamp_extrinsic(&parachain_header_proof.extrinsic)?;
-		let duration = core::time::Duration::from_millis(timestamp);
-		let timestamp = Timestamp::from_nanoseconds(duration.as_nanos().saturated_into::<u64>())?
-			.into_tm_time()
-			.ok_or_else(|| anyhow!("Error decoding Timestamp, timestamp cannot be zero"))?;
+		let timestamp = decode_timestamp(&parachain_header_proof.extrinsic)?;
 
 		Ok((
 			Height::new(para_id as u64, parachain_header.number as u64),
 			Self { root: root.into(), timestamp },
 		))
 	}
+
+	/// Builds the consensus state of a standalone chain's header that was finalized by GRANDPA,
+	/// whose height uses `para_id` as the revision number.
+	pub fn from_standalone_header(
+		header_proof: ParachainHeaderProofs,
+		para_id: u32,
+		header: &generic::Header<u32, BlakeTwo256>,
+	) -> Result<(Height, Self), Error> {
+		let root = header.state_root.0.to_vec();
+		let timestamp = decode_timestamp(&header_proof.extrinsic)?;
+
+		Ok((
+			Height::new(para_id as u64, header.number as u64),
+			Self { root: root.into(), timestamp },
+		))
+	}
+}
+
+fn decode_timestamp(extrinsic: &[u8]) -> Result<Time, Error> {
+	let timestamp = decode_timestamp_extrinsic(extrinsic)?;
+	let duration = core::time::Duration::from_millis(timestamp);
+	let timestamp = Timestamp::from_nanoseconds(duration.as_nanos().saturated_into::<u64>())?
+		.into_tm_time()
+		.ok_or_else(|| anyhow!("Error decoding Timestamp, timestamp cannot be zero"))?;
+	Ok(timestamp)
 }
 
 impl ibc::core::ics02_client::client_consensus::ConsensusState for ConsensusState {
@@ -51,7 +51,7 @@ use ibc::{
 	test_utils::get_dummy_account_id,
 	Height,
 };
-use light_client_common::config::RuntimeStorage;
+use light_client_common::{config::RuntimeStorage, RelayChain};
 use sp_core::{hexdisplay::AsBytesRef, H256};
 use std::time::Duration;
 use subxt::config::substrate::{BlakeTwo256, SubstrateHeader};
@@ -148,6 +148,7 @@ async fn test_continuous_update_of_grandpa_client() {
 			para_id: prover.para_id,
 			current_set_id: client_state.current_set_id,
 			current_authorities: client_state.current_authorities,
+			standalone: false,
 			_phantom: Default::default(),
 		};
 		let subxt_block_number: subxt::rpc::types::BlockNumber = decoded_para_head.number.into();
@@ -261,6 +262,7 @@ async fn test_continuous_update_of_grandpa_client() {
 		let header = Header {
 			finality_proof: proof.finality_proof,
 			parachain_headers: proof.parachain_headers.clone(),
+			ancestry_proofs: proof.ancestry_proofs.clone(),
 			height: Height::new(prover.para_id as u64, finalized_para_header.number as u64),
 		};
 		let msg = MsgUpdateAnyClient {
@@ -312,3 +314,48 @@ async fn test_continuous_update_of_grandpa_client() {
 		}
 	}
 }
+
+#[test]
+fn test_standalone_client_state_periods() {
+	let client_state = ClientState::<HostFunctionsManager> {
+		relay_chain: RelayChain::new(
+			"Westend",
+			Duration::from_secs(3 * 60 * 60),
+			Duration::from_secs(60 * 60),
+		)
+		.unwrap(),
+		latest_relay_height: 10,
+		latest_para_height: 10,
+		para_id: 1,
+		standalone: true,
+		..Default::default()
+	};
+
+	let raw = crate::proto::ClientState::from(client_state.clone());
+	let decoded = ClientState::<HostFunctionsManager>::try_from(raw.clone()).unwrap();
+	assert_eq!(decoded, client_state);
+	assert_eq!(decoded.chain_id(), ChainId::new("standalone".to_string(), 1));
+	assert!(!decoded.expired(Duration::from_secs(60 * 60)));
+	assert!(decoded.expired(Duration::from_secs(60 * 60 + 1)));
+
+	// the trusting period must be shorter than the unbonding period
+	let params = raw.relay_chain_params.clone().unwrap();
+	let invalid = crate::proto::ClientState {
+		relay_chain_params: Some(crate::proto::RelayChainParams {
+			trusting_period: 3 * 60 * 60,
+			..params
+		}),
+		..raw.clone()
+	};
+	assert!(ClientState::<HostFunctionsManager>::try_from(invalid).is_err());
+
+	// relay chains that aren't well-known must have parameters
+	let invalid = crate::proto::ClientState { relay_chain_params: None, ..raw.clone() };
+	assert!(ClientState::<HostFunctionsManager>::try_from(invalid).is_err());
+
+	// client states encoded before relay chains were configurable use the well-known periods
+	let legacy = crate::proto::ClientState { relay_chain: 0, relay_chain_params: None, ..raw };
+	let decoded = ClientState::<HostFunctionsManager>::try_from(legacy).unwrap();
+	assert_eq!(decoded.relay_chain, RelayChain::polkadot());
+	assert_eq!(decoded.trusting_period(), RelayChain::polkadot().unbonding_period / 3);
+}
@@ -73,6 +73,12 @@ pub struct ParachainHeadersWithProof {
 }
 
 impl ibc::core::ics02_client::client_message::ClientMessage for ClientMessage {
+	/// Always `None`: beefy client heights are revisioned by the parachain id, which is only known
+	/// to the client state.
+	fn maybe_header_height(&self) -> Option<ibc::Height> {
+		None
+	}
+
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
 		self.encode_vec()
 	}
@@ -23,7 +23,9 @@ use sp_core::H256;
 use sp_runtime::SaturatedConversion;
 use tendermint_proto::Protobuf;
 
-use crate::proto::{BeefyAuthoritySet, ClientState as RawClientState};
+use crate::proto::{
+	BeefyAuthoritySet, ClientState as RawClientState, RelayChainParams as RawRelayChainParams,
+};
 
 use crate::{client_message::BeefyHeader, error::Error};
 
@@ -42,7 +44,7 @@ pub const BEEFY_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.beefy.v1.Client
 pub struct ClientState<H> {
 	/// The chain id
 	pub chain_id: ChainId,
-	/// Relay chain
+	/// Relay chain and the periods of the client
 	pub relay_chain: RelayChain,
 	/// Latest mmr root hash
 	pub mmr_root_hash: H256,
@@ -81,6 +83,7 @@ impl<H: Clone> ClientState<H> {
 					.to_string(),
 			))
 		}
+		relay_chain.validate()?;
 		let chain_id = ChainId::new(relay_chain.to_string(), para_id.into());
 
 		Ok(Self {
@@ -248,6 +251,13 @@ where
 		self.frozen_height()
 	}
 
+	fn with_frozen_height(
+		self,
+		height: Height,
+	) -> Result<Self, ibc::core::ics02_client::error::Error> {
+		self.with_frozen_height(height).map_err(Into::into)
+	}
+
 	fn upgrade(
 		self,
 		upgrade_height: Height,
@@ -293,7 +303,11 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 			.ok_or_else(|| Error::Custom(format!("Next authority set is missing")))?;
 
 		let mmr_root_hash = H256::decode(&mut &*raw.mmr_root_hash)?;
-		let relay_chain = RelayChain::from_i32(raw.relay_chain)?;
+		let relay_chain = RelayChain::from_proto(
+			raw.relay_chain,
+			raw.relay_chain_params
+				.map(|params| (params.name, params.unbonding_period, params.trusting_period)),
+		)?;
 		let chain_id = ChainId::new(relay_chain.to_string(), raw.para_id.into());
 
 		Ok(Self {
@@ -329,7 +343,12 @@ impl<H> From<ClientState<H>> for RawClientState {
 				len: client_state.next_authority_set.len,
 				authority_root: client_state.next_authority_set.root.encode(),
 			}),
-			relay_chain: client_state.relay_chain as i32,
+			relay_chain: client_state.relay_chain.to_i32(),
+			relay_chain_params: Some(RawRelayChainParams {
+				name: client_state.relay_chain.name.clone(),
+				unbonding_period: client_state.relay_chain.unbonding_period.as_secs(),
+				trusting_period: client_state.relay_chain.trusting_period.as_secs(),
+			}),
 			para_id: client_state.para_id,
 			latest_para_height: client_state.latest_para_height,
 		}
@@ -344,7 +363,7 @@ pub mod test_util {
 	pub fn get_dummy_beefy_state() -> AnyClientState {
 		AnyClientState::Beefy(
 			ClientState::new(
-				RelayChain::Rococo,
+				RelayChain::rococo(),
 				2000,
 				0,
 				Default::default(),
@@ -0,0 +1,539 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generates the fixtures of the ics12-ethereum tests in `../src/fixtures`.
+//!
+//! The data is synthetic: the sync committees are made of deterministic keys, the beacon states
+//! only contain the fields the light client proves, and the execution state holds a made-up IBC
+//! contract with a few filler accounts. The blocks are signed with the genesis validators root
+//! and the fork schedule of mainnet, so that the domain computation is the same as on mainnet.
+//!
+//! Run with `cargo run --release` from this directory.
+
+use alloy_trie::{proof::ProofRetainer, HashBuilder, Nibbles};
+use blst::min_pk::{AggregatePublicKey, AggregateSignature, SecretKey};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::collections::BTreeMap;
+
+type Hash = [u8; 32];
+
+const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// Genesis time and genesis validators root of mainnet.
+const GENESIS_TIME: u64 = 1606824023;
+const GENESIS_VALIDATORS_ROOT: &str =
+	"4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95";
+/// The epochs and versions of the mainnet forks, from Altair to Deneb.
+const FORKS: [(u64, u8); 4] = [(74240, 1), (144896, 2), (194048, 3), (269568, 4)];
+const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+const SYNC_COMMITTEE_SIZE: usize = 512;
+const SLOTS_PER_EPOCH: u64 = 32;
+const SLOTS_PER_SYNC_COMMITTEE_PERIOD: u64 = 8192;
+/// The execution block numbers are derived from the slots with this offset.
+const BLOCK_NUMBER_OFFSET: u64 = 11_350_000;
+const IBC_ADDRESS: &str = "7a2f1e6b9c4d0a5e3f8b2c1d6e9a0f4b3c7d8e5a";
+const IBC_COMMITMENTS_SLOT: u8 = 3;
+
+// generalized indices in the beacon state and block body
+const CURRENT_SYNC_COMMITTEE_INDEX: u64 = 54;
+const NEXT_SYNC_COMMITTEE_INDEX: u64 = 55;
+const FINALIZED_CHECKPOINT_EPOCH_INDEX: u64 = 104;
+const FINALIZED_ROOT_INDEX: u64 = 105;
+const EXECUTION_PAYLOAD_INDEX: u64 = 25;
+
+fn sha256(data: &[u8]) -> Hash {
+	Sha256::digest(data).into()
+}
+
+fn keccak_256(data: &[u8]) -> Hash {
+	Keccak256::digest(data).into()
+}
+
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+	let mut data = left.to_vec();
+	data.extend(right);
+	sha256(&data)
+}
+
+fn merkleize(chunks: &[Hash], limit: usize) -> Hash {
+	let width = limit.max(chunks.len()).next_power_of_two();
+	let mut layer = chunks.to_vec();
+	layer.resize(width, [0; 32]);
+	while layer.len() > 1 {
+		layer = layer.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+	}
+	layer[0]
+}
+
+fn u64_chunk(value: u64) -> Hash {
+	let mut chunk = [0; 32];
+	chunk[..8].copy_from_slice(&value.to_le_bytes());
+	chunk
+}
+
+fn pack(bytes: &[u8]) -> Vec<Hash> {
+	bytes
+		.chunks(32)
+		.map(|bytes| {
+			let mut chunk = [0; 32];
+			chunk[..bytes.len()].copy_from_slice(bytes);
+			chunk
+		})
+		.collect()
+}
+
+/// Deterministic stand-in for the fields whose content doesn't matter to the light client.
+fn filler(tag: &str) -> Hash {
+	sha256(tag.as_bytes())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+/// A merkle tree where only the given leaves are known, every other subtree is a filler hash.
+struct SparseTree {
+	leaves: BTreeMap<u64, Hash>,
+	tag: String,
+}
+
+fn depth(index: u64) -> u32 {
+	63 - index.leading_zeros()
+}
+
+impl SparseTree {
+	fn has_leaf_under(&self, index: u64) -> bool {
+		self.leaves.keys().any(|&leaf| {
+			depth(leaf) >= depth(index) && (leaf >> (depth(leaf) - depth(index))) == index
+		})
+	}
+
+	fn node(&self, index: u64) -> Hash {
+		if let Some(leaf) = self.leaves.get(&index) {
+			return *leaf
+		}
+		if self.has_leaf_under(index) {
+			hash_pair(&self.node(2 * index), &self.node(2 * index + 1))
+		} else {
+			filler(&format!("{}/{}", self.tag, index))
+		}
+	}
+
+	fn root(&self) -> Hash {
+		self.node(1)
+	}
+
+	fn branch(&self, mut index: u64) -> Vec<String> {
+		let mut branch = vec![];
+		while index > 1 {
+			branch.push(to_hex(&self.node(index ^ 1)));
+			index >>= 1;
+		}
+		branch
+	}
+}
+
+struct Committee {
+	secret_keys: Vec<SecretKey>,
+	public_keys: Vec<[u8; 48]>,
+	aggregate_public_key: [u8; 48],
+}
+
+impl Committee {
+	fn new(period: u32) -> Self {
+		let secret_keys = (0..SYNC_COMMITTEE_SIZE)
+			.map(|i| {
+				let ikm =
+					sha256(format!("ics12-ethereum sync committee {period} member {i}").as_bytes());
+				SecretKey::key_gen(&ikm, &[]).unwrap()
+			})
+			.collect::<Vec<_>>();
+		let public_keys = secret_keys.iter().map(|key| key.sk_to_pk()).collect::<Vec<_>>();
+		let aggregate_public_key =
+			AggregatePublicKey::aggregate(&public_keys.iter().collect::<Vec<_>>(), false)
+				.unwrap()
+				.to_public_key()
+				.compress();
+		Committee {
+			secret_keys,
+			public_keys: public_keys.iter().map(|key| key.compress()).collect(),
+			aggregate_public_key,
+		}
+	}
+
+	fn root(&self) -> Hash {
+		let leaves =
+			self.public_keys.iter().map(|key| merkleize(&pack(key), 2)).collect::<Vec<_>>();
+		hash_pair(
+			&merkleize(&leaves, SYNC_COMMITTEE_SIZE),
+			&merkleize(&pack(&self.aggregate_public_key), 2),
+		)
+	}
+
+	fn json(&self) -> Value {
+		json!({
+			"pubkeys": self.public_keys.iter().map(|key| to_hex(key)).collect::<Vec<_>>(),
+			"aggregate_pubkey": to_hex(&self.aggregate_public_key),
+		})
+	}
+}
+
+struct Block {
+	beacon: Value,
+	execution: Value,
+	execution_branch: Vec<String>,
+	root: Hash,
+	state: SparseTree,
+	slot: u64,
+}
+
+impl Block {
+	/// Builds the beacon block of `slot`, with an execution payload committing to
+	/// `execution_state_root` and a beacon state with the given leaves.
+	fn new(slot: u64, execution_state_root: Hash, state_leaves: BTreeMap<u64, Hash>) -> Block {
+		let block_number = slot + BLOCK_NUMBER_OFFSET;
+		let timestamp = GENESIS_TIME + slot * 12;
+		let tag = |field: &str| filler(&format!("{field}/{slot}"));
+		let fee_recipient = &tag("fee_recipient")[..20];
+		let logs_bloom = vec![0u8; 256];
+		let extra_data = b"beaverbuild.org".to_vec();
+		let gas_limit = 30_000_000u64;
+		let gas_used = 12_000_000 + (slot % 1000) * 9_973;
+		let base_fee: u128 = 20_000_000_000 + (slot % 97) as u128 * 1_000_000;
+		let mut base_fee_chunk = [0u8; 32];
+		base_fee_chunk[..16].copy_from_slice(&base_fee.to_le_bytes());
+		let fields = [
+			tag("parent_hash"),
+			merkleize(&pack(fee_recipient), 1),
+			execution_state_root,
+			tag("receipts_root"),
+			merkleize(&pack(&logs_bloom), 8),
+			tag("prev_randao"),
+			u64_chunk(block_number),
+			u64_chunk(gas_limit),
+			u64_chunk(gas_used),
+			u64_chunk(timestamp),
+			hash_pair(&merkleize(&pack(&extra_data), 1), &u64_chunk(extra_data.len() as u64)),
+			base_fee_chunk,
+			tag("block_hash"),
+			tag("transactions_root"),
+			tag("withdrawals_root"),
+		];
+		let execution_root = merkleize(&fields, 16);
+		let execution = json!({
+			"parent_hash": to_hex(&fields[0]),
+			"fee_recipient": to_hex(fee_recipient),
+			"state_root": to_hex(&execution_state_root),
+			"receipts_root": to_hex(&fields[3]),
+			"logs_bloom": to_hex(&logs_bloom),
+			"prev_randao": to_hex(&fields[5]),
+			"block_number": block_number.to_string(),
+			"gas_limit": gas_limit.to_string(),
+			"gas_used": gas_used.to_string(),
+			"timestamp": timestamp.to_string(),
+			"extra_data": to_hex(&extra_data),
+			"base_fee_per_gas": base_fee.to_string(),
+			"block_hash": to_hex(&fields[12]),
+			"transactions_root": to_hex(&fields[13]),
+			"withdrawals_root": to_hex(&fields[14]),
+		});
+		let body = SparseTree {
+			leaves: BTreeMap::from([(EXECUTION_PAYLOAD_INDEX, execution_root)]),
+			tag: format!("body/{slot}"),
+		};
+		let state = SparseTree { leaves: state_leaves, tag: format!("state/{slot}") };
+		let proposer_index = slot * 7919 % 900_000;
+		let parent_root = tag("parent_root");
+		let state_root = state.root();
+		let body_root = body.root();
+		let root = merkleize(
+			&[u64_chunk(slot), u64_chunk(proposer_index), parent_root, state_root, body_root],
+			8,
+		);
+		let beacon = json!({
+			"slot": slot.to_string(),
+			"proposer_index": proposer_index.to_string(),
+			"parent_root": to_hex(&parent_root),
+			"state_root": to_hex(&state_root),
+			"body_root": to_hex(&body_root),
+		});
+		Block {
+			beacon,
+			execution,
+			execution_branch: body.branch(EXECUTION_PAYLOAD_INDEX),
+			root,
+			state,
+			slot,
+		}
+	}
+
+	fn header(&self) -> Value {
+		json!({
+			"beacon": self.beacon,
+			"execution": self.execution,
+			"execution_branch": self.execution_branch,
+		})
+	}
+}
+
+fn fork_version(epoch: u64) -> [u8; 4] {
+	let version = FORKS
+		.iter()
+		.rev()
+		.find(|(fork_epoch, _)| *fork_epoch <= epoch)
+		.map(|(_, version)| *version)
+		.unwrap_or(0);
+	[version, 0, 0, 0]
+}
+
+/// Signs the attested block with every member of the committee but one in 17.
+fn sign(committee: &Committee, attested: &Block, signature_slot: u64) -> Value {
+	let mut version = [0u8; 32];
+	version[..4].copy_from_slice(&fork_version((signature_slot - 1) / SLOTS_PER_EPOCH));
+	let genesis_validators_root: Hash =
+		hex::decode(GENESIS_VALIDATORS_ROOT).unwrap().try_into().unwrap();
+	let fork_data_root = hash_pair(&version, &genesis_validators_root);
+	let mut domain = [0u8; 32];
+	domain[..4].copy_from_slice(&DOMAIN_SYNC_COMMITTEE);
+	domain[4..].copy_from_slice(&fork_data_root[..28]);
+	let signing_root = hash_pair(&attested.root, &domain);
+	let mut bits = [0u8; SYNC_COMMITTEE_SIZE / 8];
+	let mut signatures = vec![];
+	for i in 0..SYNC_COMMITTEE_SIZE {
+		if i % 17 == 0 {
+			continue
+		}
+		bits[i / 8] |= 1 << (i % 8);
+		signatures.push(committee.secret_keys[i].sign(&signing_root, DST, &[]));
+	}
+	let signature = AggregateSignature::aggregate(&signatures.iter().collect::<Vec<_>>(), false)
+		.unwrap()
+		.to_signature()
+		.compress();
+	json!({ "sync_committee_bits": to_hex(&bits), "sync_committee_signature": to_hex(&signature) })
+}
+
+/// Builds a Merkle-Patricia trie of the entries, returns its root and the proofs of the targets.
+fn trie(
+	entries: &BTreeMap<Hash, Vec<u8>>,
+	targets: &[Hash],
+) -> (Hash, BTreeMap<Hash, Vec<String>>) {
+	let retainer =
+		ProofRetainer::new(targets.iter().map(|target| Nibbles::unpack(target)).collect());
+	let mut builder = HashBuilder::default().with_proof_retainer(retainer);
+	for (key, value) in entries {
+		builder.add_leaf(Nibbles::unpack(key), value);
+	}
+	let root: Hash = builder.root().0;
+	let nodes = builder.take_proof_nodes();
+	let proofs = targets
+		.iter()
+		.map(|target| {
+			let proof = nodes
+				.matching_nodes_sorted(&Nibbles::unpack(target))
+				.into_iter()
+				.map(|(_, node)| to_hex(&node))
+				.collect();
+			(*target, proof)
+		})
+		.collect();
+	(root, proofs)
+}
+
+fn trim_leading_zeros(value: &Hash) -> Vec<u8> {
+	let first = value.iter().position(|byte| *byte != 0).unwrap_or(32);
+	value[first..].to_vec()
+}
+
+/// Storage slot of the commitment at `path` in the commitments mapping of the IBC contract.
+fn commitment_slot(path: &str) -> Hash {
+	let mut key = b"ibc".to_vec();
+	key.extend(path.as_bytes());
+	let mut slot = keccak_256(&key).to_vec();
+	let mut commitments_slot = [0u8; 32];
+	commitments_slot[31] = IBC_COMMITMENTS_SLOT;
+	slot.extend(commitments_slot);
+	keccak_256(&slot)
+}
+
+/// Builds the execution state of the block and returns its root together with the
+/// `eth_getProof` response for the IBC contract.
+fn execution_state(
+	block_number: u64,
+	commitments: &[(&str, Hash)],
+	absent: &[&str],
+) -> (Hash, Value) {
+	let mut storage = BTreeMap::new();
+	for i in 0..24u64 {
+		let slot = keccak_256(format!("ibc storage {i}").as_bytes());
+		let value = keccak_256(format!("ibc value {i} at {}", block_number / 1000).as_bytes());
+		storage.insert(slot, value);
+	}
+	for (path, commitment) in commitments {
+		storage.insert(commitment_slot(path), keccak_256(commitment));
+	}
+	let storage_entries = storage
+		.iter()
+		.map(|(slot, value)| {
+			(keccak_256(slot), rlp::encode(&trim_leading_zeros(value).as_slice()).to_vec())
+		})
+		.collect::<BTreeMap<_, _>>();
+	let slots = commitments
+		.iter()
+		.map(|(path, _)| commitment_slot(path))
+		.chain(absent.iter().map(|path| commitment_slot(path)))
+		.collect::<Vec<_>>();
+	let (storage_root, storage_proofs) =
+		trie(&storage_entries, &slots.iter().map(|slot| keccak_256(slot)).collect::<Vec<_>>());
+
+	let code_hash = keccak_256(b"ibc contract code");
+	let nonce = 1u64;
+	let account = |nonce: u64, balance: u64, storage_root: &Hash, code_hash: &Hash| {
+		let mut stream = rlp::RlpStream::new_list(4);
+		stream.append(&nonce);
+		stream.append(&balance);
+		stream.append(&storage_root.as_slice());
+		stream.append(&code_hash.as_slice());
+		stream.out().to_vec()
+	};
+	let ibc_address = hex::decode(IBC_ADDRESS).unwrap();
+	let mut accounts = BTreeMap::new();
+	accounts.insert(keccak_256(&ibc_address), account(nonce, 0, &storage_root, &code_hash));
+	let empty_root = keccak_256(&rlp::encode(&"".as_bytes()));
+	let empty_code = keccak_256(&[]);
+	for i in 0..64u64 {
+		let address = &keccak_256(format!("account {i}").as_bytes())[..20];
+		let balance = u64::from_be_bytes(
+			keccak_256(format!("balance {i} {block_number}").as_bytes())[..8]
+				.try_into()
+				.unwrap(),
+		) >> 8;
+		accounts.insert(keccak_256(address), account(i * 3, balance, &empty_root, &empty_code));
+	}
+	let (state_root, account_proofs) = trie(&accounts, &[keccak_256(&ibc_address)]);
+	let storage_proof = slots
+		.iter()
+		.map(|slot| {
+			let value = storage.get(slot).map(trim_leading_zeros).unwrap_or_default();
+			let value = if value.is_empty() {
+				"0x0".to_string()
+			} else {
+				format!("0x{}", hex::encode(&value).trim_start_matches('0'))
+			};
+			json!({
+				"key": to_hex(slot),
+				"value": value,
+				"proof": storage_proofs[&keccak_256(slot)],
+			})
+		})
+		.collect::<Vec<_>>();
+	let proof = json!({
+		"address": to_hex(&ibc_address),
+		"accountProof": account_proofs[&keccak_256(&ibc_address)],
+		"balance": "0x0",
+		"codeHash": to_hex(&code_hash),
+		"nonce": format!("0x{nonce:x}"),
+		"storageHash": to_hex(&storage_root),
+		"storageProof": storage_proof,
+	});
+	(state_root, proof)
+}
+
+fn main() {
+	let out = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/fixtures");
+	let committees = [Committee::new(0), Committee::new(1), Committee::new(2)];
+	let committee_roots = committees.iter().map(|committee| committee.root()).collect::<Vec<_>>();
+	let base = 800 * SLOTS_PER_SYNC_COMMITTEE_PERIOD;
+	let commitment = sha256(b"packet commitment of transfer/channel-0/1");
+	println!("packet commitment: {}", hex::encode(commitment));
+	let path = "commitments/ports/transfer/channels/channel-0/sequences/1";
+	let absent = "commitments/ports/transfer/channels/channel-0/sequences/2";
+
+	let mut proofs = serde_json::Map::new();
+	let mut execution_state_root = |slot: u64, commitments: &[(&str, Hash)], absent: &[&str]| {
+		let block_number = slot + BLOCK_NUMBER_OFFSET;
+		let (root, proof) = execution_state(block_number, commitments, absent);
+		proofs.insert(block_number.to_string(), proof);
+		root
+	};
+	let state = |current: usize, next: usize, finalized: Option<&Block>| {
+		let mut leaves = BTreeMap::from([
+			(CURRENT_SYNC_COMMITTEE_INDEX, committee_roots[current]),
+			(NEXT_SYNC_COMMITTEE_INDEX, committee_roots[next]),
+		]);
+		if let Some(finalized) = finalized {
+			leaves.insert(
+				FINALIZED_CHECKPOINT_EPOCH_INDEX,
+				u64_chunk(finalized.slot / SLOTS_PER_EPOCH),
+			);
+			leaves.insert(FINALIZED_ROOT_INDEX, finalized.root);
+		}
+		leaves
+	};
+
+	let bootstrap =
+		Block::new(base + 64, execution_state_root(base + 64, &[], &[path]), state(0, 1, None));
+	let bootstrap_json = json!({
+		"version": "capella",
+		"data": {
+			"header": bootstrap.header(),
+			"current_sync_committee": committees[0].json(),
+			"current_sync_committee_branch": bootstrap.state.branch(CURRENT_SYNC_COMMITTEE_INDEX),
+		}
+	});
+	println!("bootstrap root: {}", hex::encode(bootstrap.root));
+
+	// The first update finalizes a block of period 800 and brings the committee of period 801,
+	// which signs the second update with the packet commitment in the finalized execution state.
+	// Only the finalized blocks have an execution state with proofs, the attested ones have a
+	// placeholder state root.
+	let mut updates = vec![];
+	let finalized_1 =
+		Block::new(base + 4096, execution_state_root(base + 4096, &[], &[path]), state(0, 1, None));
+	let attested_1 = Block::new(base + 4160, [0x11; 32], state(0, 1, Some(&finalized_1)));
+	let period_801 = base + SLOTS_PER_SYNC_COMMITTEE_PERIOD;
+	let finalized_2 = Block::new(
+		period_801 + 96,
+		execution_state_root(period_801 + 96, &[(path, commitment)], &[absent]),
+		state(1, 2, None),
+	);
+	let attested_2 = Block::new(period_801 + 160, [0x22; 32], state(1, 2, Some(&finalized_2)));
+	for (attested, finalized, next, signer) in
+		[(&attested_1, &finalized_1, 1, 0), (&attested_2, &finalized_2, 2, 1)]
+	{
+		let signature_slot = attested.slot + 1;
+		updates.push(json!({
+			"version": "capella",
+			"data": {
+				"attested_header": attested.header(),
+				"next_sync_committee": committees[next].json(),
+				"next_sync_committee_branch": attested.state.branch(NEXT_SYNC_COMMITTEE_INDEX),
+				"finalized_header": finalized.header(),
+				"finality_branch": attested.state.branch(FINALIZED_ROOT_INDEX),
+				"sync_aggregate": sign(&committees[signer], attested, signature_slot),
+				"signature_slot": signature_slot.to_string(),
+			}
+		}));
+	}
+
+	let write = |name: &str, value: &Value| {
+		std::fs::write(format!("{out}/{name}"), serde_json::to_string_pretty(value).unwrap() + "\n")
+			.unwrap()
+	};
+	write("bootstrap.json", &bootstrap_json);
+	write("updates.json", &Value::Array(updates));
+	write("proofs.json", &Value::Object(proofs));
+}
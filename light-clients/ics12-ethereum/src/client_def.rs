@@ -0,0 +1,419 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::{fmt::Debug, marker::PhantomData};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	client_message::{ClientMessage, Misbehaviour},
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+	error::Error,
+	proof::{verify_membership, verify_non_membership},
+	verify::{verify_light_client_update, verify_signed_update},
+	HostFunctions,
+};
+use ibc::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as _,
+			client_def::{ClientDef, ConsensusUpdateResult},
+			client_state::ClientState as _,
+			error::Error as Ics02Error,
+		},
+		ics03_connection::connection::ConnectionEnd,
+		ics04_channel::{
+			channel::ChannelEnd,
+			commitment::{AcknowledgementCommitment, PacketCommitment},
+			packet::Sequence,
+		},
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
+				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+			},
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	prelude::*,
+	Height,
+};
+use light_client_common::verify_delay_passed;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct EthereumClient<T>(PhantomData<T>);
+
+impl<H> ClientDef for EthereumClient<H>
+where
+	H: HostFunctions,
+{
+	type ClientMessage = ClientMessage;
+	type ClientState = ClientState<H>;
+	type ConsensusState = ConsensusState;
+
+	fn verify_client_message<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		message: Self::ClientMessage,
+	) -> Result<(), Ics02Error> {
+		match message {
+			ClientMessage::Header(header) => {
+				verify_light_client_update(&client_state, &header.update)?;
+				// the account proof is verified while extracting the storage root
+				ConsensusState::from_header::<H>(&header, &client_state.ibc_address)?;
+			},
+			ClientMessage::Misbehaviour(Misbehaviour { first_update, second_update }) => {
+				let first_header = &first_update.finalized_header.beacon;
+				let second_header = &second_update.finalized_header.beacon;
+				if first_header.slot != second_header.slot || first_header == second_header {
+					Err(Error::Custom(format!(
+						"Misbehaviour must finalize different headers at the same slot, first: {first_header:?}, second: {second_header:?}"
+					)))?
+				}
+				verify_signed_update(&client_state, &first_update)?;
+				verify_signed_update(&client_state, &second_update)?;
+			},
+		}
+		Ok(())
+	}
+
+	fn update_state<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: ClientId,
+		client_state: Self::ClientState,
+		message: Self::ClientMessage,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		let header = match message {
+			ClientMessage::Header(header) => header,
+			_ => unreachable!(
+				"02-client will check for misbehaviour before calling update_state; qed"
+			),
+		};
+		let consensus_state = ConsensusState::from_header::<H>(&header, &client_state.ibc_address)?;
+		let height = header.height();
+		let client_state = client_state.from_update(&header.update);
+
+		let mut consensus_states = vec![];
+		// Skip duplicate consensus states
+		if ctx.consensus_state(&client_id, height).is_err() {
+			consensus_states.push((
+				height,
+				Ctx::AnyConsensusState::wrap(&consensus_state)
+					.ok_or_else(|| Error::Custom("Ctx::AnyConsensusState".to_string()))?,
+			));
+		}
+
+		Ok((client_state, ConsensusUpdateResult::Batch(consensus_states)))
+	}
+
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		mut client_state: Self::ClientState,
+		_header: Self::ClientMessage,
+	) -> Result<Self::ClientState, Ics02Error> {
+		client_state.frozen_height = Some(client_state.latest_height());
+		Ok(client_state)
+	}
+
+	fn check_for_misbehaviour<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: ClientId,
+		client_state: Self::ClientState,
+		message: Self::ClientMessage,
+	) -> Result<bool, Ics02Error> {
+		match message {
+			ClientMessage::Header(header) => {
+				// we simply check that this update doesn't include a competing consensus state for
+				// a height we already processed.
+				let consensus_state =
+					ConsensusState::from_header::<H>(&header, &client_state.ibc_address)?;
+				match ctx.maybe_consensus_state(&client_id, header.height())? {
+					Some(cs) => {
+						let cs: ConsensusState =
+							cs.downcast().ok_or(Ics02Error::client_args_type_mismatch(
+								client_state.client_type().to_owned(),
+							))?;
+
+						Ok(cs != consensus_state)
+					},
+					None => Ok(false),
+				}
+			},
+			// the updates of the misbehaviour were verified to finalize conflicting headers
+			ClientMessage::Misbehaviour(_) => Ok(true),
+		}
+	}
+
+	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_old_client_state: &Self::ClientState,
+		_upgrade_client_state: &Self::ClientState,
+		_upgrade_consensus_state: &Self::ConsensusState,
+		_proof_upgrade_client: Vec<u8>,
+		_proof_upgrade_consensus_state: Vec<u8>,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		Err(Error::Custom("Ethereum Client doesn't need client upgrades".to_string()).into())
+	}
+
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		_old_client_state: Self::ClientState,
+		_substitute_client_state: Self::ClientState,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		Err(Error::Custom("Ethereum Client doesn't need client upgrades".to_string()).into())
+	}
+
+	fn verify_client_consensus_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		counterparty_client_id: &ClientId,
+		consensus_height: Height,
+		expected_consensus_state: &Ctx::AnyConsensusState,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ClientConsensusStatePath {
+			client_id: counterparty_client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let value = expected_consensus_state.encode_to_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			prefix,
+			proof,
+			root,
+			path,
+			value,
+		)?;
+		Ok(())
+	}
+
+	// Consensus state will be verified in the verification functions  before these are called
+	fn verify_connection_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			prefix,
+			proof,
+			root,
+			path,
+			value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_channel_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			prefix,
+			proof,
+			root,
+			path,
+			value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_client_full_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		counterparty_client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ClientStatePath(counterparty_client_id.clone());
+		let value = expected_client_state.encode_to_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			prefix,
+			proof,
+			root,
+			path,
+			value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_data<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+
+		verify_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			commitment_path,
+			commitment.into_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: AcknowledgementCommitment,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			ack_path,
+			ack.into_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_next_sequence_recv<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let seq_bytes = codec::Encode::encode(&u64::from(sequence));
+
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		verify_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			seq_path,
+			seq_bytes,
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_non_membership::<H, _>(
+			&client_state.ibc_commitments_slot,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			receipt_path,
+		)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,200 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	error::Error,
+	proto::{
+		client_message, ClientMessage as RawClientMessage, Header as RawHeader,
+		Misbehaviour as RawMisbehaviour,
+	},
+	types::LightClientUpdate,
+};
+use alloc::{string::ToString, vec::Vec};
+use anyhow::anyhow;
+use ibc::Height;
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+/// Protobuf type url for Ethereum header
+pub const ETHEREUM_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.lightclients.ethereum.v1.ClientMessage";
+pub const ETHEREUM_HEADER_TYPE_URL: &str = "/ibc.lightclients.ethereum.v1.Header";
+pub const ETHEREUM_MISBEHAVIOUR_TYPE_URL: &str = "/ibc.lightclients.ethereum.v1.Misbehaviour";
+
+/// A finalized beacon chain header with the proof of the IBC contract account in the execution
+/// state of its block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+	/// Light client update that finalizes the header
+	pub update: LightClientUpdate,
+	/// Merkle-Patricia trie nodes of the proof of the IBC contract account in the state of the
+	/// finalized execution block
+	pub account_proof: Vec<Vec<u8>>,
+}
+
+impl Header {
+	/// Height of the finalized execution block
+	pub fn height(&self) -> Height {
+		Height::new(0, self.update.finalized_header.execution.block_number)
+	}
+}
+
+/// Misbehaviour type for Ethereum. If both updates are valid and finalize different beacon chain
+/// headers at the same slot, the sync committee has signed conflicting checkpoints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Misbehaviour {
+	pub first_update: LightClientUpdate,
+	pub second_update: LightClientUpdate,
+}
+
+/// [`ClientMessage`] for ICS12-ETHEREUM
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientMessage {
+	/// Header variant for updating the client
+	Header(Header),
+	/// Misbehaviour variant for freezing the client.
+	Misbehaviour(Misbehaviour),
+}
+
+impl ibc::core::ics02_client::client_message::ClientMessage for ClientMessage {
+	fn maybe_header_height(&self) -> Option<Height> {
+		match self {
+			ClientMessage::Header(header) => Some(header.height()),
+			ClientMessage::Misbehaviour(_) => None,
+		}
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<RawHeader> for Header {}
+
+impl TryFrom<RawHeader> for Header {
+	type Error = Error;
+
+	fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
+		Ok(Header {
+			update: raw
+				.update
+				.ok_or_else(|| anyhow!("Light client update is required!"))?
+				.try_into()?,
+			account_proof: raw.account_proof,
+		})
+	}
+}
+
+impl From<Header> for RawHeader {
+	fn from(header: Header) -> Self {
+		RawHeader { update: Some(header.update.into()), account_proof: header.account_proof }
+	}
+}
+
+impl Protobuf<RawMisbehaviour> for Misbehaviour {}
+
+impl TryFrom<RawMisbehaviour> for Misbehaviour {
+	type Error = Error;
+
+	fn try_from(raw: RawMisbehaviour) -> Result<Self, Self::Error> {
+		Ok(Misbehaviour {
+			first_update: raw
+				.first_update
+				.ok_or_else(|| anyhow!("First light client update is required!"))?
+				.try_into()?,
+			second_update: raw
+				.second_update
+				.ok_or_else(|| anyhow!("Second light client update is required!"))?
+				.try_into()?,
+		})
+	}
+}
+
+impl From<Misbehaviour> for RawMisbehaviour {
+	fn from(misbehaviour: Misbehaviour) -> Self {
+		RawMisbehaviour {
+			first_update: Some(misbehaviour.first_update.into()),
+			second_update: Some(misbehaviour.second_update.into()),
+		}
+	}
+}
+
+impl Protobuf<RawClientMessage> for ClientMessage {}
+
+impl TryFrom<RawClientMessage> for ClientMessage {
+	type Error = Error;
+
+	fn try_from(raw_client_message: RawClientMessage) -> Result<Self, Self::Error> {
+		let message = match raw_client_message
+			.message
+			.ok_or_else(|| anyhow!("Must supply either Header or Misbehaviour type!"))?
+		{
+			client_message::Message::Header(raw_header) =>
+				ClientMessage::Header(Header::try_from(raw_header)?),
+			client_message::Message::Misbehaviour(raw_misbehaviour) =>
+				ClientMessage::Misbehaviour(Misbehaviour::try_from(raw_misbehaviour)?),
+		};
+
+		Ok(message)
+	}
+}
+
+impl From<ClientMessage> for RawClientMessage {
+	fn from(client_message: ClientMessage) -> Self {
+		match client_message {
+			ClientMessage::Header(header) =>
+				RawClientMessage { message: Some(client_message::Message::Header(header.into())) },
+			ClientMessage::Misbehaviour(misbehaviour) => RawClientMessage {
+				message: Some(client_message::Message::Misbehaviour(misbehaviour.into())),
+			},
+		}
+	}
+}
+
+impl TryFrom<Any> for ClientMessage {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		let msg = match &*any.type_url {
+			ETHEREUM_CLIENT_MESSAGE_TYPE_URL =>
+				<Self as Protobuf<RawClientMessage>>::decode_vec(&any.value)
+					.map_err(|e| anyhow!("{e:?}"))?,
+			ETHEREUM_HEADER_TYPE_URL =>
+				Self::Header(Header::decode_vec(&any.value).map_err(|e| anyhow!("{e:?}"))?),
+			ETHEREUM_MISBEHAVIOUR_TYPE_URL => Self::Misbehaviour(
+				Misbehaviour::decode_vec(&any.value).map_err(|e| anyhow!("{e:?}"))?,
+			),
+			_ => Err(anyhow!("Unknown type: {}", any.type_url))?,
+		};
+
+		Ok(msg)
+	}
+}
+
+impl From<ClientMessage> for Any {
+	fn from(msg: ClientMessage) -> Self {
+		match msg {
+			ClientMessage::Header(header) => Any {
+				value: header.encode_vec().expect("failed to encode ClientMessage.header"),
+				type_url: ETHEREUM_HEADER_TYPE_URL.to_string(),
+			},
+			ClientMessage::Misbehaviour(misbehaviour) => Any {
+				value: misbehaviour
+					.encode_vec()
+					.expect("failed to encode ClientMessage.misbehaviour"),
+				type_url: ETHEREUM_MISBEHAVIOUR_TYPE_URL.to_string(),
+			},
+		}
+	}
+}
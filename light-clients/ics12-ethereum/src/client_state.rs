@@ -0,0 +1,266 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::string::ToString;
+use core::{convert::TryFrom, fmt::Debug, marker::PhantomData, time::Duration};
+use ibc::prelude::*;
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256};
+use tendermint_proto::Protobuf;
+
+use crate::proto::ClientState as RawClientState;
+
+use crate::{
+	client_def::EthereumClient,
+	error::Error,
+	types::{
+		compute_sync_committee_period_at_slot, decode_hash, ForkParameters, LightClientUpdate,
+		SyncCommittee,
+	},
+};
+
+use ibc::{
+	core::{ics02_client::client_state::ClientType, ics24_host::identifier::ChainId},
+	Height,
+};
+
+/// Protobuf type url for Ethereum ClientState
+pub const ETHEREUM_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.ethereum.v1.ClientState";
+
+#[derive(PartialEq, Clone, Debug, Eq)]
+pub struct ClientState<H> {
+	/// EIP-155 chain id of the execution layer
+	pub chain_id: u64,
+	/// Root of the validators at the genesis of the beacon chain
+	pub genesis_validators_root: H256,
+	/// Forks of the beacon chain
+	pub fork_parameters: ForkParameters,
+	/// Slot of the latest finalized beacon chain header
+	pub finalized_slot: u64,
+	/// Execution block number of the latest finalized beacon chain header
+	pub latest_execution_height: u64,
+	/// Sync committee of the period of the latest finalized header
+	pub current_sync_committee: SyncCommittee,
+	/// Sync committee of the next period, if it is known
+	pub next_sync_committee: Option<SyncCommittee>,
+	/// Address of the IBC contract
+	pub ibc_address: H160,
+	/// Storage slot of the mapping of the commitments in the IBC contract
+	pub ibc_commitments_slot: H256,
+	/// Period within which the client must be updated
+	pub trusting_period: Duration,
+	/// Block height when the client was frozen due to a misbehaviour
+	pub frozen_height: Option<Height>,
+	/// Phantom type
+	pub _phantom: PhantomData<H>,
+}
+
+impl<H: Clone> Protobuf<RawClientState> for ClientState<H> {}
+
+impl<H: Clone> ClientState<H> {
+	/// Should only be called if this update has been verified successfully
+	pub fn from_update(mut self, update: &LightClientUpdate) -> Self {
+		let store_period = compute_sync_committee_period_at_slot(self.finalized_slot);
+		let finalized_period =
+			compute_sync_committee_period_at_slot(update.finalized_header.beacon.slot);
+		match self.next_sync_committee.take() {
+			None if finalized_period == store_period =>
+				self.next_sync_committee = update.next_sync_committee.clone(),
+			// the sync committees rotate once a header of the next period is finalized
+			Some(next_sync_committee) if finalized_period == store_period + 1 => {
+				self.current_sync_committee = next_sync_committee;
+				self.next_sync_committee = update.next_sync_committee.clone();
+			},
+			next_sync_committee => self.next_sync_committee = next_sync_committee,
+		}
+		if update.finalized_header.beacon.slot > self.finalized_slot {
+			self.finalized_slot = update.finalized_header.beacon.slot;
+			self.latest_execution_height =
+				self.latest_execution_height.max(update.finalized_header.execution.block_number);
+		}
+		self
+	}
+
+	pub fn with_frozen_height(self, h: Height) -> Result<Self, Error> {
+		if h == Height::zero() {
+			return Err(Error::Custom(
+				"ClientState frozen height must be greater than zero".to_string(),
+			))
+		}
+		Ok(Self { frozen_height: Some(h), ..self })
+	}
+
+	/// Verify that the client is at a sufficient height and unfrozen at the given height
+	pub fn verify_height(&self, height: Height) -> Result<(), Error> {
+		let latest_height = self.latest_height();
+		if latest_height < height {
+			return Err(Error::Custom(format!(
+				"Insufficient height, known height: {latest_height}, given height: {height}"
+			)))
+		}
+
+		match self.frozen_height {
+			Some(frozen_height) if frozen_height <= height =>
+				Err(Error::Custom(format!("Client has been frozen at height {frozen_height}"))),
+			_ => Ok(()),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeOptions;
+
+impl<H> ClientState<H> {
+	pub fn latest_height(&self) -> Height {
+		Height::new(0, self.latest_execution_height)
+	}
+
+	pub fn chain_id(&self) -> ChainId {
+		ChainId::from_string(&self.chain_id.to_string())
+	}
+
+	pub fn client_type() -> ClientType {
+		"12-ethereum".to_string()
+	}
+
+	pub fn frozen_height(&self) -> Option<Height> {
+		self.frozen_height
+	}
+
+	pub fn upgrade(
+		mut self,
+		_upgrade_height: Height,
+		_upgrade_options: UpgradeOptions,
+		_chain_id: ChainId,
+	) -> Self {
+		self.frozen_height = None;
+		self
+	}
+
+	/// Check if the state is expired when `elapsed` time has passed since the latest consensus
+	/// state timestamp
+	pub fn expired(&self, elapsed: Duration) -> bool {
+		elapsed > self.trusting_period
+	}
+}
+
+impl<H> ibc::core::ics02_client::client_state::ClientState for ClientState<H>
+where
+	H: crate::HostFunctions,
+{
+	type UpgradeOptions = UpgradeOptions;
+	type ClientDef = EthereumClient<H>;
+
+	fn chain_id(&self) -> ChainId {
+		self.chain_id()
+	}
+
+	fn client_def(&self) -> Self::ClientDef {
+		EthereumClient::default()
+	}
+
+	fn client_type(&self) -> ClientType {
+		Self::client_type()
+	}
+
+	fn latest_height(&self) -> Height {
+		self.latest_height()
+	}
+
+	fn frozen_height(&self) -> Option<Height> {
+		self.frozen_height()
+	}
+
+	fn with_frozen_height(
+		self,
+		height: Height,
+	) -> Result<Self, ibc::core::ics02_client::error::Error> {
+		self.with_frozen_height(height).map_err(Into::into)
+	}
+
+	fn upgrade(
+		self,
+		upgrade_height: Height,
+		upgrade_options: UpgradeOptions,
+		chain_id: ChainId,
+	) -> Self {
+		self.upgrade(upgrade_height, upgrade_options, chain_id)
+	}
+
+	fn expired(&self, elapsed: Duration) -> bool {
+		self.expired(elapsed)
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl<H> TryFrom<RawClientState> for ClientState<H> {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		let fork_parameters = raw
+			.fork_parameters
+			.ok_or_else(|| Error::Custom("Fork parameters are missing".to_string()))?
+			.try_into()?;
+		let current_sync_committee = raw
+			.current_sync_committee
+			.ok_or_else(|| Error::Custom("Current sync committee is missing".to_string()))?
+			.try_into()?;
+		let next_sync_committee = raw.next_sync_committee.map(TryInto::try_into).transpose()?;
+		if raw.ibc_contract_address.len() != 20 {
+			return Err(Error::Custom(format!(
+				"Invalid IBC contract address length: {}",
+				raw.ibc_contract_address.len()
+			)))
+		}
+
+		Ok(Self {
+			chain_id: raw.chain_id,
+			genesis_validators_root: decode_hash(&raw.genesis_validators_root)?,
+			fork_parameters,
+			finalized_slot: raw.finalized_slot,
+			latest_execution_height: raw.latest_execution_height,
+			current_sync_committee,
+			next_sync_committee,
+			ibc_address: H160::from_slice(&raw.ibc_contract_address),
+			ibc_commitments_slot: decode_hash(&raw.ibc_commitments_slot)?,
+			trusting_period: Duration::from_secs(raw.trusting_period),
+			frozen_height: raw.frozen_height.map(|height| Height::new(0, height)),
+			_phantom: Default::default(),
+		})
+	}
+}
+
+impl<H> From<ClientState<H>> for RawClientState {
+	fn from(client_state: ClientState<H>) -> Self {
+		RawClientState {
+			chain_id: client_state.chain_id,
+			genesis_validators_root: client_state.genesis_validators_root.as_bytes().to_vec(),
+			fork_parameters: Some(client_state.fork_parameters.into()),
+			finalized_slot: client_state.finalized_slot,
+			latest_execution_height: client_state.latest_execution_height,
+			current_sync_committee: Some(client_state.current_sync_committee.into()),
+			next_sync_committee: client_state.next_sync_committee.map(Into::into),
+			ibc_contract_address: client_state.ibc_address.as_bytes().to_vec(),
+			ibc_commitments_slot: client_state.ibc_commitments_slot.as_bytes().to_vec(),
+			trusting_period: client_state.trusting_period.as_secs(),
+			frozen_height: client_state
+				.frozen_height
+				.map(|frozen_height| frozen_height.revision_height),
+		}
+	}
+}
@@ -0,0 +1,107 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use core::{convert::Infallible, fmt::Debug};
+use serde::Serialize;
+use sp_core::H160;
+use tendermint::time::Time;
+use tendermint_proto::{google::protobuf as tpb, Protobuf};
+
+use crate::proto::ConsensusState as RawConsensusState;
+
+use crate::{client_message::Header, error::Error, proof::verify_storage_root, HostFunctions};
+use ibc::{core::ics23_commitment::commitment::CommitmentRoot, timestamp::Timestamp};
+
+/// Protobuf type url for Ethereum Consensus State
+pub const ETHEREUM_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.ethereum.v1.ConsensusState";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ConsensusState {
+	pub timestamp: Time,
+	/// Storage root of the IBC contract
+	pub root: CommitmentRoot,
+}
+
+impl ConsensusState {
+	pub fn new(root: Vec<u8>, timestamp: Time) -> Self {
+		Self { timestamp, root: root.into() }
+	}
+
+	/// Consensus state of the finalized execution block of the header, whose root is the storage
+	/// root of the IBC contract at `ibc_address` proven by the account proof of the header.
+	pub fn from_header<H: HostFunctions>(
+		header: &Header,
+		ibc_address: &H160,
+	) -> Result<Self, Error> {
+		let execution = &header.update.finalized_header.execution;
+		let root =
+			verify_storage_root::<H>(&execution.state_root, ibc_address, &header.account_proof)?;
+		let nanoseconds = execution
+			.timestamp
+			.checked_mul(1_000_000_000)
+			.ok_or_else(|| Error::Custom("Timestamp overflowed!".to_string()))?;
+		let timestamp =
+			Timestamp::from_nanoseconds(nanoseconds)?.into_tm_time().ok_or_else(|| {
+				Error::Custom("Error decoding Timestamp, timestamp cannot be zero".to_string())
+			})?;
+
+		Ok(Self { root: root.as_bytes().to_vec().into(), timestamp })
+	}
+}
+
+impl ibc::core::ics02_client::client_consensus::ConsensusState for ConsensusState {
+	type Error = Infallible;
+
+	fn root(&self) -> &CommitmentRoot {
+		&self.root
+	}
+
+	fn timestamp(&self) -> Timestamp {
+		self.timestamp.into()
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+	type Error = Error;
+
+	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+		let prost_types::Timestamp { seconds, nanos } = raw
+			.timestamp
+			.ok_or_else(|| Error::Custom(format!("Invalid consensus state: missing timestamp")))?;
+		let proto_timestamp = tpb::Timestamp { seconds, nanos };
+		let timestamp = proto_timestamp.try_into().map_err(|e| {
+			Error::Custom(format!("Invalid consensus state: invalid timestamp {e}"))
+		})?;
+
+		Ok(Self { root: raw.root.into(), timestamp })
+	}
+}
+
+impl From<ConsensusState> for RawConsensusState {
+	fn from(value: ConsensusState) -> Self {
+		let tpb::Timestamp { seconds, nanos } = value.timestamp.into();
+		let timestamp = prost_types::Timestamp { seconds, nanos };
+
+		RawConsensusState { timestamp: Some(timestamp), root: value.root.into_vec() }
+	}
+}
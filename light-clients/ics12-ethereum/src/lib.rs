@@ -0,0 +1,62 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::all)]
+
+//! ICS-12: Ethereum sync committee IBC light client protocol implementation.
+//!
+//! The client follows the finalized beacon chain headers signed by Ethereum's sync committees, as
+//! specified by the Altair light client protocol, and uses the storage root of the IBC contract in
+//! the execution state of each finalized block as the commitment root of its consensus states.
+
+extern crate alloc;
+extern crate core;
+
+use core::fmt::Debug;
+use types::{BlsPublicKey, BlsSignature};
+
+pub mod client_def;
+pub mod client_message;
+pub mod client_state;
+pub mod consensus_state;
+pub mod error;
+pub mod proof;
+mod proto;
+pub mod ssz;
+pub mod types;
+pub mod verify;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Host functions that allow the light client perform cryptographic operations in native.
+pub trait HostFunctions: Clone + Send + Sync + Eq + Debug + Default {
+	/// Sha256 hash function, used by the merkleization of the beacon chain types
+	fn sha256(input: &[u8]) -> [u8; 32];
+
+	/// Keccak 256 hash function, used by the Merkle-Patricia tries of the execution layer
+	fn keccak_256(input: &[u8]) -> [u8; 32];
+
+	/// Verifies that `signature` is the aggregate of the BLS12-381 signatures of `message` by all
+	/// the `public_keys`, using the proof of possession scheme of Ethereum.
+	fn bls12_381_fast_aggregate_verify(
+		public_keys: &[BlsPublicKey],
+		message: &[u8; 32],
+		signature: &BlsSignature,
+	) -> bool;
+}
@@ -0,0 +1,229 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of the Merkle-Patricia trie proofs of the execution layer, which prove the storage
+//! root of the IBC contract and the commitments in its storage.
+
+use crate::{error::Error, HostFunctions};
+use alloc::{collections::BTreeMap, format, string::ToString, vec, vec::Vec};
+use ibc::core::{
+	ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+	ics24_host::Path,
+};
+use rlp::Rlp;
+use sp_core::{H160, H256};
+
+/// Root of the empty trie, the keccak 256 hash of the rlp encoding of an empty string
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+	0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+	0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Reads the value of `key` in the trie with the given root out of the trie nodes in `proof`.
+/// Returns `None` if the proof shows that the key isn't in the trie.
+pub fn read_proof<H: HostFunctions>(
+	root: &H256,
+	key: &[u8],
+	proof: &[Vec<u8>],
+) -> Result<Option<Vec<u8>>, Error> {
+	if *root == EMPTY_TRIE_ROOT {
+		return Ok(None)
+	}
+	let nodes = proof
+		.iter()
+		.map(|node| (H256::from(H::keccak_256(node)), node.as_slice()))
+		.collect::<BTreeMap<_, _>>();
+	let lookup = |hash: &H256| {
+		nodes
+			.get(hash)
+			.copied()
+			.ok_or_else(|| Error::Custom(format!("Trie node {hash:?} is missing from the proof")))
+	};
+	let nibbles = to_nibbles(key);
+	let mut offset = 0;
+	let mut node = lookup(root)?;
+	loop {
+		let rlp = Rlp::new(node);
+		let child = match rlp.item_count()? {
+			// branch node
+			17 => match nibbles.get(offset) {
+				Some(nibble) => {
+					offset += 1;
+					rlp.at(*nibble as usize)?
+				},
+				None => {
+					let value = rlp.at(16)?.data()?;
+					return Ok((!value.is_empty()).then(|| value.to_vec()))
+				},
+			},
+			// leaf or extension node
+			2 => {
+				let (path, is_leaf) = decode_path(rlp.at(0)?.data()?)?;
+				if !nibbles[offset..].starts_with(&path) {
+					return Ok(None)
+				}
+				offset += path.len();
+				if is_leaf {
+					if offset != nibbles.len() {
+						return Ok(None)
+					}
+					return Ok(Some(rlp.at(1)?.data()?.to_vec()))
+				}
+				rlp.at(1)?
+			},
+			count => return Err(Error::Custom(format!("Invalid trie node with {count} items"))),
+		};
+		// nodes shorter than a hash are embedded in their parent
+		node = if child.is_list() {
+			child.as_raw()
+		} else {
+			match child.data()? {
+				[] => return Ok(None),
+				hash if hash.len() == 32 => lookup(&H256::from_slice(hash))?,
+				hash => return Err(Error::Custom(format!("Invalid trie node hash {hash:?}"))),
+			}
+		};
+	}
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+	bytes.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+/// Decodes the hex prefix encoded path of a leaf or extension node, returns its nibbles and
+/// whether the node is a leaf.
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+	let first = *encoded
+		.first()
+		.ok_or_else(|| Error::Custom("Trie node has an empty path".to_string()))?;
+	let flag = first >> 4;
+	if flag > 3 {
+		return Err(Error::Custom(format!("Invalid trie node path flag {flag}")))
+	}
+	let mut nibbles = if flag & 1 == 1 { vec![first & 0x0f] } else { vec![] };
+	nibbles.extend(to_nibbles(&encoded[1..]));
+	Ok((nibbles, flag & 2 == 2))
+}
+
+/// Proves the storage root of the account with the given address in the state trie.
+pub fn verify_storage_root<H: HostFunctions>(
+	state_root: &H256,
+	address: &H160,
+	proof: &[Vec<u8>],
+) -> Result<H256, Error> {
+	let account = read_proof::<H>(state_root, &H::keccak_256(address.as_bytes()), proof)?
+		.ok_or_else(|| Error::Custom(format!("Account {address:?} is not in the state trie")))?;
+	// accounts are encoded as [nonce, balance, storage_root, code_hash]
+	let account = Rlp::new(&account);
+	if account.item_count()? != 4 {
+		return Err(Error::Custom(format!("Invalid account {address:?}")))
+	}
+	let storage_root = account.at(2)?.data()?;
+	if storage_root.len() != 32 {
+		return Err(Error::Custom(format!("Invalid storage root of account {address:?}")))
+	}
+	Ok(H256::from_slice(storage_root))
+}
+
+/// Storage slot of the commitment of `path` in the IBC contract. The contract keeps the
+/// commitments in a `mapping(bytes32 => bytes32)` at `commitments_slot`, keyed by the keccak 256
+/// hash of the prefixed path.
+pub fn commitment_slot<H: HostFunctions>(
+	commitments_slot: &H256,
+	prefix: &CommitmentPrefix,
+	path: &Path,
+) -> H256 {
+	let mut key = prefix.as_bytes().to_vec();
+	key.extend(path.to_string().as_bytes());
+	let mut slot = H::keccak_256(&key).to_vec();
+	slot.extend(commitments_slot.as_bytes());
+	H::keccak_256(&slot).into()
+}
+
+/// Reads the commitment of `path` out of the storage proof of the IBC contract.
+fn read_commitment<H: HostFunctions>(
+	commitments_slot: &H256,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: &Path,
+) -> Result<Option<H256>, Error> {
+	if root.as_bytes().len() != 32 {
+		return Err(Error::Custom(format!(
+			"invalid commitment root length: {}",
+			root.as_bytes().len()
+		)))
+	}
+	let proof: Vec<Vec<u8>> = codec::Decode::decode(&mut proof.as_bytes())?;
+	let slot = commitment_slot::<H>(commitments_slot, prefix, path);
+	let value = read_proof::<H>(
+		&H256::from_slice(root.as_bytes()),
+		&H::keccak_256(slot.as_bytes()),
+		&proof,
+	)?;
+	value
+		.map(|value| {
+			// storage values are rlp encoded without their leading zeroes
+			let value = Rlp::new(&value).data()?.to_vec();
+			if value.len() > 32 {
+				return Err(Error::Custom(format!("Invalid storage value for path: {path}")))
+			}
+			let mut commitment = H256::zero();
+			commitment[32 - value.len()..].copy_from_slice(&value);
+			Ok(commitment)
+		})
+		.transpose()
+}
+
+/// Membership proof verification of the keccak 256 hash of `value` in the storage of the IBC
+/// contract
+pub fn verify_membership<H, P>(
+	commitments_slot: &H256,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+	value: Vec<u8>,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let path: Path = path.into();
+	let commitment = read_commitment::<H>(commitments_slot, prefix, proof, root, &path)?;
+	if commitment != Some(H::keccak_256(&value).into()) {
+		return Err(Error::Custom(format!("Failed to verify proof for path: {path}")))
+	}
+	Ok(())
+}
+
+/// Non-membership proof verification of `path` in the storage of the IBC contract
+pub fn verify_non_membership<H, P>(
+	commitments_slot: &H256,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let path: Path = path.into();
+	if read_commitment::<H>(commitments_slot, prefix, proof, root, &path)?.is_some() {
+		return Err(Error::Custom(format!("Found a commitment for path: {path}")))
+	}
+	Ok(())
+}
@@ -0,0 +1,110 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The parts of SSZ merkleization needed to compute the roots of the beacon chain types that the
+//! light client verifies, see <https://github.com/ethereum/consensus-specs/blob/dev/ssz/simple-serialize.md#merkleization>.
+
+use crate::HostFunctions;
+use alloc::vec::Vec;
+use sp_core::H256;
+
+/// Number of bytes in a merkleization chunk
+pub const BYTES_PER_CHUNK: usize = 32;
+
+/// Hash of the concatenation of two nodes of a merkle tree
+pub fn hash_pair<H: HostFunctions>(left: &H256, right: &H256) -> H256 {
+	let mut input = [0u8; 2 * BYTES_PER_CHUNK];
+	input[..BYTES_PER_CHUNK].copy_from_slice(left.as_bytes());
+	input[BYTES_PER_CHUNK..].copy_from_slice(right.as_bytes());
+	H::sha256(&input).into()
+}
+
+/// Root of the merkle tree of the chunks, padded with zero chunks up to the next power of two of
+/// `limit` leaves.
+pub fn merkleize<H: HostFunctions>(chunks: &[H256], limit: usize) -> H256 {
+	let mut width = limit.max(chunks.len()).next_power_of_two();
+	let mut layer = chunks.to_vec();
+	let mut zero_hash = H256::zero();
+	while width > 1 {
+		if layer.len() % 2 == 1 {
+			layer.push(zero_hash);
+		}
+		layer = layer.chunks(2).map(|pair| hash_pair::<H>(&pair[0], &pair[1])).collect();
+		zero_hash = hash_pair::<H>(&zero_hash, &zero_hash);
+		width /= 2;
+	}
+	layer.first().copied().unwrap_or(zero_hash)
+}
+
+/// Mixes the length of a list into the root of its elements.
+pub fn mix_in_length<H: HostFunctions>(root: &H256, length: usize) -> H256 {
+	hash_pair::<H>(root, &uint64(length as u64))
+}
+
+/// Splits the bytes into chunks, the last one padded with zeroes.
+pub fn pack(bytes: &[u8]) -> Vec<H256> {
+	bytes
+		.chunks(BYTES_PER_CHUNK)
+		.map(|chunk| {
+			let mut padded = H256::zero();
+			padded[..chunk.len()].copy_from_slice(chunk);
+			padded
+		})
+		.collect()
+}
+
+/// Root of an unsigned 64 bit integer
+pub fn uint64(value: u64) -> H256 {
+	let mut chunk = H256::zero();
+	chunk[..8].copy_from_slice(&value.to_le_bytes());
+	chunk
+}
+
+/// Root of a byte vector with a fixed length
+pub fn byte_vector<H: HostFunctions>(bytes: &[u8]) -> H256 {
+	let chunks = pack(bytes);
+	merkleize::<H>(&chunks, chunks.len())
+}
+
+/// Root of a byte list of at most `max_length` bytes
+pub fn byte_list<H: HostFunctions>(bytes: &[u8], max_length: usize) -> H256 {
+	let limit = (max_length + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK;
+	mix_in_length::<H>(&merkleize::<H>(&pack(bytes), limit), bytes.len())
+}
+
+/// Checks that `leaf` is the node at `generalized_index` of the merkle tree with the given `root`,
+/// where `branch` holds the sibling nodes on the path from the leaf to the root.
+pub fn is_valid_merkle_branch<H: HostFunctions>(
+	leaf: &H256,
+	branch: &[H256],
+	generalized_index: u64,
+	root: &H256,
+) -> bool {
+	if generalized_index == 0 {
+		return false
+	}
+	let depth = (u64::BITS - 1 - generalized_index.leading_zeros()) as usize;
+	if branch.len() != depth {
+		return false
+	}
+	let computed = branch.iter().enumerate().fold(*leaf, |node, (i, sibling)| {
+		if (generalized_index >> i) & 1 == 1 {
+			hash_pair::<H>(sibling, &node)
+		} else {
+			hash_pair::<H>(&node, sibling)
+		}
+	});
+	computed == *root
+}
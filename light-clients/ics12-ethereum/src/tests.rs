@@ -0,0 +1,441 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_message::{ClientMessage, Header},
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+	mock::{
+		AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager, MockClientTypes,
+	},
+	proof::{verify_membership, verify_non_membership},
+	ssz,
+	types::{
+		BeaconBlockHeader, ExecutionPayloadHeader, Fork, ForkParameters, LightClientHeader,
+		LightClientUpdate, SyncAggregate, SyncCommittee, CURRENT_SYNC_COMMITTEE_INDEX,
+	},
+};
+use codec::Encode;
+use ibc::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as _,
+			client_state::ClientState as _,
+			context::{ClientKeeper, ClientReader},
+			error::Error as Ics02Error,
+			handler::{dispatch, ClientResult, ClientResult::Update},
+			msgs::{
+				create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient, ClientMsg,
+			},
+		},
+		ics04_channel::packet::Sequence,
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::{
+			identifier::{ChainId, ChannelId, ClientId, PortId},
+			path::CommitmentsPath,
+		},
+	},
+	events::IbcEvent,
+	handler::HandlerOutput,
+	mock::{context::MockContext, host::MockHostType},
+	test_utils::get_dummy_account_id,
+	timestamp::Timestamp,
+	Height,
+};
+use ibc_proto::google::protobuf::Any;
+use sp_core::{H160, H256, U256};
+use std::{marker::PhantomData, str::FromStr, time::Duration};
+use tendermint_proto::Protobuf;
+
+// The fixtures are synthetic, made by the generator in `fixtures-generator` (see
+// `fixtures/README.md`), in the format of the beacon node light client API and of `eth_getProof`.
+// The updates finalize a header in sync committee period 800 that brings the next sync committee,
+// then a header in period 801 signed by that committee, and the proofs are for the IBC contract
+// in the state of each finalized execution block.
+const BOOTSTRAP: &str = include_str!("fixtures/bootstrap.json");
+const UPDATES: &str = include_str!("fixtures/updates.json");
+const PROOFS: &str = include_str!("fixtures/proofs.json");
+
+const GENESIS_VALIDATORS_ROOT: &str =
+	"0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95";
+const IBC_ADDRESS: &str = "0x7a2f1e6b9c4d0a5e3f8b2c1d6e9a0f4b3c7d8e5a";
+const IBC_COMMITMENTS_SLOT: u64 = 3;
+/// Commitment of the packet with sequence 1 on channel-0 of the transfer port, which is in the
+/// storage of the IBC contract since the second finalized block.
+const PACKET_COMMITMENT: &str = "0402921bbe2769f1e4261f3603c09bc454c45fc4fc3f589bf00f6dc270d20b5b";
+
+fn bytes(value: &json::Value) -> Vec<u8> {
+	hex::decode(value.as_str().unwrap().trim_start_matches("0x")).unwrap()
+}
+
+fn hash(value: &json::Value) -> H256 {
+	H256::from_slice(&bytes(value))
+}
+
+fn number(value: &json::Value) -> u64 {
+	value.as_str().unwrap().parse().unwrap()
+}
+
+fn branch(value: &json::Value) -> Vec<H256> {
+	value.as_array().unwrap().iter().map(hash).collect()
+}
+
+/// The fixtures are signed with the genesis validators root and the fork schedule of mainnet.
+fn fork_parameters() -> ForkParameters {
+	ForkParameters {
+		genesis_fork_version: [0, 0, 0, 0],
+		forks: vec![
+			Fork { version: [1, 0, 0, 0], epoch: 74240 },
+			Fork { version: [2, 0, 0, 0], epoch: 144896 },
+			Fork { version: [3, 0, 0, 0], epoch: 194048 },
+			Fork { version: [4, 0, 0, 0], epoch: 269568 },
+		],
+	}
+}
+
+fn light_client_header(value: &json::Value) -> LightClientHeader {
+	let beacon = &value["beacon"];
+	let execution = &value["execution"];
+	LightClientHeader {
+		beacon: BeaconBlockHeader {
+			slot: number(&beacon["slot"]),
+			proposer_index: number(&beacon["proposer_index"]),
+			parent_root: hash(&beacon["parent_root"]),
+			state_root: hash(&beacon["state_root"]),
+			body_root: hash(&beacon["body_root"]),
+		},
+		execution: ExecutionPayloadHeader {
+			parent_hash: hash(&execution["parent_hash"]),
+			fee_recipient: H160::from_slice(&bytes(&execution["fee_recipient"])),
+			state_root: hash(&execution["state_root"]),
+			receipts_root: hash(&execution["receipts_root"]),
+			logs_bloom: bytes(&execution["logs_bloom"]),
+			prev_randao: hash(&execution["prev_randao"]),
+			block_number: number(&execution["block_number"]),
+			gas_limit: number(&execution["gas_limit"]),
+			gas_used: number(&execution["gas_used"]),
+			timestamp: number(&execution["timestamp"]),
+			extra_data: bytes(&execution["extra_data"]),
+			base_fee_per_gas: U256::from_dec_str(execution["base_fee_per_gas"].as_str().unwrap())
+				.unwrap(),
+			block_hash: hash(&execution["block_hash"]),
+			transactions_root: hash(&execution["transactions_root"]),
+			withdrawals_root: hash(&execution["withdrawals_root"]),
+			blob_gas_used: None,
+			excess_blob_gas: None,
+		},
+		execution_branch: branch(&value["execution_branch"]),
+	}
+}
+
+fn sync_committee(value: &json::Value) -> SyncCommittee {
+	SyncCommittee {
+		public_keys: value["pubkeys"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|key| bytes(key).try_into().unwrap())
+			.collect(),
+		aggregate_public_key: bytes(&value["aggregate_pubkey"]).try_into().unwrap(),
+	}
+}
+
+fn light_client_update(value: &json::Value) -> LightClientUpdate {
+	LightClientUpdate {
+		attested_header: light_client_header(&value["attested_header"]),
+		next_sync_committee: Some(sync_committee(&value["next_sync_committee"])),
+		next_sync_committee_branch: branch(&value["next_sync_committee_branch"]),
+		finalized_header: light_client_header(&value["finalized_header"]),
+		finality_branch: branch(&value["finality_branch"]),
+		sync_aggregate: SyncAggregate {
+			sync_committee_bits: bytes(&value["sync_aggregate"]["sync_committee_bits"]),
+			sync_committee_signature: bytes(&value["sync_aggregate"]["sync_committee_signature"])
+				.try_into()
+				.unwrap(),
+		},
+		signature_slot: number(&value["signature_slot"]),
+	}
+}
+
+fn trie_proof(value: &json::Value) -> Vec<Vec<u8>> {
+	value.as_array().unwrap().iter().map(bytes).collect()
+}
+
+/// `eth_getProof` response for the IBC contract at the block
+fn contract_proof(block_number: u64) -> json::Value {
+	let proofs: json::Value = json::from_str(PROOFS).unwrap();
+	proofs[block_number.to_string()].clone()
+}
+
+fn updates() -> Vec<LightClientUpdate> {
+	let updates: json::Value = json::from_str(UPDATES).unwrap();
+	updates
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|update| light_client_update(&update["data"]))
+		.collect()
+}
+
+fn header(update: LightClientUpdate) -> Header {
+	let proof = contract_proof(update.finalized_header.execution.block_number);
+	Header { update, account_proof: trie_proof(&proof["accountProof"]) }
+}
+
+/// Client and consensus states bootstrapped from the fixture, after checking the proof of the
+/// current sync committee.
+fn bootstrap() -> (ClientState<HostFunctionsManager>, ConsensusState) {
+	let bootstrap: json::Value = json::from_str(BOOTSTRAP).unwrap();
+	let bootstrap = &bootstrap["data"];
+	let header = light_client_header(&bootstrap["header"]);
+	assert!(header.is_valid::<HostFunctionsManager>());
+	let current_sync_committee = sync_committee(&bootstrap["current_sync_committee"]);
+	assert!(ssz::is_valid_merkle_branch::<HostFunctionsManager>(
+		&current_sync_committee.hash_tree_root::<HostFunctionsManager>(),
+		&branch(&bootstrap["current_sync_committee_branch"]),
+		CURRENT_SYNC_COMMITTEE_INDEX,
+		&header.beacon.state_root,
+	));
+
+	let client_state = ClientState {
+		chain_id: 1,
+		genesis_validators_root: H256::from_str(GENESIS_VALIDATORS_ROOT).unwrap(),
+		fork_parameters: fork_parameters(),
+		finalized_slot: header.beacon.slot,
+		latest_execution_height: header.execution.block_number,
+		current_sync_committee,
+		next_sync_committee: None,
+		ibc_address: H160::from_str(IBC_ADDRESS).unwrap(),
+		ibc_commitments_slot: H256::from_low_u64_be(IBC_COMMITMENTS_SLOT),
+		trusting_period: Duration::from_secs(27 * 60 * 60),
+		frozen_height: None,
+		_phantom: PhantomData,
+	};
+	let proof = contract_proof(header.execution.block_number);
+	let timestamp = Timestamp::from_nanoseconds(header.execution.timestamp * 1_000_000_000)
+		.unwrap()
+		.into_tm_time()
+		.unwrap();
+	let consensus_state =
+		ConsensusState::new(hash(&proof["storageHash"]).as_bytes().to_vec(), timestamp);
+
+	(client_state, consensus_state)
+}
+
+fn create_client(ctx: &mut MockContext<MockClientTypes>) -> ClientId {
+	let (client_state, consensus_state) = bootstrap();
+	let create_client = MsgCreateAnyClient {
+		client_state: AnyClientState::Ethereum(client_state),
+		consensus_state: AnyConsensusState::Ethereum(consensus_state),
+		signer: get_dummy_account_id(),
+	};
+	let res = dispatch(ctx, ClientMsg::CreateClient(create_client)).unwrap();
+	ctx.store_client_result(res.result).unwrap();
+	ClientId::new(&ClientState::<HostFunctionsManager>::client_type(), 0).unwrap()
+}
+
+fn mock_context() -> MockContext<MockClientTypes> {
+	let mut ctx = MockContext::<MockClientTypes>::new(
+		ChainId::new("mockgaiaA".to_string(), 1),
+		MockHostType::Mock,
+		5,
+		Height::new(1, 11),
+	);
+	ctx.block_time = Duration::from_secs(600);
+	ctx
+}
+
+fn update_client(
+	ctx: &MockContext<MockClientTypes>,
+	client_id: &ClientId,
+	header: Header,
+) -> Result<HandlerOutput<ClientResult<MockContext<MockClientTypes>>>, Ics02Error> {
+	let msg = MsgUpdateAnyClient {
+		client_id: client_id.clone(),
+		client_message: AnyClientMessage::Ethereum(ClientMessage::Header(header)),
+		signer: get_dummy_account_id(),
+	};
+	dispatch(ctx, ClientMsg::UpdateClient(msg))
+}
+
+#[test]
+fn test_continuous_update_of_ethereum_client() {
+	let mut ctx = mock_context();
+	let client_id = create_client(&mut ctx);
+	let updates = updates();
+
+	for update in updates.iter().cloned() {
+		let height = Height::new(0, update.finalized_header.execution.block_number);
+		let HandlerOutput { result, mut events, log } =
+			update_client(&ctx, &client_id, header(update.clone())).unwrap();
+		assert_eq!(events.len(), 1);
+		let event = events.pop().unwrap();
+		assert!(matches!(event, IbcEvent::UpdateClient(ref e) if e.client_id() == &client_id));
+		assert!(log.is_empty());
+		ctx.store_client_result(result.clone()).unwrap();
+		match result {
+			Update(upd_res) => {
+				assert_eq!(upd_res.client_id, client_id);
+				assert!(!upd_res.client_state.is_frozen(&ctx, &client_id));
+				assert_eq!(upd_res.client_state, ctx.latest_client_states(&client_id).clone());
+				assert_eq!(upd_res.client_state.latest_height(), height);
+			},
+			_ => unreachable!("update handler result has incorrect type"),
+		}
+
+		let consensus_state = ctx.consensus_state(&client_id, height).unwrap();
+		let proof = contract_proof(height.revision_height);
+		assert_eq!(consensus_state.root().as_bytes(), hash(&proof["storageHash"]).as_bytes(),);
+	}
+
+	// the next sync committee of the first update took over in the period of the second one
+	let client_state = match ctx.latest_client_states(&client_id).clone() {
+		AnyClientState::Ethereum(client_state) => client_state,
+		_ => unreachable!("client state has incorrect type"),
+	};
+	assert_eq!(Some(client_state.current_sync_committee), updates[0].next_sync_committee);
+	assert_eq!(client_state.next_sync_committee, updates[1].next_sync_committee);
+	assert_eq!(client_state.finalized_slot, updates[1].finalized_header.beacon.slot);
+
+	// updates can't be applied twice
+	assert!(update_client(&ctx, &client_id, header(updates[1].clone())).is_err());
+}
+
+#[test]
+fn test_update_of_next_period_requires_next_sync_committee() {
+	let mut ctx = mock_context();
+	let client_id = create_client(&mut ctx);
+	// the client doesn't know the committee that signed the second update yet
+	assert!(update_client(&ctx, &client_id, header(updates()[1].clone())).is_err());
+}
+
+#[test]
+fn test_update_with_invalid_sync_aggregate_fails() {
+	let mut ctx = mock_context();
+	let client_id = create_client(&mut ctx);
+	let update = updates()[0].clone();
+
+	let mut tampered = update.clone();
+	tampered.sync_aggregate.sync_committee_signature[10] ^= 1;
+	assert!(update_client(&ctx, &client_id, header(tampered)).is_err());
+
+	// the signature doesn't match the claimed participants
+	let mut tampered = update.clone();
+	tampered.sync_aggregate.sync_committee_bits = vec![0xff; 64];
+	assert!(update_client(&ctx, &client_id, header(tampered)).is_err());
+
+	// less than two thirds of the sync committee participated
+	let mut tampered = update.clone();
+	tampered.sync_aggregate.sync_committee_bits[40..].fill(0);
+	assert!(update_client(&ctx, &client_id, header(tampered)).is_err());
+
+	// the execution payload header isn't part of the finalized block
+	let mut tampered = update.clone();
+	tampered.finalized_header.execution.block_number += 1;
+	assert!(update_client(&ctx, &client_id, header(tampered)).is_err());
+
+	// the account proof isn't for the state of the finalized block
+	let mut tampered = header(update.clone());
+	tampered.account_proof = trie_proof(
+		&contract_proof(updates()[1].finalized_header.execution.block_number)["accountProof"],
+	);
+	assert!(update_client(&ctx, &client_id, tampered).is_err());
+
+	assert!(update_client(&ctx, &client_id, header(update)).is_ok());
+}
+
+#[test]
+fn test_verify_packet_commitment() {
+	let (client_state, _) = bootstrap();
+	let updates = updates();
+	let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+	let commitment_path = |sequence: u64| CommitmentsPath {
+		port_id: PortId::transfer(),
+		channel_id: ChannelId::new(0),
+		sequence: Sequence::from(sequence),
+	};
+	let storage_proof = |proof: &json::Value, index: usize| {
+		CommitmentProofBytes::try_from(trie_proof(&proof["storageProof"][index]["proof"]).encode())
+			.unwrap()
+	};
+	let packet_commitment = hex::decode(PACKET_COMMITMENT).unwrap();
+
+	let proof = contract_proof(updates[1].finalized_header.execution.block_number);
+	let root = CommitmentRoot::from_bytes(hash(&proof["storageHash"]).as_bytes());
+	verify_membership::<HostFunctionsManager, _>(
+		&client_state.ibc_commitments_slot,
+		&prefix,
+		&storage_proof(&proof, 0),
+		&root,
+		commitment_path(1),
+		packet_commitment.clone(),
+	)
+	.unwrap();
+	assert!(verify_membership::<HostFunctionsManager, _>(
+		&client_state.ibc_commitments_slot,
+		&prefix,
+		&storage_proof(&proof, 0),
+		&root,
+		commitment_path(1),
+		vec![1; 32],
+	)
+	.is_err());
+	assert!(verify_non_membership::<HostFunctionsManager, _>(
+		&client_state.ibc_commitments_slot,
+		&prefix,
+		&storage_proof(&proof, 0),
+		&root,
+		commitment_path(1),
+	)
+	.is_err());
+	verify_non_membership::<HostFunctionsManager, _>(
+		&client_state.ibc_commitments_slot,
+		&prefix,
+		&storage_proof(&proof, 1),
+		&root,
+		commitment_path(2),
+	)
+	.unwrap();
+
+	// the packet wasn't committed yet in the first finalized block
+	let proof = contract_proof(updates[0].finalized_header.execution.block_number);
+	let root = CommitmentRoot::from_bytes(hash(&proof["storageHash"]).as_bytes());
+	verify_non_membership::<HostFunctionsManager, _>(
+		&client_state.ibc_commitments_slot,
+		&prefix,
+		&storage_proof(&proof, 0),
+		&root,
+		commitment_path(1),
+	)
+	.unwrap();
+}
+
+#[test]
+fn test_client_message_protobuf_round_trip() {
+	let (client_state, consensus_state) = bootstrap();
+	let client_state = AnyClientState::Ethereum(client_state);
+	let any: Any = client_state.clone().into();
+	assert_eq!(AnyClientState::try_from(any).unwrap(), client_state);
+
+	let consensus_state = AnyConsensusState::Ethereum(consensus_state);
+	let any: Any = consensus_state.clone().into();
+	assert_eq!(AnyConsensusState::try_from(any).unwrap(), consensus_state);
+
+	let message = ClientMessage::Header(header(updates()[0].clone()));
+	let decoded = ClientMessage::decode_vec(&message.encode_vec().unwrap()).unwrap();
+	assert_eq!(decoded, message);
+	let any: Any = AnyClientMessage::Ethereum(message.clone()).into();
+	assert_eq!(AnyClientMessage::try_from(any).unwrap(), AnyClientMessage::Ethereum(message));
+}
@@ -0,0 +1,545 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Beacon chain types of the Altair light client protocol, with the Capella execution payload
+//! headers that carry the state roots of the execution layer.
+
+use crate::{
+	error::Error,
+	proto::{
+		BeaconBlockHeader as RawBeaconBlockHeader,
+		ExecutionPayloadHeader as RawExecutionPayloadHeader, Fork as RawFork,
+		ForkParameters as RawForkParameters, LightClientHeader as RawLightClientHeader,
+		LightClientUpdate as RawLightClientUpdate, SyncAggregate as RawSyncAggregate,
+		SyncCommittee as RawSyncCommittee,
+	},
+	ssz, HostFunctions,
+};
+use alloc::{format, string::ToString, vec, vec::Vec};
+use sp_core::{H160, H256, U256};
+
+/// Compressed BLS12-381 public key
+pub type BlsPublicKey = [u8; 48];
+/// Compressed BLS12-381 signature
+pub type BlsSignature = [u8; 96];
+/// Version of a beacon chain fork
+pub type Version = [u8; 4];
+
+/// Number of slots in an epoch
+pub const SLOTS_PER_EPOCH: u64 = 32;
+/// Number of epochs a sync committee serves for
+pub const EPOCHS_PER_SYNC_COMMITTEE_PERIOD: u64 = 256;
+/// Number of validators in a sync committee
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+/// Domain type of the signatures of the sync committees
+pub const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+/// Generalized index of the root of the finalized checkpoint in the beacon state
+pub const FINALIZED_ROOT_INDEX: u64 = 105;
+/// Generalized index of the current sync committee in the beacon state
+pub const CURRENT_SYNC_COMMITTEE_INDEX: u64 = 54;
+/// Generalized index of the next sync committee in the beacon state
+pub const NEXT_SYNC_COMMITTEE_INDEX: u64 = 55;
+/// Generalized index of the execution payload in the beacon block body
+pub const EXECUTION_PAYLOAD_INDEX: u64 = 25;
+/// Size of the bloom filter of the logs of an execution block
+pub const BYTES_PER_LOGS_BLOOM: usize = 256;
+/// Maximum size of the extra data of an execution block
+pub const MAX_EXTRA_DATA_BYTES: usize = 32;
+
+/// Epoch of the slot
+pub fn compute_epoch_at_slot(slot: u64) -> u64 {
+	slot / SLOTS_PER_EPOCH
+}
+
+/// Sync committee period of the slot
+pub fn compute_sync_committee_period_at_slot(slot: u64) -> u64 {
+	compute_epoch_at_slot(slot) / EPOCHS_PER_SYNC_COMMITTEE_PERIOD
+}
+
+/// Domain of the signatures of `domain_type`, for the fork with the given version.
+pub fn compute_domain<H: HostFunctions>(
+	domain_type: [u8; 4],
+	fork_version: Version,
+	genesis_validators_root: H256,
+) -> H256 {
+	let mut version = H256::zero();
+	version[..4].copy_from_slice(&fork_version);
+	let fork_data_root = ssz::hash_pair::<H>(&version, &genesis_validators_root);
+	let mut domain = H256::zero();
+	domain[..4].copy_from_slice(&domain_type);
+	domain[4..].copy_from_slice(&fork_data_root[..28]);
+	domain
+}
+
+/// Root that is signed to sign the object with the given root in `domain`.
+pub fn compute_signing_root<H: HostFunctions>(object_root: H256, domain: H256) -> H256 {
+	ssz::hash_pair::<H>(&object_root, &domain)
+}
+
+/// A fork of the beacon chain, which changes the signature domains from its first epoch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fork {
+	/// Version of the fork
+	pub version: Version,
+	/// Epoch the fork activates at
+	pub epoch: u64,
+}
+
+/// The forks of the beacon chain tracked by the client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForkParameters {
+	/// Version of the genesis fork
+	pub genesis_fork_version: Version,
+	/// Forks sorted by their activation epoch
+	pub forks: Vec<Fork>,
+}
+
+impl ForkParameters {
+	/// Version of the fork that is active at the epoch
+	pub fn fork_version(&self, epoch: u64) -> Version {
+		self.forks
+			.iter()
+			.rev()
+			.find(|fork| fork.epoch <= epoch)
+			.map(|fork| fork.version)
+			.unwrap_or(self.genesis_fork_version)
+	}
+}
+
+/// Header of a beacon chain block
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct BeaconBlockHeader {
+	pub slot: u64,
+	pub proposer_index: u64,
+	pub parent_root: H256,
+	pub state_root: H256,
+	pub body_root: H256,
+}
+
+impl BeaconBlockHeader {
+	pub fn hash_tree_root<H: HostFunctions>(&self) -> H256 {
+		let fields = [
+			ssz::uint64(self.slot),
+			ssz::uint64(self.proposer_index),
+			self.parent_root,
+			self.state_root,
+			self.body_root,
+		];
+		ssz::merkleize::<H>(&fields, fields.len())
+	}
+}
+
+/// Header of the execution payload of a beacon chain block. The blob gas fields are only present
+/// since the Deneb fork.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ExecutionPayloadHeader {
+	pub parent_hash: H256,
+	pub fee_recipient: H160,
+	pub state_root: H256,
+	pub receipts_root: H256,
+	pub logs_bloom: Vec<u8>,
+	pub prev_randao: H256,
+	pub block_number: u64,
+	pub gas_limit: u64,
+	pub gas_used: u64,
+	pub timestamp: u64,
+	pub extra_data: Vec<u8>,
+	pub base_fee_per_gas: U256,
+	pub block_hash: H256,
+	pub transactions_root: H256,
+	pub withdrawals_root: H256,
+	pub blob_gas_used: Option<u64>,
+	pub excess_blob_gas: Option<u64>,
+}
+
+impl ExecutionPayloadHeader {
+	pub fn hash_tree_root<H: HostFunctions>(&self) -> H256 {
+		let mut base_fee_per_gas = H256::zero();
+		self.base_fee_per_gas.to_little_endian(base_fee_per_gas.as_bytes_mut());
+		let mut fields = vec![
+			self.parent_hash,
+			ssz::byte_vector::<H>(self.fee_recipient.as_bytes()),
+			self.state_root,
+			self.receipts_root,
+			ssz::byte_vector::<H>(&self.logs_bloom),
+			self.prev_randao,
+			ssz::uint64(self.block_number),
+			ssz::uint64(self.gas_limit),
+			ssz::uint64(self.gas_used),
+			ssz::uint64(self.timestamp),
+			ssz::byte_list::<H>(&self.extra_data, MAX_EXTRA_DATA_BYTES),
+			base_fee_per_gas,
+			self.block_hash,
+			self.transactions_root,
+			self.withdrawals_root,
+		];
+		fields.extend(self.blob_gas_used.map(ssz::uint64));
+		fields.extend(self.excess_blob_gas.map(ssz::uint64));
+		ssz::merkleize::<H>(&fields, fields.len())
+	}
+}
+
+/// A beacon chain block header together with the header of its execution payload.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LightClientHeader {
+	pub beacon: BeaconBlockHeader,
+	pub execution: ExecutionPayloadHeader,
+	/// Proof of the execution payload header in the body of the beacon block
+	pub execution_branch: Vec<H256>,
+}
+
+impl LightClientHeader {
+	/// Checks that the execution payload header belongs to the beacon block.
+	pub fn is_valid<H: HostFunctions>(&self) -> bool {
+		ssz::is_valid_merkle_branch::<H>(
+			&self.execution.hash_tree_root::<H>(),
+			&self.execution_branch,
+			EXECUTION_PAYLOAD_INDEX,
+			&self.beacon.body_root,
+		)
+	}
+}
+
+/// The validators that sign the beacon chain headers during a sync committee period.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncCommittee {
+	pub public_keys: Vec<BlsPublicKey>,
+	pub aggregate_public_key: BlsPublicKey,
+}
+
+impl SyncCommittee {
+	pub fn hash_tree_root<H: HostFunctions>(&self) -> H256 {
+		let public_keys = self
+			.public_keys
+			.iter()
+			.map(|public_key| ssz::byte_vector::<H>(public_key))
+			.collect::<Vec<_>>();
+		ssz::hash_pair::<H>(
+			&ssz::merkleize::<H>(&public_keys, SYNC_COMMITTEE_SIZE),
+			&ssz::byte_vector::<H>(&self.aggregate_public_key),
+		)
+	}
+}
+
+/// The aggregate signature of a beacon chain header by the participants of a sync committee.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncAggregate {
+	/// Bit vector of the members of the sync committee that participated in the signature
+	pub sync_committee_bits: Vec<u8>,
+	pub sync_committee_signature: BlsSignature,
+}
+
+impl SyncAggregate {
+	/// Indices of the sync committee members that participated in the signature.
+	pub fn participants(&self) -> impl Iterator<Item = usize> + '_ {
+		(0..SYNC_COMMITTEE_SIZE).filter(move |index| {
+			self.sync_committee_bits
+				.get(index / 8)
+				.map_or(false, |byte| (byte >> (index % 8)) & 1 == 1)
+		})
+	}
+}
+
+/// An update of the light client to a finalized beacon chain header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LightClientUpdate {
+	/// Header signed by the sync committee
+	pub attested_header: LightClientHeader,
+	/// Sync committee of the period after the one of the attested header
+	pub next_sync_committee: Option<SyncCommittee>,
+	/// Proof of the next sync committee in the state of the attested header
+	pub next_sync_committee_branch: Vec<H256>,
+	/// Header finalized by the attested header
+	pub finalized_header: LightClientHeader,
+	/// Proof of the finalized header in the state of the attested header
+	pub finality_branch: Vec<H256>,
+	pub sync_aggregate: SyncAggregate,
+	/// Slot of the block that includes the sync aggregate
+	pub signature_slot: u64,
+}
+
+/// Decodes a 32 byte hash
+pub(crate) fn decode_hash(bytes: &[u8]) -> Result<H256, Error> {
+	if bytes.len() != 32 {
+		return Err(Error::Custom(format!("Invalid hash length: {}", bytes.len())))
+	}
+	Ok(H256::from_slice(bytes))
+}
+
+fn decode_array<const N: usize>(bytes: &[u8], name: &str) -> Result<[u8; N], Error> {
+	bytes
+		.try_into()
+		.map_err(|_| Error::Custom(format!("Invalid {name} length: {}", bytes.len())))
+}
+
+fn decode_branch(branch: Vec<Vec<u8>>) -> Result<Vec<H256>, Error> {
+	branch.iter().map(|node| decode_hash(node)).collect()
+}
+
+fn encode_branch(branch: Vec<H256>) -> Vec<Vec<u8>> {
+	branch.into_iter().map(|node| node.as_bytes().to_vec()).collect()
+}
+
+impl TryFrom<RawFork> for Fork {
+	type Error = Error;
+
+	fn try_from(raw: RawFork) -> Result<Self, Self::Error> {
+		Ok(Self { version: decode_array(&raw.version, "fork version")?, epoch: raw.epoch })
+	}
+}
+
+impl From<Fork> for RawFork {
+	fn from(fork: Fork) -> Self {
+		RawFork { version: fork.version.to_vec(), epoch: fork.epoch }
+	}
+}
+
+impl TryFrom<RawForkParameters> for ForkParameters {
+	type Error = Error;
+
+	fn try_from(raw: RawForkParameters) -> Result<Self, Self::Error> {
+		let forks = raw.forks.into_iter().map(Fork::try_from).collect::<Result<Vec<_>, _>>()?;
+		if forks.windows(2).any(|pair| pair[0].epoch > pair[1].epoch) {
+			return Err(Error::Custom("Forks must be sorted by their epoch".to_string()))
+		}
+		Ok(Self {
+			genesis_fork_version: decode_array(&raw.genesis_fork_version, "fork version")?,
+			forks,
+		})
+	}
+}
+
+impl From<ForkParameters> for RawForkParameters {
+	fn from(fork_parameters: ForkParameters) -> Self {
+		RawForkParameters {
+			genesis_fork_version: fork_parameters.genesis_fork_version.to_vec(),
+			forks: fork_parameters.forks.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl TryFrom<RawBeaconBlockHeader> for BeaconBlockHeader {
+	type Error = Error;
+
+	fn try_from(raw: RawBeaconBlockHeader) -> Result<Self, Self::Error> {
+		Ok(Self {
+			slot: raw.slot,
+			proposer_index: raw.proposer_index,
+			parent_root: decode_hash(&raw.parent_root)?,
+			state_root: decode_hash(&raw.state_root)?,
+			body_root: decode_hash(&raw.body_root)?,
+		})
+	}
+}
+
+impl From<BeaconBlockHeader> for RawBeaconBlockHeader {
+	fn from(header: BeaconBlockHeader) -> Self {
+		RawBeaconBlockHeader {
+			slot: header.slot,
+			proposer_index: header.proposer_index,
+			parent_root: header.parent_root.as_bytes().to_vec(),
+			state_root: header.state_root.as_bytes().to_vec(),
+			body_root: header.body_root.as_bytes().to_vec(),
+		}
+	}
+}
+
+impl TryFrom<RawExecutionPayloadHeader> for ExecutionPayloadHeader {
+	type Error = Error;
+
+	fn try_from(raw: RawExecutionPayloadHeader) -> Result<Self, Self::Error> {
+		if raw.logs_bloom.len() != BYTES_PER_LOGS_BLOOM {
+			return Err(Error::Custom(format!(
+				"Invalid logs bloom length: {}",
+				raw.logs_bloom.len()
+			)))
+		}
+		if raw.extra_data.len() > MAX_EXTRA_DATA_BYTES {
+			return Err(Error::Custom(format!(
+				"Invalid extra data length: {}",
+				raw.extra_data.len()
+			)))
+		}
+		let base_fee_per_gas: [u8; 32] = decode_array(&raw.base_fee_per_gas, "base fee")?;
+		Ok(Self {
+			parent_hash: decode_hash(&raw.parent_hash)?,
+			fee_recipient: H160(decode_array(&raw.fee_recipient, "fee recipient")?),
+			state_root: decode_hash(&raw.state_root)?,
+			receipts_root: decode_hash(&raw.receipts_root)?,
+			logs_bloom: raw.logs_bloom,
+			prev_randao: decode_hash(&raw.prev_randao)?,
+			block_number: raw.block_number,
+			gas_limit: raw.gas_limit,
+			gas_used: raw.gas_used,
+			timestamp: raw.timestamp,
+			extra_data: raw.extra_data,
+			base_fee_per_gas: U256::from_little_endian(&base_fee_per_gas),
+			block_hash: decode_hash(&raw.block_hash)?,
+			transactions_root: decode_hash(&raw.transactions_root)?,
+			withdrawals_root: decode_hash(&raw.withdrawals_root)?,
+			blob_gas_used: raw.blob_gas_used,
+			excess_blob_gas: raw.excess_blob_gas,
+		})
+	}
+}
+
+impl From<ExecutionPayloadHeader> for RawExecutionPayloadHeader {
+	fn from(header: ExecutionPayloadHeader) -> Self {
+		let mut base_fee_per_gas = vec![0u8; 32];
+		header.base_fee_per_gas.to_little_endian(&mut base_fee_per_gas);
+		RawExecutionPayloadHeader {
+			parent_hash: header.parent_hash.as_bytes().to_vec(),
+			fee_recipient: header.fee_recipient.as_bytes().to_vec(),
+			state_root: header.state_root.as_bytes().to_vec(),
+			receipts_root: header.receipts_root.as_bytes().to_vec(),
+			logs_bloom: header.logs_bloom,
+			prev_randao: header.prev_randao.as_bytes().to_vec(),
+			block_number: header.block_number,
+			gas_limit: header.gas_limit,
+			gas_used: header.gas_used,
+			timestamp: header.timestamp,
+			extra_data: header.extra_data,
+			base_fee_per_gas,
+			block_hash: header.block_hash.as_bytes().to_vec(),
+			transactions_root: header.transactions_root.as_bytes().to_vec(),
+			withdrawals_root: header.withdrawals_root.as_bytes().to_vec(),
+			blob_gas_used: header.blob_gas_used,
+			excess_blob_gas: header.excess_blob_gas,
+		}
+	}
+}
+
+impl TryFrom<RawLightClientHeader> for LightClientHeader {
+	type Error = Error;
+
+	fn try_from(raw: RawLightClientHeader) -> Result<Self, Self::Error> {
+		Ok(Self {
+			beacon: raw
+				.beacon
+				.ok_or_else(|| Error::Custom("Beacon block header is missing".to_string()))?
+				.try_into()?,
+			execution: raw
+				.execution
+				.ok_or_else(|| Error::Custom("Execution payload header is missing".to_string()))?
+				.try_into()?,
+			execution_branch: decode_branch(raw.execution_branch)?,
+		})
+	}
+}
+
+impl From<LightClientHeader> for RawLightClientHeader {
+	fn from(header: LightClientHeader) -> Self {
+		RawLightClientHeader {
+			beacon: Some(header.beacon.into()),
+			execution: Some(header.execution.into()),
+			execution_branch: encode_branch(header.execution_branch),
+		}
+	}
+}
+
+impl TryFrom<RawSyncCommittee> for SyncCommittee {
+	type Error = Error;
+
+	fn try_from(raw: RawSyncCommittee) -> Result<Self, Self::Error> {
+		if raw.public_keys.len() != SYNC_COMMITTEE_SIZE {
+			return Err(Error::Custom(format!(
+				"Invalid sync committee size: {}",
+				raw.public_keys.len()
+			)))
+		}
+		Ok(Self {
+			public_keys: raw
+				.public_keys
+				.iter()
+				.map(|public_key| decode_array(public_key, "public key"))
+				.collect::<Result<_, _>>()?,
+			aggregate_public_key: decode_array(&raw.aggregate_public_key, "public key")?,
+		})
+	}
+}
+
+impl From<SyncCommittee> for RawSyncCommittee {
+	fn from(sync_committee: SyncCommittee) -> Self {
+		RawSyncCommittee {
+			public_keys: sync_committee.public_keys.iter().map(|key| key.to_vec()).collect(),
+			aggregate_public_key: sync_committee.aggregate_public_key.to_vec(),
+		}
+	}
+}
+
+impl TryFrom<RawSyncAggregate> for SyncAggregate {
+	type Error = Error;
+
+	fn try_from(raw: RawSyncAggregate) -> Result<Self, Self::Error> {
+		if raw.sync_committee_bits.len() != SYNC_COMMITTEE_SIZE / 8 {
+			return Err(Error::Custom(format!(
+				"Invalid sync committee bits length: {}",
+				raw.sync_committee_bits.len()
+			)))
+		}
+		Ok(Self {
+			sync_committee_bits: raw.sync_committee_bits,
+			sync_committee_signature: decode_array(&raw.sync_committee_signature, "signature")?,
+		})
+	}
+}
+
+impl From<SyncAggregate> for RawSyncAggregate {
+	fn from(sync_aggregate: SyncAggregate) -> Self {
+		RawSyncAggregate {
+			sync_committee_bits: sync_aggregate.sync_committee_bits,
+			sync_committee_signature: sync_aggregate.sync_committee_signature.to_vec(),
+		}
+	}
+}
+
+impl TryFrom<RawLightClientUpdate> for LightClientUpdate {
+	type Error = Error;
+
+	fn try_from(raw: RawLightClientUpdate) -> Result<Self, Self::Error> {
+		Ok(Self {
+			attested_header: raw
+				.attested_header
+				.ok_or_else(|| Error::Custom("Attested header is missing".to_string()))?
+				.try_into()?,
+			next_sync_committee: raw.next_sync_committee.map(TryInto::try_into).transpose()?,
+			next_sync_committee_branch: decode_branch(raw.next_sync_committee_branch)?,
+			finalized_header: raw
+				.finalized_header
+				.ok_or_else(|| Error::Custom("Finalized header is missing".to_string()))?
+				.try_into()?,
+			finality_branch: decode_branch(raw.finality_branch)?,
+			sync_aggregate: raw
+				.sync_aggregate
+				.ok_or_else(|| Error::Custom("Sync aggregate is missing".to_string()))?
+				.try_into()?,
+			signature_slot: raw.signature_slot,
+		})
+	}
+}
+
+impl From<LightClientUpdate> for RawLightClientUpdate {
+	fn from(update: LightClientUpdate) -> Self {
+		RawLightClientUpdate {
+			attested_header: Some(update.attested_header.into()),
+			next_sync_committee: update.next_sync_committee.map(Into::into),
+			next_sync_committee_branch: encode_branch(update.next_sync_committee_branch),
+			finalized_header: Some(update.finalized_header.into()),
+			finality_branch: encode_branch(update.finality_branch),
+			sync_aggregate: Some(update.sync_aggregate.into()),
+			signature_slot: update.signature_slot,
+		}
+	}
+}
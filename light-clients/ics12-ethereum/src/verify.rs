@@ -0,0 +1,164 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of the light client updates signed by the sync committees, see
+//! <https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md>.
+
+use crate::{
+	client_state::ClientState,
+	error::Error,
+	ssz,
+	types::{
+		compute_domain, compute_epoch_at_slot, compute_signing_root,
+		compute_sync_committee_period_at_slot, LightClientUpdate, DOMAIN_SYNC_COMMITTEE,
+		FINALIZED_ROOT_INDEX, NEXT_SYNC_COMMITTEE_INDEX, SYNC_COMMITTEE_SIZE,
+	},
+	HostFunctions,
+};
+use alloc::{format, string::ToString, vec::Vec};
+
+/// Verifies an update that advances the client to a newer finalized header, or that brings the
+/// next sync committee of the period of its latest finalized header.
+pub fn verify_light_client_update<H: HostFunctions>(
+	client_state: &ClientState<H>,
+	update: &LightClientUpdate,
+) -> Result<(), Error> {
+	let finalized_slot = update.finalized_header.beacon.slot;
+	let store_period = compute_sync_committee_period_at_slot(client_state.finalized_slot);
+	let finalized_period = compute_sync_committee_period_at_slot(finalized_slot);
+	let next_period_known = client_state.next_sync_committee.is_some();
+	let max_period = if next_period_known { store_period + 1 } else { store_period };
+	if finalized_period < store_period || finalized_period > max_period {
+		return Err(Error::Custom(format!(
+			"Finalized header of period {finalized_period} can't be applied in period {store_period}"
+		)))
+	}
+	// an update may keep the finalized header of the client if it brings the next sync committee
+	let learns_next_sync_committee = finalized_slot == client_state.finalized_slot &&
+		!next_period_known &&
+		update.next_sync_committee.is_some();
+	if finalized_slot <= client_state.finalized_slot && !learns_next_sync_committee {
+		return Err(Error::Custom(format!(
+			"Update doesn't advance the finalized slot {}",
+			client_state.finalized_slot
+		)))
+	}
+
+	verify_signed_update(client_state, update)
+}
+
+/// Verifies that the update is signed by a supermajority of the sync committee the client knows
+/// for the signature slot, together with the proofs of the finalized header and the next sync
+/// committee it carries.
+pub fn verify_signed_update<H: HostFunctions>(
+	client_state: &ClientState<H>,
+	update: &LightClientUpdate,
+) -> Result<(), Error> {
+	let participants = update.sync_aggregate.participants().collect::<Vec<_>>();
+	if participants.len() * 3 < SYNC_COMMITTEE_SIZE * 2 {
+		return Err(Error::Custom(format!(
+			"Insufficient sync committee participation: {}",
+			participants.len()
+		)))
+	}
+
+	let attested_slot = update.attested_header.beacon.slot;
+	let finalized_slot = update.finalized_header.beacon.slot;
+	if !(update.signature_slot > attested_slot && attested_slot >= finalized_slot) {
+		return Err(Error::Custom(format!(
+			"Invalid slots, signature slot: {}, attested slot: {attested_slot}, finalized slot: {finalized_slot}",
+			update.signature_slot
+		)))
+	}
+	if !update.attested_header.is_valid::<H>() || !update.finalized_header.is_valid::<H>() {
+		return Err(Error::Custom("Invalid execution payload header proof".to_string()))
+	}
+
+	let store_period = compute_sync_committee_period_at_slot(client_state.finalized_slot);
+	let signature_period = compute_sync_committee_period_at_slot(update.signature_slot);
+	let attested_period = compute_sync_committee_period_at_slot(attested_slot);
+	let finalized_period = compute_sync_committee_period_at_slot(finalized_slot);
+	let sync_committee = match (signature_period, &client_state.next_sync_committee) {
+		(period, _) if period == store_period => &client_state.current_sync_committee,
+		(period, Some(next_sync_committee)) if period == store_period + 1 => next_sync_committee,
+		_ =>
+			return Err(Error::Custom(format!(
+				"Unknown sync committee for period {signature_period}, latest period: {store_period}"
+			))),
+	};
+
+	if !ssz::is_valid_merkle_branch::<H>(
+		&update.finalized_header.beacon.hash_tree_root::<H>(),
+		&update.finality_branch,
+		FINALIZED_ROOT_INDEX,
+		&update.attested_header.beacon.state_root,
+	) {
+		return Err(Error::Custom("Invalid finality proof".to_string()))
+	}
+
+	if let Some(next_sync_committee) = &update.next_sync_committee {
+		// the next sync committee is only applied together with the finalized header, so both
+		// must be of the same period.
+		if attested_period != finalized_period {
+			return Err(Error::Custom(format!(
+				"Next sync committee of period {attested_period} is attested with a finalized header of period {finalized_period}"
+			)))
+		}
+		if attested_period == store_period {
+			if let Some(known) = &client_state.next_sync_committee {
+				if known != next_sync_committee {
+					return Err(Error::Custom("Conflicting next sync committee".to_string()))
+				}
+			}
+		}
+		if !ssz::is_valid_merkle_branch::<H>(
+			&next_sync_committee.hash_tree_root::<H>(),
+			&update.next_sync_committee_branch,
+			NEXT_SYNC_COMMITTEE_INDEX,
+			&update.attested_header.beacon.state_root,
+		) {
+			return Err(Error::Custom("Invalid next sync committee proof".to_string()))
+		}
+	}
+
+	let public_keys =
+		participants
+			.into_iter()
+			.map(|index| {
+				sync_committee.public_keys.get(index).copied().ok_or_else(|| {
+					Error::Custom(format!("Sync committee member {index} is unknown"))
+				})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+	let fork_version = client_state
+		.fork_parameters
+		.fork_version(compute_epoch_at_slot(update.signature_slot.max(1) - 1));
+	let domain = compute_domain::<H>(
+		DOMAIN_SYNC_COMMITTEE,
+		fork_version,
+		client_state.genesis_validators_root,
+	);
+	let signing_root =
+		compute_signing_root::<H>(update.attested_header.beacon.hash_tree_root::<H>(), domain);
+	if !H::bls12_381_fast_aggregate_verify(
+		&public_keys,
+		&signing_root.0,
+		&update.sync_aggregate.sync_committee_signature,
+	) {
+		return Err(Error::Custom("Invalid sync committee signature".to_string()))
+	}
+
+	Ok(())
+}
@@ -176,8 +176,10 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		todo!()
 	}
 
-	fn update_state_on_misbehaviour(
+	fn update_state_on_misbehaviour<Ctx: ReaderContext>(
 		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
 		_client_state: Self::ClientState,
 		_client_message: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
@@ -221,12 +223,13 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		_client_state: &Self::ClientState,
 		_height: Height,
 		_prefix: &CommitmentPrefix,
 		_proof: &CommitmentProofBytes,
 		_root: &CommitmentRoot,
-		_client_id: &ClientId,
+		_counterparty_client_id: &ClientId,
 		_consensus_height: Height,
 		_expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error> {
@@ -268,12 +271,13 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		_client_id: &ClientId,
 		_client_state: &Self::ClientState,
 		_height: Height,
 		_prefix: &CommitmentPrefix,
 		_proof: &CommitmentProofBytes,
 		_root: &CommitmentRoot,
-		_client_id: &ClientId,
+		_counterparty_client_id: &ClientId,
 		_expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Error> {
 		todo!()
@@ -538,7 +538,7 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
 	pub const ExpectedBlockTime: u64 = MILLISECS_PER_BLOCK as u64;
-	pub const RelayChainId: RelayChain = RelayChain::Rococo;
+	pub RelayChainId: RelayChain = RelayChain::rococo();
 	pub const SpamProtectionDeposit: Balance = 1_000_000_000_000;
 	pub const NativeAssetId: AssetId = 1;
 	pub const MinimumConnectionDelay: u64 = 0; // well we don't need the security tbh.
@@ -708,6 +708,11 @@ parameter_types! {
 	pub AssetIdUSDT: AssetId = 0;
 	pub FlatFeeUSDTAmount: Balance = 0;
 	pub IbcIcs20ServiceCharge: Perbill = Perbill::from_rational(0_u32, 1000_u32 );
+	pub const DefaultDenomDecimals: Option<u8> = Some(12);
+	pub const EscrowAuditPeriod: BlockNumber = 100;
+	pub const MaxWasmFuel: u64 = 50_000_000;
+	pub const WeightPerWasmFuel: Weight = Weight::from_ref_time(1_000);
+	pub const MaxWasmCodeSize: u32 = 3 * 1024 * 1024;
 }
 
 fn create_alice_key() -> <Runtime as pallet_ibc::Config>::AccountIdConversion {
@@ -732,14 +737,19 @@ impl pallet_ibc::Config for Runtime {
 	type ParaId = parachain_info::Pallet<Runtime>;
 	type RelayChain = RelayChainId;
 	type WeightInfo = ();
+	type LightClients = (
+		pallet_ibc::weight::TendermintWeights,
+		pallet_ibc::weight::GrandpaWeights,
+		pallet_ibc::weight::BeefyWeights,
+		pallet_ibc::weight::EthereumWeights,
+		pallet_ibc::weight::WasmWeights,
+	);
 	type AdminOrigin = EnsureRoot<AccountId>;
 	type FreezeOrigin = EnsureRoot<AccountId>;
 	type SpamProtectionDeposit = SpamProtectionDeposit;
 	type TransferOrigin = EnsureSigned<Self::IbcAccountId>;
 	type RelayerOrigin = EnsureSigned<Self::AccountId>;
 	type MemoMessage = MemoMessage;
-	type IsReceiveEnabled = sp_core::ConstBool<true>;
-	type IsSendEnabled = sp_core::ConstBool<true>;
 	type HandleMemo = ();
 	type PalletPrefix = IbcTriePrefix;
 	type LightClientProtocol = GRANDPA;
@@ -751,6 +761,13 @@ impl pallet_ibc::Config for Runtime {
 	type FlatFeeConverter = NonFlatFeeConverter<Runtime>;
 	type FlatFeeAssetId = AssetIdUSDT;
 	type FlatFeeAmount = FlatFeeUSDTAmount;
+	type DenomMetadataOrigin = EnsureRoot<AccountId>;
+	type DefaultDenomDecimals = DefaultDenomDecimals;
+	type EscrowAuditPeriod = EscrowAuditPeriod;
+	type BlockChannelOnEscrowDrift = sp_core::ConstBool<true>;
+	type MaxWasmFuel = MaxWasmFuel;
+	type WeightPerWasmFuel = WeightPerWasmFuel;
+	type MaxWasmCodeSize = MaxWasmCodeSize;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -1032,6 +1049,14 @@ impl_runtime_apis! {
 			Ibc::get_denom_traces(key, limit, count_total)
 		}
 
+		fn denom_metadata(asset_id: AssetId) -> Option<ibc_primitives::QueryDenomMetadataResponse> {
+			Ibc::get_denom_metadata(asset_id)
+		}
+
+		fn total_escrow(channel_id: Vec<u8>) -> Vec<(Vec<u8>, u128)> {
+			Ibc::get_total_escrow(channel_id)
+		}
+
 		fn block_events(extrinsic_index: Option<u32>) -> Vec<Result<pallet_ibc::events::IbcEvent, pallet_ibc::errors::IbcError>> {
 			let mut raw_events = frame_system::Pallet::<Self>::read_events_no_consensus().into_iter();
 			if let Some(idx) = extrinsic_index {
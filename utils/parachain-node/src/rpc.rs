@@ -25,8 +25,8 @@ use std::sync::Arc;
 use parachain_runtime::{opaque::Block, AccountId, AssetId, Balance, Index as Nonce};
 
 use ibc_runtime_api::IbcRuntimeApi;
-use sc_client_api::{AuxStore, BlockBackend, ProofProvider};
-pub use sc_rpc::DenyUnsafe;
+use sc_client_api::{AuxStore, BlockBackend, BlockchainEvents, ProofProvider};
+pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
@@ -45,6 +45,8 @@ pub struct FullDeps<C, P> {
 	pub chain_props: Properties,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Executor for subscription tasks
+	pub subscription_executor: SubscriptionTaskExecutor,
 }
 
 /// Instantiate all RPC extensions.
@@ -58,6 +60,7 @@ where
 		+ HeaderMetadata<Block, Error = BlockChainError>
 		+ ProofProvider<Block>
 		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>
 		+ Send
 		+ Sync
 		+ 'static,
@@ -72,11 +75,12 @@ where
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcExtension::new(());
-	let FullDeps { client, pool, deny_unsafe, chain_props } = deps;
+	let FullDeps { client, pool, deny_unsafe, chain_props, subscription_executor } = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
 	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
-	module.merge(IbcRpcHandler::new(client.clone(), chain_props).into_rpc())?;
+	module
+		.merge(IbcRpcHandler::new(client.clone(), chain_props, subscription_executor).into_rpc())?;
 
 	Ok(module)
 }
@@ -220,12 +220,13 @@ async fn start_node_impl(
 		let transaction_pool = transaction_pool.clone();
 		let chain_props = parachain_config.chain_spec.properties();
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
 				chain_props: chain_props.clone(),
+				subscription_executor,
 			};
 
 			crate::rpc::create_full(deps).map_err(Into::into)
@@ -64,6 +64,7 @@ impl ChainInfo for ParachainRuntimeChainInfo {
 			pool: deps.pool,
 			deny_unsafe: deps.deny_unsafe,
 			chain_props: Default::default(),
+			subscription_executor: deps.subscription_executor,
 		};
 		parachain_node::rpc::create_full(full_deps).expect("Rpc to be initialized")
 	}
@@ -141,6 +142,7 @@ mod tests {
 				para_id: 100,
 				current_set_id: 1,
 				current_authorities: Default::default(),
+				standalone: false,
 				_phantom: Default::default(),
 			};
 
@@ -169,6 +171,7 @@ mod tests {
 					unknown_headers: Default::default(),
 				},
 				parachain_headers: Default::default(),
+				ancestry_proofs: Default::default(),
 			};
 			let msg_create_client = MsgCreateAnyClient::<LocalClientTypes> {
 				client_state: AnyClientState::Grandpa(client_state),